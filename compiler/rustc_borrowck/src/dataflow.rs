@@ -248,9 +248,9 @@ fn new(body: &'mir Body<'tcx>, regioncx: &'mir RegionInferenceContext<'tcx>) ->
 }
 
 impl<'tcx> PoloniusOutOfScopePrecomputer<'_, 'tcx> {
-    /// Loans are in scope while they are live: whether they are contained within any live region.
-    /// In the location-insensitive analysis, a loan will be contained in a region if the issuing
-    /// region can reach it in the subset graph. So this is a reachability problem.
+    /// Loans are in scope while they are live: whether they flow into any live region. In the
+    /// location-sensitive analysis, a loan flows into a region at a given point if the issuing
+    /// region can reach it at this point, see `polonius::loan_liveness`.
     fn precompute_loans_out_of_scope(
         &mut self,
         loan_idx: BorrowIndex,
@@ -260,11 +260,9 @@ fn precompute_loans_out_of_scope(
         let sccs = self.regioncx.constraint_sccs();
         let universal_regions = self.regioncx.universal_regions();
 
-        // We first handle the cases where the loan doesn't go out of scope, depending on the issuing
-        // region's successors.
+        // We first handle the case where the loan doesn't go out of scope, depending on the issuing
+        // region's successors: via applied member constraints, which are not tied to any point.
         for successor in self.regioncx.region_graph().depth_first_search(issuing_region) {
-            // 1. Via applied member constraints
-            //
             // The issuing region can flow into the choice regions, and they are either:
             // - placeholders or free regions themselves,
             // - or also transitively outlive a free region.
@@ -281,14 +279,15 @@ fn precompute_loans_out_of_scope(
                     return;
                 }
             }
+        }
 
-            // 2. Via regions that are live at all points: placeholders and free regions.
-            //
-            // If the issuing region outlives such a region, its loan escapes the function and
-            // cannot go out of scope. We can early return.
-            if self.regioncx.is_region_live_at_all_points(successor) {
-                return;
-            }
+        // Via regions that are live at all points: placeholders and free regions.
+        //
+        // If the issuing region outlives such a region at all points, its loan escapes the function
+        // and cannot go out of scope. We can early return. Loans flowing into such a region only at
+        // some point escape from there, and are live at all the points that follow.
+        if self.regioncx.is_loan_escaping(loan_idx) {
+            return;
         }
 
         let first_block = loan_issued_at.block;
@@ -299,9 +298,14 @@ fn precompute_loans_out_of_scope(
         let first_lo = loan_issued_at.statement_index;
         let first_hi = first_bb_data.statements.len();
 
-        if let Some(kill_location) =
-            self.loan_kill_location(loan_idx, loan_issued_at, first_block, first_lo, first_hi)
-        {
+        if let Some(kill_location) = self.loan_kill_location(
+            loan_idx,
+            issuing_region,
+            loan_issued_at,
+            first_block,
+            first_lo,
+            first_hi,
+        ) {
             debug!("loan {:?} gets killed at {:?}", loan_idx, kill_location);
             self.loans_out_of_scope_at_location.entry(kill_location).or_default().push(loan_idx);
 
@@ -322,9 +326,14 @@ fn precompute_loans_out_of_scope(
         while let Some(block) = self.visit_stack.pop() {
             let bb_data = &self.body[block];
             let num_stmts = bb_data.statements.len();
-            if let Some(kill_location) =
-                self.loan_kill_location(loan_idx, loan_issued_at, block, 0, num_stmts)
-            {
+            if let Some(kill_location) = self.loan_kill_location(
+                loan_idx,
+                issuing_region,
+                loan_issued_at,
+                block,
+                0,
+                num_stmts,
+            ) {
                 debug!("loan {:?} gets killed at {:?}", loan_idx, kill_location);
                 self.loans_out_of_scope_at_location
                     .entry(kill_location)
@@ -348,11 +357,12 @@ fn precompute_loans_out_of_scope(
     }
 
     /// Returns the lowest statement in `start..=end`, where the loan goes out of scope, if any.
-    /// This is the statement where the issuing region can't reach any of the regions that are live
-    /// at this point.
+    /// This is the statement where the loan doesn't flow into any of the regions that are live at
+    /// this point.
     fn loan_kill_location(
         &self,
         loan_idx: BorrowIndex,
+        issuing_region: RegionVid,
         loan_issued_at: Location,
         block: BasicBlock,
         start: usize,
@@ -368,20 +378,35 @@ fn loan_kill_location(
                 continue;
             }
 
-            // - the loan goes out of scope at `location` if it's not contained within any regions
-            // live at this point.
-            //
-            // FIXME: if the issuing region `i` can reach a live region `r` at point `p`, and `r` is
-            // live at point `q`, then it's guaranteed that `i` would reach `r` at point `q`.
-            // Reachability is location-insensitive, and we could take advantage of that, by jumping
-            // to a further point than just the next statement: we can jump to the furthest point
-            // within the block where `r` is live.
+            // - the loans whose liveness was too costly to compute are in scope wherever their
+            // issuing region is, as with NLLs.
+            if self.regioncx.has_nll_scope(loan_idx) {
+                if self.regioncx.region_contains(issuing_region, location) {
+                    continue;
+                }
+                return Some(location);
+            }
+
+            // - the loan goes out of scope at `location` if it doesn't flow into any regions live
+            // at this point.
             if self.regioncx.is_loan_live_at(loan_idx, location) {
+                // The scopes of the set-of-loans model used to be checked to be equal to the ones
+                // of the set-of-points model of NLLs, when loan liveness was location-insensitive.
+                // They can now be smaller, which is the point of this analysis, e.g. for the NLL
+                // problem case #3. What still holds is that they are contained within the NLL
+                // scopes: the loans only flow into the regions their issuing region outlives, so
+                // they can only be live where their issuing region is. This is a lookup in the
+                // value of the issuing region, so it is checked in all builds.
+                assert!(
+                    self.regioncx.region_contains(issuing_region, location),
+                    "polonius loan {loan_idx:?} is live at {location:?}, outside of its NLL scope, \
+                    for body {:?}",
+                    self.body.span,
+                );
                 continue;
             }
 
-            // No live region is reachable from the issuing region: the loan is killed at this
-            // point.
+            // The loan doesn't reach any live region at this point: the loan is killed here.
             return Some(location);
         }
 
@@ -396,33 +421,26 @@ pub fn new(
         regioncx: &'mir RegionInferenceContext<'tcx>,
         borrow_set: &'mir BorrowSet<'tcx>,
     ) -> Self {
-        let mut borrows_out_of_scope_at_location =
-            calculate_borrows_out_of_scope_at_location(body, regioncx, borrow_set);
-
         // The in-tree polonius analysis computes loans going out of scope using the set-of-loans
-        // model, and makes sure they're identical to the existing computation of the set-of-points
-        // model.
-        if tcx.sess.opts.unstable_opts.polonius.is_next_enabled() {
-            let mut polonius_prec = PoloniusOutOfScopePrecomputer::new(body, regioncx);
-            for (loan_idx, loan_data) in borrow_set.iter_enumerated() {
-                let issuing_region = loan_data.region;
-                let loan_issued_at = loan_data.reserve_location;
-
-                polonius_prec.precompute_loans_out_of_scope(
-                    loan_idx,
-                    issuing_region,
-                    loan_issued_at,
-                );
-            }
-
-            assert_eq!(
-                borrows_out_of_scope_at_location, polonius_prec.loans_out_of_scope_at_location,
-                "polonius loan scopes differ from NLL borrow scopes, for body {:?}",
-                body.span,
-            );
-
-            borrows_out_of_scope_at_location = polonius_prec.loans_out_of_scope_at_location;
-        }
+        // model, from the location-sensitive loan liveness. Its scopes can be smaller than the
+        // ones of the set-of-points model of NLLs, e.g. for the NLL problem case #3.
+        let borrows_out_of_scope_at_location =
+            if tcx.sess.opts.unstable_opts.polonius.is_next_enabled() {
+                let mut polonius_prec = PoloniusOutOfScopePrecomputer::new(body, regioncx);
+                for (loan_idx, loan_data) in borrow_set.iter_enumerated() {
+                    let issuing_region = loan_data.region;
+                    let loan_issued_at = loan_data.reserve_location;
+
+                    polonius_prec.precompute_loans_out_of_scope(
+                        loan_idx,
+                        issuing_region,
+                        loan_issued_at,
+                    );
+                }
+                polonius_prec.loans_out_of_scope_at_location
+            } else {
+                calculate_borrows_out_of_scope_at_location(body, regioncx, borrow_set)
+            };
 
         Borrows { tcx, body, borrow_set, borrows_out_of_scope_at_location }
     }
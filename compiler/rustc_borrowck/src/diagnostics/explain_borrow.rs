@@ -3,6 +3,8 @@
 #![allow(rustc::diagnostic_outside_of_impl)]
 #![allow(rustc::untranslatable_diagnostic)]
 
+use std::io;
+
 use rustc_errors::{Applicability, Diagnostic};
 use rustc_hir as hir;
 use rustc_hir::intravisit::Visitor;
@@ -10,8 +12,8 @@
 use rustc_infer::infer::NllRegionVariableOrigin;
 use rustc_middle::middle::resolve_bound_vars::ObjectLifetimeDefault;
 use rustc_middle::mir::{
-    Body, CallSource, CastKind, ConstraintCategory, FakeReadCause, Local, LocalInfo, Location,
-    Operand, Place, Rvalue, Statement, StatementKind, TerminatorKind,
+    create_dump_file, Body, CallSource, CastKind, ConstraintCategory, FakeReadCause, Local,
+    LocalInfo, Location, Operand, Place, Rvalue, Statement, StatementKind, TerminatorKind,
 };
 use rustc_middle::ty::adjustment::PointerCoercion;
 use rustc_middle::ty::{self, RegionVid, Ty, TyCtxt};
@@ -387,6 +389,48 @@ fn add_lifetime_bound_suggestion_to_diagnostic(
 }
 
 impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
+    /// With `-Zborrowck-explain`, writes out why `from_region` has to outlive the region that
+    /// meets `target_test`, which caused `error`: the path of outlives constraints between the
+    /// two, and the points where the regions along it are live, both as annotated source and as
+    /// a graphviz graph. The files go into `-Zdump-mir-dir`, numbered in the order in which the
+    /// errors are reported.
+    pub(crate) fn dump_borrowck_explanation(
+        &self,
+        error: &str,
+        from_region: RegionVid,
+        target_test: impl Fn(RegionVid) -> bool,
+        error_location: Option<Location>,
+    ) {
+        let tcx = self.infcx.tcx;
+        if !tcx.sess.opts.unstable_opts.borrowck_explain {
+            return;
+        }
+        let Some((path, _)) =
+            self.regioncx.find_constraint_paths_between_regions(from_region, target_test)
+        else {
+            return;
+        };
+        let index = self.next_explanation.replace_with(|index| *index + 1);
+
+        let _: io::Result<()> = try {
+            let mut file =
+                create_dump_file(tcx, "txt", false, "borrowck-explain", &index, self.body)?;
+            self.regioncx.dump_constraint_path(
+                tcx,
+                self.body,
+                error,
+                &path,
+                error_location,
+                &mut file,
+            )?;
+        };
+        let _: io::Result<()> = try {
+            let mut file =
+                create_dump_file(tcx, "dot", false, "borrowck-explain", &index, self.body)?;
+            self.regioncx.dump_graphviz_constraint_path(&path, error_location, &mut file)?;
+        };
+    }
+
     fn free_region_constraint_info(
         &self,
         borrow_region: RegionVid,
@@ -453,6 +497,13 @@ pub(crate) fn explain_why_borrow_contains_point(
             }
         }
 
+        self.dump_borrowck_explanation(
+            &format!("the borrow `{borrow}` is live at {use_location:?}"),
+            borrow_region_vid,
+            |r| r == region_sub,
+            Some(use_location),
+        );
+
         match find_use::find(body, regioncx, tcx, region_sub, use_location) {
             Some(Cause::LiveVar(local, location)) => {
                 let span = body.source_info(location).span;
@@ -368,6 +368,12 @@ pub(crate) fn report_region_errors(&mut self, nll_errors: RegionErrors<'tcx>) {
                     error_element,
                 } => {
                     let error_vid = self.regioncx.region_from_element(longer_fr, &error_element);
+                    self.dump_borrowck_explanation(
+                        &format!("{longer_fr:?} must outlive {error_vid:?}"),
+                        longer_fr,
+                        |r| self.regioncx.provides_universal_region(r, longer_fr, error_vid),
+                        None,
+                    );
 
                     // Find the code to blame for the fact that `longer_fr` outlives `error_fr`.
                     let (_, cause) = self.regioncx.find_outlives_blame_span(
@@ -432,6 +438,12 @@ pub(crate) fn report_region_error(
                 self.regioncx.provides_universal_region(r, fr, outlived_fr)
             });
         let BlameConstraint { category, cause, variance_info, .. } = blame_constraint;
+        self.dump_borrowck_explanation(
+            &format!("{fr:?} must outlive {outlived_fr:?}"),
+            fr,
+            |r| self.regioncx.provides_universal_region(r, fr, outlived_fr),
+            None,
+        );
 
         debug!("report_region_error: category={:?} {:?} {:?}", category, cause, variance_info);
 
@@ -304,6 +304,7 @@ fn do_mir_borrowck<'tcx>(
             local_names: IndexVec::from_elem(None, &promoted_body.local_decls),
             region_names: RefCell::default(),
             next_region_name: RefCell::new(1),
+            next_explanation: RefCell::new(0),
             polonius_output: None,
             move_errors: Vec::new(),
             diags,
@@ -345,6 +346,7 @@ fn visit_operand(&mut self, operand: &Operand<'tcx>, location: Location) {
         local_names,
         region_names: RefCell::default(),
         next_region_name: RefCell::new(1),
+        next_explanation: RefCell::new(0),
         polonius_output,
         move_errors: Vec::new(),
         diags,
@@ -572,6 +574,9 @@ struct MirBorrowckCtxt<'cx, 'tcx> {
     /// The counter for generating new region names.
     next_region_name: RefCell<usize>,
 
+    /// The counter for numbering the files written with `-Zborrowck-explain`.
+    next_explanation: RefCell<usize>,
+
     /// Results of Polonius analysis.
     polonius_output: Option<Rc<PoloniusOutput>>,
 
@@ -158,6 +158,12 @@ pub(crate) fn compute_regions<'cx, 'tcx>(
         elements,
     );
 
+    // With `-Zpolonius=next`, compute the location-sensitive liveness of loans, now that the
+    // liveness of all regions is known.
+    if infcx.tcx.sess.opts.unstable_opts.polonius.is_next_enabled() {
+        regioncx.compute_live_loans(body, elements, borrow_set);
+    }
+
     // If requested: dump NLL facts, and run legacy polonius analysis.
     let polonius_output = all_facts.as_ref().and_then(|all_facts| {
         if infcx.tcx.sess.opts.unstable_opts.nll_facts {
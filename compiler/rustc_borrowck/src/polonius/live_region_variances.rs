@@ -0,0 +1,116 @@
+//! The variance of the live regions, used by `-Zpolonius=next` to know in which direction loans
+//! flow through a region along the CFG, see `loan_liveness`.
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_middle::ty::relate::{self, Relate, RelateResult, TypeRelation};
+use rustc_middle::ty::{self, RegionVid, Ty, TyCtxt, TypeVisitableExt};
+
+use crate::universal_regions::UniversalRegions;
+
+/// Records the variance of the regions of `value` in `live_region_variances`. `value` is the type
+/// of a live local, or a part of it its destructor can access. A region appearing with different
+/// variances in the live values is recorded as invariant.
+pub(crate) fn record_live_region_variances<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    universal_regions: &UniversalRegions<'tcx>,
+    live_region_variances: &mut FxHashMap<RegionVid, ty::Variance>,
+    value: impl Relate<'tcx>,
+) {
+    let mut extractor = VarianceExtractor {
+        tcx,
+        universal_regions,
+        ambient_variance: ty::Variance::Covariant,
+        live_region_variances,
+    };
+    extractor.relate(value, value).expect("Can't have a type error relating to itself");
+}
+
+/// Relates a value to itself, to find the variance of each of its regions: the ambient variance
+/// when the region is reached.
+struct VarianceExtractor<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    universal_regions: &'a UniversalRegions<'tcx>,
+    ambient_variance: ty::Variance,
+    live_region_variances: &'a mut FxHashMap<RegionVid, ty::Variance>,
+}
+
+impl<'tcx> TypeRelation<'tcx> for VarianceExtractor<'_, 'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn tag(&self) -> &'static str {
+        "VarianceExtractor"
+    }
+
+    fn a_is_expected(&self) -> bool {
+        true
+    }
+
+    fn relate_with_variance<T: Relate<'tcx>>(
+        &mut self,
+        variance: ty::Variance,
+        _info: ty::VarianceDiagInfo<'tcx>,
+        a: T,
+        b: T,
+    ) -> RelateResult<'tcx, T> {
+        let old_ambient_variance = self.ambient_variance;
+        self.ambient_variance = self.ambient_variance.xform(variance);
+        let r = self.relate(a, b);
+        self.ambient_variance = old_ambient_variance;
+        r
+    }
+
+    fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
+        assert_eq!(a, b);
+        if !a.has_free_regions() {
+            return Ok(a);
+        }
+        relate::structurally_relate_tys(self, a, b)
+    }
+
+    fn regions(
+        &mut self,
+        a: ty::Region<'tcx>,
+        b: ty::Region<'tcx>,
+    ) -> RelateResult<'tcx, ty::Region<'tcx>> {
+        assert_eq!(a, b);
+        // The regions bound in the value are not live on their own.
+        if a.is_bound() || self.ambient_variance == ty::Variance::Bivariant {
+            return Ok(a);
+        }
+
+        let region = self.universal_regions.to_region_vid(a);
+        let ambient_variance = self.ambient_variance;
+        self.live_region_variances
+            .entry(region)
+            .and_modify(|variance| {
+                if *variance != ambient_variance {
+                    *variance = ty::Variance::Invariant;
+                }
+            })
+            .or_insert(ambient_variance);
+        Ok(a)
+    }
+
+    fn consts(
+        &mut self,
+        a: ty::Const<'tcx>,
+        b: ty::Const<'tcx>,
+    ) -> RelateResult<'tcx, ty::Const<'tcx>> {
+        assert_eq!(a, b);
+        relate::structurally_relate_consts(self, a, b)
+    }
+
+    fn binders<T>(
+        &mut self,
+        a: ty::Binder<'tcx, T>,
+        _b: ty::Binder<'tcx, T>,
+    ) -> RelateResult<'tcx, ty::Binder<'tcx, T>>
+    where
+        T: Relate<'tcx>,
+    {
+        self.relate(a.skip_binder(), a.skip_binder())?;
+        Ok(a)
+    }
+}
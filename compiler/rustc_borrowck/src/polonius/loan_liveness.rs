@@ -0,0 +1,265 @@
+//! The location-sensitive loan liveness computed with `-Zpolonius=next`.
+//!
+//! With NLLs, a loan is in scope wherever its issuing region is, and the value of a region is the
+//! union of the points where the regions it outlives are live, wherever the outlives constraints
+//! between them arise. That is why NLLs reject the "problem case #3" of the NLL RFC:
+//!
+//! ```text
+//! fn get_default<'r>(map: &'r mut HashMap<K, V>, key: K) -> &'r mut V {
+//!     match map.get_mut(&key) {
+//!         Some(value) => value,
+//!         None => {
+//!             map.insert(key, V::default());
+//!             map.get_mut(&key).unwrap()
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! The loan of `*map` flows into `'r` in the `Some` arm only, yet `'r` is live everywhere, so the
+//! loan is considered live in the `None` arm as well.
+//!
+//! Here, loans flow through a graph whose nodes are regions at a given point instead:
+//! - an outlives constraint `'a: 'b` arising at point `p` is an edge from `('a, p)` to `('b, p)`,
+//!   and a constraint holding at all points is such an edge at every point,
+//! - a region live at `p` and at its successor `q` in the CFG is an edge in the direction of its
+//!   variance in the types of the live locals: from `('a, p)` to `('a, q)` if it is covariant,
+//!   from `('a, q)` to `('a, p)` if it is contravariant, and both if it is invariant. A region that
+//!   is covariant and only becomes live at `q` is also an edge from `('a, p)` to `('a, q)`.
+//!
+//! A loan is then live at the points where it reaches a live region, and at the points where it
+//! crosses a CFG edge on its way to one. The scopes of the loans are computed from it in
+//! `dataflow.rs`.
+//!
+//! The traversal of a loan only reaches the regions its issuing region outlives, at the points
+//! where one of them is live, or where it is issued: these are all points of the issuing region in
+//! NLLs. So the scope of a loan is always contained within its NLL scope, and its traversal is
+//! bounded by the size of the NLL scope times the number of regions. As the latter can be large,
+//! a loan whose traversal visits more nodes than a fixed number per point of the body gets its NLL
+//! scope instead: like computing its NLL scope, the cost of each loan is then at most linear in the
+//! size of the body.
+//!
+//! Regions that are live at all points, and the ones outliving them at all points, are not
+//! followed: a loan reaching one of them at a point escapes the function from there, and is live at
+//! all the points that follow. This bounds the cost of the loans escaping via the return place,
+//! and the liveness of the locals whose types only contain such regions is not computed.
+
+use itertools::Either;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_index::bit_set::BitSet;
+use rustc_index::IndexVec;
+use rustc_middle::mir::{Body, Location};
+use rustc_middle::ty::{self, RegionVid};
+use rustc_mir_dataflow::points::{DenseLocationMap, PointIndex};
+
+use crate::borrow_set::BorrowSet;
+use crate::constraints::OutlivesConstraintSet;
+use crate::region_infer::values::{LiveLoans, LivenessValues};
+use crate::type_check::Locations;
+
+/// The number of nodes per point of the body that the traversal of a loan may visit, before the
+/// loan falls back to its NLL scope.
+const VISITED_NODES_PER_POINT: usize = 16;
+
+/// Computes the points where each loan of `borrow_set` is live, by following the outlives
+/// constraints only at the points where they arise, and the regions only where they are live, in
+/// the direction of their variance.
+///
+/// The liveness of every region must already be known, except for the regions live at all points
+/// and the ones outliving them at all points: a loan reaching them at a point escapes the function
+/// there, and is live at every point reachable from it. Loans issued into such regions are only
+/// recorded as escaping.
+pub(crate) fn compute_loan_liveness<'tcx>(
+    body: &Body<'tcx>,
+    elements: &DenseLocationMap,
+    liveness: &LivenessValues,
+    outlives_constraints: &OutlivesConstraintSet<'tcx>,
+    borrow_set: &BorrowSet<'tcx>,
+    num_region_vars: usize,
+    is_live_at_all_points: impl Fn(RegionVid) -> bool,
+) -> LiveLoans {
+    // Index the constraints by their `sup` region: the ones that hold at all points, and the ones
+    // that only hold at a single point.
+    let mut constraints_everywhere: IndexVec<RegionVid, Vec<RegionVid>> =
+        IndexVec::from_elem_n(Vec::new(), num_region_vars);
+    let mut constraints_at_point: FxHashMap<(RegionVid, PointIndex), Vec<RegionVid>> =
+        FxHashMap::default();
+    for constraint in outlives_constraints.outlives() {
+        match constraint.locations {
+            Locations::All(_) => constraints_everywhere[constraint.sup].push(constraint.sub),
+            Locations::Single(location) => {
+                let point = elements.point_from_location(location);
+                let at_point = constraints_at_point.entry((constraint.sup, point)).or_default();
+                at_point.push(constraint.sub);
+            }
+        }
+    }
+
+    let escaping_regions =
+        compute_escaping_regions(&constraints_everywhere, num_region_vars, is_live_at_all_points);
+
+    let visit_budget = VISITED_NODES_PER_POINT * elements.num_points();
+
+    // The traversal state is reused for all the loans.
+    let mut live_loans = LiveLoans::new(borrow_set.len());
+    let mut visited = FxHashSet::default();
+    let mut escaped = BitSet::new_empty(elements.num_points());
+    let mut stack = Vec::new();
+    let mut escaped_stack = Vec::new();
+    for (loan, loan_data) in borrow_set.iter_enumerated() {
+        let issued_at = elements.point_from_location(loan_data.reserve_location);
+
+        // A loan is always live where it is issued.
+        live_loans.live_loans.insert(issued_at, loan);
+
+        // A loan issued into an escaping region never goes out of scope.
+        if escaping_regions.contains(loan_data.region) {
+            live_loans.escaping_loans.insert(loan);
+            continue;
+        }
+
+        visited.clear();
+        escaped.clear();
+        visited.insert((loan_data.region, issued_at));
+        stack.push((loan_data.region, issued_at));
+
+        while let Some((region, point)) = stack.pop() {
+            // The traversal is too costly, the loan gets the scope of its issuing region instead,
+            // which contains the one we would have computed.
+            if visited.len() > visit_budget {
+                stack.clear();
+                live_loans.loans_with_nll_scope.insert(loan);
+                break;
+            }
+
+            // The loan is already live at this point and all the ones following it.
+            if escaped.contains(point) {
+                continue;
+            }
+
+            // The loan escapes the function here: it is live at all the points reachable from
+            // this one, and there is no need to follow the constraints further.
+            if escaping_regions.contains(region) {
+                escaped_stack.push(point);
+                while let Some(point) = escaped_stack.pop() {
+                    if escaped.insert(point) {
+                        live_loans.live_loans.insert(point, loan);
+                        escaped_stack.extend(successors(body, elements, point));
+                    }
+                }
+                continue;
+            }
+
+            if liveness.is_live_at_point(region, point) {
+                live_loans.live_loans.insert(point, loan);
+            }
+
+            // Follow the outlives constraints that hold at this point.
+            let at_point = constraints_at_point.get(&(region, point)).into_iter().flatten();
+            for &sub in constraints_everywhere[region].iter().chain(at_point) {
+                if visited.insert((sub, point)) {
+                    stack.push((sub, point));
+                }
+            }
+
+            // Follow the CFG as long as the region is live, forward if the region is covariant
+            // and backward if it is contravariant.
+            let variance = liveness
+                .live_region_variances
+                .as_ref()
+                .and_then(|variances| variances.get(&region).copied())
+                .unwrap_or(ty::Variance::Covariant);
+            if matches!(variance, ty::Variance::Covariant | ty::Variance::Invariant) {
+                for successor in successors(body, elements, point) {
+                    if !liveness.is_live_at_point(region, successor) {
+                        continue;
+                    }
+                    live_loans.live_loans.insert(point, loan);
+                    if visited.insert((region, successor)) {
+                        stack.push((region, successor));
+                    }
+                }
+            }
+            if matches!(variance, ty::Variance::Contravariant | ty::Variance::Invariant)
+                && liveness.is_live_at_point(region, point)
+            {
+                for predecessor in predecessors(body, elements, point) {
+                    if liveness.is_live_at_point(region, predecessor)
+                        && visited.insert((region, predecessor))
+                    {
+                        stack.push((region, predecessor));
+                    }
+                }
+            }
+        }
+    }
+
+    live_loans
+}
+
+/// Computes the regions that outlive a region live at all points, at all points: the regions live
+/// at all points themselves, and the ones reaching them via constraints holding at all points.
+fn compute_escaping_regions(
+    constraints_everywhere: &IndexVec<RegionVid, Vec<RegionVid>>,
+    num_region_vars: usize,
+    is_live_at_all_points: impl Fn(RegionVid) -> bool,
+) -> BitSet<RegionVid> {
+    let mut outlived_by: IndexVec<RegionVid, Vec<RegionVid>> =
+        IndexVec::from_elem_n(Vec::new(), num_region_vars);
+    for (sup, subs) in constraints_everywhere.iter_enumerated() {
+        for &sub in subs {
+            outlived_by[sub].push(sup);
+        }
+    }
+
+    let mut escaping_regions = BitSet::new_empty(num_region_vars);
+    let mut stack: Vec<_> =
+        constraints_everywhere.indices().filter(|&region| is_live_at_all_points(region)).collect();
+    for &region in &stack {
+        escaping_regions.insert(region);
+    }
+    while let Some(region) = stack.pop() {
+        for &sup in &outlived_by[region] {
+            if escaping_regions.insert(sup) {
+                stack.push(sup);
+            }
+        }
+    }
+    escaping_regions
+}
+
+/// The successors of `point` in the CFG: the next statement in the same block, or the entry points
+/// of the successor blocks for a terminator.
+fn successors<'a>(
+    body: &'a Body<'_>,
+    elements: &'a DenseLocationMap,
+    point: PointIndex,
+) -> impl Iterator<Item = PointIndex> + 'a {
+    let location = elements.to_location(point);
+    let block_data = &body.basic_blocks[location.block];
+    if location.statement_index < block_data.statements.len() {
+        Either::Left(std::iter::once(point + 1))
+    } else {
+        Either::Right(block_data.terminator().successors().map(|block| elements.entry_point(block)))
+    }
+}
+
+/// The predecessors of `point` in the CFG: the previous statement in the same block, or the
+/// terminators of the predecessor blocks for the entry point of a block.
+fn predecessors<'a>(
+    body: &'a Body<'_>,
+    elements: &'a DenseLocationMap,
+    point: PointIndex,
+) -> impl Iterator<Item = PointIndex> + 'a {
+    let location = elements.to_location(point);
+    if location.statement_index > 0 {
+        let previous = Location { statement_index: location.statement_index - 1, ..location };
+        Either::Left(std::iter::once(elements.point_from_location(previous)))
+    } else {
+        Either::Right(
+            body.basic_blocks.predecessors()[location.block]
+                .iter()
+                .map(|&block| elements.point_from_location(body.terminator_loc(block))),
+        )
+    }
+}
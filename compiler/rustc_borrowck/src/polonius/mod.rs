@@ -1,7 +1,7 @@
 //! Functions dedicated to fact generation for the `-Zpolonius=legacy` datalog implementation.
 //!
 //! Will be removed in the future, once the in-tree `-Zpolonius=next` implementation reaches feature
-//! parity.
+//! parity. The loan liveness of the latter is computed in `loan_liveness`.
 
 use rustc_middle::mir::{Body, LocalKind, Location, START_BLOCK};
 use rustc_middle::ty::TyCtxt;
@@ -14,7 +14,12 @@
 use crate::universal_regions::UniversalRegions;
 
 mod loan_invalidations;
+mod live_region_variances;
 mod loan_kills;
+mod loan_liveness;
+
+pub(crate) use live_region_variances::record_live_region_variances;
+pub(crate) use loan_liveness::compute_loan_liveness;
 
 /// When requested, emit most of the facts needed by polonius:
 /// - moves and assignments
@@ -1,12 +1,15 @@
 //! As part of generating the regions, if you enable `-Zdump-mir=nll`,
 //! we will generate an annotated copy of the MIR that includes the
 //! state of region inference. This code handles emitting the region
-//! context internal state.
+//! context internal state, as well as the constraint paths written out
+//! for errors with `-Zborrowck-explain`.
 
 use super::{OutlivesConstraint, RegionInferenceContext};
 use crate::type_check::Locations;
 use rustc_infer::infer::NllRegionVariableOrigin;
-use rustc_middle::ty::TyCtxt;
+use rustc_middle::mir::{Body, Location};
+use rustc_middle::ty::{RegionVid, TyCtxt};
+use rustc_span::Span;
 use std::io::{self, Write};
 
 // Room for "'_#NNNNr" before things get misaligned.
@@ -55,6 +58,60 @@ pub(crate) fn dump_mir(&self, tcx: TyCtxt<'tcx>, out: &mut dyn Write) -> io::Res
         Ok(())
     }
 
+    /// Write out the outlives constraints along `path`, which led to `error`, with the source
+    /// that each of them comes from, followed by the points where the regions along the path
+    /// are live. `error_location` is the point of the error, if it has one.
+    pub(crate) fn dump_constraint_path(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        body: &Body<'tcx>,
+        error: &str,
+        path: &[OutlivesConstraint<'tcx>],
+        error_location: Option<Location>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        writeln!(out, "// {error}")?;
+        writeln!(out, "// in `{}`", tcx.def_path_str(body.source.def_id()))?;
+        if let Some(location) = error_location {
+            writeln!(out)?;
+            writeln!(out, "Error at {location:?}")?;
+            write_annotated_span(tcx, body.source_info(location).span, "the error is here", out)?;
+        }
+
+        writeln!(out)?;
+        writeln!(out, "Outlives Constraints")?;
+        for (i, constraint) in path.iter().enumerate() {
+            let OutlivesConstraint { sup, sub, locations, category, span, .. } = constraint;
+            writeln!(out)?;
+            writeln!(out, "{i}: {sup:?}: {sub:?} due to {category:?} at {locations:?}")?;
+            write_annotated_span(tcx, *span, &format!("{sup:?}: {sub:?}"), out)?;
+        }
+
+        writeln!(out)?;
+        writeln!(out, "Liveness")?;
+        for region in regions_along(path) {
+            writeln!(out)?;
+            if self.universal_regions.is_universal_region(region) {
+                writeln!(out, "{region:?} is a universal region, live at all points")?;
+                continue;
+            }
+            let points = self.liveness_constraints.pretty_print_live_points(region);
+            writeln!(out, "{region:?} live at {points}")?;
+            let mut spans: Vec<Span> = self
+                .liveness_constraints
+                .live_locations(region)
+                .map(|location| body.source_info(location).span)
+                .collect();
+            spans.sort();
+            spans.dedup();
+            for span in spans {
+                write_annotated_span(tcx, span, &format!("{region:?} is live here"), out)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Debugging aid: Invokes the `with_msg` callback repeatedly with
     /// our internal region constraints. These are dumped into the
     /// -Zdump-mir file so that we can figure out why the region
@@ -87,3 +144,44 @@ fn for_each_constraint(
         Ok(())
     }
 }
+
+/// The regions along a constraint path, in the order in which the path visits them.
+pub(super) fn regions_along(path: &[OutlivesConstraint<'_>]) -> Vec<RegionVid> {
+    let mut regions: Vec<RegionVid> = path.first().map(|c| c.sup).into_iter().collect();
+    for constraint in path {
+        if !regions.contains(&constraint.sub) {
+            regions.push(constraint.sub);
+        }
+    }
+    regions
+}
+
+/// Write out the source line that `span` starts on, and underline `span` with `label`.
+fn write_annotated_span(
+    tcx: TyCtxt<'_>,
+    span: Span,
+    label: &str,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    if span.is_dummy() {
+        return writeln!(out, "  --> (no source) {label}");
+    }
+    let source_map = tcx.sess.source_map();
+    writeln!(out, "  --> {}", source_map.span_to_embeddable_string(span))?;
+    let lo = source_map.lookup_char_pos(span.lo());
+    let hi = source_map.lookup_char_pos(span.hi());
+    let Some(line) = lo.file.get_line(lo.line - 1) else {
+        return Ok(());
+    };
+    // Spans over several lines are underlined up to the end of their first line.
+    let end = if hi.line == lo.line { hi.col_display } else { line.chars().count() };
+    let gutter = " ".repeat(lo.line.to_string().len());
+    writeln!(out, "{gutter} |")?;
+    writeln!(out, "{} | {line}", lo.line)?;
+    writeln!(
+        out,
+        "{gutter} | {}{} {label}",
+        " ".repeat(lo.col_display),
+        "^".repeat(end.saturating_sub(lo.col_display).max(1)),
+    )
+}
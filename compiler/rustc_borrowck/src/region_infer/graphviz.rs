@@ -26,6 +26,23 @@ pub(crate) fn dump_graphviz_scc_constraints(&self, mut w: &mut dyn Write) -> io:
 
         dot::render(&SccConstraints { regioncx: self, nodes_per_scc }, &mut w)
     }
+
+    /// Write out the constraint `path` behind an error, for `-Zborrowck-explain`. The regions
+    /// that are live at `error_location` are drawn in bold.
+    pub(crate) fn dump_graphviz_constraint_path(
+        &self,
+        path: &[OutlivesConstraint<'tcx>],
+        error_location: Option<Location>,
+        mut w: &mut dyn Write,
+    ) -> io::Result<()> {
+        let regions = dump_mir::regions_along(path);
+        let live_at_error = regions
+            .iter()
+            .copied()
+            .filter(|&r| error_location.is_some_and(|l| self.liveness_constraints.is_live_at(r, l)))
+            .collect();
+        dot::render(&ConstraintPath { regioncx: self, path, regions, live_at_error }, &mut w)
+    }
 }
 
 struct RawConstraints<'a, 'tcx> {
@@ -137,3 +154,62 @@ fn target(&'this self, edge: &(ConstraintSccIndex, ConstraintSccIndex)) -> Const
         edge.1
     }
 }
+
+struct ConstraintPath<'a, 'tcx> {
+    regioncx: &'a RegionInferenceContext<'tcx>,
+    path: &'a [OutlivesConstraint<'tcx>],
+    regions: Vec<RegionVid>,
+    live_at_error: Vec<RegionVid>,
+}
+
+impl<'a, 'this, 'tcx> dot::Labeller<'this> for ConstraintPath<'a, 'tcx> {
+    type Node = RegionVid;
+    type Edge = OutlivesConstraint<'tcx>;
+
+    fn graph_id(&'this self) -> dot::Id<'this> {
+        dot::Id::new("ConstraintPath").unwrap()
+    }
+    fn node_id(&'this self, n: &RegionVid) -> dot::Id<'this> {
+        dot::Id::new(format!("r{}", n.index())).unwrap()
+    }
+    fn node_shape(&'this self, _node: &RegionVid) -> Option<dot::LabelText<'this>> {
+        Some(dot::LabelText::LabelStr(Cow::Borrowed("box")))
+    }
+    fn node_label(&'this self, n: &RegionVid) -> dot::LabelText<'this> {
+        let label = if self.regioncx.universal_regions.is_universal_region(*n) {
+            format!("{n:?} (universal)")
+        } else {
+            let points = self.regioncx.liveness_constraints.pretty_print_live_points(*n);
+            format!("{n:?}\nlive at {points}")
+        };
+        dot::LabelText::LabelStr(label.into())
+    }
+    fn node_style(&'this self, n: &RegionVid) -> dot::Style {
+        if self.live_at_error.contains(n) { dot::Style::Bold } else { dot::Style::None }
+    }
+    fn edge_label(&'this self, e: &OutlivesConstraint<'tcx>) -> dot::LabelText<'this> {
+        dot::LabelText::LabelStr(format!("{:?}\n{:?}", e.category, e.locations).into())
+    }
+}
+
+impl<'a, 'this, 'tcx> dot::GraphWalk<'this> for ConstraintPath<'a, 'tcx> {
+    type Node = RegionVid;
+    type Edge = OutlivesConstraint<'tcx>;
+
+    fn nodes(&'this self) -> dot::Nodes<'this, RegionVid> {
+        (&self.regions[..]).into()
+    }
+    fn edges(&'this self) -> dot::Edges<'this, OutlivesConstraint<'tcx>> {
+        self.path.into()
+    }
+
+    // Render `a: b` as `a -> b`, like the full constraint graph.
+
+    fn source(&'this self, edge: &OutlivesConstraint<'tcx>) -> RegionVid {
+        edge.sup
+    }
+
+    fn target(&'this self, edge: &OutlivesConstraint<'tcx>) -> RegionVid {
+        edge.sub
+    }
+}
@@ -22,6 +22,7 @@
 use rustc_mir_dataflow::points::DenseLocationMap;
 use rustc_span::Span;
 
+use crate::borrow_set::BorrowSet;
 use crate::constraints::graph::{self, NormalConstraintGraph, RegionGraph};
 use crate::dataflow::BorrowIndex;
 use crate::{
@@ -29,6 +30,7 @@
     diagnostics::{RegionErrorKind, RegionErrors, UniverseInfo},
     member_constraints::{MemberConstraintSet, NllMemberConstraintIndex},
     nll::PoloniusOutput,
+    polonius,
     region_infer::reverse_sccs::ReverseSccGraph,
     region_infer::values::{
         LivenessValues, PlaceholderIndices, RegionElement, RegionValues, ToElementIndex,
@@ -2264,13 +2266,47 @@ pub(crate) fn is_region_live_at_all_points(&self, region: RegionVid) -> bool {
         live_at_all_points
     }
 
+    /// When using `-Zpolonius=next`, computes the points where each loan is live, following the
+    /// outlives constraints only where they hold. See `polonius::loan_liveness`.
+    pub(crate) fn compute_live_loans(
+        &mut self,
+        body: &Body<'tcx>,
+        elements: &DenseLocationMap,
+        borrow_set: &BorrowSet<'tcx>,
+    ) {
+        let live_loans = polonius::compute_loan_liveness(
+            body,
+            elements,
+            &self.liveness_constraints,
+            &self.constraints,
+            borrow_set,
+            self.definitions.len(),
+            |region| self.is_region_live_at_all_points(region),
+        );
+        self.liveness_constraints.loans = Some(live_loans);
+    }
+
     /// Returns whether the `loan_idx` is live at the given `location`: whether its issuing
-    /// region is contained within the type of a variable that is live at this point.
+    /// region flows, at this point, into the type of a variable that is live here.
     /// Note: for now, the sets of live loans is only available when using `-Zpolonius=next`.
     pub(crate) fn is_loan_live_at(&self, loan_idx: BorrowIndex, location: Location) -> bool {
         let point = self.liveness_constraints.point_from_location(location);
         self.liveness_constraints.is_loan_live_at(loan_idx, point)
     }
+
+    /// Returns whether the `loan_idx` escapes the function where it is issued: whether its
+    /// issuing region outlives a region live at all points, at all points.
+    /// Note: for now, this is only available when using `-Zpolonius=next`.
+    pub(crate) fn is_loan_escaping(&self, loan_idx: BorrowIndex) -> bool {
+        self.liveness_constraints.is_loan_escaping(loan_idx)
+    }
+
+    /// Returns whether the `loan_idx` is in scope wherever its issuing region is, as with NLLs,
+    /// because its location-sensitive liveness was too costly to compute.
+    /// Note: for now, this is only available when using `-Zpolonius=next`.
+    pub(crate) fn has_nll_scope(&self, loan_idx: BorrowIndex) -> bool {
+        self.liveness_constraints.has_nll_scope(loan_idx)
+    }
 }
 
 impl<'tcx> RegionDefinition<'tcx> {
@@ -1,6 +1,6 @@
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::fx::FxIndexSet;
-use rustc_index::bit_set::SparseBitMatrix;
+use rustc_index::bit_set::{BitSet, SparseBitMatrix};
 use rustc_index::interval::IntervalSet;
 use rustc_index::interval::SparseIntervalMatrix;
 use rustc_index::Idx;
@@ -50,27 +50,36 @@ pub(crate) struct LivenessValues {
     /// region is live, only that it is.
     points: Option<SparseIntervalMatrix<RegionVid, PointIndex>>,
 
-    /// When using `-Zpolonius=next`, for each point: the loans flowing into the live regions at
-    /// that point.
+    /// When using `-Zpolonius=next`, the variance of the regions in the types of the live locals,
+    /// which is the direction loans flow through them along the CFG, see `polonius::loan_liveness`.
+    /// The other live regions are covariant.
+    pub(crate) live_region_variances: Option<FxHashMap<RegionVid, ty::Variance>>,
+
+    /// When using `-Zpolonius=next`, for each point: the loans that are live at that point, see
+    /// `polonius::loan_liveness`.
     pub(crate) loans: Option<LiveLoans>,
 }
 
-/// Data used to compute the loans that are live at a given point in the CFG, when using
-/// `-Zpolonius=next`.
+/// The loans that are live at a given point in the CFG, when using `-Zpolonius=next`.
 pub(crate) struct LiveLoans {
-    /// The set of loans that flow into a given region. When individual regions are marked as live
-    /// in the CFG, these inflowing loans are recorded as live.
-    pub(crate) inflowing_loans: SparseBitMatrix<RegionVid, BorrowIndex>,
-
     /// The set of loans that are live at a given point in the CFG.
     pub(crate) live_loans: SparseBitMatrix<PointIndex, BorrowIndex>,
+
+    /// The loans whose issuing region outlives a region live at all points, at all points: they
+    /// never go out of scope, and their live points are not recorded past where they are issued.
+    pub(crate) escaping_loans: BitSet<BorrowIndex>,
+
+    /// The loans whose liveness was too costly to compute: they are in scope wherever their issuing
+    /// region is, as with NLLs.
+    pub(crate) loans_with_nll_scope: BitSet<BorrowIndex>,
 }
 
 impl LiveLoans {
     pub(crate) fn new(num_loans: usize) -> Self {
         LiveLoans {
             live_loans: SparseBitMatrix::new(num_loans),
-            inflowing_loans: SparseBitMatrix::new(num_loans),
+            escaping_loans: BitSet::new_empty(num_loans),
+            loans_with_nll_scope: BitSet::new_empty(num_loans),
         }
     }
 }
@@ -82,6 +91,7 @@ pub(crate) fn with_specific_points(elements: Rc<DenseLocationMap>) -> Self {
             live_regions: None,
             points: Some(SparseIntervalMatrix::new(elements.num_points())),
             elements,
+            live_region_variances: None,
             loans: None,
         }
     }
@@ -95,6 +105,7 @@ pub(crate) fn without_specific_points(elements: Rc<DenseLocationMap>) -> Self {
             live_regions: Some(Default::default()),
             points: None,
             elements,
+            live_region_variances: None,
             loans: None,
         }
     }
@@ -123,13 +134,6 @@ pub(crate) fn add_location(&mut self, region: RegionVid, location: Location) {
                 self.live_regions.as_mut().unwrap().insert(region);
             }
         }
-
-        // When available, record the loans flowing into this region as live at the given point.
-        if let Some(loans) = self.loans.as_mut() {
-            if let Some(inflowing) = loans.inflowing_loans.row(region) {
-                loans.live_loans.union_row(point, inflowing);
-            }
-        }
     }
 
     /// Records `region` as being live at all the given `points`.
@@ -142,17 +146,6 @@ pub(crate) fn add_points(&mut self, region: RegionVid, points: &IntervalSet<Poin
                 self.live_regions.as_mut().unwrap().insert(region);
             }
         }
-
-        // When available, record the loans flowing into this region as live at the given points.
-        if let Some(loans) = self.loans.as_mut() {
-            if let Some(inflowing) = loans.inflowing_loans.row(region) {
-                if !inflowing.is_empty() {
-                    for point in points.iter() {
-                        loans.live_loans.union_row(point, inflowing);
-                    }
-                }
-            }
-        }
     }
 
     /// Records `region` as being live at all the control-flow points.
@@ -166,7 +159,11 @@ pub(crate) fn add_all_points(&mut self, region: RegionVid) {
 
     /// Returns whether `region` is marked live at the given `location`.
     pub(crate) fn is_live_at(&self, region: RegionVid, location: Location) -> bool {
-        let point = self.elements.point_from_location(location);
+        self.is_live_at_point(region, self.elements.point_from_location(location))
+    }
+
+    /// Returns whether `region` is marked live at the given `point`.
+    pub(crate) fn is_live_at_point(&self, region: RegionVid, point: PointIndex) -> bool {
         if let Some(points) = &self.points {
             points.row(region).is_some_and(|r| r.contains(point))
         } else {
@@ -190,12 +187,15 @@ fn live_points(&self, region: RegionVid) -> impl Iterator<Item = PointIndex> + '
             .take_while(|&p| self.elements.point_in_range(p))
     }
 
+    /// Returns an iterator of all the locations where `region` is live.
+    pub(crate) fn live_locations(&self, region: RegionVid) -> impl Iterator<Item = Location> + '_ {
+        self.live_points(region).map(|p| self.elements.to_location(p))
+    }
+
     /// For debugging purposes, returns a pretty-printed string of the points where the `region` is
     /// live.
     pub(crate) fn pretty_print_live_points(&self, region: RegionVid) -> String {
-        pretty_print_region_elements(
-            self.live_points(region).map(|p| RegionElement::Location(self.elements.to_location(p))),
-        )
+        pretty_print_region_elements(self.live_locations(region).map(RegionElement::Location))
     }
 
     #[inline]
@@ -211,6 +211,25 @@ pub(crate) fn is_loan_live_at(&self, loan_idx: BorrowIndex, point: PointIndex) -
             .live_loans
             .contains(point, loan_idx)
     }
+
+    /// When using `-Zpolonius=next`, returns whether the `loan_idx` escapes the function.
+    pub(crate) fn is_loan_escaping(&self, loan_idx: BorrowIndex) -> bool {
+        self.loans
+            .as_ref()
+            .expect("Accessing live loans requires `-Zpolonius=next`")
+            .escaping_loans
+            .contains(loan_idx)
+    }
+
+    /// When using `-Zpolonius=next`, returns whether the `loan_idx` is in scope wherever its
+    /// issuing region is.
+    pub(crate) fn has_nll_scope(&self, loan_idx: BorrowIndex) -> bool {
+        self.loans
+            .as_ref()
+            .expect("Accessing live loans requires `-Zpolonius=next`")
+            .loans_with_nll_scope
+            .contains(loan_idx)
+    }
 }
 
 /// Maps from `ty::PlaceholderRegion` values that are used in the rest of
@@ -1,5 +1,5 @@
 use itertools::{Either, Itertools};
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_middle::mir::visit::{TyContext, Visitor};
 use rustc_middle::mir::{Body, Local, Location, SourceInfo};
 use rustc_middle::ty::visit::TypeVisitable;
@@ -18,7 +18,7 @@
     universal_regions::UniversalRegions,
 };
 
-use super::TypeChecker;
+use super::{Locations, TypeChecker};
 
 mod local_use_map;
 mod polonius;
@@ -47,11 +47,19 @@ pub(super) fn generate<'mir, 'tcx>(
         typeck.infcx.num_region_vars(),
         typeck.borrowck_context.universal_regions,
         &typeck.borrowck_context.constraints.outlives_constraints,
+        typeck.tcx().sess.opts.unstable_opts.polonius.is_next_enabled(),
     );
     let (relevant_live_locals, boring_locals) =
         compute_relevant_live_locals(typeck.tcx(), &free_regions, body);
     let facts_enabled = use_polonius || AllFacts::enabled(typeck.tcx());
 
+    // With `-Zpolonius=next`, record the variance of the live regions, see
+    // `polonius::loan_liveness`.
+    if typeck.tcx().sess.opts.unstable_opts.polonius.is_next_enabled() {
+        typeck.borrowck_context.constraints.liveness_constraints.live_region_variances =
+            Some(FxHashMap::default());
+    }
+
     let polonius_drop_used = facts_enabled.then(|| {
         let mut drop_used = Vec::new();
         polonius::populate_access_facts(typeck, body, location_table, move_data, &mut drop_used);
@@ -108,10 +116,15 @@ fn compute_relevant_live_locals<'tcx>(
 /// regions. For these regions, we do not need to compute
 /// liveness, since the outlives constraints will ensure that they
 /// are live over the whole fn body anyhow.
+///
+/// With `-Zpolonius=next`, loans only flow through the constraints at the points where they hold,
+/// so only the constraints holding at all points are followed: the regions outliving free regions
+/// at a single point still need liveness.
 fn regions_that_outlive_free_regions<'tcx>(
     num_region_vars: usize,
     universal_regions: &UniversalRegions<'tcx>,
     constraint_set: &OutlivesConstraintSet<'tcx>,
+    location_sensitive: bool,
 ) -> FxHashSet<RegionVid> {
     // Build a graph of the outlives constraints thus far. This is
     // a reverse graph, so for each constraint `R1: R2` we have an
@@ -120,7 +133,6 @@ fn regions_that_outlive_free_regions<'tcx>(
     // regions that are forced to outlive some free region.
     let rev_constraint_graph = constraint_set.reverse_graph(num_region_vars);
     let fr_static = universal_regions.fr_static;
-    let rev_region_graph = rev_constraint_graph.region_graph(constraint_set, fr_static);
 
     // Stack for the depth-first search. Start out with all the free regions.
     let mut stack: Vec<_> = universal_regions.universal_regions().collect();
@@ -134,8 +146,10 @@ fn regions_that_outlive_free_regions<'tcx>(
     // push them onto the stack for later.
     while let Some(sub_region) = stack.pop() {
         stack.extend(
-            rev_region_graph
-                .outgoing_regions(sub_region)
+            rev_constraint_graph
+                .outgoing_edges(sub_region, constraint_set, fr_static)
+                .filter(|c| !location_sensitive || matches!(c.locations, Locations::All(_)))
+                .map(|c| c.sup)
                 .filter(|&r| outlives_free_region.insert(r)),
         );
     }
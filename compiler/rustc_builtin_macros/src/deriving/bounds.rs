@@ -1,9 +1,11 @@
 use crate::deriving::generic::*;
-use crate::deriving::path_std;
+use crate::deriving::{path_std, pathvec_std};
 
-use rustc_ast::MetaItem;
+use rustc_ast::{self as ast, GenericArg, MetaItem};
 use rustc_expand::base::{Annotatable, ExtCtxt};
+use rustc_span::symbol::{kw, sym, Ident};
 use rustc_span::Span;
+use thin_vec::ThinVec;
 
 pub fn expand_deriving_copy(
     cx: &mut ExtCtxt<'_>,
@@ -50,3 +52,85 @@ pub fn expand_deriving_const_param_ty(
 
     trait_def.expand(cx, mitem, item, push);
 }
+
+/// Derives `unsafe impl<__Src, const __ASSUME: Assume> TransmuteFrom<__Src, __ASSUME> for T where
+/// T: BikeshedIntrinsicFrom<__Src, T, __ASSUME>`.
+///
+/// The deriving type is used as the context of the transmutability check: its own fields are
+/// visible from it, so deriving the trait is how the author of a type allows safe code to create
+/// values of that type from raw bits.
+pub fn expand_deriving_transmute_from(
+    cx: &mut ExtCtxt<'_>,
+    span: Span,
+    mitem: &MetaItem,
+    item: &Annotatable,
+    push: &mut dyn FnMut(Annotatable),
+    is_const: bool,
+) {
+    let src = Ident::new(sym::__Src, span);
+    let assume = Ident::new(sym::__ASSUME, span);
+
+    let trait_def = TraitDef {
+        span,
+        path: ty::Path::new_(
+            pathvec_std!(mem::TransmuteFrom),
+            vec![
+                Box::new(ty::Ty::Path(ty::Path::new_local(sym::__Src))),
+                Box::new(ty::Ty::Path(ty::Path::new_local(sym::__ASSUME))),
+            ],
+            ty::PathKind::Std,
+        ),
+        skip_path_as_bound: true,
+        needs_copy_as_bound_if_packed: false,
+        additional_bounds: Vec::new(),
+        supports_unions: true,
+        methods: Vec::new(),
+        associated_types: Vec::new(),
+        is_const,
+    };
+
+    // The generic parameters and the where clause that `TraitDef` doesn't know how to add.
+    let src_param = cx.typaram(span, src, Vec::new(), None);
+    let assume_param = ast::GenericParam {
+        id: ast::DUMMY_NODE_ID,
+        ident: assume,
+        attrs: ast::AttrVec::new(),
+        bounds: Vec::new(),
+        is_placeholder: false,
+        kind: ast::GenericParamKind::Const {
+            ty: cx.ty_path(cx.path_global(span, cx.std_path(&[sym::mem, sym::Assume]))),
+            kw_span: span,
+            default: None,
+        },
+        colon_span: None,
+    };
+    let self_ty = cx.ty_ident(span, Ident::new(kw::SelfUpper, span));
+    let bikeshed_path = cx.path_all(
+        span,
+        true,
+        cx.std_path(&[sym::mem, sym::BikeshedIntrinsicFrom]),
+        vec![
+            GenericArg::Type(cx.ty_ident(span, src)),
+            GenericArg::Type(self_ty.clone()),
+            GenericArg::Const(cx.const_ident(span, assume)),
+        ],
+    );
+    let predicate = ast::WherePredicate::BoundPredicate(ast::WhereBoundPredicate {
+        span,
+        bound_generic_params: ThinVec::new(),
+        bounded_ty: self_ty,
+        bounds: vec![cx.trait_bound(bikeshed_path, false)],
+    });
+
+    trait_def.expand(cx, mitem, item, &mut |mut annotatable| {
+        if let Annotatable::Item(item) = &mut annotatable
+            && let ast::ItemKind::Impl(impl_) = &mut item.kind
+        {
+            impl_.unsafety = ast::Unsafe::Yes(span);
+            impl_.generics.params.push(src_param.clone());
+            impl_.generics.params.push(assume_param.clone());
+            impl_.generics.where_clause.predicates.push(predicate.clone());
+        }
+        push(annotatable)
+    });
+}
@@ -128,6 +128,7 @@ pub fn register_builtin_macros(resolver: &mut dyn ResolverExpand) {
         PartialOrd: partial_ord::expand_deriving_partial_ord,
         RustcDecodable: decodable::expand_deriving_rustc_decodable,
         RustcEncodable: encodable::expand_deriving_rustc_encodable,
+        TransmuteFrom: bounds::expand_deriving_transmute_from,
     }
 
     let client = proc_macro::bridge::client::Client::expand1(proc_macro::quote);
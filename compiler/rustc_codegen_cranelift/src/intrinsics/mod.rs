@@ -1246,6 +1246,13 @@ fn codegen_regular_intrinsic_call<'tcx>(
             // nop at runtime.
         }
 
+        sym::const_make_global => {
+            intrinsic_args!(fx, args => (ptr); intrinsic);
+
+            // returns the pointer at runtime.
+            ret.write_cvalue(fx, ptr);
+        }
+
         sym::black_box => {
             intrinsic_args!(fx, args => (a); intrinsic);
 
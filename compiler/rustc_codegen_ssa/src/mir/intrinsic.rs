@@ -290,6 +290,11 @@ pub fn codegen_intrinsic_call(
                 return;
             }
 
+            sym::const_make_global => {
+                // returns the pointer at runtime.
+                args[0].immediate()
+            }
+
             // This requires that atomic intrinsics follow a specific naming pattern:
             // "atomic_<operation>[_<ordering>]"
             name if let Some(atomic) = name_str.strip_prefix("atomic_") => {
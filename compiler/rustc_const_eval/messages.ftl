@@ -200,6 +200,13 @@ const_eval_long_running =
     .label = the const evaluator is currently interpreting this expression
     .help = the constant being evaluated
 
+const_eval_make_global_not_heap =
+    pointer passed to `const_make_global` does not point to a heap allocation: {$ptr}
+const_eval_make_global_twice =
+    {$alloc} was already made global
+const_eval_make_global_with_offset =
+    pointer passed to `const_make_global` does not point to the start of its allocation: {$ptr}
+
 const_eval_match_eq_non_const = cannot match on `{$ty}` in {const_eval_const_context}s
     .note = `{$ty}` cannot be compared in compile-time, and therefore cannot be used in `match`es
 
@@ -215,6 +222,9 @@ const_eval_modified_global =
 const_eval_mut_deref =
     mutation through a reference is not allowed in {const_eval_const_context}s
 
+const_eval_mutable_heap_ptr_in_final_note =
+    heap allocations can only remain in the final value once they were made global with `const_make_global`
+
 const_eval_mutable_ptr_in_final = encountered mutable pointer in final value of {const_eval_intern_kind}
 
 const_eval_non_const_fmt_macro_call =
@@ -11,6 +11,7 @@
 use rustc_span::Span;
 use rustc_target::abi::{self, Abi};
 
+use super::profile::ConstEvalProfile;
 use super::{CanAccessMutGlobal, CompileTimeEvalContext, CompileTimeInterpreter};
 use crate::const_eval::CheckAlignment;
 use crate::errors;
@@ -294,8 +295,19 @@ pub fn eval_in_interpreter<'mir, 'tcx>(
     // `is_static` just means "in static", it could still be a promoted!
     debug_assert_eq!(is_static, ecx.tcx.static_mutability(cid.instance.def_id()).is_some());
 
+    if ecx.tcx.sess.opts.unstable_opts.const_eval_profile {
+        ecx.machine.profile = Some(Box::new(ConstEvalProfile::new()));
+    }
+
     let res = ecx.load_mir(cid.instance.def, cid.promoted);
-    match res.and_then(|body| eval_body_using_ecx(&mut ecx, cid, body)) {
+    let res = res.and_then(|body| {
+        let res = eval_body_using_ecx(&mut ecx, cid, body);
+        if let Some(profile) = &ecx.machine.profile {
+            profile.dump(*ecx.tcx, cid, body);
+        }
+        res
+    });
+    match res {
         Err(error) => {
             let (error, backtrace) = error.into_parts();
             backtrace.print_backtrace();
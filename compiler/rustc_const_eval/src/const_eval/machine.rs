@@ -24,10 +24,12 @@
 use crate::fluent_generated as fluent;
 use crate::interpret::{
     self, compile_time_machine, AllocId, AllocRange, ConstAllocation, CtfeProvenance, FnArg, FnVal,
-    Frame, ImmTy, InterpCx, InterpResult, OpTy, PlaceTy, Pointer, PointerArithmetic, Scalar,
+    Frame, ImmTy, InterpCx, InterpResult, MPlaceTy, OpTy, PlaceTy, Pointer, PointerArithmetic,
+    Scalar, StackPopJump,
 };
 
 use super::error::*;
+use super::profile::ConstEvalProfile;
 
 /// When hitting this many interpreted terminators we emit a deny by default lint
 /// that notfies the user that their constant takes a long time to evaluate. If that's
@@ -58,6 +60,9 @@ pub struct CompileTimeInterpreter<'mir, 'tcx> {
 
     /// Whether to check alignment during evaluation.
     pub(super) check_alignment: CheckAlignment,
+
+    /// Where the evaluation spends its time, if `-Zconst-eval-profile` is enabled.
+    pub(super) profile: Option<Box<ConstEvalProfile<'tcx>>>,
 }
 
 #[derive(Copy, Clone)]
@@ -91,6 +96,7 @@ pub(crate) fn new(
             stack: Vec::new(),
             can_access_mut_global,
             check_alignment,
+            profile: None,
         }
     }
 }
@@ -189,7 +195,10 @@ fn may_leak(self) -> bool {
 impl<'mir, 'tcx: 'mir> CompileTimeEvalContext<'mir, 'tcx> {
     fn location_triple_for_span(&self, span: Span) -> (Symbol, u32, u32) {
         let topmost = span.ctxt().outer_expn().expansion_cause().unwrap_or(span);
-        let caller = self.tcx.sess.source_map().lookup_char_pos(topmost.lo());
+        let source_map = self.tcx.sess.source_map();
+        let caller = source_map.lookup_char_pos(topmost.lo());
+        // Merged doctests point back to the line they come from, like in diagnostics.
+        let line = source_map.merged_doctest_offset_line(&caller.file.name, caller.line);
 
         use rustc_session::{config::RemapPathScopeComponents, RemapFileNameExt};
         (
@@ -200,7 +209,7 @@ fn location_triple_for_span(&self, span: Span) -> (Symbol, u32, u32) {
                     .for_scope(self.tcx.sess, RemapPathScopeComponents::DIAGNOSTICS)
                     .to_string_lossy(),
             ),
-            u32::try_from(caller.line).unwrap(),
+            u32::try_from(line).unwrap(),
             u32::try_from(caller.col_display).unwrap().checked_add(1).unwrap(),
         )
     }
@@ -494,6 +503,10 @@ fn call_intrinsic(
                     align,
                     interpret::MemoryKind::Machine(MemoryKind::Heap),
                 )?;
+                let instance = ecx.frame().instance;
+                if let Some(profile) = &mut ecx.machine.profile {
+                    profile.allocation(instance, Size::from_bytes(size));
+                }
                 ecx.write_pointer(ptr, dest)?;
             }
             sym::const_deallocate => {
@@ -528,6 +541,11 @@ fn call_intrinsic(
                     )?;
                 }
             }
+            sym::const_make_global => {
+                let ptr = ecx.read_pointer(&args[0])?;
+                ecx.make_const_heap_ptr_global(ptr)?;
+                ecx.write_pointer(ptr, dest)?;
+            }
             // The intrinsic represents whether the value is known to the optimizer (LLVM).
             // We're not doing any optimizations here, so there is no optimizer that could know the value.
             // (We know the value here in the machine of course, but this is the runtime of that code,
@@ -584,6 +602,13 @@ fn binary_ptr_op(
         throw_unsup_format!("pointer arithmetic or comparison is not supported at compile-time");
     }
 
+    fn before_terminator(ecx: &mut InterpCx<'mir, 'tcx, Self>) -> InterpResult<'tcx> {
+        if let Some(profile) = &mut ecx.machine.profile {
+            profile.step(ecx.machine.stack.last().unwrap().instance);
+        }
+        Ok(())
+    }
+
     fn increment_const_eval_counter(ecx: &mut InterpCx<'mir, 'tcx, Self>) -> InterpResult<'tcx> {
         // The step limit has already been hit in a previous call to `increment_const_eval_counter`.
 
@@ -654,10 +679,40 @@ fn init_frame_extra(
         if !ecx.recursion_limit.value_within_limit(ecx.stack().len() + 1) {
             throw_exhaust!(StackFrameLimitReached)
         } else {
+            if let Some(profile) = &mut ecx.machine.profile {
+                profile.enter(ecx.machine.stack.last().map(|caller| caller.instance), frame.instance);
+            }
             Ok(frame)
         }
     }
 
+    #[inline(always)]
+    fn after_stack_pop(
+        ecx: &mut InterpCx<'mir, 'tcx, Self>,
+        frame: Frame<'mir, 'tcx>,
+        unwinding: bool,
+    ) -> InterpResult<'tcx, StackPopJump> {
+        // Unwinding is not supported at compile-time.
+        assert!(!unwinding);
+        if let Some(profile) = &mut ecx.machine.profile {
+            profile.leave(frame.instance);
+        }
+        Ok(StackPopJump::Normal)
+    }
+
+    #[inline(always)]
+    fn after_local_allocated(
+        ecx: &mut InterpCx<'mir, 'tcx, Self>,
+        frame: usize,
+        _local: mir::Local,
+        mplace: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        if let Some(profile) = &mut ecx.machine.profile {
+            profile.allocation(ecx.machine.stack[frame].instance, mplace.layout.size);
+        }
+        Ok(())
+    }
+
     #[inline(always)]
     fn stack<'a>(
         ecx: &'a InterpCx<'mir, 'tcx, Self>,
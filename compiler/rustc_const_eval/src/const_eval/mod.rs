@@ -11,6 +11,7 @@
 mod eval_queries;
 mod fn_queries;
 mod machine;
+mod profile;
 mod valtrees;
 
 pub use error::*;
@@ -0,0 +1,131 @@
+//! The profiler behind `-Zconst-eval-profile`. It records, for each function the interpreter
+//! runs while evaluating a constant or static, how many terminators it evaluated, how much memory
+//! it allocated and how much time it took, and writes that out as a report per evaluation.
+
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use rustc_data_structures::fx::{FxHasher, FxIndexMap};
+use rustc_middle::mir::interpret::GlobalId;
+use rustc_middle::mir::{create_dump_file, Body};
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_target::abi::Size;
+
+/// What the interpreter did in one function, summed over all calls to it.
+#[derive(Default)]
+struct FnProfile {
+    calls: u64,
+    /// The number of terminators evaluated in the function itself.
+    steps: u64,
+    /// The number of locals that needed to be backed by memory, and of heap allocations.
+    allocations: u64,
+    allocated_bytes: u64,
+    /// The time spent in the function itself, excluding the functions it called.
+    self_time: Duration,
+}
+
+pub(crate) struct ConstEvalProfile<'tcx> {
+    functions: FxIndexMap<ty::Instance<'tcx>, FnProfile>,
+    start: Instant,
+    /// The last time we attributed the elapsed time to a function.
+    last_event: Instant,
+}
+
+impl<'tcx> ConstEvalProfile<'tcx> {
+    pub(super) fn new() -> Self {
+        let now = Instant::now();
+        ConstEvalProfile { functions: FxIndexMap::default(), start: now, last_event: now }
+    }
+
+    /// Attributes the time since the last event to `instance`, if there is one.
+    fn charge_time(&mut self, instance: Option<ty::Instance<'tcx>>) {
+        let now = Instant::now();
+        if let Some(instance) = instance {
+            self.functions.entry(instance).or_default().self_time += now - self.last_event;
+        }
+        self.last_event = now;
+    }
+
+    /// `caller` is `None` for the root frame of the evaluation.
+    pub(super) fn enter(&mut self, caller: Option<ty::Instance<'tcx>>, callee: ty::Instance<'tcx>) {
+        self.charge_time(caller);
+        self.functions.entry(callee).or_default().calls += 1;
+    }
+
+    pub(super) fn leave(&mut self, instance: ty::Instance<'tcx>) {
+        self.charge_time(Some(instance));
+    }
+
+    pub(super) fn step(&mut self, instance: ty::Instance<'tcx>) {
+        self.functions.entry(instance).or_default().steps += 1;
+    }
+
+    pub(super) fn allocation(&mut self, instance: ty::Instance<'tcx>, size: Size) {
+        let function = self.functions.entry(instance).or_default();
+        function.allocations += 1;
+        function.allocated_bytes += size.bytes();
+    }
+
+    /// Writes the report for the evaluation of `cid` into `-Zdump-mir-dir`.
+    pub(super) fn dump(&self, tcx: TyCtxt<'tcx>, cid: GlobalId<'tcx>, body: &Body<'tcx>) {
+        // Generic constants are evaluated once per instance, which all need their own report.
+        let name = with_no_trimmed_paths!(cid.instance.to_string());
+        let disambiguator = if cid.instance.args.is_empty() {
+            0
+        } else {
+            let mut hasher = FxHasher::default();
+            name.hash(&mut hasher);
+            hasher.finish()
+        };
+        let _: io::Result<()> = try {
+            let mut file =
+                create_dump_file(tcx, "txt", false, "const-eval-profile", &disambiguator, body)?;
+            self.write(&name, cid, &mut file)?;
+        };
+    }
+
+    fn write(&self, name: &str, cid: GlobalId<'tcx>, out: &mut dyn Write) -> io::Result<()> {
+        let promoted = cid.promoted.map_or_else(String::new, |p| format!("::promoted[{p:?}]"));
+        let sum = |f: fn(&FnProfile) -> u64| self.functions.values().map(f).sum::<u64>();
+        writeln!(out, "// const-eval profile of `{name}{promoted}`")?;
+        writeln!(
+            out,
+            "// {} steps, {} allocations ({} bytes), {:.3}ms",
+            sum(|f| f.steps),
+            sum(|f| f.allocations),
+            sum(|f| f.allocated_bytes),
+            millis(self.last_event - self.start),
+        )?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "{:>10} {:>8} {:>11} {:>10} {:>12}  function",
+            "steps", "calls", "allocations", "bytes", "self time"
+        )?;
+
+        // The functions doing the most work come first.
+        let mut functions: Vec<_> = self.functions.iter().collect();
+        functions.sort_by(|(_, a), (_, b)| {
+            b.steps.cmp(&a.steps).then_with(|| b.self_time.cmp(&a.self_time))
+        });
+        for (instance, function) in functions {
+            writeln!(
+                out,
+                "{:>10} {:>8} {:>11} {:>10} {:>10.3}ms  {}",
+                function.steps,
+                function.calls,
+                function.allocations,
+                function.allocated_bytes,
+                millis(function.self_time),
+                with_no_trimmed_paths!(instance.to_string()),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
@@ -33,6 +33,8 @@ pub(crate) struct MutablePtrInFinal {
     #[primary_span]
     pub span: Span,
     pub kind: InternKind,
+    #[note(const_eval_mutable_heap_ptr_in_final_note)]
+    pub heap: Option<()>,
 }
 
 #[derive(Diagnostic)]
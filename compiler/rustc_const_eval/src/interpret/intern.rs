@@ -12,6 +12,14 @@
 //! so all inner allocations are marked mutable. Some of them could potentially be made immutable,
 //! but that would require relying on type information, and given how many ways Rust has to lie
 //! about type information, we want to avoid doing that.
+//!
+//! Heap allocations made with `const_allocate` are interned like every other allocation. Since
+//! the types that own heap memory (like `Vec` or `String`) point to it with raw, mutable pointers,
+//! we make one exception to the "no mutable pointers" rule above: mutable pointers are accepted to
+//! the heap allocations that were explicitly made global with `const_make_global`. That made them
+//! immutable already, so they could not be written to or deallocated during the rest of the
+//! evaluation, and they are interned immutably. Types are not involved: whether a type could mutate
+//! its heap memory through a shared reference cannot be told from its fields.
 
 use rustc_ast::Mutability;
 use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
@@ -133,10 +141,14 @@ pub fn intern_const_alloc_recursive<
     // We need to distinguish "has just been interned" from "was already in `tcx`",
     // so we track this in a separate set.
     let mut just_interned: FxHashSet<_> = std::iter::once(base_alloc_id).collect();
+    // The subset of `just_interned` that are heap allocations made global, see the module docs.
+    let mut just_interned_global_heap: FxHashSet<AllocId> = FxHashSet::default();
     // Whether we encountered a bad mutable pointer.
     // We want to first report "dangling" and then "mutable", so we need to delay reporting these
     // errors.
     let mut found_bad_mutable_pointer = false;
+    // Whether one of the bad mutable pointers points to a heap allocation.
+    let mut found_bad_heap_pointer = false;
 
     // Keep interning as long as there are things to intern.
     // We show errors if there are dangling pointers, or mutable pointers in immutable contexts
@@ -174,8 +186,19 @@ pub fn intern_const_alloc_recursive<
             // immutable. We exclude promoteds from this, since things like `&mut []` and
             // `&None::<Cell<i32>>` lead to promotion that can produce mutable pointers. We rely
             // on the promotion analysis not screwing up to ensure that it is sound to intern
-            // promoteds as immutable.
-            found_bad_mutable_pointer = true;
+            // promoteds as immutable. Heap allocations made global are fine, they are immutable
+            // already.
+            if !just_interned_global_heap.contains(&alloc_id) {
+                match ecx.memory.alloc_map.get(&alloc_id) {
+                    Some((MemoryKind::Machine(const_eval::MemoryKind::Heap), alloc)) => {
+                        if alloc.mutability.is_mut() {
+                            found_bad_mutable_pointer = true;
+                            found_bad_heap_pointer = true;
+                        }
+                    }
+                    _ => found_bad_mutable_pointer = true,
+                }
+            }
         }
         if ecx.tcx.try_get_global_alloc(alloc_id).is_some() {
             // Already interned.
@@ -183,9 +206,20 @@ pub fn intern_const_alloc_recursive<
             continue;
         }
         just_interned.insert(alloc_id);
-        // We always intern with `inner_mutability`, and furthermore we ensured above that if
-        // that is "immutable", then there are *no* mutable pointers anywhere in the newly
-        // interned memory -- justifying that we can indeed intern immutably. However this also
+        // Heap allocations made global are always interned immutably, even in a `static mut`.
+        let mutability = match ecx.memory.alloc_map.get(&alloc_id) {
+            Some((MemoryKind::Machine(const_eval::MemoryKind::Heap), alloc))
+                if alloc.mutability.is_not() =>
+            {
+                just_interned_global_heap.insert(alloc_id);
+                Mutability::Not
+            }
+            _ => inner_mutability,
+        };
+        // Other than that, we always intern with `inner_mutability`, and furthermore we ensured
+        // above that if that is "immutable", then there are *no* mutable pointers anywhere in the
+        // newly interned memory, except to heap allocations made global -- justifying that we can
+        // indeed intern immutably. However this also
         // means we can *not* easily intern immutably here if `prov.immutable()` is true and
         // `inner_mutability` is `Mut`: there might be other pointers to that allocation, and
         // we'd have to somehow check that they are *all* immutable before deciding that this
@@ -193,15 +227,16 @@ pub fn intern_const_alloc_recursive<
         // pointers before deciding which allocations can be made immutable; but for now we are
         // okay with losing some potential for immutability here. This can anyway only affect
         // `static mut`.
-        todo.extend(intern_shallow(ecx, alloc_id, inner_mutability).map_err(|()| {
+        todo.extend(intern_shallow(ecx, alloc_id, mutability).map_err(|()| {
             ecx.tcx.dcx().emit_err(DanglingPtrInFinal { span: ecx.tcx.span, kind: intern_kind })
         })?);
     }
     if found_bad_mutable_pointer {
-        return Err(ecx
-            .tcx
-            .dcx()
-            .emit_err(MutablePtrInFinal { span: ecx.tcx.span, kind: intern_kind }));
+        return Err(ecx.tcx.dcx().emit_err(MutablePtrInFinal {
+            span: ecx.tcx.span,
+            kind: intern_kind,
+            heap: found_bad_heap_pointer.then_some(()),
+        }));
     }
 
     Ok(())
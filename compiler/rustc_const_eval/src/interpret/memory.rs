@@ -357,6 +357,38 @@ pub fn deallocate_ptr(
         Ok(())
     }
 
+    /// Marks the heap allocation made during const evaluation that `ptr` points to as final: it
+    /// becomes immutable, so it can neither be written to nor deallocated any more, and can then
+    /// remain in the final value of the evaluation (see `intern`).
+    pub fn make_const_heap_ptr_global(
+        &mut self,
+        ptr: Pointer<Option<M::Provenance>>,
+    ) -> InterpResult<'tcx>
+    where
+        M: Machine<'mir, 'tcx, MemoryKind = crate::const_eval::MemoryKind>,
+    {
+        let (alloc_id, offset, _prov) = self.ptr_get_alloc_id(ptr)?;
+        if offset.bytes() != 0 {
+            throw_ub_custom!(fluent::const_eval_make_global_with_offset, ptr = format!("{ptr:?}"));
+        }
+
+        let Ok((kind, alloc)) = self.memory.alloc_map.get_mut_or(alloc_id, || Err(())) else {
+            if self.tcx.try_get_global_alloc(alloc_id).is_none() {
+                throw_ub!(PointerUseAfterFree(alloc_id, CheckInAllocMsg::MemoryAccessTest));
+            }
+            throw_ub_custom!(fluent::const_eval_make_global_not_heap, ptr = format!("{ptr:?}"));
+        };
+        if *kind != MemoryKind::Machine(crate::const_eval::MemoryKind::Heap) {
+            throw_ub_custom!(fluent::const_eval_make_global_not_heap, ptr = format!("{ptr:?}"));
+        }
+        // Heap allocations are only ever immutable once they were made global.
+        if alloc.mutability.is_not() {
+            throw_ub_custom!(fluent::const_eval_make_global_twice, alloc = alloc_id);
+        }
+        alloc.mutability = Mutability::Not;
+        Ok(())
+    }
+
     /// Internal helper function to determine the allocation and offset of a pointer (if any).
     #[inline(always)]
     fn get_ptr_access(
@@ -579,7 +611,7 @@ fn get_global_alloc(
     /// The caller is responsible for calling the access hooks!
     ///
     /// You almost certainly want to use `get_ptr_alloc`/`get_ptr_alloc_mut` instead.
-    fn get_alloc_raw(
+    pub fn get_alloc_raw(
         &self,
         id: AllocId,
     ) -> InterpResult<'tcx, &Allocation<M::Provenance, M::AllocExtra, M::Bytes>> {
@@ -656,7 +688,7 @@ pub fn get_alloc_mutability<'a>(&'a self, id: AllocId) -> InterpResult<'tcx, Mut
     ///
     /// Also returns a ptr to `self.extra` so that the caller can use it in parallel with the
     /// allocation.
-    fn get_alloc_raw_mut(
+    pub fn get_alloc_raw_mut(
         &mut self,
         id: AllocId,
     ) -> InterpResult<'tcx, (&mut Allocation<M::Provenance, M::AllocExtra, M::Bytes>, &mut M)> {
@@ -1045,6 +1077,21 @@ pub fn get_bytes_strip_provenance<'b>(&'b self) -> InterpResult<'tcx, &'a [u8]>
     pub fn has_provenance(&self) -> bool {
         !self.alloc.provenance().range_empty(self.range, &self.tcx)
     }
+
+    /// Returns the bytes in the range of the `AllocRef` as they are stored, without checking
+    /// whether they are initialized or part of a pointer. This is meant for handing memory to
+    /// code outside the interpreter; use `has_provenance` to make sure no pointers escape that way.
+    pub fn inspect_with_uninit_and_ptr_outside_interpreter(&self) -> &'a [u8] {
+        let range = self.range.start.bytes_usize()..self.range.end().bytes_usize();
+        self.alloc.inspect_with_uninit_and_ptr_outside_interpreter(range)
+    }
+
+    /// Returns whether the byte at `offset` is initialized.
+    ///
+    /// `offset` is relative to this allocation reference, not the base of the allocation.
+    pub fn is_init(&self, offset: Size) -> bool {
+        self.alloc.init_mask().get(self.range.start + offset)
+    }
 }
 
 impl<'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
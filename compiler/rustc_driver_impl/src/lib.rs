@@ -358,6 +358,8 @@ fn run_compiler(
         let sess = &compiler.sess;
         let codegen_backend = &*compiler.codegen_backend;
 
+        register_rustdoc_test_files(sess);
+
         // This implements `-Whelp`. It should be handled very early, like
         // `--help`/`-Zhelp`/`-Chelp`. This is the earliest it can run, because
         // it must happen after lints are registered, during session creation.
@@ -452,6 +454,10 @@ fn run_compiler(
                 sess.code_stats.print_type_sizes();
             }
 
+            if sess.opts.unstable_opts.print_coroutine_layout {
+                sess.code_stats.print_coroutine_layouts();
+            }
+
             if sess.opts.unstable_opts.print_vtable_sizes {
                 let crate_name = queries.global_ctxt()?.enter(|tcx| tcx.crate_name(LOCAL_CRATE));
 
@@ -490,6 +496,26 @@ fn make_output(matches: &getopts::Matches) -> (Option<PathBuf>, Option<OutFileNa
     (odir, ofile)
 }
 
+/// Names the module files of a crate of merged doctests after the doctests they contain. The file
+/// at `UNSTABLE_RUSTDOC_TEST_FILES` lists one module file per line, as
+/// `<module file>\t<line offset>\t<doctest path>`.
+fn register_rustdoc_test_files(sess: &Session) {
+    let Ok(list) = env::var("UNSTABLE_RUSTDOC_TEST_FILES") else {
+        return;
+    };
+    let files = fs::read_to_string(&list).expect("UNSTABLE_RUSTDOC_TEST_FILES needs to be readable");
+    for entry in files.lines() {
+        let mut fields = entry.splitn(3, '\t');
+        let (Some(file), Some(line), Some(path)) = (fields.next(), fields.next(), fields.next())
+        else {
+            panic!("malformed entry in UNSTABLE_RUSTDOC_TEST_FILES: `{entry}`");
+        };
+        let line = isize::from_str_radix(line, 10)
+            .expect("UNSTABLE_RUSTDOC_TEST_FILES line offsets need to be numbers");
+        sess.source_map().register_doctest_file(PathBuf::from(file), PathBuf::from(path), line);
+    }
+}
+
 // Extract input (string or file and optional path) from matches.
 fn make_input(
     early_dcx: &EarlyDiagCtxt,
@@ -513,7 +539,17 @@ fn make_input(
                 );
                 let line = isize::from_str_radix(&line, 10)
                     .expect("UNSTABLE_RUSTDOC_TEST_LINE needs to be an number");
-                let file_name = FileName::doc_test_source_code(PathBuf::from(path), line);
+                // The column offsets of each line are optional, as they are only needed for
+                // coverage mappings.
+                let cols = env::var("UNSTABLE_RUSTDOC_TEST_COL").map_or(Vec::new(), |cols| {
+                    cols.split(',')
+                        .map(|col| {
+                            usize::from_str_radix(col, 10)
+                                .expect("UNSTABLE_RUSTDOC_TEST_COL needs to be a list of numbers")
+                        })
+                        .collect()
+                });
+                let file_name = FileName::doc_test_source_code(PathBuf::from(path), line, cols);
                 Ok(Some(Input::Str { name: file_name, input: src }))
             } else {
                 Ok(Some(Input::Str { name: FileName::anon_source_code(&src), input: src }))
@@ -1220,7 +1220,7 @@ pub fn resolve_path(
             FileName::Real(name) => name
                 .into_local_path()
                 .expect("attempting to resolve a file path in an external file"),
-            FileName::DocTest(path, _) => path,
+            FileName::DocTest(path, _, _) => path,
             other => {
                 return Err(parse_sess.dcx().create_err(errors::ResolveRelativePath {
                     span,
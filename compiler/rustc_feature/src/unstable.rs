@@ -436,6 +436,9 @@ pub fn internal(&self, feature: Symbol) -> bool {
     (unstable, deprecated_safe, "1.61.0", Some(94978)),
     /// Allows having using `suggestion` in the `#[deprecated]` attribute.
     (unstable, deprecated_suggestion, "1.61.0", Some(94785)),
+    /// Allows `box` patterns (see `box_patterns`) to match through any pointer type implementing
+    /// `DerefPure`.
+    (incomplete, deref_patterns, "CURRENT_RUSTC_VERSION", Some(87121)),
     /// Allows using the `#[diagnostic]` attribute tool namespace
     (unstable, diagnostic_namespace, "1.73.0", Some(111996)),
     /// Controls errors in trait implementations.
@@ -344,6 +344,11 @@ pub fn check_intrinsic_type(tcx: TyCtxt<'_>, it: &hir::ForeignItem<'_>) {
                 vec![Ty::new_mut_ptr(tcx, tcx.types.u8), tcx.types.usize, tcx.types.usize],
                 Ty::new_unit(tcx),
             ),
+            sym::const_make_global => (
+                0,
+                vec![Ty::new_mut_ptr(tcx, tcx.types.u8)],
+                Ty::new_imm_ptr(tcx, tcx.types.u8),
+            ),
 
             sym::ptr_offset_from => (
                 1,
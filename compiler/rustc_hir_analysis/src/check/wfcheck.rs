@@ -914,7 +914,9 @@ fn check_param_wf(tcx: TyCtxt<'_>, param: &hir::GenericParam<'_>) -> Result<(),
         hir::GenericParamKind::Const { ty: hir_ty, default: _, is_host_effect: _ } => {
             let ty = tcx.type_of(param.def_id).instantiate_identity();
 
-            if tcx.features().adt_const_params {
+            // Built-in derives, like `TransmuteFrom`, may generate const parameters of ADT types.
+            if tcx.features().adt_const_params || hir_ty.span.allows_unstable(sym::adt_const_params)
+            {
                 enter_wf_checking_ctxt(tcx, hir_ty.span, param.def_id, |wfcx| {
                     let trait_def_id =
                         tcx.require_lang_item(LangItem::ConstParamTy, Some(hir_ty.span));
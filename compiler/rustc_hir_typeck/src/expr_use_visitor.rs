@@ -458,6 +458,10 @@ fn maybe_read_scrutinee<'t>(
                             needs_to_be_read = true;
                         }
                     }
+                    PatKind::Box(_) if place.place.ty().is_adt() && !place.place.ty().is_box() => {
+                        // A deref pattern calls `Deref::deref` on the place.
+                        needs_to_be_read = true;
+                    }
                     PatKind::Or(_)
                     | PatKind::Box(_)
                     | PatKind::Ref(..)
@@ -726,6 +726,16 @@ fn cat_pattern_<F>(
                 self.cat_pattern_(place_with_id, subpat, op)?;
             }
 
+            PatKind::Box(subpat)
+                if place_with_id.place.ty().is_adt() && !place_with_id.place.ty().is_box() =>
+            {
+                // A deref pattern (`box p1` on a type other than `Box`) calls `Deref::deref` and
+                // matches `p1` against the result, which is a temporary.
+                let ty = self.pat_ty_adjusted(subpat)?;
+                let subplace = self.cat_rvalue(pat.hir_id, pat.span, ty);
+                self.cat_pattern_(subplace, subpat, op)?;
+            }
+
             PatKind::Box(subpat) | PatKind::Ref(subpat, _) => {
                 // box p1, &p1, &mut p1. we can ignore the mutability of
                 // PatKind::Ref since that information is already contained
@@ -431,6 +431,15 @@ fn check_pat_lit(
             };
         }
 
+        // With `deref_patterns`, a string literal matches a `str`, e.g. in `box "foo"` on a
+        // `String`: it is compared with a reference to the place, see `TestKind::Eq`.
+        if self.tcx.features().deref_patterns
+            && let hir::ExprKind::Lit(Spanned { node: ast::LitKind::Str(..), .. }) = lt.kind
+            && self.try_structurally_resolve_type(span, expected).is_str()
+        {
+            pat_ty = self.tcx.types.str_;
+        }
+
         // Somewhat surprising: in this case, the subtyping relation goes the
         // opposite way as the other cases. Actually what we really want is not
         // a subtyping relation at all but rather that there exists a LUB
@@ -1949,6 +1958,14 @@ fn check_pat_box(
         pat_info: PatInfo<'tcx, '_>,
     ) -> Ty<'tcx> {
         let tcx = self.tcx;
+        if tcx.features().deref_patterns {
+            let expected = self.try_structurally_resolve_type(span, expected);
+            if let ty::Adt(def, _) = expected.kind()
+                && !def.is_box()
+            {
+                return self.check_pat_deref(span, inner, expected, pat_info);
+            }
+        }
         let (box_ty, inner_ty) = match self.check_dereferenceable(span, expected, inner) {
             Ok(()) => {
                 // Here, `demand::subtype` is good enough, but I don't
@@ -1970,6 +1987,35 @@ fn check_pat_box(
         box_ty
     }
 
+    /// Checks a deref pattern, i.e. a `box` pattern on a type other than `Box` with the
+    /// `deref_patterns` feature. The inner pattern is matched against the target of the `Deref`
+    /// impl, which must be pure for exhaustiveness checking to be sound.
+    fn check_pat_deref(
+        &self,
+        span: Span,
+        inner: &'tcx Pat<'tcx>,
+        expected: Ty<'tcx>,
+        pat_info: PatInfo<'tcx, '_>,
+    ) -> Ty<'tcx> {
+        let tcx = self.tcx;
+        // FIXME(deref_patterns): use `DerefMut` when required.
+        self.register_bound(
+            expected,
+            tcx.require_lang_item(hir::LangItem::DerefPure, Some(span)),
+            self.misc(span),
+        );
+        // `<expected as Deref>::Target`
+        let ty = Ty::new_projection(
+            tcx,
+            tcx.require_lang_item(hir::LangItem::DerefTarget, Some(span)),
+            [expected],
+        );
+        let ty = self.normalize(span, ty);
+        let ty = self.try_structurally_resolve_type(span, ty);
+        self.check_pat(inner, ty, pat_info);
+        expected
+    }
+
     // Precondition: Pat is Ref(inner)
     fn check_pat_ref(
         &self,
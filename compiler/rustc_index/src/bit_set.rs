@@ -1,10 +1,15 @@
-use std::fmt;
-use std::iter;
-use std::marker::PhantomData;
-use std::mem;
-use std::ops::{BitAnd, BitAndAssign, BitOrAssign, Bound, Not, Range, RangeBounds, Shl};
-use std::rc::Rc;
-use std::slice;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{BitAnd, BitAndAssign, BitOrAssign, Bound, Not, Range, RangeBounds, Shl};
+use core::slice;
 
 use arrayvec::ArrayVec;
 use smallvec::{smallvec, SmallVec};
@@ -2006,7 +2011,7 @@ fn checked_shr(self, rhs: u32) -> Option<Self> {
     }
 }
 
-impl std::fmt::Debug for FiniteBitSet<u32> {
+impl fmt::Debug for FiniteBitSet<u32> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:032b}", self.0)
     }
@@ -4,6 +4,11 @@
 )]
 #![cfg_attr(all(feature = "nightly", test), feature(stmt_expr_attributes))]
 #![cfg_attr(feature = "nightly", allow(internal_features))]
+// Without the `nightly` feature, this crate only needs `alloc`, so that crates like
+// `rustc_pattern_analysis` can be used in `no_std` environments.
+#![cfg_attr(not(any(feature = "nightly", test)), no_std)]
+
+extern crate alloc;
 
 pub mod bit_set;
 #[cfg(feature = "nightly")]
@@ -33,6 +38,6 @@
 #[macro_export]
 macro_rules! static_assert_size {
     ($ty:ty, $size:expr) => {
-        const _: [(); $size] = [(); ::std::mem::size_of::<$ty>()];
+        const _: [(); $size] = [(); ::core::mem::size_of::<$ty>()];
     };
 }
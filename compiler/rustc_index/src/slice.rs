@@ -1,4 +1,5 @@
-use std::{
+use alloc::borrow::ToOwned;
+use core::{
     fmt,
     marker::PhantomData,
     ops::{Index, IndexMut},
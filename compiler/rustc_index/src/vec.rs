@@ -1,13 +1,14 @@
 #[cfg(feature = "rustc_serialize")]
 use rustc_serialize::{Decodable, Decoder, Encodable, Encoder};
 
-use std::borrow::{Borrow, BorrowMut};
-use std::fmt;
-use std::hash::Hash;
-use std::marker::PhantomData;
-use std::ops::{Deref, DerefMut, RangeBounds};
-use std::slice;
-use std::vec;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::{Borrow, BorrowMut};
+use core::fmt;
+use core::hash::Hash;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut, RangeBounds};
+use core::slice;
 
 use crate::{Idx, IndexSlice};
 
@@ -127,9 +128,9 @@ pub fn drain_enumerated<R: RangeBounds<usize>>(
         range: R,
     ) -> impl Iterator<Item = (I, T)> + '_ {
         let begin = match range.start_bound() {
-            std::ops::Bound::Included(i) => *i,
-            std::ops::Bound::Excluded(i) => i.checked_add(1).unwrap(),
-            std::ops::Bound::Unbounded => 0,
+            core::ops::Bound::Included(i) => *i,
+            core::ops::Bound::Excluded(i) => i.checked_add(1).unwrap(),
+            core::ops::Bound::Unbounded => 0,
         };
         self.raw.drain(range).enumerate().map(move |(n, t)| (I::new(begin + n), t))
     }
@@ -120,10 +120,10 @@ fn encode(&self, e: &mut E) {
         let step = if ord {
             quote! {
                 #gate_rustc_only
-                impl ::std::iter::Step for #name {
+                impl ::core::iter::Step for #name {
                     #[inline]
                     fn steps_between(start: &Self, end: &Self) -> Option<usize> {
-                        <usize as ::std::iter::Step>::steps_between(
+                        <usize as ::core::iter::Step>::steps_between(
                             &Self::index(*start),
                             &Self::index(*end),
                         )
@@ -142,15 +142,15 @@ fn backward_checked(start: Self, u: usize) -> Option<Self> {
 
                 // Safety: The implementation of `Step` upholds all invariants.
                 #gate_rustc_only
-                unsafe impl ::std::iter::TrustedStep for #name {}
+                unsafe impl ::core::iter::TrustedStep for #name {}
             }
         } else {
             quote! {}
         };
 
         let debug_impl = quote! {
-            impl ::std::fmt::Debug for #name {
-                fn fmt(&self, fmt: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+            impl ::core::fmt::Debug for #name {
+                fn fmt(&self, fmt: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
                     write!(fmt, #debug_format, self.as_u32())
                 }
             }
@@ -260,7 +260,7 @@ impl #name {
                 }
             }
 
-            impl std::ops::Add<usize> for #name {
+            impl ::core::ops::Add<usize> for #name {
                 type Output = Self;
 
                 #[inline]
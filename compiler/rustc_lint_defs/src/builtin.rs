@@ -57,6 +57,7 @@
         INVALID_TYPE_PARAM_DEFAULT,
         IRREFUTABLE_LET_PATTERNS,
         LARGE_ASSIGNMENTS,
+        LARGE_FUTURES,
         LATE_BOUND_LIFETIME_ARGUMENTS,
         LEGACY_DERIVE_HELPERS,
         LONG_RUNNING_CONST_EVAL,
@@ -3168,6 +3169,48 @@
     "detects large moves or copies",
 }
 
+declare_lint! {
+    /// The `large_futures` lint detects futures of `async` blocks and functions that are larger
+    /// than a given size.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,compile_fail
+    /// #![deny(large_futures)]
+    /// async fn wait() {}
+    ///
+    /// pub async fn big() {
+    ///     let buffer = [0u8; 32768];
+    ///     wait().await;
+    ///     println!("{}", buffer.len());
+    /// }
+    ///
+    /// fn main() {
+    ///     let _ = big();
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// A future stores every local that is live across an `.await` point, so holding a large
+    /// value across one makes the future itself large. Large futures are expensive to move and
+    /// can overflow the stack when they are created or polled. Consider boxing the large values,
+    /// or dropping them before the `.await`.
+    ///
+    /// The size limit is 16384 bytes by default and can be changed with
+    /// `-Zfuture-size-limit=<bytes>`. `-Zprint-coroutine-layout` shows which locals a future
+    /// stores and how much space each of them takes.
+    ///
+    /// The size of a future is only known once it is monomorphized, so this lint only fires in
+    /// builds that generate code, not under `cargo check`. A future in a generic function is
+    /// reported once, with the size of its largest instantiation.
+    pub LARGE_FUTURES,
+    Allow,
+    "detects futures larger than a given size",
+}
+
 declare_lint! {
     /// The `deprecated_cfg_attr_crate_type_name` lint detects uses of the
     /// `#![cfg_attr(..., crate_type = "...")]` and
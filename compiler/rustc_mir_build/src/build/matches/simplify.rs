@@ -313,6 +313,11 @@ fn simplify_match_pair<'pat>(
                 Ok(())
             }
 
+            PatKind::DerefPattern { .. } => {
+                // Calling `Deref::deref` isn't a simplification; it is done by a `Deref` test.
+                Err(match_pair)
+            }
+
             PatKind::Or { .. } => Err(match_pair),
         }
     }
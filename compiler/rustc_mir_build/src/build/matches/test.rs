@@ -69,6 +69,13 @@ pub(super) fn test<'pat>(&mut self, match_pair: &MatchPair<'pat, 'tcx>) -> Test<
                 Test { span: match_pair.pattern.span, kind: TestKind::Len { len: len as u64, op } }
             }
 
+            PatKind::DerefPattern { ref subpattern } => {
+                let tcx = self.tcx;
+                let ref_ty = Ty::new_imm_ref(tcx, tcx.lifetimes.re_erased, subpattern.ty);
+                let temp = self.temp(ref_ty, match_pair.pattern.span);
+                Test { span: match_pair.pattern.span, kind: TestKind::Deref { temp } }
+            }
+
             PatKind::Or { .. } => bug!("or-patterns should have already been handled"),
 
             PatKind::AscribeUserType { .. }
@@ -116,6 +123,7 @@ pub(super) fn add_cases_to_switch<'pat>(
             | PatKind::InlineConstant { .. }
             | PatKind::Leaf { .. }
             | PatKind::Deref { .. }
+            | PatKind::DerefPattern { .. }
             | PatKind::Error(_) => {
                 // don't know how to add these patterns to a switch
                 false
@@ -251,37 +259,37 @@ pub(super) fn perform_test(
                         );
                     }
                     let re_erased = tcx.lifetimes.re_erased;
-                    let ref_string = self.temp(Ty::new_imm_ref(tcx, re_erased, ty), test.span);
                     let ref_str_ty = Ty::new_imm_ref(tcx, re_erased, tcx.types.str_);
                     let ref_str = self.temp(ref_str_ty, test.span);
-                    let deref = tcx.require_lang_item(LangItem::Deref, None);
-                    let method = trait_method(tcx, deref, sym::deref, [ty]);
                     let eq_block = self.cfg.start_new_block();
-                    self.cfg.push_assign(
-                        block,
+                    // `let ref_str: &str = <String as Deref>::deref(&place);`
+                    self.call_deref(block, eq_block, place, ty, ref_str, test.span);
+                    self.non_scalar_compare(
+                        eq_block,
+                        success_block,
+                        fail_block,
                         source_info,
-                        ref_string,
-                        Rvalue::Ref(re_erased, BorrowKind::Shared, place),
+                        value,
+                        ref_str,
+                        ref_str_ty,
                     );
-                    self.cfg.terminate(
+                } else if ty.is_str() {
+                    if !tcx.features().deref_patterns {
+                        bug!("matching on `str` went through without enabling deref_patterns");
+                    }
+                    // A string literal matching a `str` in a deref pattern, e.g. `box "foo"` on a
+                    // `String`: `value` is a `&str`, so we compare it to `&place`.
+                    let re_erased = tcx.lifetimes.re_erased;
+                    let ref_str_ty = Ty::new_imm_ref(tcx, re_erased, tcx.types.str_);
+                    let ref_str = self.temp(ref_str_ty, test.span);
+                    self.cfg.push_assign(
                         block,
                         source_info,
-                        TerminatorKind::Call {
-                            func: Operand::Constant(Box::new(ConstOperand {
-                                span: test.span,
-                                user_ty: None,
-                                const_: method,
-                            })),
-                            args: vec![Spanned { node: Operand::Move(ref_string), span: DUMMY_SP }],
-                            destination: ref_str,
-                            target: Some(eq_block),
-                            unwind: UnwindAction::Continue,
-                            call_source: CallSource::Misc,
-                            fn_span: source_info.span,
-                        },
+                        ref_str,
+                        Rvalue::Ref(re_erased, BorrowKind::Shared, place),
                     );
                     self.non_scalar_compare(
-                        eq_block,
+                        block,
                         success_block,
                         fail_block,
                         source_info,
@@ -372,9 +380,59 @@ pub(super) fn perform_test(
                     Operand::Move(expected),
                 );
             }
+
+            TestKind::Deref { temp } => {
+                let [target_block] = *target_blocks else {
+                    bug!("`TestKind::Deref` should have one target block");
+                };
+                // `let temp = <Ty as Deref>::deref(&place);`
+                self.call_deref(block, target_block, place, place_ty.ty, temp, test.span);
+            }
         }
     }
 
+    /// Emits `temp = <ty as Deref>::deref(&place)` at the end of `block`, continuing to
+    /// `target_block`.
+    fn call_deref(
+        &mut self,
+        block: BasicBlock,
+        target_block: BasicBlock,
+        place: Place<'tcx>,
+        ty: Ty<'tcx>,
+        temp: Place<'tcx>,
+        span: Span,
+    ) {
+        let tcx = self.tcx;
+        let source_info = self.source_info(span);
+        let re_erased = tcx.lifetimes.re_erased;
+        let ref_src = self.temp(Ty::new_imm_ref(tcx, re_erased, ty), span);
+        let deref = tcx.require_lang_item(LangItem::Deref, None);
+        let method = trait_method(tcx, deref, sym::deref, [ty]);
+        self.cfg.push_assign(
+            block,
+            source_info,
+            ref_src,
+            Rvalue::Ref(re_erased, BorrowKind::Shared, place),
+        );
+        self.cfg.terminate(
+            block,
+            source_info,
+            TerminatorKind::Call {
+                func: Operand::Constant(Box::new(ConstOperand {
+                    span,
+                    user_ty: None,
+                    const_: method,
+                })),
+                args: vec![Spanned { node: Operand::Move(ref_src), span: DUMMY_SP }],
+                destination: temp,
+                target: Some(target_block),
+                unwind: UnwindAction::Continue,
+                call_source: CallSource::Misc,
+                fn_span: source_info.span,
+            },
+        );
+    }
+
     /// Compare using the provided built-in comparison operator
     fn compare(
         &mut self,
@@ -734,6 +792,16 @@ pub(super) fn sort_candidate<'pat>(
 
             (&TestKind::Range { .. }, _) => None,
 
+            (&TestKind::Deref { temp }, PatKind::DerefPattern { subpattern }) => {
+                // The subpattern now applies to the result of the `deref()` call.
+                candidate.match_pairs.remove(match_pair_index);
+                let place = PlaceBuilder::from(temp).deref();
+                candidate.match_pairs.push(MatchPair::new(place, subpattern, self));
+                Some(0)
+            }
+
+            (&TestKind::Deref { .. }, _) => None,
+
             (&TestKind::Eq { .. } | &TestKind::Len { .. }, _) => {
                 // The call to `self.test(&match_pair)` below is not actually used to generate any
                 // MIR. Instead, we just want to compare with `test` (the parameter of the method)
@@ -833,6 +901,7 @@ fn values_not_contained_in_range(
 impl Test<'_> {
     pub(super) fn targets(&self) -> usize {
         match self.kind {
+            TestKind::Deref { .. } => 1,
             TestKind::Eq { .. } | TestKind::Range(_) | TestKind::Len { .. } => 2,
             TestKind::Switch { adt_def, .. } => {
                 // While the switch that we generate doesn't test for all
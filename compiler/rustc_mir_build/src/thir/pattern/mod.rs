@@ -262,6 +262,11 @@ fn lower_pattern_unadjusted(&mut self, pat: &'tcx hir::Pat<'tcx>) -> Box<Pat<'tc
                 return self.lower_path(qpath, pat.hir_id, pat.span);
             }
 
+            hir::PatKind::Box(subpattern) if ty.is_adt() && !ty.is_box() => {
+                // With `deref_patterns`, this matches through the `Deref` impl of `ty`.
+                PatKind::DerefPattern { subpattern: self.lower_pattern(subpattern) }
+            }
+
             hir::PatKind::Ref(subpattern, _) | hir::PatKind::Box(subpattern) => {
                 PatKind::Deref { subpattern: self.lower_pattern(subpattern) }
             }
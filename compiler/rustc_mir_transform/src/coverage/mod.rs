@@ -302,8 +302,10 @@ fn make_code_region(
         // If neither nudge could be applied, stick with the empty span coordinates.
     }
 
-    // Apply an offset so that code in doctests has correct line numbers.
-    // FIXME(#79417): Currently we have no way to offset doctest _columns_.
+    // Apply an offset so that code in doctests has correct line and column numbers. The column
+    // offsets depend on the line in the doctest, so they are applied first.
+    start_col = source_map.doctest_offset_col(&file.name, start_line, start_col);
+    end_col = source_map.doctest_offset_col(&file.name, end_line, end_col);
     start_line = source_map.doctest_offset_line(&file.name, start_line);
     end_line = source_map.doctest_offset_line(&file.name, end_line);
 
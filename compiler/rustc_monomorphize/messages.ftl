@@ -12,6 +12,11 @@ monomorphize_large_assignments =
     .label = value moved from here
     .note = The current maximum size is {$limit}, but it can be customized with the move_size_limit attribute: `#![move_size_limit = "..."]`
 
+monomorphize_large_futures =
+    this future is {$size} bytes, which is larger than the limit of {$limit} bytes
+    .note = `-Zprint-coroutine-layout` shows which locals the future holds across `.await` points
+    .help = consider boxing large values that are held across `.await` points, or dropping them before the `.await`
+
 monomorphize_no_optimized_mir =
     missing optimized MIR for an item in the crate `{$crate_name}`
     .note = missing optimized MIR for this item (was the crate `{$crate_name}` compiled with `--emit=metadata`?)
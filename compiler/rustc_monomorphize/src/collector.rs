@@ -185,19 +185,30 @@
 use rustc_middle::ty::{GenericArgKind, GenericArgs};
 use rustc_middle::{middle::codegen_fn_attrs::CodegenFnAttrFlags, mir::visit::TyContext};
 use rustc_session::config::EntryFnType;
-use rustc_session::lint::builtin::LARGE_ASSIGNMENTS;
+use rustc_session::lint::builtin::{LARGE_ASSIGNMENTS, LARGE_FUTURES};
 use rustc_session::Limit;
 use rustc_span::source_map::{dummy_spanned, respan, Spanned};
 use rustc_span::symbol::{sym, Ident};
 use rustc_span::{Span, DUMMY_SP};
 use rustc_target::abi::Size;
+use std::collections::VecDeque;
 use std::path::PathBuf;
 
 use crate::errors::{
-    self, EncounteredErrorWhileInstantiating, LargeAssignmentsLint, NoOptimizedMir, RecursionLimit,
-    TypeLengthLimit,
+    self, EncounteredErrorWhileInstantiating, LargeAssignmentsLint, LargeFuturesLint,
+    NoOptimizedMir, RecursionLimit, TypeLengthLimit,
 };
 
+/// The size in bytes above which the `large_futures` lint fires, unless `-Zfuture-size-limit` is
+/// given.
+const DEFAULT_FUTURE_SIZE_LIMIT: usize = 16384;
+
+/// The futures larger than the limit of the `large_futures` lint, by the span where they are
+/// created, with the lint root and the size of their largest instantiation. The lints are only
+/// emitted once collection is done, so that each future is reported once even if it is in a
+/// generic function instantiated many times.
+type LargeFutures = FxHashMap<Span, (hir::HirId, Size)>;
+
 #[derive(PartialEq)]
 pub enum MonoItemCollectionMode {
     Eager,
@@ -210,13 +221,16 @@ pub struct UsageMap<'tcx> {
 
     // Maps every mono item to the mono items that use it.
     user_map: FxHashMap<MonoItem<'tcx>, Vec<MonoItem<'tcx>>>,
+
+    // The roots the collection started from.
+    roots: Vec<MonoItem<'tcx>>,
 }
 
 type MonoItems<'tcx> = Vec<Spanned<MonoItem<'tcx>>>;
 
 impl<'tcx> UsageMap<'tcx> {
-    fn new() -> UsageMap<'tcx> {
-        UsageMap { used_map: FxHashMap::default(), user_map: FxHashMap::default() }
+    fn new(roots: Vec<MonoItem<'tcx>>) -> UsageMap<'tcx> {
+        UsageMap { used_map: FxHashMap::default(), user_map: FxHashMap::default(), roots }
     }
 
     fn record_used<'a>(
@@ -238,6 +252,23 @@ pub fn get_user_items(&self, item: MonoItem<'tcx>) -> &[MonoItem<'tcx>] {
         self.user_map.get(&item).map(|items| items.as_slice()).unwrap_or(&[])
     }
 
+    /// Maps every item reachable from the roots to the item that uses it on a shortest path from
+    /// a root. Following these links up to a root tells why an item was instantiated.
+    pub fn instantiation_parents(&self) -> FxHashMap<MonoItem<'tcx>, MonoItem<'tcx>> {
+        let mut parents = FxHashMap::default();
+        let mut visited: FxHashSet<_> = self.roots.iter().copied().collect();
+        let mut queue: VecDeque<_> = self.roots.iter().copied().collect();
+        while let Some(item) = queue.pop_front() {
+            for &used_item in self.used_map.get(&item).into_iter().flatten() {
+                if visited.insert(used_item) {
+                    parents.insert(used_item, item);
+                    queue.push_back(used_item);
+                }
+            }
+        }
+        parents
+    }
+
     /// Internally iterate over all inlined items used by `item`.
     pub fn for_each_inlined_used_item<F>(&self, tcx: TyCtxt<'tcx>, item: MonoItem<'tcx>, mut f: F)
     where
@@ -266,12 +297,14 @@ pub fn collect_crate_mono_items(
     debug!("building mono item graph, beginning at roots");
 
     let mut visited = MTLock::new(FxHashSet::default());
-    let mut usage_map = MTLock::new(UsageMap::new());
+    let mut usage_map = MTLock::new(UsageMap::new(roots.clone()));
+    let mut large_futures = MTLock::new(LargeFutures::default());
     let recursion_limit = tcx.recursion_limit();
 
     {
         let visited: MTLockRef<'_, _> = &mut visited;
         let usage_map: MTLockRef<'_, _> = &mut usage_map;
+        let large_futures: MTLockRef<'_, _> = &mut large_futures;
 
         tcx.sess.time("monomorphization_collector_graph_walk", || {
             par_for_each_in(roots, |root| {
@@ -283,14 +316,36 @@ pub fn collect_crate_mono_items(
                     &mut recursion_depths,
                     recursion_limit,
                     usage_map,
+                    large_futures,
                 );
             });
         });
     }
 
+    lint_large_futures(tcx, large_futures.into_inner());
+
     (visited.into_inner(), usage_map.into_inner())
 }
 
+/// Emits the `large_futures` lint for the futures found during collection, in source order.
+fn lint_large_futures(tcx: TyCtxt<'_>, large_futures: LargeFutures) {
+    let limit = future_size_limit(tcx);
+    let mut large_futures: Vec<_> = large_futures.into_iter().collect();
+    large_futures.sort_by_key(|&(span, _)| span);
+    for (span, (lint_root, size)) in large_futures {
+        tcx.emit_node_span_lint(
+            LARGE_FUTURES,
+            lint_root,
+            span,
+            LargeFuturesLint { size: size.bytes(), limit: limit as u64 },
+        );
+    }
+}
+
+fn future_size_limit(tcx: TyCtxt<'_>) -> usize {
+    tcx.sess.opts.unstable_opts.future_size_limit.unwrap_or(DEFAULT_FUTURE_SIZE_LIMIT)
+}
+
 // Find all non-generic items by walking the HIR. These items serve as roots to
 // start monomorphizing from.
 #[instrument(skip(tcx, mode), level = "debug")]
@@ -331,7 +386,10 @@ fn collect_roots(tcx: TyCtxt<'_>, mode: MonoItemCollectionMode) -> Vec<MonoItem<
 
 /// Collect all monomorphized items reachable from `starting_point`, and emit a note diagnostic if a
 /// post-monomorphization error is encountered during a collection step.
-#[instrument(skip(tcx, visited, recursion_depths, recursion_limit, usage_map), level = "debug")]
+#[instrument(
+    skip(tcx, visited, recursion_depths, recursion_limit, usage_map, large_futures),
+    level = "debug"
+)]
 fn collect_items_rec<'tcx>(
     tcx: TyCtxt<'tcx>,
     starting_item: Spanned<MonoItem<'tcx>>,
@@ -339,6 +397,7 @@ fn collect_items_rec<'tcx>(
     recursion_depths: &mut DefIdMap<usize>,
     recursion_limit: Limit,
     usage_map: MTLockRef<'_, UsageMap<'tcx>>,
+    large_futures: MTLockRef<'_, LargeFutures>,
 ) {
     if !visited.lock_mut().insert(starting_item.node) {
         // We've been here already, no need to search again.
@@ -416,7 +475,7 @@ fn collect_items_rec<'tcx>(
             check_type_length_limit(tcx, instance);
 
             rustc_data_structures::stack::ensure_sufficient_stack(|| {
-                collect_used_items(tcx, instance, &mut used_items);
+                collect_used_items(tcx, instance, &mut used_items, large_futures);
             });
         }
         MonoItem::GlobalAsm(item_id) => {
@@ -472,7 +531,15 @@ fn collect_items_rec<'tcx>(
     usage_map.lock_mut().record_used(starting_item.node, &used_items);
 
     for used_item in used_items {
-        collect_items_rec(tcx, used_item, visited, recursion_depths, recursion_limit, usage_map);
+        collect_items_rec(
+            tcx,
+            used_item,
+            visited,
+            recursion_depths,
+            recursion_limit,
+            usage_map,
+            large_futures,
+        );
     }
 
     if let Some((def_id, depth)) = recursion_depth_reset {
@@ -595,6 +662,7 @@ struct MirUsedCollector<'a, 'tcx> {
     instance: Instance<'tcx>,
     /// Spans for move size lints already emitted. Helps avoid duplicate lints.
     move_size_spans: Vec<Span>,
+    large_futures: MTLockRef<'a, LargeFutures>,
     visiting_call_terminator: bool,
     /// Set of functions for which it is OK to move large data into.
     skip_move_check_fns: Option<Vec<DefId>>,
@@ -722,6 +790,28 @@ fn lint_large_assignment(
         );
         self.move_size_spans.push(span);
     }
+
+    /// Records the creation of futures larger than the limit of the `large_futures` lint.
+    fn check_future_size(&mut self, rvalue: &mir::Rvalue<'tcx>, location: Location) {
+        let limit = future_size_limit(self.tcx);
+        let ty = self.monomorphize(rvalue.ty(self.body, self.tcx));
+        let Ok(layout) = self.tcx.layout_of(ty::ParamEnv::reveal_all().and(ty)) else {
+            return;
+        };
+        if layout.size.bytes_usize() <= limit {
+            return;
+        }
+        let source_info = self.body.source_info(location);
+        // As for `large_assignments`, we can't lint on futures from foreign crates.
+        let Some(lint_root) = source_info.scope.lint_root(&self.body.source_scopes) else {
+            return;
+        };
+        self.large_futures
+            .lock_mut()
+            .entry(source_info.span)
+            .and_modify(|(_, size)| *size = (*size).max(layout.size))
+            .or_insert((lint_root, layout.size));
+    }
 }
 
 impl<'a, 'tcx> MirVisitor<'tcx> for MirUsedCollector<'a, 'tcx> {
@@ -799,6 +889,13 @@ fn visit_rvalue(&mut self, rvalue: &mir::Rvalue<'tcx>, location: Location) {
                     self.output.push(respan(span, MonoItem::Static(def_id)));
                 }
             }
+            mir::Rvalue::Aggregate(ref kind, _) => {
+                if let mir::AggregateKind::Coroutine(def_id, _) = **kind {
+                    if self.tcx.coroutine_is_async(def_id) {
+                        self.check_future_size(rvalue, location);
+                    }
+                }
+            }
             _ => { /* not interesting */ }
         }
 
@@ -1467,11 +1564,12 @@ fn build_skip_move_check_fns(tcx: TyCtxt<'_>) -> Vec<DefId> {
 }
 
 /// Scans the MIR in order to find function calls, closures, and drop-glue.
-#[instrument(skip(tcx, output), level = "debug")]
+#[instrument(skip(tcx, output, large_futures), level = "debug")]
 fn collect_used_items<'tcx>(
     tcx: TyCtxt<'tcx>,
     instance: Instance<'tcx>,
     output: &mut MonoItems<'tcx>,
+    large_futures: MTLockRef<'_, LargeFutures>,
 ) {
     let body = tcx.instance_mir(instance.def);
 
@@ -1483,6 +1581,7 @@ fn collect_used_items<'tcx>(
         output,
         instance,
         move_size_spans: vec![],
+        large_futures,
         visiting_call_terminator: false,
         skip_move_check_fns: None,
     }
@@ -72,6 +72,15 @@ pub struct LargeAssignmentsLint {
     pub limit: u64,
 }
 
+#[derive(LintDiagnostic)]
+#[diag(monomorphize_large_futures)]
+#[note]
+#[help]
+pub struct LargeFuturesLint {
+    pub size: u64,
+    pub limit: u64,
+}
+
 #[derive(Diagnostic)]
 #[diag(monomorphize_symbol_already_defined)]
 pub struct SymbolAlreadyDefined {
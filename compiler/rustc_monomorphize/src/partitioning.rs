@@ -98,7 +98,7 @@
 use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
 
-use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap};
 use rustc_data_structures::sync;
 use rustc_hir::def::DefKind;
 use rustc_hir::def_id::{DefId, DefIdSet, LOCAL_CRATE};
@@ -111,7 +111,7 @@
 };
 use rustc_middle::query::Providers;
 use rustc_middle::ty::print::{characteristic_def_id_of_type, with_no_trimmed_paths};
-use rustc_middle::ty::{self, visit::TypeVisitableExt, InstanceDef, TyCtxt};
+use rustc_middle::ty::{self, visit::TypeVisitableExt, InstanceDef, Ty, TyCtxt};
 use rustc_session::config::{DumpMonoStatsFormat, SwitchWithOptPath};
 use rustc_session::CodegenUnits;
 use rustc_span::symbol::Symbol;
@@ -1149,9 +1149,7 @@ fn collect_and_partition_mono_items(tcx: TyCtxt<'_>, (): ()) -> (&DefIdSet, &[Co
 
     // Output monomorphization stats per def_id
     if let SwitchWithOptPath::Enabled(ref path) = tcx.sess.opts.unstable_opts.dump_mono_stats {
-        if let Err(err) =
-            dump_mono_items_stats(tcx, codegen_units, path, tcx.crate_name(LOCAL_CRATE))
-        {
+        if let Err(err) = dump_mono_stats(tcx, codegen_units, &usage_map, &items, path) {
             tcx.dcx().emit_fatal(CouldntDumpMonoStats { error: err.to_string() });
         }
     }
@@ -1210,13 +1208,14 @@ fn collect_and_partition_mono_items(tcx: TyCtxt<'_>, (): ()) -> (&DefIdSet, &[Co
     (tcx.arena.alloc(mono_items), codegen_units)
 }
 
-/// Outputs stats about instantiation counts and estimated size, per `MonoItem`'s
-/// def, to a file in the given output directory.
-fn dump_mono_items_stats<'tcx>(
+/// Outputs the files of `-Zdump-mono-stats` to the given output directory, or to the current
+/// directory if there is none.
+fn dump_mono_stats<'tcx>(
     tcx: TyCtxt<'tcx>,
     codegen_units: &[CodegenUnit<'tcx>],
+    usage_map: &UsageMap<'tcx>,
+    items: &FxHashSet<MonoItem<'tcx>>,
     output_directory: &Option<PathBuf>,
-    crate_name: Symbol,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let output_directory = if let Some(ref directory) = output_directory {
         fs::create_dir_all(directory)?;
@@ -1224,7 +1223,27 @@ fn dump_mono_items_stats<'tcx>(
     } else {
         Path::new(".")
     };
+    let crate_name = tcx.crate_name(LOCAL_CRATE);
+    dump_mono_items_stats(tcx, codegen_units, usage_map, output_directory, crate_name)?;
+    dump_type_sizes(tcx, items, output_directory, crate_name)
+}
+
+/// The number of callers shown for an instantiation before its chain of callers is cut short.
+const MAX_CALLER_CHAIN_LEN: usize = 8;
 
+/// The number of types listed by `dump_type_sizes`.
+const MAX_REPORTED_TYPES: usize = 50;
+
+/// Outputs stats about instantiation counts and estimated size, per `MonoItem`'s
+/// def, to a file in the given output directory. Every instantiation comes with the chain of
+/// users that caused it to be collected.
+fn dump_mono_items_stats<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    codegen_units: &[CodegenUnit<'tcx>],
+    usage_map: &UsageMap<'tcx>,
+    output_directory: &Path,
+    crate_name: Symbol,
+) -> Result<(), Box<dyn std::error::Error>> {
     let format = tcx.sess.opts.unstable_opts.dump_mono_stats_format;
     let ext = format.extension();
     let filename = format!("{crate_name}.mono_items.{ext}");
@@ -1243,6 +1262,7 @@ fn dump_mono_items_stats<'tcx>(
                 items_per_def_id.entry(mono_item.def_id()).or_default().push(mono_item);
             });
     }
+    let parents = usage_map.instantiation_parents();
 
     #[derive(serde::Serialize)]
     struct MonoItem {
@@ -1250,6 +1270,16 @@ struct MonoItem {
         instantiation_count: usize,
         size_estimate: usize,
         total_estimate: usize,
+        instances: Vec<Instantiation>,
+    }
+
+    #[derive(serde::Serialize)]
+    struct Instantiation {
+        name: String,
+        size_estimate: usize,
+        /// The item that caused this one to be collected, then the item that caused that one to
+        /// be collected, and so on. Empty for the roots of the collection.
+        callers: Vec<String>,
     }
 
     // Output stats sorted by total instantiated size, from heaviest to lightest
@@ -1260,7 +1290,18 @@ struct MonoItem {
             let instantiation_count = items.len();
             let size_estimate = items[0].size_estimate(tcx);
             let total_estimate = instantiation_count * size_estimate;
-            MonoItem { name, instantiation_count, size_estimate, total_estimate }
+            // Items that are copied into several codegen units are listed once.
+            let mut instances: Vec<_> = items
+                .iter()
+                .map(|&&item| Instantiation {
+                    name: with_no_trimmed_paths!(item.to_string()),
+                    size_estimate: item.size_estimate(tcx),
+                    callers: caller_chain(item, &parents),
+                })
+                .collect();
+            instances.sort_by(|a, b| a.name.cmp(&b.name));
+            instances.dedup_by(|a, b| a.name == b.name);
+            MonoItem { name, instantiation_count, size_estimate, total_estimate, instances }
         })
         .collect();
     stats.sort_unstable_by_key(|item| cmp::Reverse(item.total_estimate));
@@ -1275,12 +1316,217 @@ struct MonoItem {
                 )?;
                 writeln!(file, "| --- | ---: | ---: | ---: |")?;
 
-                for MonoItem { name, instantiation_count, size_estimate, total_estimate } in stats {
+                for MonoItem { name, instantiation_count, size_estimate, total_estimate, .. } in
+                    &stats
+                {
                     writeln!(
                         file,
                         "| `{name}` | {instantiation_count} | {size_estimate} | {total_estimate} |"
                     )?;
                 }
+
+                writeln!(file)?;
+                writeln!(file, "## Instantiations")?;
+                for MonoItem { name, instances, .. } in stats {
+                    writeln!(file)?;
+                    writeln!(file, "### `{name}`")?;
+                    writeln!(file)?;
+                    for Instantiation { name, size_estimate, callers } in instances {
+                        write!(file, "- `{name}` (estimated cost {size_estimate}), ")?;
+                        if callers.is_empty() {
+                            writeln!(file, "a root")?;
+                        } else {
+                            let callers: Vec<_> =
+                                callers.iter().map(|caller| format!("`{caller}`")).collect();
+                            writeln!(file, "used by {}", callers.join(" <- "))?;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The chain of users that caused `item` to be collected, as far as `MAX_CALLER_CHAIN_LEN`.
+fn caller_chain<'tcx>(
+    mut item: MonoItem<'tcx>,
+    parents: &FxHashMap<MonoItem<'tcx>, MonoItem<'tcx>>,
+) -> Vec<String> {
+    let mut callers = Vec::new();
+    while let Some(&parent) = parents.get(&item) {
+        if callers.len() == MAX_CALLER_CHAIN_LEN {
+            callers.push("...".to_string());
+            break;
+        }
+        callers.push(with_no_trimmed_paths!(parent.to_string()));
+        item = parent;
+    }
+    callers
+}
+
+/// Outputs the largest types of the locals of all monomorphized functions, and of all statics,
+/// to a file in the given output directory. Closures and coroutines are broken down into their
+/// captured state, and coroutines additionally into the locals they save across suspension
+/// points, as those are what makes futures large.
+fn dump_type_sizes<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    items: &FxHashSet<MonoItem<'tcx>>,
+    output_directory: &Path,
+    crate_name: Symbol,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let format = tcx.sess.opts.unstable_opts.dump_mono_stats_format;
+    let ext = format.extension();
+    let filename = format!("{crate_name}.type_sizes.{ext}");
+    let output_path = output_directory.join(&filename);
+    let file = File::create(&output_path)?;
+    let mut file = BufWriter::new(file);
+
+    let param_env = ty::ParamEnv::reveal_all();
+    let size_of =
+        |ty: Ty<'tcx>| tcx.layout_of(param_env.and(ty)).map_or(0, |layout| layout.size.bytes());
+
+    // Sort the items so that the item a type is attributed to does not depend on hashing.
+    let mut items: Vec<_> = items
+        .iter()
+        .filter(|item| item.is_user_defined())
+        .map(|&item| (with_no_trimmed_paths!(item.to_string()), item))
+        .collect();
+    items.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    // Every type, with the first item using it.
+    let mut types: FxIndexMap<Ty<'tcx>, &str> = FxIndexMap::default();
+    for (name, item) in &items {
+        match *item {
+            MonoItem::Fn(instance) => {
+                for local_decl in &tcx.instance_mir(instance.def).local_decls {
+                    let ty = instance.instantiate_mir_and_normalize_erasing_regions(
+                        tcx,
+                        param_env,
+                        ty::EarlyBinder::bind(local_decl.ty),
+                    );
+                    types.entry(ty).or_insert(name);
+                }
+            }
+            MonoItem::Static(def_id) => {
+                let ty = tcx.normalize_erasing_regions(
+                    param_env,
+                    tcx.type_of(def_id).instantiate_identity(),
+                );
+                types.entry(ty).or_insert(name);
+            }
+            MonoItem::GlobalAsm(_) => {}
+        }
+    }
+
+    #[derive(serde::Serialize)]
+    struct TypeSize {
+        ty: String,
+        size: u64,
+        align: u64,
+        used_by: String,
+        fields: Vec<FieldSize>,
+    }
+
+    #[derive(serde::Serialize)]
+    struct FieldSize {
+        /// Either "upvar" or "saved local".
+        kind: &'static str,
+        name: String,
+        ty: String,
+        size: u64,
+    }
+
+    let mut layouts: Vec<_> = types
+        .into_iter()
+        .filter_map(|(ty, used_by)| Some((ty, tcx.layout_of(param_env.and(ty)).ok()?, used_by)))
+        .filter(|(_, layout, _)| !layout.is_zst())
+        .collect();
+    // Stable, so that types of the same size stay in order of their first use.
+    layouts.sort_by_key(|(_, layout, _)| cmp::Reverse(layout.size));
+    layouts.truncate(MAX_REPORTED_TYPES);
+
+    let field_size = |kind: &'static str, name: String, ty: Ty<'tcx>| FieldSize {
+        kind,
+        name,
+        ty: with_no_trimmed_paths!(ty.to_string()),
+        size: size_of(ty),
+    };
+    let upvars = |def_id: DefId, upvar_tys: &ty::List<Ty<'tcx>>| -> Vec<FieldSize> {
+        let captures = def_id.as_local().map_or(&[][..], |def_id| tcx.closure_captures(def_id));
+        upvar_tys
+            .iter()
+            .enumerate()
+            .map(|(i, ty)| {
+                let name = captures
+                    .get(i)
+                    .map_or_else(|| format!("#{i}"), |capture| capture.to_string(tcx));
+                field_size("upvar", name, ty)
+            })
+            .collect()
+    };
+    let stats: Vec<_> = layouts
+        .into_iter()
+        .map(|(ty, layout, used_by)| {
+            let mut fields = match *ty.kind() {
+                ty::Closure(def_id, args) => upvars(def_id, args.as_closure().upvar_tys()),
+                ty::Coroutine(def_id, args) => {
+                    let mut fields = upvars(def_id, args.as_coroutine().upvar_tys());
+                    let coroutine_layout = if tcx.is_mir_available(def_id) {
+                        tcx.coroutine_layout(def_id)
+                    } else {
+                        None
+                    };
+                    if let Some(coroutine_layout) = coroutine_layout {
+                        for (local, saved) in coroutine_layout.field_tys.iter_enumerated() {
+                            let ty = tcx.normalize_erasing_regions(
+                                param_env,
+                                ty::EarlyBinder::bind(saved.ty).instantiate(tcx, args),
+                            );
+                            let name = coroutine_layout.field_names[local]
+                                .map_or_else(|| format!("{local:?}"), |name| name.to_string());
+                            fields.push(field_size("saved local", name, ty));
+                        }
+                    }
+                    fields
+                }
+                _ => Vec::new(),
+            };
+            fields.sort_by_key(|field| cmp::Reverse(field.size));
+            TypeSize {
+                ty: with_no_trimmed_paths!(ty.to_string()),
+                size: layout.size.bytes(),
+                align: layout.align.abi.bytes(),
+                used_by: used_by.to_string(),
+                fields,
+            }
+        })
+        .collect();
+
+    if !stats.is_empty() {
+        match format {
+            DumpMonoStatsFormat::Json => serde_json::to_writer(file, &stats)?,
+            DumpMonoStatsFormat::Markdown => {
+                writeln!(file, "| Type | Size | Alignment | First Used By |")?;
+                writeln!(file, "| --- | ---: | ---: | --- |")?;
+                for TypeSize { ty, size, align, used_by, .. } in &stats {
+                    writeln!(file, "| `{ty}` | {size} | {align} | `{used_by}` |")?;
+                }
+
+                for TypeSize { ty, size, fields, .. } in stats {
+                    if fields.is_empty() {
+                        continue;
+                    }
+                    writeln!(file)?;
+                    writeln!(file, "## `{ty}` ({size} bytes)")?;
+                    writeln!(file)?;
+                    writeln!(file, "| Field | Kind | Type | Size |")?;
+                    writeln!(file, "| --- | --- | --- | ---: |")?;
+                    for FieldSize { kind, name, ty, size } in fields {
+                        writeln!(file, "| `{name}` | {kind} | `{ty}` | {size} |")?;
+                    }
+                }
             }
         }
     }
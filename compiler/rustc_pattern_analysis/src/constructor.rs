@@ -148,9 +148,10 @@
 //! we assume they never cover each other. In order to respect the invariants of
 //! [`SplitConstructorSet`], we give each `Opaque` constructor a unique id so we can recognize it.
 
-use std::cmp::{self, max, min, Ordering};
-use std::fmt;
-use std::iter::once;
+use alloc::vec::Vec;
+use core::cmp::{self, max, min, Ordering};
+use core::fmt;
+use core::iter::once;
 
 use smallvec::SmallVec;
 
@@ -634,7 +635,7 @@ fn split(
 
 impl OpaqueId {
     pub fn new() -> Self {
-        use std::sync::atomic::{AtomicU32, Ordering};
+        use core::sync::atomic::{AtomicU32, Ordering};
         static OPAQUE_ID: AtomicU32 = AtomicU32::new(0);
         OpaqueId(OPAQUE_ID.fetch_add(1, Ordering::SeqCst))
     }
@@ -676,6 +677,10 @@ pub enum Constructor<Cx: TypeCx> {
     Opaque(OpaqueId),
     /// Or-pattern.
     Or,
+    /// Deref patterns (enabled by the `deref_patterns` feature) match a smart pointer through its
+    /// pointee. They are a different view of the values than the constructors of the pointer type
+    /// itself, see `ConstructorSet::split`. Carries the type of the pointee.
+    DerefPattern(Cx::Ty),
     /// Wildcard pattern.
     Wildcard,
     /// Fake extra constructor for enums that aren't allowed to be matched exhaustively. Also used
@@ -709,6 +714,7 @@ fn clone(&self) -> Self {
             Constructor::Str(value) => Constructor::Str(value.clone()),
             Constructor::Opaque(inner) => Constructor::Opaque(inner.clone()),
             Constructor::Or => Constructor::Or,
+            Constructor::DerefPattern(ty) => Constructor::DerefPattern(ty.clone()),
             Constructor::Wildcard => Constructor::Wildcard,
             Constructor::NonExhaustive => Constructor::NonExhaustive,
             Constructor::Hidden => Constructor::Hidden,
@@ -799,6 +805,10 @@ pub(crate) fn is_covered_by(&self, cx: &Cx, other: &Self) -> Result<bool, Cx::Er
                 self_val == other_val
             }
             (Slice(self_slice), Slice(other_slice)) => self_slice.is_covered_by(*other_slice),
+            (DerefPattern(_), DerefPattern(_)) => true,
+            // Deref patterns and the normal constructors of the pointer type are different views
+            // of the values, see `ConstructorSet::split`.
+            (DerefPattern(_), _) | (_, DerefPattern(_)) => false,
 
             // Opaque constructors don't interact with anything unless they come from the
             // syntactically identical pattern.
@@ -908,12 +918,15 @@ pub fn split<'a>(
         let mut missing_empty = Vec::new();
         // Nonempty constructors found missing.
         let mut missing = Vec::new();
-        // Constructors in `ctors`, except wildcards and opaques.
+        // Constructors in `ctors`, except wildcards, opaques and deref patterns.
         let mut seen = Vec::new();
+        // The deref patterns of a column all have the same pointee type, so we keep only one.
+        let mut deref_pattern = None;
         for ctor in ctors.cloned() {
             match ctor {
                 Opaque(..) => present.push(ctor),
                 Wildcard => {} // discard wildcards
+                DerefPattern(..) => deref_pattern = Some(ctor),
                 _ => seen.push(ctor),
             }
         }
@@ -1042,6 +1055,18 @@ pub fn split<'a>(
             }
         }
 
+        if let Some(ctor) = deref_pattern {
+            // A deref pattern looks through the pointer: the deref patterns and the wildcards of
+            // the column match all the values of the type iff they match all the pointees, so no
+            // constructor of the pointer type itself is missing. The other constructors are still
+            // present, for the reachability of their rows.
+            // FIXME(deref_patterns): these rows don't count towards exhaustiveness, even though
+            // they could cover what the deref patterns don't.
+            present.push(ctor);
+            missing.clear();
+            missing_empty.clear();
+        }
+
         SplitConstructorSet { present, missing, missing_empty }
     }
 }
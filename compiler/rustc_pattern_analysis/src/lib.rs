@@ -1,7 +1,45 @@
 //! Analysis of patterns, notably match exhaustiveness checking.
+//!
+//! The algorithm is described in [`usefulness`]. Nothing in it is specific to Rust: it only
+//! manipulates patterns made of [constructors][`constructor::Constructor`] applied to fields, and
+//! asks a [`TypeCx`] for the information it needs about types. rustc implements `TypeCx` in
+//! [`rustc`]; other users (rust-analyzer, or the compiler of another language with pattern
+//! matching) provide their own implementation.
+//!
+//! # Using this crate outside of rustc
+//!
+//! Disable the default `rustc` feature. The crate then only depends on `core` and `alloc`, and can
+//! be used in `no_std` environments.
+//!
+//! To check a match:
+//! 1. implement [`TypeCx`] for your type context, notably [`TypeCx::ctors_for_ty`], which
+//!    describes the values of a type as a [`ConstructorSet`], and [`TypeCx::ctor_sub_tys`], which
+//!    gives the types of the fields of a constructor;
+//! 2. lower the pattern of each arm to a [`DeconstructedPat`], which is a [`Constructor`] applied
+//!    to subpatterns, and wrap it in a [`MatchArm`];
+//! 3. call [`compute_match_usefulness`]. The returned [`UsefulnessReport`] tells which arms are
+//!    redundant, and contains the patterns that the match is missing as [`WitnessPat`]s.
+//!
+//! Witnesses only use constructors and the types provided by the `TypeCx`, so they can be converted
+//! back to the user's pattern language, or printed as-is with their `Debug` impl.
+//!
+//! Example implementations of `TypeCx`, for a small set of types, can be found in the tests of this
+//! crate, in `tests/common/mod.rs`.
+//!
+//! [`usefulness`]: crate::usefulness
+//! [`rustc`]: crate::rustc
+//! [`ConstructorSet`]: crate::constructor::ConstructorSet
+//! [`Constructor`]: crate::constructor::Constructor
+//! [`DeconstructedPat`]: crate::pat::DeconstructedPat
+//! [`WitnessPat`]: crate::pat::WitnessPat
+//! [`compute_match_usefulness`]: crate::usefulness::compute_match_usefulness
+//! [`UsefulnessReport`]: crate::usefulness::UsefulnessReport
 
 #![allow(rustc::untranslatable_diagnostic)]
 #![allow(rustc::diagnostic_outside_of_impl)]
+#![cfg_attr(not(any(feature = "rustc", test)), no_std)]
+
+extern crate alloc;
 
 pub mod constructor;
 #[cfg(feature = "rustc")]
@@ -23,7 +61,7 @@
 #[cfg(feature = "rustc")]
 rustc_fluent_macro::fluent_messages! { "../messages.ftl" }
 
-use std::fmt;
+use core::fmt;
 
 #[cfg(feature = "rustc")]
 pub mod index {
@@ -35,11 +73,18 @@ pub mod index {
 #[cfg(not(feature = "rustc"))]
 pub mod index {
     // Slower version when the indices of variants are something else.
-    pub trait Idx: Copy + PartialEq + Eq + std::hash::Hash {}
-    impl<T: Copy + PartialEq + Eq + std::hash::Hash> Idx for T {}
+    use core::hash::BuildHasherDefault;
+
+    use rustc_hash::FxHasher;
+
+    pub trait Idx: Copy + PartialEq + Eq + core::hash::Hash {}
+    impl<T: Copy + PartialEq + Eq + core::hash::Hash> Idx for T {}
+
+    type FxHashMap<K, V> = hashbrown::HashMap<K, V, BuildHasherDefault<FxHasher>>;
+    type FxHashSet<T> = hashbrown::HashSet<T, BuildHasherDefault<FxHasher>>;
 
     #[derive(Debug)]
-    pub struct IdxContainer<K, V>(pub rustc_hash::FxHashMap<K, V>);
+    pub struct IdxContainer<K, V>(pub FxHashMap<K, V>);
     impl<K: Idx, V> IdxContainer<K, V> {
         pub fn len(&self) -> usize {
             self.0.len()
@@ -50,7 +95,7 @@ pub fn iter_enumerated(&self) -> impl Iterator<Item = (K, &V)> {
     }
 
     #[derive(Debug)]
-    pub struct IdxSet<T>(pub rustc_hash::FxHashSet<T>);
+    pub struct IdxSet<T>(pub FxHashSet<T>);
     impl<T: Idx> IdxSet<T> {
         pub fn new_empty(_len: usize) -> Self {
             Self(Default::default())
@@ -84,7 +129,8 @@ impl<'a, T: ?Sized> Captures<'a> for T {}
 
 /// Context that provides type information about constructors.
 ///
-/// Most of the crate is parameterized on a type that implements this trait.
+/// Most of the crate is parameterized on a type that implements this trait. This is the only thing
+/// a user of this crate needs to implement; see the crate-level docs for an overview.
 pub trait TypeCx: Sized + fmt::Debug {
     /// The type of a pattern.
     type Ty: Clone + fmt::Debug;
@@ -99,7 +145,11 @@ pub trait TypeCx: Sized + fmt::Debug {
     /// Extra data to store in a pattern.
     type PatData: Clone;
 
+    /// Whether empty types are considered uninhabited everywhere, so that matching on them is not
+    /// required. This is rustc's `exhaustive_patterns` feature; other users can return `false`.
     fn is_exhaustive_patterns_feature_on(&self) -> bool;
+    /// Whether empty types are considered uninhabited behind places that are known to contain a
+    /// valid value. This is rustc's `min_exhaustive_patterns` feature.
     fn is_min_exhaustive_patterns_feature_on(&self) -> bool;
 
     /// The number of fields for this constructor.
@@ -118,11 +168,13 @@ fn ctor_sub_tys<'a>(
     /// This must follow the invariants of `ConstructorSet`
     fn ctors_for_ty(&self, ty: &Self::Ty) -> Result<ConstructorSet<Self>, Self::Error>;
 
-    /// Write the name of the variant represented by `pat`. Used for the best-effort `Debug` impl of
-    /// `DeconstructedPat`. Only invoqued when `pat.ctor()` is `Struct | Variant(_) | UnionField`.
+    /// Write the name of the variant represented by `ctor` for type `ty`. Used for the best-effort
+    /// `Debug` impls of `DeconstructedPat` and `WitnessPat`. Only invoked when `ctor` is
+    /// `Struct | Variant(_) | UnionField`.
     fn write_variant_name(
         f: &mut fmt::Formatter<'_>,
-        pat: &crate::pat::DeconstructedPat<Self>,
+        ctor: &Constructor<Self>,
+        ty: &Self::Ty,
     ) -> fmt::Result;
 
     /// Raise a bug.
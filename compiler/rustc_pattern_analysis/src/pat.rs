@@ -1,7 +1,8 @@
 //! As explained in [`crate::usefulness`], values and patterns are made from constructors applied to
 //! fields. This file defines types that represent patterns in this way.
-use std::cell::Cell;
-use std::fmt;
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::fmt;
 
 use smallvec::{smallvec, SmallVec};
 
@@ -144,78 +145,93 @@ fn collect_redundant_subpatterns<'a>(&'a self, subpats: &mut Vec<&'a Self>) {
     }
 }
 
-/// This is best effort and not good enough for a `Display` impl.
 impl<Cx: TypeCx> fmt::Debug for DeconstructedPat<Cx> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let pat = self;
-        let mut first = true;
-        let mut start_or_continue = |s| {
-            if first {
-                first = false;
-                ""
-            } else {
-                s
-            }
-        };
-        let mut start_or_comma = || start_or_continue(", ");
+        write_pat(f, self.ctor(), self.ty(), self.iter_fields())
+    }
+}
 
-        match pat.ctor() {
-            Struct | Variant(_) | UnionField => {
-                Cx::write_variant_name(f, pat)?;
-                // Without `cx`, we can't know which field corresponds to which, so we can't
-                // get the names of the fields. Instead we just display everything as a tuple
-                // struct, which should be good enough.
-                write!(f, "(")?;
-                for p in pat.iter_fields() {
-                    write!(f, "{}", start_or_comma())?;
-                    write!(f, "{p:?}")?;
-                }
-                write!(f, ")")
-            }
-            // Note: given the expansion of `&str` patterns done in `expand_pattern`, we should
-            // be careful to detect strings here. However a string literal pattern will never
-            // be reported as a non-exhaustiveness witness, so we can ignore this issue.
-            Ref => {
-                let subpattern = pat.iter_fields().next().unwrap();
-                write!(f, "&{:?}", subpattern)
+/// Writes a pattern made of `ctor` applied to `fields`. This is shared between `DeconstructedPat`
+/// and `WitnessPat`, so that witnesses can be reported without knowing anything about the types
+/// involved beyond what `TypeCx` tells us.
+///
+/// This is best effort and not good enough for a `Display` impl.
+fn write_pat<'a, Cx: TypeCx, P: fmt::Debug + 'a>(
+    f: &mut fmt::Formatter<'_>,
+    ctor: &Constructor<Cx>,
+    ty: &Cx::Ty,
+    mut fields: impl Iterator<Item = &'a P>,
+) -> fmt::Result {
+    let mut first = true;
+    let mut start_or_continue = |s| {
+        if first {
+            first = false;
+            ""
+        } else {
+            s
+        }
+    };
+    let mut start_or_comma = || start_or_continue(", ");
+
+    match ctor {
+        Struct | Variant(_) | UnionField => {
+            Cx::write_variant_name(f, ctor, ty)?;
+            // Without `cx`, we can't know which field corresponds to which, so we can't
+            // get the names of the fields. Instead we just display everything as a tuple
+            // struct, which should be good enough.
+            write!(f, "(")?;
+            for p in fields {
+                write!(f, "{}", start_or_comma())?;
+                write!(f, "{p:?}")?;
             }
-            Slice(slice) => {
-                let mut subpatterns = pat.iter_fields();
-                write!(f, "[")?;
-                match slice.kind {
-                    SliceKind::FixedLen(_) => {
-                        for p in subpatterns {
-                            write!(f, "{}{:?}", start_or_comma(), p)?;
-                        }
+            write!(f, ")")
+        }
+        // Note: given the expansion of `&str` patterns done in `expand_pattern`, we should
+        // be careful to detect strings here. However a string literal pattern will never
+        // be reported as a non-exhaustiveness witness, so we can ignore this issue.
+        Ref => {
+            let subpattern = fields.next().unwrap();
+            write!(f, "&{:?}", subpattern)
+        }
+        DerefPattern(_) => {
+            let subpattern = fields.next().unwrap();
+            write!(f, "box {:?}", subpattern)
+        }
+        Slice(slice) => {
+            write!(f, "[")?;
+            match slice.kind {
+                SliceKind::FixedLen(_) => {
+                    for p in fields {
+                        write!(f, "{}{:?}", start_or_comma(), p)?;
                     }
-                    SliceKind::VarLen(prefix_len, _) => {
-                        for p in subpatterns.by_ref().take(prefix_len) {
-                            write!(f, "{}{:?}", start_or_comma(), p)?;
-                        }
-                        write!(f, "{}", start_or_comma())?;
-                        write!(f, "..")?;
-                        for p in subpatterns {
-                            write!(f, "{}{:?}", start_or_comma(), p)?;
-                        }
+                }
+                SliceKind::VarLen(prefix_len, _) => {
+                    for p in fields.by_ref().take(prefix_len) {
+                        write!(f, "{}{:?}", start_or_comma(), p)?;
+                    }
+                    write!(f, "{}", start_or_comma())?;
+                    write!(f, "..")?;
+                    for p in fields {
+                        write!(f, "{}{:?}", start_or_comma(), p)?;
                     }
                 }
-                write!(f, "]")
             }
-            Bool(b) => write!(f, "{b}"),
-            // Best-effort, will render signed ranges incorrectly
-            IntRange(range) => write!(f, "{range:?}"),
-            F32Range(lo, hi, end) => write!(f, "{lo}{end}{hi}"),
-            F64Range(lo, hi, end) => write!(f, "{lo}{end}{hi}"),
-            Str(value) => write!(f, "{value:?}"),
-            Opaque(..) => write!(f, "<constant pattern>"),
-            Or => {
-                for pat in pat.iter_fields() {
-                    write!(f, "{}{:?}", start_or_continue(" | "), pat)?;
-                }
-                Ok(())
+            write!(f, "]")
+        }
+        Bool(b) => write!(f, "{b}"),
+        // Best-effort, will render signed ranges incorrectly
+        IntRange(range) => write!(f, "{range:?}"),
+        F32Range(lo, hi, end) => write!(f, "{lo}{end}{hi}"),
+        F64Range(lo, hi, end) => write!(f, "{lo}{end}{hi}"),
+        Str(value) => write!(f, "{value:?}"),
+        Opaque(..) => write!(f, "<constant pattern>"),
+        Or => {
+            for pat in fields {
+                write!(f, "{}{:?}", start_or_continue(" | "), pat)?;
             }
-            Wildcard | Missing { .. } | NonExhaustive | Hidden => write!(f, "_ : {:?}", pat.ty()),
+            Ok(())
         }
+        Wildcard | Missing { .. } | NonExhaustive | Hidden => write!(f, "_ : {:?}", ty),
     }
 }
 
@@ -303,7 +319,6 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 
 /// Same idea as `DeconstructedPat`, except this is a fictitious pattern built up for diagnostics
 /// purposes. As such they don't use interning and can be cloned.
-#[derive(Debug)]
 pub struct WitnessPat<Cx: TypeCx> {
     ctor: Constructor<Cx>,
     pub(crate) fields: Vec<WitnessPat<Cx>>,
@@ -343,3 +358,11 @@ pub fn iter_fields(&self) -> impl Iterator<Item = &WitnessPat<Cx>> {
         self.fields.iter()
     }
 }
+
+/// Witnesses are printed the same way as `DeconstructedPat`s, which is enough for users of this
+/// crate to report them. rustc instead converts them back to `thir::Pat`s.
+impl<Cx: TypeCx> fmt::Debug for WitnessPat<Cx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_pat(f, self.ctor(), self.ty(), self.iter_fields())
+    }
+}
@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use crate::constructor::{Constructor, SplitConstructorSet};
 use crate::pat::{DeconstructedPat, PatOrWild};
 use crate::{Captures, MatchArm, TypeCx};
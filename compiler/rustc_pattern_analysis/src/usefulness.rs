@@ -713,9 +713,13 @@
 //! I (Nadrieril) prefer to put new tests in `ui/pattern/usefulness` unless there's a specific
 //! reason not to, for example if they crucially depend on a particular feature like `or_patterns`.
 
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
 use rustc_index::bit_set::BitSet;
 use smallvec::{smallvec, SmallVec};
-use std::fmt;
 
 use crate::constructor::{Constructor, ConstructorSet, IntRange};
 use crate::pat::{DeconstructedPat, PatOrWild, WitnessPat};
@@ -800,10 +804,12 @@ fn is_known_valid(self) -> bool {
     /// fields.
     ///
     /// Pending further opsem decisions, the current behavior is: validity is preserved, except
-    /// inside `&` and union fields where validity is reset to `MaybeInvalid`.
+    /// inside `&`, union fields and deref patterns where validity is reset to `MaybeInvalid`.
     fn specialize<Cx: TypeCx>(self, ctor: &Constructor<Cx>) -> Self {
-        // We preserve validity except when we go inside a reference or a union field.
-        if matches!(ctor, Constructor::Ref | Constructor::UnionField) {
+        // We preserve validity except when we go inside a reference or a union field. Deref
+        // patterns go through a reference too.
+        if matches!(ctor, Constructor::Ref | Constructor::UnionField | Constructor::DerefPattern(_))
+        {
             // Validity of `x: &T` does not imply validity of `*x: T`.
             MaybeInvalid
         } else {
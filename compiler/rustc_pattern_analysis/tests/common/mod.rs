@@ -0,0 +1,171 @@
+//! An example implementation of `TypeCx` for a small language with booleans, bytes, tuples and
+//! enums, along with helpers to build patterns and check matches.
+//!
+//! This is meant as a starting point for users of this crate outside of rustc: nothing here depends
+//! on rustc types.
+
+use std::fmt;
+
+use rustc_pattern_analysis::constructor::{
+    Constructor, ConstructorSet, IntRange, MaybeInfiniteInt, RangeEnd, VariantVisibility,
+};
+use rustc_pattern_analysis::pat::DeconstructedPat;
+use rustc_pattern_analysis::usefulness::{
+    compute_match_usefulness, UsefulnessReport, ValidityConstraint,
+};
+use rustc_pattern_analysis::{Captures, MatchArm, TypeCx};
+
+/// The types of our language. Every type here is inhabited, so we never need to report empty
+/// constructors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ty {
+    Bool,
+    U8,
+    Tuple(&'static [Ty]),
+    /// An enum, given by the field types of each of its variants. Must have at least one variant.
+    Enum(&'static [&'static [Ty]]),
+}
+
+impl Ty {
+    fn sub_tys(&self, ctor: &Constructor<Cx>) -> Vec<Ty> {
+        match (ctor, *self) {
+            (Constructor::Struct, Ty::Tuple(tys)) => tys.to_vec(),
+            (Constructor::Variant(idx), Ty::Enum(variants)) => variants[*idx].to_vec(),
+            (
+                Constructor::Bool(..)
+                | Constructor::IntRange(..)
+                | Constructor::NonExhaustive
+                | Constructor::Hidden
+                | Constructor::Missing
+                | Constructor::Wildcard,
+                _,
+            ) => vec![],
+            _ => panic!("unexpected constructor {ctor:?} for type {self:?}"),
+        }
+    }
+
+    fn ctor_set(&self) -> ConstructorSet<Cx> {
+        match *self {
+            Ty::Bool => ConstructorSet::Bool,
+            Ty::U8 => ConstructorSet::Integers {
+                range_1: IntRange::from_range(
+                    MaybeInfiniteInt::new_finite_uint(0),
+                    MaybeInfiniteInt::new_finite_uint(u8::MAX.into()),
+                    RangeEnd::Included,
+                ),
+                range_2: None,
+            },
+            Ty::Tuple(..) => ConstructorSet::Struct { empty: false },
+            Ty::Enum(variants) => {
+                #[cfg(feature = "rustc")]
+                let variants = variants.iter().map(|_| VariantVisibility::Visible).collect();
+                #[cfg(not(feature = "rustc"))]
+                let variants = rustc_pattern_analysis::index::IdxContainer(
+                    (0..variants.len()).map(|idx| (idx, VariantVisibility::Visible)).collect(),
+                );
+                ConstructorSet::Variants { variants, non_exhaustive: false }
+            }
+        }
+    }
+}
+
+/// The type context. Our types carry all the information we need, so this is empty.
+#[derive(Debug)]
+pub struct Cx;
+
+impl TypeCx for Cx {
+    type Ty = Ty;
+    type Error = ();
+    type VariantIdx = usize;
+    type StrLit = ();
+    type ArmData = ();
+    type PatData = ();
+
+    fn is_exhaustive_patterns_feature_on(&self) -> bool {
+        false
+    }
+
+    fn is_min_exhaustive_patterns_feature_on(&self) -> bool {
+        false
+    }
+
+    fn ctor_arity(&self, ctor: &Constructor<Self>, ty: &Self::Ty) -> usize {
+        ty.sub_tys(ctor).len()
+    }
+
+    fn ctor_sub_tys<'a>(
+        &'a self,
+        ctor: &'a Constructor<Self>,
+        ty: &'a Self::Ty,
+    ) -> impl Iterator<Item = Self::Ty> + ExactSizeIterator + Captures<'a> {
+        ty.sub_tys(ctor).into_iter()
+    }
+
+    fn ctors_for_ty(&self, ty: &Self::Ty) -> Result<ConstructorSet<Self>, Self::Error> {
+        Ok(ty.ctor_set())
+    }
+
+    fn write_variant_name(
+        f: &mut fmt::Formatter<'_>,
+        ctor: &Constructor<Self>,
+        _ty: &Self::Ty,
+    ) -> fmt::Result {
+        match ctor {
+            // Tuples have no name.
+            Constructor::Struct => Ok(()),
+            Constructor::Variant(idx) => write!(f, "Variant{idx}"),
+            _ => unreachable!(),
+        }
+    }
+
+    fn bug(&self, fmt: fmt::Arguments<'_>) -> Self::Error {
+        panic!("{fmt}")
+    }
+}
+
+pub fn pat(
+    ctor: Constructor<Cx>,
+    fields: Vec<DeconstructedPat<Cx>>,
+    ty: Ty,
+) -> DeconstructedPat<Cx> {
+    DeconstructedPat::new(ctor, fields, ty, ())
+}
+
+pub fn wild(ty: Ty) -> DeconstructedPat<Cx> {
+    pat(Constructor::Wildcard, vec![], ty)
+}
+
+pub fn bool(b: bool) -> DeconstructedPat<Cx> {
+    pat(Constructor::Bool(b), vec![], Ty::Bool)
+}
+
+/// The pattern `lo..=hi`.
+pub fn u8_range(lo: u8, hi: u8) -> DeconstructedPat<Cx> {
+    let range = IntRange::from_range(
+        MaybeInfiniteInt::new_finite_uint(lo.into()),
+        MaybeInfiniteInt::new_finite_uint(hi.into()),
+        RangeEnd::Included,
+    );
+    pat(Constructor::IntRange(range), vec![], Ty::U8)
+}
+
+pub fn tuple(ty: Ty, fields: Vec<DeconstructedPat<Cx>>) -> DeconstructedPat<Cx> {
+    pat(Constructor::Struct, fields, ty)
+}
+
+pub fn variant(ty: Ty, idx: usize, fields: Vec<DeconstructedPat<Cx>>) -> DeconstructedPat<Cx> {
+    pat(Constructor::Variant(idx), fields, ty)
+}
+
+/// Checks a match on a value of type `ty` whose arms have the patterns `pats`, and no guards.
+pub fn check(ty: Ty, pats: &[DeconstructedPat<Cx>]) -> UsefulnessReport<'_, Cx> {
+    let arms: Vec<_> =
+        pats.iter().map(|pat| MatchArm { pat, has_guard: false, arm_data: () }).collect();
+    compute_match_usefulness(&Cx, &arms, ty, ValidityConstraint::ValidOnly).unwrap()
+}
+
+/// The patterns missing from a match, as printed by their `Debug` impl.
+pub fn witnesses(ty: Ty, pats: &[DeconstructedPat<Cx>]) -> Vec<String> {
+    let report = check(ty, pats);
+    report.non_exhaustiveness_witnesses.iter().map(|witness| format!("{witness:?}")).collect()
+}
@@ -0,0 +1,47 @@
+//! Checks exhaustiveness and redundancy with the example `TypeCx` of `common`.
+
+use rustc_pattern_analysis::usefulness::Usefulness;
+
+mod common;
+
+use common::*;
+
+#[test]
+fn bools() {
+    assert_eq!(witnesses(Ty::Bool, &[bool(true)]), ["false"]);
+    assert!(witnesses(Ty::Bool, &[bool(true), bool(false)]).is_empty());
+    assert!(witnesses(Ty::Bool, &[wild(Ty::Bool)]).is_empty());
+}
+
+#[test]
+fn ints() {
+    assert_eq!(witnesses(Ty::U8, &[u8_range(0, 254)]), ["255"]);
+    assert!(witnesses(Ty::U8, &[u8_range(0, 99), u8_range(100, 255)]).is_empty());
+}
+
+#[test]
+fn tuples() {
+    const TY: Ty = Ty::Tuple(&[Ty::Bool, Ty::Bool]);
+    let pats =
+        [tuple(TY, vec![bool(true), wild(Ty::Bool)]), tuple(TY, vec![wild(Ty::Bool), bool(true)])];
+    assert_eq!(witnesses(TY, &pats), ["(false, false)"]);
+}
+
+#[test]
+fn enums() {
+    const TY: Ty = Ty::Enum(&[&[], &[Ty::Bool]]);
+    let pats = [variant(TY, 0, vec![]), variant(TY, 1, vec![bool(true)])];
+    assert_eq!(witnesses(TY, &pats), ["Variant1(false)"]);
+}
+
+#[test]
+fn redundancy() {
+    let pats = [bool(true), bool(false), wild(Ty::Bool)];
+    let report = check(Ty::Bool, &pats);
+    let redundant: Vec<bool> = report
+        .arm_usefulness
+        .iter()
+        .map(|(_, usefulness)| matches!(usefulness, Usefulness::Redundant))
+        .collect();
+    assert_eq!(redundant, [false, false, true]);
+}
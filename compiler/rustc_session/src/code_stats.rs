@@ -85,9 +85,58 @@ pub struct VTableSizeInfo {
     pub upcasting_cost_percent: f64,
 }
 
+/// Why a local saved across suspension points could not share its bytes with other saved locals.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum PrefixReason {
+    /// The local is live across more than one suspension point.
+    MultipleSuspensionPoints,
+    /// The local is storage-live at the same time as this other local, which is saved at another
+    /// suspension point.
+    StorageConflict(Symbol),
+    /// The locals saved at all other suspension points are already in the prefix, so there is
+    /// nothing to overlap with.
+    NothingToOverlap,
+}
+
+/// Where a local saved across suspension points is stored in the coroutine.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum SavedLocalPlacement {
+    /// The local is stored in the prefix shared by all variants of the coroutine.
+    Prefix(PrefixReason),
+    /// The local is only stored in the variant of the suspension point `variant`, and shares its
+    /// bytes with the locals `overlaps_with` of other suspension points.
+    Overlapped { variant: String, overlaps_with: Vec<Symbol> },
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SavedLocalInfo {
+    pub name: Symbol,
+    pub type_description: String,
+    pub size: u64,
+    /// The names of the suspension points the local is live across.
+    pub live_across: Vec<String>,
+    pub placement: SavedLocalPlacement,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SuspensionPointInfo {
+    pub name: String,
+    pub span: String,
+}
+
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct CoroutineLayoutInfo {
+    pub type_description: String,
+    pub align: u64,
+    pub overall_size: u64,
+    pub suspension_points: Vec<SuspensionPointInfo>,
+    pub saved_locals: Vec<SavedLocalInfo>,
+}
+
 #[derive(Default)]
 pub struct CodeStats {
     type_sizes: Lock<FxHashSet<TypeSizeInfo>>,
+    coroutine_layouts: Lock<FxHashSet<CoroutineLayoutInfo>>,
     vtable_sizes: Lock<FxHashMap<DefId, VTableSizeInfo>>,
 }
 
@@ -122,6 +171,10 @@ pub fn record_type_size<S: ToString>(
         self.type_sizes.borrow_mut().insert(info);
     }
 
+    pub fn record_coroutine_layout(&self, info: CoroutineLayoutInfo) {
+        self.coroutine_layouts.borrow_mut().insert(info);
+    }
+
     pub fn record_vtable_size(&self, trait_did: DefId, trait_name: &str, info: VTableSizeInfo) {
         let prev = self.vtable_sizes.lock().insert(trait_did, info);
         assert!(
@@ -228,6 +281,77 @@ pub fn print_type_sizes(&self) {
         }
     }
 
+    pub fn print_coroutine_layouts(&self) {
+        let coroutine_layouts = self.coroutine_layouts.borrow();
+        // We will soon sort, so the initial order does not matter.
+        #[allow(rustc::potential_query_instability)]
+        let mut sorted: Vec<_> = coroutine_layouts.iter().collect();
+
+        // Primary sort: large-to-small.
+        // Secondary sort: description (dictionary order)
+        sorted.sort_by_key(|info| (cmp::Reverse(info.overall_size), &info.type_description));
+
+        for info in sorted {
+            let CoroutineLayoutInfo {
+                type_description,
+                overall_size,
+                align,
+                suspension_points,
+                saved_locals,
+            } = info;
+            println!(
+                "print-coroutine-layout coroutine: `{type_description}`: {overall_size} bytes, alignment: {align} bytes"
+            );
+            let indent = "    ";
+            for SuspensionPointInfo { name, span } in suspension_points {
+                println!("print-coroutine-layout {indent}suspension point `{name}`: {span}");
+            }
+
+            for local in saved_locals {
+                let SavedLocalInfo { name, type_description, size, live_across, placement } = local;
+                println!(
+                    "print-coroutine-layout {indent}local `.{name}`: {size} bytes, type: `{type_description}`"
+                );
+                let indent = "        ";
+                let live_across =
+                    live_across.iter().map(|name| format!("`{name}`")).collect::<Vec<_>>();
+                println!("print-coroutine-layout {indent}live across: {}", live_across.join(", "));
+                match placement {
+                    SavedLocalPlacement::Prefix(reason) => {
+                        let reason = match reason {
+                            PrefixReason::MultipleSuspensionPoints => {
+                                "live across more than one suspension point".to_string()
+                            }
+                            PrefixReason::StorageConflict(other) => format!(
+                                "storage-live at the same time as `.{other}` of another suspension point"
+                            ),
+                            PrefixReason::NothingToOverlap => {
+                                "no locals of other suspension points to overlap with".to_string()
+                            }
+                        };
+                        println!("print-coroutine-layout {indent}stored in the prefix: {reason}");
+                    }
+                    SavedLocalPlacement::Overlapped { variant, overlaps_with } => {
+                        if overlaps_with.is_empty() {
+                            println!(
+                                "print-coroutine-layout {indent}stored in `{variant}`, not overlapping other locals"
+                            );
+                        } else {
+                            let overlaps_with = overlaps_with
+                                .iter()
+                                .map(|name| format!("`.{name}`"))
+                                .collect::<Vec<_>>();
+                            println!(
+                                "print-coroutine-layout {indent}stored in `{variant}`, overlapping {}",
+                                overlaps_with.join(", ")
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     pub fn print_vtable_sizes(&self, crate_name: Symbol) {
         // We will soon sort, so the initial order does not matter.
         #[allow(rustc::potential_query_instability)]
@@ -782,7 +782,7 @@ pub fn opt_path(&self) -> Option<&Path> {
                 FileName::ProcMacroSourceCode(_) => None,
                 FileName::CliCrateAttr(_) => None,
                 FileName::Custom(_) => None,
-                FileName::DocTest(path, _) => Some(path),
+                FileName::DocTest(path, _, _) => Some(path),
                 FileName::InlineAsm(_) => None,
             },
         }
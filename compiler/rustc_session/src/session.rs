@@ -1,8 +1,8 @@
 use crate::code_stats::CodeStats;
 pub use crate::code_stats::{DataTypeKind, FieldInfo, FieldKind, SizeKind, VariantInfo};
 use crate::config::{
-    self, CrateType, FunctionReturn, InstrumentCoverage, OptLevel, OutFileName, OutputType,
-    RemapPathScopeComponents, SwitchWithOptPath,
+    self, CrateType, DumpSolverProofTree, FunctionReturn, InstrumentCoverage, OptLevel,
+    OutFileName, OutputType, RemapPathScopeComponents, SwitchWithOptPath,
 };
 use crate::config::{ErrorOutputType, Input};
 use crate::errors;
@@ -356,6 +356,19 @@ pub fn enable_internal_lints(&self) -> bool {
         self.unstable_options() && !self.opts.actually_rustdoc
     }
 
+    /// Which proof trees of the next trait solver to dump. Writing them into
+    /// `-Zdump-solver-proof-trees` without selecting any dumps those of the errors.
+    pub fn dump_solver_proof_tree(&self) -> DumpSolverProofTree {
+        match self.opts.unstable_opts.next_solver.map(|c| c.dump_tree) {
+            Some(DumpSolverProofTree::Never)
+                if self.opts.unstable_opts.dump_solver_proof_trees.is_some() =>
+            {
+                DumpSolverProofTree::OnError
+            }
+            dump_tree => dump_tree.unwrap_or_default(),
+        }
+    }
+
     pub fn instrument_coverage(&self) -> bool {
         self.opts.cg.instrument_coverage() != InstrumentCoverage::Off
     }
@@ -291,7 +291,10 @@ pub enum FileName {
     CliCrateAttr(Hash64),
     /// Custom sources for explicit parser calls from plugins and drivers.
     Custom(String),
-    DocTest(PathBuf, isize),
+    /// Code extracted from the documentation in the given file by `rustdoc --test`. The line
+    /// offset is added to the lines of positions in the doctest to get positions in the original
+    /// file, and the byte column offset of each line, if known, to their columns.
+    DocTest(PathBuf, isize, Vec<usize>),
     /// Post-substitution inline assembly from LLVM.
     InlineAsm(Hash64),
 }
@@ -333,7 +336,7 @@ fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             ProcMacroSourceCode(_) => write!(fmt, "<proc-macro source code>"),
             CliCrateAttr(_) => write!(fmt, "<crate attribute>"),
             Custom(ref s) => write!(fmt, "<{s}>"),
-            DocTest(ref path, _) => write!(fmt, "{}", path.display()),
+            DocTest(ref path, _, _) => write!(fmt, "{}", path.display()),
             InlineAsm(_) => write!(fmt, "<inline asm>"),
         }
     }
@@ -359,7 +362,7 @@ pub fn is_real(&self) -> bool {
             | CliCrateAttr(_)
             | Custom(_)
             | QuoteExpansion(_)
-            | DocTest(_, _)
+            | DocTest(_, _, _)
             | InlineAsm(_) => false,
         }
     }
@@ -408,8 +411,8 @@ pub fn cli_crate_attr_source_code(src: &str) -> FileName {
         FileName::CliCrateAttr(hasher.finish())
     }
 
-    pub fn doc_test_source_code(path: PathBuf, line: isize) -> FileName {
-        FileName::DocTest(path, line)
+    pub fn doc_test_source_code(path: PathBuf, line: isize, cols: Vec<usize>) -> FileName {
+        FileName::DocTest(path, line, cols)
     }
 
     pub fn inline_asm_source_code(src: &str) -> FileName {
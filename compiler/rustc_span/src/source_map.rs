@@ -10,6 +10,7 @@
 //! information, source code snippets, etc.
 
 use crate::*;
+use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::sync::{IntoDynSyncSend, MappedReadGuard, ReadGuard, RwLock};
 use rustc_data_structures::unhash::UnhashMap;
 use std::fs;
@@ -176,6 +177,10 @@ pub struct SourceMap {
 
     /// The algorithm used for hashing the contents of each source file.
     hash_kind: SourceFileHashAlgorithm,
+
+    /// Files which are named after the doctest they contain when loaded, see
+    /// [`SourceMap::register_doctest_file`].
+    doctest_files: RwLock<FxHashMap<PathBuf, FileName>>,
 }
 
 impl SourceMap {
@@ -197,6 +202,7 @@ pub fn with_file_loader_and_hash_kind(
             file_loader: IntoDynSyncSend(file_loader),
             path_mapping,
             hash_kind,
+            doctest_files: Default::default(),
         }
     }
 
@@ -210,10 +216,21 @@ pub fn file_exists(&self, path: &Path) -> bool {
 
     pub fn load_file(&self, path: &Path) -> io::Result<Lrc<SourceFile>> {
         let src = self.file_loader.read_file(path)?;
-        let filename = path.to_owned().into();
+        let filename = match self.doctest_files.borrow().get(path) {
+            Some(filename) => filename.clone(),
+            None => path.to_owned().into(),
+        };
         Ok(self.new_source_file(filename, src))
     }
 
+    /// Names the file at `path` after a doctest from `doctest_path` once it's loaded, with its
+    /// lines offset by `line`, like the doctests rustdoc passes through stdin. This is used by
+    /// rustdoc to compile several doctests, each in their own module file, into a single crate.
+    pub fn register_doctest_file(&self, path: PathBuf, doctest_path: PathBuf, line: isize) {
+        let filename = FileName::doc_test_source_code(doctest_path, line, Vec::new());
+        self.doctest_files.borrow_mut().insert(path, filename);
+    }
+
     /// Loads source file as a binary blob.
     ///
     /// Unlike `load_file`, guarantees that no normalization like BOM-removal
@@ -345,7 +362,7 @@ pub fn new_imported_source_file(
     /// If there is a doctest offset, applies it to the line.
     pub fn doctest_offset_line(&self, file: &FileName, orig: usize) -> usize {
         match file {
-            FileName::DocTest(_, offset) => {
+            FileName::DocTest(_, offset, _) => {
                 if *offset < 0 {
                     orig - (-(*offset)) as usize
                 } else {
@@ -356,6 +373,28 @@ pub fn doctest_offset_line(&self, file: &FileName, orig: usize) -> usize {
         }
     }
 
+    /// Like [`SourceMap::doctest_offset_line`], but only for the module files of merged doctests,
+    /// see [`SourceMap::register_doctest_file`]. Doctests compiled on their own keep reporting
+    /// lines of the generated code at runtime, e.g. in panic messages.
+    pub fn merged_doctest_offset_line(&self, file: &FileName, orig: usize) -> usize {
+        if self.doctest_files.borrow().values().any(|name| name == file) {
+            self.doctest_offset_line(file, orig)
+        } else {
+            orig
+        }
+    }
+
+    /// If there is a doctest offset for the given line, applies it to the byte column. The line
+    /// is the 1-based line in the doctest, before applying its own offset.
+    pub fn doctest_offset_col(&self, file: &FileName, line: usize, orig: usize) -> usize {
+        match file {
+            FileName::DocTest(_, _, offsets) => {
+                orig + line.checked_sub(1).and_then(|i| offsets.get(i)).copied().unwrap_or(0)
+            }
+            _ => orig,
+        }
+    }
+
     /// Return the SourceFile that contains the given `BytePos`
     pub fn lookup_source_file(&self, pos: BytePos) -> Lrc<SourceFile> {
         let idx = self.lookup_source_file_idx(pos);
@@ -141,6 +141,7 @@
         AssertParamIsClone,
         AssertParamIsCopy,
         AssertParamIsEq,
+        Assume,
         AsyncGenFinished,
         AsyncGenPending,
         AsyncGenReady,
@@ -161,6 +162,7 @@
         BTreeEntry,
         BTreeMap,
         BTreeSet,
+        BikeshedIntrinsicFrom,
         BinaryHeap,
         Borrow,
         BorrowMut,
@@ -312,6 +314,7 @@
         ToString,
         TokenStream,
         Trait,
+        TransmuteFrom,
         Try,
         TryCaptureGeneric,
         TryCapturePrintable,
@@ -329,9 +332,11 @@
         Yield,
         _DECLS,
         _Self,
+        __ASSUME,
         __D,
         __H,
         __S,
+        __Src,
         __awaitee,
         __try_var,
         _d,
@@ -573,6 +578,7 @@
         const_indexing,
         const_let,
         const_loop,
+        const_make_global,
         const_mut_refs,
         const_panic,
         const_panic_fmt,
@@ -663,6 +669,8 @@
         deref_method,
         deref_mut,
         deref_mut_method,
+        deref_patterns,
+        deref_pure,
         deref_target,
         derive,
         derive_const,
@@ -1005,6 +1013,7 @@
         maybe_uninit,
         maybe_uninit_uninit,
         maybe_uninit_zeroed,
+        mem,
         mem_discriminant,
         mem_drop,
         mem_forget,
@@ -17,6 +17,8 @@ trait_selection_closure_kind_mismatch = expected a closure that implements the `
 
 trait_selection_closure_kind_requirement = the requirement to implement `{$expected}` derives from here
 
+trait_selection_couldnt_dump_proof_tree = unexpected error occurred while dumping a proof tree into `{$path}`: {$error}
+
 trait_selection_dump_vtable_entries = vtable entries for `{$trait_ref}`: {$entries}
 
 trait_selection_empty_on_clause_in_rustc_on_unimplemented = empty `on`-clause in `#[rustc_on_unimplemented]`
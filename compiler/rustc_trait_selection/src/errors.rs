@@ -6,6 +6,14 @@
 use rustc_macros::Diagnostic;
 use rustc_middle::ty::{self, ClosureKind, PolyTraitRef, Ty};
 use rustc_span::{Span, Symbol};
+use std::path::PathBuf;
+
+#[derive(Diagnostic)]
+#[diag(trait_selection_couldnt_dump_proof_tree)]
+pub struct CouldntDumpProofTree {
+    pub path: PathBuf,
+    pub error: String,
+}
 
 #[derive(Diagnostic)]
 #[diag(trait_selection_dump_vtable_entries)]
@@ -0,0 +1,221 @@
+//! Dumping proof trees for `-Znext-solver=dump-tree` and `dump-tree-on-error`.
+//!
+//! By default the trees are printed to stdout with their `Debug` impl. With
+//! `-Zdump-solver-proof-trees=<dir>`, each tree is instead written into `<dir>` as JSON and as a
+//! self-contained HTML page which shows it as a collapsible tree.
+
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::path::Path;
+
+use rustc_data_structures::fx::FxHasher;
+use rustc_middle::traits::solve::inspect::{
+    AddedGoalsEvaluation, CanonicalGoalEvaluation, CanonicalGoalEvaluationKind, GoalEvaluation,
+    GoalEvaluationKind, Probe, ProbeKind, ProbeStep,
+};
+use rustc_middle::traits::solve::{
+    CandidateSource, Certainty, GoalSource, IsNormalizesToHack, MaybeCause, NoSolution, QueryResult,
+};
+use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+
+use crate::errors::CouldntDumpProofTree;
+
+/// The viewer, into which the JSON of a proof tree is embedded.
+const VIEWER: &str = include_str!("viewer.html");
+
+pub(crate) fn dump_proof_tree<'tcx>(tcx: TyCtxt<'tcx>, tree: &GoalEvaluation<'tcx>) {
+    let Some(dir) = &tcx.sess.opts.unstable_opts.dump_solver_proof_trees else {
+        let mut lock = std::io::stdout().lock();
+        let _ = lock.write_fmt(format_args!("{tree:?}\n"));
+        let _ = lock.flush();
+        return;
+    };
+
+    if let Err(error) = write_proof_tree(tcx, dir, tree) {
+        tcx.dcx().emit_warn(CouldntDumpProofTree { path: dir.clone(), error: error.to_string() });
+    }
+}
+
+fn write_proof_tree<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    dir: &Path,
+    tree: &GoalEvaluation<'tcx>,
+) -> io::Result<()> {
+    let tree = with_no_trimmed_paths!(GoalNode::new(tcx, tree));
+    let json = serde_json::to_string(&tree)?;
+
+    // Name the files after their contents, so that evaluating the same goal in the same way again
+    // doesn't produce another copy.
+    let mut hasher = FxHasher::default();
+    json.hash(&mut hasher);
+    let name = format!("proof-tree-{:016x}", hasher.finish());
+
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join(format!("{name}.json")), &json)?;
+    // The JSON ends up in a `<script>` element, which must not be closed by its contents.
+    let html = VIEWER.replace("/* PROOF_TREE */", &json.replace("</", "<\\/"));
+    fs::write(dir.join(format!("{name}.html")), html)
+}
+
+/// The evaluation of a goal, with all types already printed.
+#[derive(serde::Serialize)]
+struct GoalNode {
+    goal: String,
+    /// `root`, `nested` or `normalizes-to hack`.
+    kind: &'static str,
+    /// The goal as evaluated by the solver, after canonicalization.
+    canonical_goal: String,
+    /// `evaluation`, `overflow`, `cycle` or `provisional cache hit`. Only an `evaluation` has
+    /// revisions.
+    evaluation: &'static str,
+    result: &'static str,
+    /// Goals involved in a cycle are evaluated again until their result reaches a fixpoint.
+    revisions: Vec<ProbeNode>,
+    /// The nested goals the caller has to prove when using the result.
+    returned_goals: Vec<String>,
+}
+
+impl GoalNode {
+    fn new<'tcx>(tcx: TyCtxt<'tcx>, eval: &GoalEvaluation<'tcx>) -> GoalNode {
+        let kind = match eval.kind {
+            GoalEvaluationKind::Root { .. } => "root",
+            GoalEvaluationKind::Nested { is_normalizes_to_hack: IsNormalizesToHack::No } => {
+                "nested"
+            }
+            GoalEvaluationKind::Nested { is_normalizes_to_hack: IsNormalizesToHack::Yes } => {
+                "normalizes-to hack"
+            }
+        };
+        let CanonicalGoalEvaluation { goal, kind: evaluation, result } = &eval.evaluation;
+        let (evaluation, revisions) = match evaluation {
+            CanonicalGoalEvaluationKind::Overflow => ("overflow", vec![]),
+            CanonicalGoalEvaluationKind::CycleInStack => ("cycle", vec![]),
+            CanonicalGoalEvaluationKind::ProvisionalCacheHit => ("provisional cache hit", vec![]),
+            CanonicalGoalEvaluationKind::Evaluation { revisions } => (
+                "evaluation",
+                revisions.iter().map(|step| ProbeNode::new(tcx, &step.evaluation)).collect(),
+            ),
+        };
+        GoalNode {
+            goal: eval.uncanonicalized_goal.predicate.to_string(),
+            kind,
+            canonical_goal: goal.value.goal.predicate.to_string(),
+            evaluation,
+            result: query_result(result),
+            revisions,
+            returned_goals: eval
+                .returned_goals
+                .iter()
+                .map(|goal| goal.predicate.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// A probe, which is either a candidate for proving the goal or some other computation whose
+/// inference progress is discarded.
+#[derive(serde::Serialize)]
+struct ProbeNode {
+    kind: String,
+    /// Only the root probe of a goal and candidates have a result.
+    result: Option<&'static str>,
+    steps: Vec<StepNode>,
+}
+
+impl ProbeNode {
+    fn new<'tcx>(tcx: TyCtxt<'tcx>, probe: &Probe<'tcx>) -> ProbeNode {
+        let (kind, result) = match &probe.kind {
+            ProbeKind::Root { result } => ("root".to_string(), Some(result)),
+            ProbeKind::NormalizedSelfTyAssembly => {
+                ("normalizing the self type for candidate assembly".to_string(), None)
+            }
+            ProbeKind::UnsizeAssembly => ("assembling candidates for unsizing".to_string(), None),
+            ProbeKind::CommitIfOk => ("commit_if_ok".to_string(), None),
+            ProbeKind::UpcastProjectionCompatibility => {
+                ("probing for projection compatibility for upcasting".to_string(), None)
+            }
+            ProbeKind::MiscCandidate { name, result } => {
+                (format!("candidate: {name}"), Some(result))
+            }
+            ProbeKind::TraitCandidate { source, result } => {
+                let source = match *source {
+                    CandidateSource::Impl(def_id) => format!("`{}`", tcx.def_path_str(def_id)),
+                    CandidateSource::BuiltinImpl(source) => format!("builtin impl ({source:?})"),
+                    CandidateSource::ParamEnv(idx) => format!("where-bound #{idx}"),
+                    CandidateSource::AliasBound => "alias bound".to_string(),
+                };
+                (format!("candidate: {source}"), Some(result))
+            }
+        };
+        ProbeNode {
+            kind,
+            result: result.map(query_result),
+            steps: probe.steps.iter().map(|step| StepNode::new(tcx, step)).collect(),
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+enum StepNode {
+    /// A goal added to be proven by the next `EvaluateGoals` step.
+    AddGoal {
+        source: &'static str,
+        goal: String,
+    },
+    EvaluateGoals {
+        result: &'static str,
+        iterations: Vec<Vec<GoalNode>>,
+    },
+    Probe {
+        probe: ProbeNode,
+    },
+    CommitIfOkStart,
+    CommitIfOkSuccess,
+}
+
+impl StepNode {
+    fn new<'tcx>(tcx: TyCtxt<'tcx>, step: &ProbeStep<'tcx>) -> StepNode {
+        match step {
+            ProbeStep::AddGoal(source, goal) => StepNode::AddGoal {
+                source: match source {
+                    GoalSource::Misc => "misc",
+                    GoalSource::ImplWhereBound => "impl where-bound",
+                },
+                goal: goal.value.data.predicate.to_string(),
+            },
+            ProbeStep::EvaluateGoals(AddedGoalsEvaluation { evaluations, result }) => {
+                StepNode::EvaluateGoals {
+                    result: match *result {
+                        Ok(certainty) => certainty_str(certainty),
+                        Err(NoSolution) => "no solution",
+                    },
+                    iterations: evaluations
+                        .iter()
+                        .map(|goals| goals.iter().map(|goal| GoalNode::new(tcx, goal)).collect())
+                        .collect(),
+                }
+            }
+            ProbeStep::NestedProbe(probe) => StepNode::Probe { probe: ProbeNode::new(tcx, probe) },
+            ProbeStep::CommitIfOkStart => StepNode::CommitIfOkStart,
+            ProbeStep::CommitIfOkSuccess => StepNode::CommitIfOkSuccess,
+        }
+    }
+}
+
+fn query_result(result: &QueryResult<'_>) -> &'static str {
+    match result {
+        Ok(response) => certainty_str(response.value.certainty),
+        Err(NoSolution) => "no solution",
+    }
+}
+
+fn certainty_str(certainty: Certainty) -> &'static str {
+    match certainty {
+        Certainty::Yes => "yes",
+        Certainty::Maybe(MaybeCause::Ambiguity) => "ambiguous",
+        Certainty::Maybe(MaybeCause::Overflow) => "overflow",
+    }
+}
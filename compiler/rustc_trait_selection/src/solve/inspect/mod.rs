@@ -5,3 +5,6 @@
 
 mod analyse;
 pub use analyse::*;
+
+mod dump;
+pub(crate) use dump::dump_proof_tree;
@@ -388,9 +388,7 @@ fn report_selection_error(
     ) -> ErrorGuaranteed {
         let tcx = self.tcx;
 
-        if tcx.sess.opts.unstable_opts.next_solver.map(|c| c.dump_tree).unwrap_or_default()
-            == DumpSolverProofTree::OnError
-        {
+        if tcx.sess.dump_solver_proof_tree() == DumpSolverProofTree::OnError {
             dump_proof_tree(root_obligation, self.infcx);
         }
 
@@ -1568,9 +1566,7 @@ fn error_implies(&self, cond: ty::Predicate<'tcx>, error: ty::Predicate<'tcx>) -
 
     #[instrument(skip(self), level = "debug")]
     fn report_fulfillment_error(&self, error: &FulfillmentError<'tcx>) -> ErrorGuaranteed {
-        if self.tcx.sess.opts.unstable_opts.next_solver.map(|c| c.dump_tree).unwrap_or_default()
-            == DumpSolverProofTree::OnError
-        {
+        if self.tcx.sess.dump_solver_proof_tree() == DumpSolverProofTree::OnError {
             dump_proof_tree(&error.root_obligation, self.infcx);
         }
 
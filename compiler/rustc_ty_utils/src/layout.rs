@@ -9,6 +9,9 @@
 };
 use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::{self, AdtDef, EarlyBinder, GenericArgsRef, Ty, TyCtxt, TypeVisitableExt};
+use rustc_session::code_stats::{
+    CoroutineLayoutInfo, PrefixReason, SavedLocalInfo, SavedLocalPlacement, SuspensionPointInfo,
+};
 use rustc_session::{DataTypeKind, FieldInfo, FieldKind, SizeKind, VariantInfo};
 use rustc_span::symbol::Symbol;
 use rustc_target::abi::*;
@@ -68,6 +71,11 @@ fn layout_of<'tcx>(
         record_layout_for_printing(&cx, layout);
     }
 
+    // Likewise for `-Zprint-coroutine-layout`.
+    if cx.tcx.sess.opts.unstable_opts.print_coroutine_layout {
+        record_coroutine_layout_for_printing(&cx, layout);
+    }
+
     sanity_check_layout(&cx, &layout);
 
     Ok(layout)
@@ -615,6 +623,17 @@ enum SavedLocalEligibility {
     Ineligible(Option<FieldIdx>),
 }
 
+/// Why a CoroutineSavedLocal was deemed ineligible for overlap, for `-Zprint-coroutine-layout`.
+#[derive(Clone, Copy, Debug)]
+enum IneligibilityReason {
+    /// The local is saved at more than one suspension point.
+    MultipleVariants,
+    /// The local conflicts with this other local, which is assigned to another variant.
+    StorageConflict(CoroutineSavedLocal),
+    /// Fewer than two variants had overlap-eligible locals left.
+    NothingToOverlap,
+}
+
 // When laying out coroutines, we divide our saved local fields into two
 // categories: overlap-eligible and overlap-ineligible.
 //
@@ -637,11 +656,17 @@ enum SavedLocalEligibility {
 /// Compute the eligibility and assignment of each local.
 fn coroutine_saved_local_eligibility(
     info: &CoroutineLayout<'_>,
-) -> (BitSet<CoroutineSavedLocal>, IndexVec<CoroutineSavedLocal, SavedLocalEligibility>) {
+) -> (
+    BitSet<CoroutineSavedLocal>,
+    IndexVec<CoroutineSavedLocal, SavedLocalEligibility>,
+    IndexVec<CoroutineSavedLocal, Option<IneligibilityReason>>,
+) {
     use SavedLocalEligibility::*;
 
     let mut assignments: IndexVec<CoroutineSavedLocal, SavedLocalEligibility> =
         IndexVec::from_elem(Unassigned, &info.field_tys);
+    let mut reasons: IndexVec<CoroutineSavedLocal, Option<IneligibilityReason>> =
+        IndexVec::from_elem(None, &info.field_tys);
 
     // The saved locals not eligible for overlap. These will get
     // "promoted" to the prefix of our coroutine.
@@ -666,6 +691,7 @@ fn coroutine_saved_local_eligibility(
                     );
                     ineligible_locals.insert(*local);
                     assignments[*local] = Ineligible(None);
+                    reasons[*local] = Some(IneligibilityReason::MultipleVariants);
                 }
                 Ineligible(_) => {}
             }
@@ -697,6 +723,7 @@ fn coroutine_saved_local_eligibility(
                 if conflicts_a > conflicts_b { (local_a, local_b) } else { (local_b, local_a) };
             ineligible_locals.insert(remove);
             assignments[remove] = Ineligible(None);
+            reasons[remove] = Some(IneligibilityReason::StorageConflict(other));
             trace!("removing local {:?} due to conflict with {:?}", remove, other);
         }
     }
@@ -717,6 +744,9 @@ fn coroutine_saved_local_eligibility(
             for assignment in assignments.iter_mut() {
                 *assignment = Ineligible(None);
             }
+            for reason in reasons.iter_mut() {
+                reason.get_or_insert(IneligibilityReason::NothingToOverlap);
+            }
             ineligible_locals.insert_all();
         }
     }
@@ -729,7 +759,7 @@ fn coroutine_saved_local_eligibility(
     }
     debug!("coroutine saved local assignments: {:?}", assignments);
 
-    (ineligible_locals, assignments)
+    (ineligible_locals, assignments, reasons)
 }
 
 /// Compute the full coroutine layout.
@@ -746,7 +776,7 @@ fn coroutine_layout<'tcx>(
     let Some(info) = tcx.coroutine_layout(def_id) else {
         return Err(error(cx, LayoutError::Unknown(ty)));
     };
-    let (ineligible_locals, assignments) = coroutine_saved_local_eligibility(info);
+    let (ineligible_locals, assignments, _) = coroutine_saved_local_eligibility(info);
 
     // Build a prefix layout, including "promoting" all ineligible
     // locals as part of the prefix. We compute the layout of all of
@@ -1174,3 +1204,114 @@ fn variant_info_for_coroutine<'tcx>(
         },
     )
 }
+
+fn record_coroutine_layout_for_printing<'tcx>(
+    cx: &LayoutCx<'tcx, TyCtxt<'tcx>>,
+    layout: TyAndLayout<'tcx>,
+) {
+    // As in `record_layout_for_printing`, only coroutines resulting from the final codegen
+    // session are interesting.
+    if layout.ty.has_non_region_param() || !cx.param_env.caller_bounds().is_empty() {
+        return;
+    }
+    let ty::Coroutine(def_id, args) = *layout.ty.kind() else {
+        return;
+    };
+    let Some(info) = cx.tcx.coroutine_layout(def_id) else {
+        return;
+    };
+    debug!("print-coroutine-layout t: `{:?}` record coroutine", layout.ty);
+
+    let (_, assignments, reasons) = coroutine_saved_local_eligibility(info);
+    let variant_name = |variant| ty::CoroutineArgs::variant_name(variant).into_owned();
+    let local_name = |local: CoroutineSavedLocal| {
+        info.field_names[local]
+            .unwrap_or_else(|| Symbol::intern(&format!(".coroutine_field{}", local.as_usize())))
+    };
+
+    // The first three variants are the unresumed, returned and poisoned states, all other
+    // variants are suspension points.
+    let suspension_points = info
+        .variant_source_info
+        .iter_enumerated()
+        .filter(|(variant, _)| variant.as_usize() > ty::CoroutineArgs::POISONED)
+        .map(|(variant, source_info)| SuspensionPointInfo {
+            name: variant_name(variant),
+            span: cx.tcx.sess.source_map().span_to_embeddable_string(source_info.span),
+        })
+        .collect();
+
+    // A local has the same offset in all variants it is saved in, so take it from the first one.
+    let mut placements: IndexVec<CoroutineSavedLocal, Option<(Size, Size)>> =
+        IndexVec::from_elem(None, &info.field_tys);
+    let mut live_across: IndexVec<CoroutineSavedLocal, Vec<String>> =
+        IndexVec::from_elem(vec![], &info.field_tys);
+    for (variant, fields) in info.variant_fields.iter_enumerated() {
+        let variant_layout = layout.for_variant(cx, variant);
+        for (field, &local) in fields.iter_enumerated() {
+            placements[local].get_or_insert_with(|| {
+                let offset = variant_layout.fields.offset(field.as_usize());
+                (offset, variant_layout.field(cx, field.as_usize()).size)
+            });
+            live_across[local].push(variant_name(variant));
+        }
+    }
+
+    let saved_locals = info
+        .field_tys
+        .iter_enumerated()
+        .map(|(local, field_ty)| {
+            let (offset, size) = placements[local].unwrap();
+            let placement = match assignments[local] {
+                SavedLocalEligibility::Unassigned => bug!(),
+                SavedLocalEligibility::Assigned(variant) => {
+                    // The locals of other variants that share bytes with this one.
+                    let overlaps_with = assignments
+                        .iter_enumerated()
+                        .filter(|&(other, assignment)| match *assignment {
+                            SavedLocalEligibility::Assigned(v) if v != variant => {
+                                let (other_offset, other_size) = placements[other].unwrap();
+                                offset < other_offset + other_size && other_offset < offset + size
+                            }
+                            _ => false,
+                        })
+                        .map(|(other, _)| local_name(other))
+                        .collect();
+                    SavedLocalPlacement::Overlapped {
+                        variant: variant_name(variant),
+                        overlaps_with,
+                    }
+                }
+                SavedLocalEligibility::Ineligible(_) => {
+                    let reason = match reasons[local].unwrap() {
+                        IneligibilityReason::MultipleVariants => {
+                            PrefixReason::MultipleSuspensionPoints
+                        }
+                        IneligibilityReason::StorageConflict(other) => {
+                            PrefixReason::StorageConflict(local_name(other))
+                        }
+                        IneligibilityReason::NothingToOverlap => PrefixReason::NothingToOverlap,
+                    };
+                    SavedLocalPlacement::Prefix(reason)
+                }
+            };
+            let field_ty = EarlyBinder::bind(field_ty.ty).instantiate(cx.tcx, args);
+            let field_ty = cx.tcx.normalize_erasing_regions(cx.param_env, field_ty);
+            SavedLocalInfo {
+                name: local_name(local),
+                type_description: with_no_trimmed_paths!(format!("{field_ty}")),
+                size: size.bytes(),
+                live_across: std::mem::take(&mut live_across[local]),
+                placement,
+            }
+        })
+        .collect();
+
+    cx.tcx.sess.code_stats.record_coroutine_layout(CoroutineLayoutInfo {
+        type_description: with_no_trimmed_paths!(format!("{}", layout.ty)),
+        align: layout.align.abi.bytes(),
+        overall_size: layout.size.bytes(),
+        suspension_points,
+        saved_locals,
+    });
+}
@@ -2371,6 +2371,10 @@ pub unsafe fn drop_in_place<T: ?Sized>(to_drop: *mut T) {
     /// Allocates a block of memory at compile time.
     /// At runtime, just returns a null pointer.
     ///
+    /// Allocations that are not deallocated before the end of the evaluation become part of the
+    /// final value and are made immutable. This is only accepted if they were made global with
+    /// [`const_make_global`], or if they are only reachable through immutable references.
+    ///
     /// # Safety
     ///
     /// - The `align` argument must be a power of two.
@@ -2394,6 +2398,21 @@ pub unsafe fn drop_in_place<T: ?Sized>(to_drop: *mut T) {
     #[rustc_nounwind]
     pub fn const_deallocate(ptr: *mut u8, size: usize, align: usize);
 
+    /// Marks a block of memory allocated by `intrinsics::const_allocate` at compile time as final:
+    /// it becomes immutable, and can remain in the final value of the constant or static, even
+    /// when mutable pointers point to it.
+    /// At runtime, just returns `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// - At compile time, `ptr` must point to the start of an allocation made by
+    ///   `intrinsics::const_allocate` in the same evaluation, which was not made global yet.
+    ///   It must not be written to or deallocated afterwards.
+    ///    - A compile error occurs if this constraint is violated.
+    #[rustc_const_unstable(feature = "const_heap", issue = "79597")]
+    #[rustc_nounwind]
+    pub fn const_make_global(ptr: *mut u8) -> *const u8;
+
     /// Determines whether the raw bytes of the two values are equal.
     ///
     /// This is particularly handy for arrays, since it allows things like just
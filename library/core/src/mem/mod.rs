@@ -23,7 +23,9 @@
 
 mod transmutability;
 #[unstable(feature = "transmutability", issue = "99571")]
-pub use transmutability::{Assume, BikeshedIntrinsicFrom};
+pub use transmutability::{
+    transmute_from, transmute_mut, transmute_ref, Assume, BikeshedIntrinsicFrom, TransmuteFrom,
+};
 
 #[stable(feature = "rust1", since = "1.0.0")]
 #[doc(inline)]
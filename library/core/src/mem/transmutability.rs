@@ -1,4 +1,5 @@
 use crate::marker::ConstParamTy;
+use crate::mem::ManuallyDrop;
 
 /// Are values of a type transmutable into values of another type?
 ///
@@ -15,6 +16,161 @@ pub unsafe trait BikeshedIntrinsicFrom<Src, Context, const ASSUME: Assume = { As
 {
 }
 
+/// Types whose values can be created by reinterpreting the bits of a `Src`.
+///
+/// Unlike [`BikeshedIntrinsicFrom`], which checks the visibility of the fields of `Self` from an
+/// explicit `Context`, this trait is only implemented for the primitive numeric types, `bool`, `()`,
+/// arrays of such types, and types that opted in with `#[derive(TransmuteFrom)]`.
+///
+/// Deriving `TransmuteFrom` asserts that `Self` has no safety invariants beyond its layout: any
+/// bits that make up valid values for the fields of `Self` make up a valid `Self`. The compiler
+/// then checks that the bits of any `Src` are valid for `Self`, notwithstanding whatever conditions
+/// you have asked it to [`Assume`] are satisfied. This requires `Self` to be `#[repr(C)]` or to have
+/// a primitive representation; since `UnsafeCell` has neither, types with interior mutability are
+/// never `TransmuteFrom` anything.
+///
+/// Fields of other types are checked for visibility from the deriving type: if one of them has
+/// private fields, the transmutation is rejected unless [`Assume::SAFETY`] is assumed.
+///
+/// # Examples
+///
+/// Parsing a network packet header from bytes, without `unsafe` code:
+///
+/// ```
+/// #![feature(transmutability)]
+/// use std::mem::{self, TransmuteFrom};
+///
+/// #[derive(TransmuteFrom)]
+/// #[repr(C)]
+/// struct Header {
+///     kind: u8,
+///     flags: u8,
+///     len: [u8; 2],
+/// }
+///
+/// let packet = [1u8, 0, 0, 4, 0xde, 0xad, 0xbe, 0xef];
+/// let header: &Header = mem::transmute_ref(packet.first_chunk::<4>().unwrap()).unwrap();
+/// assert_eq!(header.kind, 1);
+/// assert_eq!(u16::from_be_bytes(header.len), 4);
+/// ```
+#[unstable(feature = "transmutability", issue = "99571")]
+pub unsafe trait TransmuteFrom<Src, const ASSUME: Assume = { Assume::NOTHING }>:
+    Sized
+{
+    /// Reinterprets the bits of `src` as a value of type `Self`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the conditions assumed by `ASSUME` hold. If `ASSUME` is
+    /// [`Assume::NOTHING`], this is always safe to call; see [`transmute_from`].
+    #[unstable(feature = "transmutability", issue = "99571")]
+    unsafe fn transmute(src: Src) -> Self {
+        #[repr(C)]
+        union Transmute<Src, Dst> {
+            src: ManuallyDrop<Src>,
+            dst: ManuallyDrop<Dst>,
+        }
+
+        let transmute = Transmute::<Src, Self> { src: ManuallyDrop::new(src) };
+        // SAFETY: the compiler checked that the bits of `src` are a valid `Self`, except for the
+        // conditions in `ASSUME`, which the caller upholds. This also ensures that `Src` is at
+        // least as large as `Self`, so `dst` is fully initialized.
+        ManuallyDrop::into_inner(unsafe { transmute.dst })
+    }
+}
+
+/// Derive macro generating an impl of the trait `TransmuteFrom`.
+#[rustc_builtin_macro]
+#[unstable(feature = "transmutability", issue = "99571")]
+#[allow_internal_unstable(adt_const_params, transmutability)]
+pub macro TransmuteFrom($item:item) {
+    /* compiler built-in */
+}
+
+macro_rules! impl_transmute_from {
+    ($($t:ty),*) => {$(
+        #[unstable(feature = "transmutability", issue = "99571")]
+        unsafe impl<Src, const ASSUME: Assume> TransmuteFrom<Src, ASSUME> for $t
+        where
+            $t: BikeshedIntrinsicFrom<Src, $t, ASSUME>,
+        {
+        }
+    )*};
+}
+
+impl_transmute_from! {
+    (), bool,
+    u8, u16, u32, u64, u128, usize,
+    i8, i16, i32, i64, i128, isize,
+    f32, f64
+}
+
+// The fields of the elements are checked from the element type, which must itself have opted in.
+#[unstable(feature = "transmutability", issue = "99571")]
+unsafe impl<T, Src, const N: usize, const ASSUME: Assume> TransmuteFrom<Src, ASSUME> for [T; N]
+where
+    T: TransmuteFrom<T>,
+    [T; N]: BikeshedIntrinsicFrom<Src, T, ASSUME>,
+{
+}
+
+/// Reinterprets the bits of `src` as a value of type `Dst`.
+///
+/// This is a safe version of [`transmute`](crate::mem::transmute), for types that the compiler
+/// can prove are soundly transmutable: see [`TransmuteFrom`].
+#[unstable(feature = "transmutability", issue = "99571")]
+#[inline]
+pub fn transmute_from<Src, Dst>(src: Src) -> Dst
+where
+    Dst: TransmuteFrom<Src>,
+{
+    // SAFETY: `Dst: TransmuteFrom<Src>` holds without assuming anything.
+    unsafe { Dst::transmute(src) }
+}
+
+/// Reinterprets a reference to `N` bytes as a reference to a `Dst`.
+///
+/// Returns `None` if `bytes` is not suitably aligned for `Dst`. Every other requirement is checked
+/// at compile time: any `N` bytes must make up a valid `Dst`, which implies that `Dst` is at most
+/// `N` bytes long.
+#[unstable(feature = "transmutability", issue = "99571")]
+#[inline]
+pub fn transmute_ref<const N: usize, Dst>(bytes: &[u8; N]) -> Option<&Dst>
+where
+    Dst: TransmuteFrom<[u8; N]>,
+{
+    let ptr = (bytes as *const [u8; N]).cast::<Dst>();
+    if !ptr.is_aligned() {
+        return None;
+    }
+    // SAFETY: we checked the alignment, and `Dst: TransmuteFrom<[u8; N]>` ensures that the bytes
+    // are a valid `Dst` that has no interior mutability, and that they outlive the returned
+    // reference.
+    Some(unsafe { &*ptr })
+}
+
+/// Reinterprets a mutable reference to `N` bytes as a mutable reference to a `Dst`.
+///
+/// Returns `None` if `bytes` is not suitably aligned for `Dst`. As with [`transmute_ref`], any `N`
+/// bytes must make up a valid `Dst`; additionally, any `Dst` must make up `N` initialized bytes, so
+/// that writes through the returned reference leave valid bytes behind. Notably, `Dst` must be
+/// exactly `N` bytes long and have no padding.
+#[unstable(feature = "transmutability", issue = "99571")]
+#[inline]
+pub fn transmute_mut<const N: usize, Dst>(bytes: &mut [u8; N]) -> Option<&mut Dst>
+where
+    Dst: TransmuteFrom<[u8; N]>,
+    [u8; N]: TransmuteFrom<Dst>,
+{
+    let ptr = (bytes as *mut [u8; N]).cast::<Dst>();
+    if !ptr.is_aligned() {
+        return None;
+    }
+    // SAFETY: we checked the alignment, and the bytes and `Dst` are transmutable into each other,
+    // so reads and writes through the returned reference only ever observe valid values.
+    Some(unsafe { &mut *ptr })
+}
+
 /// What transmutation safety conditions shall the compiler assume that *you* are checking?
 #[unstable(feature = "transmutability", issue = "99571")]
 #[lang = "transmute_opts"]
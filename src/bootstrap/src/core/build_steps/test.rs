@@ -1370,6 +1370,9 @@ fn run(self, builder: &Builder<'_>) {
 
 default_test!(Assembly { path: "tests/assembly", mode: "assembly", suite: "assembly" });
 
+// Fuzzing takes a long time, so it only runs when asked for explicitly.
+test!(Fuzz { path: "tests/fuzz", mode: "fuzz", suite: "fuzz", default: false, host: true });
+
 /// Custom test step that is responsible for running the coverage tests
 /// in multiple different modes.
 ///
@@ -1620,6 +1623,12 @@ fn run(self, builder: &Builder<'_>) {
                 .arg(builder.ensure(tool::JsonDocLint { compiler: json_compiler, target }));
         }
 
+        if mode == "run-make" {
+            let run_make_support =
+                builder.ensure(tool::RunMakeSupport { compiler, target: compiler.host });
+            cmd.arg("--run-make-support-path").arg(run_make_support);
+        }
+
         if mode == "coverage-map" {
             let coverage_dump = builder.ensure(tool::CoverageDump {
                 compiler: compiler.with_stage(0),
@@ -1965,6 +1974,10 @@ fn run(self, builder: &Builder<'_>) {
             cmd.arg("--rustfix-coverage");
         }
 
+        if builder.config.cmd.reduce() {
+            cmd.arg("--reduce");
+        }
+
         cmd.env("BOOTSTRAP_CARGO", &builder.initial_cargo);
 
         cmd.arg("--channel").arg(&builder.config.channel);
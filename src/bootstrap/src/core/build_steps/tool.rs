@@ -401,6 +401,52 @@ fn run(self, builder: &Builder<'_>) -> PathBuf {
     }
 }
 
+/// The `run_make_support` library, which the `rmake.rs` recipes of `run-make` tests are linked
+/// against. It is built with the same compiler and standard library as the recipes themselves.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct RunMakeSupport {
+    pub compiler: Compiler,
+    pub target: TargetSelection,
+}
+
+impl Step for RunMakeSupport {
+    type Output = PathBuf;
+    const ONLY_HOSTS: bool = true;
+
+    fn should_run(run: ShouldRun<'_>) -> ShouldRun<'_> {
+        run.never()
+    }
+
+    fn run(self, builder: &Builder<'_>) -> PathBuf {
+        builder.ensure(compile::Std::new(self.compiler, self.target));
+
+        let cargo = prepare_tool_cargo(
+            builder,
+            self.compiler,
+            Mode::ToolStd,
+            self.target,
+            "build",
+            "src/tools/run-make-support",
+            SourceType::InTree,
+            &[],
+        );
+        let _guard = builder.msg_tool(
+            Mode::ToolStd,
+            "run-make-support",
+            self.compiler.stage,
+            &self.compiler.host,
+            &self.target,
+        );
+        builder.run(&mut cargo.into());
+
+        let lib_name = "librun_make_support.rlib";
+        let cargo_out = builder.cargo_out(self.compiler, Mode::ToolStd, self.target).join(lib_name);
+        let lib = builder.tools_dir(self.compiler).join(lib_name);
+        builder.copy(&cargo_out, &lib);
+        lib
+    }
+}
+
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, Ord, PartialOrd)]
 pub struct Rustdoc {
     /// This should only ever be 0 or 2.
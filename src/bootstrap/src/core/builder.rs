@@ -755,6 +755,7 @@ macro_rules! describe {
                 test::Rustdoc,
                 test::CoverageRunRustdoc,
                 test::Pretty,
+                test::Fuzz,
                 test::Crate,
                 test::CrateLibrustc,
                 test::CrateRustdoc,
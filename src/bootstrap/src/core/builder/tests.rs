@@ -583,6 +583,7 @@ fn test_with_no_doc_stage0() {
             force_rerun: false,
             compare_mode: None,
             rustfix_coverage: false,
+            reduce: false,
             pass: None,
             run: None,
             only_modified: false,
@@ -656,6 +657,7 @@ fn test_docs() {
             force_rerun: false,
             compare_mode: None,
             rustfix_coverage: false,
+            reduce: false,
             pass: None,
             run: None,
             only_modified: false,
@@ -381,6 +381,9 @@ pub enum Subcommand {
         /// enable this to generate a Rustfix coverage file, which is saved in
         /// `/<build_base>/rustfix_missing_coverage.txt`
         rustfix_coverage: bool,
+        #[arg(long)]
+        /// reduce failing ui tests to a minimal test case next to the test
+        reduce: bool,
     },
     /// Build and run some benchmarks
     Bench {
@@ -528,6 +531,13 @@ pub fn rustfix_coverage(&self) -> bool {
         }
     }
 
+    pub fn reduce(&self) -> bool {
+        match *self {
+            Subcommand::Test { reduce, .. } => reduce,
+            _ => false,
+        }
+    }
+
     pub fn compare_mode(&self) -> Option<&str> {
         match *self {
             Subcommand::Test { ref compare_mode, .. } => compare_mode.as_ref().map(|s| &s[..]),
@@ -0,0 +1,19 @@
+#!/bin/bash
+# Checks that the compiler crates that are also used outside of rustc, like by rust-analyzer, build
+# without their default features: `rustc_index` and `rustc_pattern_analysis` are then `no_std`.
+
+set -eo pipefail
+
+STAGE0="build/x86_64-unknown-linux-gnu/stage0/bin"
+
+echo "Check rustc_index and rustc_pattern_analysis without their default features..."
+
+RUSTC_BOOTSTRAP=1 RUSTC="$STAGE0/rustc" "$STAGE0/cargo" check \
+    --manifest-path ../Cargo.toml \
+    --locked \
+    --target-dir build/no-default-features \
+    --no-default-features \
+    -p rustc_index \
+    -p rustc_pattern_analysis
+
+echo "rustc_index and rustc_pattern_analysis build as no_std!"
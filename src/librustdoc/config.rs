@@ -10,7 +10,8 @@
 };
 use rustc_session::config::{get_cmd_lint_options, nightly_options};
 use rustc_session::config::{
-    CodegenOptions, ErrorOutputType, Externs, JsonUnusedExterns, UnstableOptions,
+    CodegenOptions, ErrorOutputType, Externs, InstrumentCoverage, JsonUnusedExterns,
+    UnstableOptions,
 };
 use rustc_session::getopts;
 use rustc_session::lint::Level;
@@ -35,6 +36,7 @@ pub(crate) enum OutputFormat {
     Json,
     #[default]
     Html,
+    Man,
 }
 
 impl OutputFormat {
@@ -50,6 +52,7 @@ fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
             "json" => Ok(OutputFormat::Json),
             "html" => Ok(OutputFormat::Html),
+            "man" => Ok(OutputFormat::Man),
             _ => Err(format!("unknown output format `{value}`")),
         }
     }
@@ -115,6 +118,9 @@ pub(crate) struct Options {
     /// Optional path to persist the doctest executables to, defaults to a
     /// temporary directory if not set.
     pub(crate) persist_doctests: Option<PathBuf>,
+    /// Optional path to write the coverage data of doctests into. If set, doctests are compiled
+    /// with `-C instrument-coverage`.
+    pub(crate) doctest_coverage: Option<PathBuf>,
     /// Runtool to run doctests with
     pub(crate) runtool: Option<String>,
     /// Arguments to pass to the runtool
@@ -125,6 +131,12 @@ pub(crate) struct Options {
     pub(crate) enable_per_target_ignores: bool,
     /// Do not run doctests, compile them if should_test is active.
     pub(crate) no_run: bool,
+    /// Whether to compile the doctests which don't need to be compiled on their own into a single
+    /// binary.
+    pub(crate) merge_doctests: bool,
+    /// Whether to compile the doctests to report the lint warnings they trigger, instead of
+    /// running them.
+    pub(crate) lint_examples: bool,
 
     /// The path to a rustc-like binary to build tests with. If not set, we
     /// default to loading from `$sysroot/bin/rustc`.
@@ -197,6 +209,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             .field("test_args", &self.test_args)
             .field("test_run_directory", &self.test_run_directory)
             .field("persist_doctests", &self.persist_doctests)
+            .field("doctest_coverage", &self.doctest_coverage)
             .field("show_coverage", &self.show_coverage)
             .field("crate_version", &self.crate_version)
             .field("runtool", &self.runtool)
@@ -204,6 +217,8 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             .field("enable-per-target-ignores", &self.enable_per_target_ignores)
             .field("run_check", &self.run_check)
             .field("no_run", &self.no_run)
+            .field("merge_doctests", &self.merge_doctests)
+            .field("lint_examples", &self.lint_examples)
             .field("nocapture", &self.nocapture)
             .field("scrape_examples_options", &self.scrape_examples_options)
             .field("unstable_features", &self.unstable_features)
@@ -265,6 +280,9 @@ pub(crate) struct RenderOptions {
     pub(crate) document_private: bool,
     /// Document items that have `doc(hidden)`.
     pub(crate) document_hidden: bool,
+    /// Whether the crate is a program, from `--crate-type bin` or `#![crate_type = "bin"]`. Its
+    /// man page is then written in section 1.
+    pub(crate) bin_crate: bool,
     /// If `true`, generate a JSON file in the crate folder instead of HTML redirection files.
     pub(crate) generate_redirect_map: bool,
     /// Show the memory layout of types in the docs.
@@ -518,6 +536,24 @@ fn println_condition(condition: Condition) {
             dcx.fatal("the `--test` flag must be passed to enable `--no-run`");
         }
 
+        let merge_doctests = matches.opt_present("merge-doctests");
+
+        if !should_test && merge_doctests {
+            dcx.fatal("the `--test` flag must be passed to enable `--merge-doctests`");
+        }
+
+        let lint_examples = matches.opt_present("lint-examples");
+
+        if !should_test && lint_examples {
+            dcx.fatal("the `--test` flag must be passed to enable `--lint-examples`");
+        }
+
+        let doctest_coverage = matches.opt_str("doctest-coverage").map(PathBuf::from);
+
+        if !should_test && doctest_coverage.is_some() {
+            dcx.fatal("the `--test` flag must be passed to enable `--doctest-coverage`");
+        }
+
         let out_dir = matches.opt_str("out-dir").map(|s| PathBuf::from(&s));
         let output = matches.opt_str("output").map(|s| PathBuf::from(&s));
         let output = match (out_dir, output) {
@@ -629,7 +665,11 @@ fn println_condition(condition: Condition) {
         let output_format = match matches.opt_str("output-format") {
             Some(s) => match OutputFormat::try_from(s.as_str()) {
                 Ok(out_fmt) => {
-                    if !out_fmt.is_json() && show_coverage {
+                    if out_fmt == OutputFormat::Man && show_coverage {
+                        dcx.fatal(
+                            "man output format isn't supported for the --show-coverage option",
+                        );
+                    } else if !out_fmt.is_json() && show_coverage {
                         dcx.fatal(
                             "html output format isn't supported for the --show-coverage option",
                         );
@@ -667,7 +707,10 @@ fn println_condition(condition: Condition) {
         let runtool = matches.opt_str("runtool");
         let runtool_args = matches.opt_strs("runtool-arg");
         let enable_per_target_ignores = matches.opt_present("enable-per-target-ignores");
-        let document_private = matches.opt_present("document-private-items");
+        // The items of a program are private, but their documentation is what its man page is
+        // made of.
+        let document_private = matches.opt_present("document-private-items")
+            || (output_format == OutputFormat::Man && bin_crate);
         let document_hidden = matches.opt_present("document-hidden-items");
         let run_check = matches.opt_present("check");
         let generate_redirect_map = matches.opt_present("generate-redirect-map");
@@ -718,12 +761,15 @@ fn println_condition(condition: Condition) {
             crate_version,
             test_run_directory,
             persist_doctests,
+            doctest_coverage,
             runtool,
             runtool_args,
             enable_per_target_ignores,
             test_builder,
             run_check,
             no_run,
+            merge_doctests,
+            lint_examples,
             nocapture,
             crate_name,
             output_format,
@@ -752,6 +798,7 @@ fn println_condition(condition: Condition) {
             markdown_playground_url,
             document_private,
             document_hidden,
+            bin_crate,
             generate_redirect_map,
             show_type_layout,
             unstable_features,
@@ -768,6 +815,18 @@ fn println_condition(condition: Condition) {
     pub(crate) fn markdown_input(&self) -> bool {
         self.input.extension().is_some_and(|e| e == "md" || e == "markdown")
     }
+
+    /// Returns `true` if doctests are compiled with `-C instrument-coverage`, either through
+    /// `--doctest-coverage` or explicitly.
+    pub(crate) fn instrument_coverage(&self) -> bool {
+        // An explicit `-C instrument-coverage` is passed after the one `--doctest-coverage` adds,
+        // so it takes precedence.
+        if self.codegen_options_strs.iter().any(|opt| opt.starts_with("instrument-coverage")) {
+            self.codegen_options.instrument_coverage() != InstrumentCoverage::Off
+        } else {
+            self.doctest_coverage.is_some()
+        }
+    }
 }
 
 /// Prints deprecation warnings for deprecated options
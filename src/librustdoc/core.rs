@@ -301,7 +301,7 @@ pub(crate) fn create_config(
 pub(crate) fn run_global_ctxt(
     tcx: TyCtxt<'_>,
     show_coverage: bool,
-    render_options: RenderOptions,
+    mut render_options: RenderOptions,
     output_format: OutputFormat,
 ) -> (clean::Crate, RenderOptions, Cache) {
     // Certain queries assume that some checks were run elsewhere
@@ -335,6 +335,21 @@ pub(crate) fn run_global_ctxt(
     });
     rustc_passes::stability::check_unused_or_stable_features(tcx);
 
+    // Crates are always compiled as libraries by rustdoc, so `tcx.crate_types()` doesn't tell
+    // whether the crate is a program.
+    if tcx
+        .hir()
+        .krate_attrs()
+        .iter()
+        .any(|attr| attr.has_name(sym::crate_type) && attr.value_str() == Some(sym::bin))
+    {
+        render_options.bin_crate = true;
+        // Like with `--crate-type bin`, see `Options::from_matches`.
+        if output_format == OutputFormat::Man {
+            render_options.document_private = true;
+        }
+    }
+
     let auto_traits =
         tcx.all_traits().filter(|&trait_def_id| tcx.trait_is_auto(trait_def_id)).collect();
 
@@ -21,10 +21,12 @@
 use rustc_target::spec::{Target, TargetTriple};
 use tempfile::Builder as TempFileBuilder;
 
+use std::borrow::Cow;
 use std::env;
 use std::io::{self, Write};
+use std::iter;
 use std::panic;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{self, Command, Stdio};
 use std::str;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -35,6 +37,9 @@
 use crate::html::markdown::{self, ErrorCodes, Ignore, LangString};
 use crate::lint::init_lints;
 
+use self::lint_examples::DocumentedItem;
+use self::merged::MergedDoctests;
+
 /// Options that apply to all doctests in a crate or Markdown file (for `rustdoc foo.md`).
 #[derive(Clone, Default)]
 pub(crate) struct GlobalTestOptions {
@@ -113,6 +118,7 @@ pub(crate) fn run(options: RustdocOptions) -> Result<(), ErrorGuaranteed> {
     let nocapture = options.nocapture;
     let externs = options.externs.clone();
     let json_unused_externs = options.json_unused_externs;
+    let lint_examples = options.lint_examples;
 
     let (tests, unused_extern_reports, compiling_test_count) =
         interface::run_compiler(config, |compiler| {
@@ -140,6 +146,7 @@ pub(crate) fn run(options: RustdocOptions) -> Result<(), ErrorGuaranteed> {
                             compiler.sess.opts.unstable_features.is_nightly_build(),
                         ),
                         tcx,
+                        examples: lint_examples.then(Vec::new),
                     };
                     hir_collector.visit_testable(
                         "".to_string(),
@@ -147,6 +154,9 @@ pub(crate) fn run(options: RustdocOptions) -> Result<(), ErrorGuaranteed> {
                         tcx.hir().span(CRATE_HIR_ID),
                         |this| tcx.hir().walk_toplevel_module(this),
                     );
+                    if let Some(examples) = hir_collector.examples.take() {
+                        lint_examples::lint_examples(tcx, &collector, &examples);
+                    }
 
                     collector
                 });
@@ -161,6 +171,11 @@ pub(crate) fn run(options: RustdocOptions) -> Result<(), ErrorGuaranteed> {
             })
         })?;
 
+    // The examples have already been linted, they don't need to be run.
+    if lint_examples {
+        return Ok(());
+    }
+
     run_tests(test_args, nocapture, tests);
 
     // Collect and warn about unused externs, but only if we've gotten
@@ -284,6 +299,31 @@ fn path(&self) -> &std::path::Path {
 // and cargo.
 // We could unify this struct the one in rustc but they have different
 // ownership semantics, so doing so would create wasteful allocations.
+/// Creates the directory into which the executable of a doctest is compiled.
+fn make_outdir(rustdoc_options: &RustdocOptions, test_id: &str) -> DirState {
+    // Coverage reports need the executables, so keep them next to the coverage data by
+    // default.
+    let persist_dir =
+        rustdoc_options.persist_doctests.as_ref().or(rustdoc_options.doctest_coverage.as_ref());
+    if let Some(mut path) = persist_dir.cloned() {
+        path.push(test_id);
+
+        if let Err(err) = std::fs::create_dir_all(&path) {
+            eprintln!("Couldn't create directory for doctest executables: {err}");
+            panic::resume_unwind(Box::new(()));
+        }
+
+        DirState::Perm(path)
+    } else {
+        DirState::Temp(
+            TempFileBuilder::new()
+                .prefix("rustdoctest")
+                .tempdir()
+                .expect("rustdoc needs a tempdir"),
+        )
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize)]
 struct UnusedExterns {
     /// Lint level of the unused_crate_dependencies lint
@@ -306,6 +346,7 @@ fn run_test(
     test: &str,
     crate_name: &str,
     line: usize,
+    cols: Vec<usize>,
     rustdoc_options: RustdocOptions,
     mut lang_string: LangString,
     no_run: bool,
@@ -326,28 +367,17 @@ fn run_test(
     let rust_out = add_exe_suffix("rust_out".to_owned(), &target);
     let output_file = outdir.path().join(rust_out);
 
-    let rustc_binary = rustdoc_options
-        .test_builder
-        .as_deref()
-        .unwrap_or_else(|| rustc_interface::util::rustc_path().expect("found rustc"));
-    let mut compiler = Command::new(&rustc_binary);
-    compiler.arg("--crate-type").arg("bin");
-    for cfg in &rustdoc_options.cfgs {
-        compiler.arg("--cfg").arg(&cfg);
-    }
-    if !rustdoc_options.check_cfgs.is_empty() {
-        compiler.arg("-Z").arg("unstable-options");
-        for check_cfg in &rustdoc_options.check_cfgs {
-            compiler.arg("--check-cfg").arg(&check_cfg);
-        }
-    }
-    if let Some(sysroot) = rustdoc_options.maybe_sysroot {
-        compiler.arg("--sysroot").arg(sysroot);
-    }
-    compiler.arg("--edition").arg(&edition.to_string());
+    let mut compiler =
+        make_rustc_command(&rustdoc_options, edition, &target, &output_file, supports_color);
     compiler.env("UNSTABLE_RUSTDOC_TEST_PATH", path);
     compiler.env("UNSTABLE_RUSTDOC_TEST_LINE", format!("{}", line as isize - line_offset as isize));
-    compiler.arg("-o").arg(&output_file);
+    if !cols.is_empty() {
+        // The offsets are indexed by the lines of the generated code, which starts with the lines
+        // added before the test.
+        let cols = iter::repeat(0).take(line_offset).chain(cols);
+        let cols = cols.map(|col| col.to_string()).collect::<Vec<_>>().join(",");
+        compiler.env("UNSTABLE_RUSTDOC_TEST_COL", cols);
+    }
     if lang_string.test_harness {
         compiler.arg("--test");
     }
@@ -357,47 +387,9 @@ fn run_test(
         compiler.arg("-Z").arg("unstable-options");
         compiler.arg("-W").arg("unused_crate_dependencies");
     }
-    for lib_str in &rustdoc_options.lib_strs {
-        compiler.arg("-L").arg(&lib_str);
-    }
-    for extern_str in &rustdoc_options.extern_strs {
-        compiler.arg("--extern").arg(&extern_str);
-    }
-    compiler.arg("-Ccodegen-units=1");
-    for codegen_options_str in &rustdoc_options.codegen_options_strs {
-        compiler.arg("-C").arg(&codegen_options_str);
-    }
-    for unstable_option_str in &rustdoc_options.unstable_opts_strs {
-        compiler.arg("-Z").arg(&unstable_option_str);
-    }
     if no_run && !lang_string.compile_fail && rustdoc_options.persist_doctests.is_none() {
         compiler.arg("--emit=metadata");
     }
-    compiler.arg("--target").arg(match target {
-        TargetTriple::TargetTriple(s) => s,
-        TargetTriple::TargetJson { path_for_rustdoc, .. } => {
-            path_for_rustdoc.to_str().expect("target path must be valid unicode").to_string()
-        }
-    });
-    if let ErrorOutputType::HumanReadable(kind) = rustdoc_options.error_format {
-        let (short, color_config) = kind.unzip();
-
-        if short {
-            compiler.arg("--error-format").arg("short");
-        }
-
-        match color_config {
-            ColorConfig::Never => {
-                compiler.arg("--color").arg("never");
-            }
-            ColorConfig::Always => {
-                compiler.arg("--color").arg("always");
-            }
-            ColorConfig::Auto => {
-                compiler.arg("--color").arg(if supports_color { "always" } else { "never" });
-            }
-        }
-    }
 
     compiler.arg("-");
     compiler.stdin(Stdio::piped());
@@ -465,6 +457,102 @@ fn drop(&mut self) {
         return Ok(());
     }
 
+    run_test_binary(
+        output_file,
+        &[],
+        &lang_string,
+        &rustdoc_options,
+        runtool,
+        runtool_args,
+        test_id,
+    )
+}
+
+/// Builds the `rustc` invocation shared by every way of compiling doctests: the flags forwarded
+/// from the rustdoc command line, the edition, the target and the output file.
+fn make_rustc_command(
+    rustdoc_options: &RustdocOptions,
+    edition: Edition,
+    target: &TargetTriple,
+    output_file: &Path,
+    supports_color: bool,
+) -> Command {
+    let rustc_binary = rustdoc_options
+        .test_builder
+        .as_deref()
+        .unwrap_or_else(|| rustc_interface::util::rustc_path().expect("found rustc"));
+    let mut compiler = Command::new(&rustc_binary);
+    compiler.arg("--crate-type").arg("bin");
+    for cfg in &rustdoc_options.cfgs {
+        compiler.arg("--cfg").arg(&cfg);
+    }
+    if !rustdoc_options.check_cfgs.is_empty() {
+        compiler.arg("-Z").arg("unstable-options");
+        for check_cfg in &rustdoc_options.check_cfgs {
+            compiler.arg("--check-cfg").arg(&check_cfg);
+        }
+    }
+    if let Some(sysroot) = &rustdoc_options.maybe_sysroot {
+        compiler.arg("--sysroot").arg(sysroot);
+    }
+    compiler.arg("--edition").arg(&edition.to_string());
+    compiler.arg("-o").arg(output_file);
+    for lib_str in &rustdoc_options.lib_strs {
+        compiler.arg("-L").arg(&lib_str);
+    }
+    for extern_str in &rustdoc_options.extern_strs {
+        compiler.arg("--extern").arg(&extern_str);
+    }
+    compiler.arg("-Ccodegen-units=1");
+    if rustdoc_options.doctest_coverage.is_some() {
+        // Added before the user's codegen options so that they can still pick another mode.
+        compiler.arg("-Cinstrument-coverage");
+    }
+    for codegen_options_str in &rustdoc_options.codegen_options_strs {
+        compiler.arg("-C").arg(&codegen_options_str);
+    }
+    for unstable_option_str in &rustdoc_options.unstable_opts_strs {
+        compiler.arg("-Z").arg(&unstable_option_str);
+    }
+    compiler.arg("--target").arg(match target {
+        TargetTriple::TargetTriple(s) => s.clone(),
+        TargetTriple::TargetJson { path_for_rustdoc, .. } => {
+            path_for_rustdoc.to_str().expect("target path must be valid unicode").to_string()
+        }
+    });
+    if let ErrorOutputType::HumanReadable(kind) = rustdoc_options.error_format {
+        let (short, color_config) = kind.unzip();
+
+        if short {
+            compiler.arg("--error-format").arg("short");
+        }
+
+        match color_config {
+            ColorConfig::Never => {
+                compiler.arg("--color").arg("never");
+            }
+            ColorConfig::Always => {
+                compiler.arg("--color").arg("always");
+            }
+            ColorConfig::Auto => {
+                compiler.arg("--color").arg(if supports_color { "always" } else { "never" });
+            }
+        }
+    }
+    compiler
+}
+
+/// Runs a compiled doctest executable, passing `args` to it, and checks its exit status against
+/// what the code block's attributes expect.
+fn run_test_binary(
+    output_file: PathBuf,
+    args: &[String],
+    lang_string: &LangString,
+    rustdoc_options: &RustdocOptions,
+    runtool: Option<String>,
+    runtool_args: Vec<String>,
+    test_id: &str,
+) -> Result<(), TestFailure> {
     // Run the code!
     let mut cmd;
 
@@ -477,9 +565,16 @@ fn drop(&mut self) {
     } else {
         cmd = Command::new(output_file);
     }
-    if let Some(run_directory) = rustdoc_options.test_run_directory {
+    cmd.args(args);
+    if let Some(run_directory) = &rustdoc_options.test_run_directory {
         cmd.current_dir(run_directory);
     }
+    if let Some(coverage_dir) = &rustdoc_options.doctest_coverage {
+        // The path must not be relative to `run_directory`. `%p` and `%m` are replaced by the
+        // profiler runtime to keep the data of different processes and executables apart.
+        let profile_file = coverage_dir.join(format!("{test_id}-%p-%m.profraw"));
+        cmd.env("LLVM_PROFILE_FILE", make_maybe_absolute_path(profile_file));
+    }
 
     let result = if rustdoc_options.nocapture {
         cmd.status().map(|status| process::Output {
@@ -554,6 +649,98 @@ pub(crate) fn make_test(
     prog.push_str(&crate_attrs);
     prog.push_str(&crates);
 
+    let result = check_for_main_and_extern_crate(
+        crate_name,
+        s,
+        crates,
+        everything_else,
+        edition,
+        &mut supports_color,
+    );
+    let Ok((already_has_main, already_has_extern_crate)) = result else {
+        // If the parser panicked due to a fatal error, pass the test code through unchanged.
+        // The error will be reported during compilation.
+        return (s.to_owned(), 0, false);
+    };
+
+    // Don't inject `extern crate std` because it's already injected by the
+    // compiler.
+    if !already_has_extern_crate && !opts.no_crate_inject && crate_name != Some("std") {
+        if let Some(crate_name) = crate_name {
+            // Don't inject `extern crate` if the crate is never used.
+            // NOTE: this is terribly inaccurate because it doesn't actually
+            // parse the source, but only has false positives, not false
+            // negatives.
+            if s.contains(crate_name) {
+                // rustdoc implicitly inserts an `extern crate` item for the own crate
+                // which may be unused, so we need to allow the lint.
+                prog.push_str("#[allow(unused_extern_crates)]\n");
+
+                prog.push_str(&format!("extern crate r#{crate_name};\n"));
+                line_offset += 1;
+            }
+        }
+    }
+
+    // FIXME: This code cannot yet handle no_std test cases yet
+    if dont_insert_main || already_has_main || prog.contains("![no_std]") {
+        prog.push_str(everything_else);
+    } else {
+        let returns_result = everything_else.trim_end().ends_with("(())");
+        // Give each doctest main function a unique name.
+        // This is for example needed for the tooling around `-C instrument-coverage`.
+        let inner_fn_name = if let Some(test_id) = test_id {
+            format!("_doctest_main_{test_id}")
+        } else {
+            "_inner".into()
+        };
+        let inner_attr = if test_id.is_some() { "#[allow(non_snake_case)] " } else { "" };
+        let (main_pre, main_post) = if returns_result {
+            (
+                format!(
+                    "fn main() {{ {inner_attr}fn {inner_fn_name}() -> Result<(), impl core::fmt::Debug> {{\n",
+                ),
+                format!("\n}} {inner_fn_name}().unwrap() }}"),
+            )
+        } else if test_id.is_some() {
+            (
+                format!("fn main() {{ {inner_attr}fn {inner_fn_name}() {{\n",),
+                format!("\n}} {inner_fn_name}() }}"),
+            )
+        } else {
+            ("fn main() {\n".into(), "\n}".into())
+        };
+        // Note on newlines: We insert a line/newline *before*, and *after*
+        // the doctest and adjust the `line_offset` accordingly.
+        // In the case of `-C instrument-coverage`, this means that the generated
+        // inner `main` function spans from the doctest opening codeblock to the
+        // closing one. For example
+        // /// ``` <- start of the inner main
+        // /// <- code under doctest
+        // /// ``` <- end of the inner main
+        line_offset += 1;
+
+        prog.extend([&main_pre, everything_else, &main_post].iter().cloned());
+    }
+
+    debug!("final doctest:\n{prog}");
+
+    (prog, line_offset, supports_color)
+}
+
+/// Parses the doctest to find out whether it already has a `main` function and whether it already
+/// contains an `extern crate` item for `crate_name`.
+///
+/// Returns an error if the parser hit a fatal error; the test code is then expected to be passed
+/// to the compiler unchanged so that the error gets reported there.
+fn check_for_main_and_extern_crate(
+    crate_name: Option<&str>,
+    s: &str,
+    crates: String,
+    everything_else: &str,
+    edition: Edition,
+    supports_color: &mut bool,
+) -> Result<(bool, bool), FatalError> {
     // Uses librustc_ast to parse the doctest and find if there's a main fn and the extern
     // crate already is included.
     let result = rustc_driver::catch_fatal_errors(|| {
@@ -573,7 +760,7 @@ pub(crate) fn make_test(
                 rustc_driver::DEFAULT_LOCALE_RESOURCES.to_vec(),
                 false,
             );
-            supports_color = HumanEmitter::stderr(ColorConfig::Auto, fallback_bundle.clone())
+            *supports_color = HumanEmitter::stderr(ColorConfig::Auto, fallback_bundle.clone())
                 .diagnostic_width(Some(80))
                 .supports_color();
 
@@ -647,11 +834,7 @@ pub(crate) fn make_test(
             (found_main, found_extern_crate, found_macro)
         })
     });
-    let Ok((already_has_main, already_has_extern_crate, found_macro)) = result else {
-        // If the parser panicked due to a fatal error, pass the test code through unchanged.
-        // The error will be reported during compilation.
-        return (s.to_owned(), 0, false);
-    };
+    let (already_has_main, already_has_extern_crate, found_macro) = result?;
 
     // If a doctest's `fn main` is being masked by a wrapper macro, the parsing loop above won't
     // see it. In that case, run the old text-based scan to see if they at least have a main
@@ -668,69 +851,7 @@ pub(crate) fn make_test(
         already_has_main
     };
 
-    // Don't inject `extern crate std` because it's already injected by the
-    // compiler.
-    if !already_has_extern_crate && !opts.no_crate_inject && crate_name != Some("std") {
-        if let Some(crate_name) = crate_name {
-            // Don't inject `extern crate` if the crate is never used.
-            // NOTE: this is terribly inaccurate because it doesn't actually
-            // parse the source, but only has false positives, not false
-            // negatives.
-            if s.contains(crate_name) {
-                // rustdoc implicitly inserts an `extern crate` item for the own crate
-                // which may be unused, so we need to allow the lint.
-                prog.push_str("#[allow(unused_extern_crates)]\n");
-
-                prog.push_str(&format!("extern crate r#{crate_name};\n"));
-                line_offset += 1;
-            }
-        }
-    }
-
-    // FIXME: This code cannot yet handle no_std test cases yet
-    if dont_insert_main || already_has_main || prog.contains("![no_std]") {
-        prog.push_str(everything_else);
-    } else {
-        let returns_result = everything_else.trim_end().ends_with("(())");
-        // Give each doctest main function a unique name.
-        // This is for example needed for the tooling around `-C instrument-coverage`.
-        let inner_fn_name = if let Some(test_id) = test_id {
-            format!("_doctest_main_{test_id}")
-        } else {
-            "_inner".into()
-        };
-        let inner_attr = if test_id.is_some() { "#[allow(non_snake_case)] " } else { "" };
-        let (main_pre, main_post) = if returns_result {
-            (
-                format!(
-                    "fn main() {{ {inner_attr}fn {inner_fn_name}() -> Result<(), impl core::fmt::Debug> {{\n",
-                ),
-                format!("\n}} {inner_fn_name}().unwrap() }}"),
-            )
-        } else if test_id.is_some() {
-            (
-                format!("fn main() {{ {inner_attr}fn {inner_fn_name}() {{\n",),
-                format!("\n}} {inner_fn_name}() }}"),
-            )
-        } else {
-            ("fn main() {\n".into(), "\n}".into())
-        };
-        // Note on newlines: We insert a line/newline *before*, and *after*
-        // the doctest and adjust the `line_offset` accordingly.
-        // In the case of `-C instrument-coverage`, this means that the generated
-        // inner `main` function spans from the doctest opening codeblock to the
-        // closing one. For example
-        // /// ``` <- start of the inner main
-        // /// <- code under doctest
-        // /// ``` <- end of the inner main
-        line_offset += 1;
-
-        prog.extend([&main_pre, everything_else, &main_post].iter().cloned());
-    }
-
-    debug!("final doctest:\n{prog}");
-
-    (prog, line_offset, supports_color)
+    Ok((already_has_main, already_has_extern_crate))
 }
 
 fn check_if_attr_is_complete(source: &str, edition: Edition) -> bool {
@@ -871,6 +992,27 @@ enum PartitionState {
     (before, after, crates)
 }
 
+/// Returns the number of bytes preceding the code of each line of `test` on the corresponding line
+/// of its source file, given by `original_line` for each index of a line of the test. This is 4
+/// for a line written in a `//! ` comment, or 6 for a hidden `/// # ` line. The offset is 0 for
+/// empty lines and lines that can't be found.
+fn col_offsets<'a>(test: &str, original_line: impl Fn(usize) -> Option<Cow<'a, str>>) -> Vec<usize> {
+    test.lines()
+        .enumerate()
+        .map(|(i, code)| {
+            // Empty lines may have been stripped of their doc comment prefix or trailing
+            // whitespace, and contain no code anyway.
+            if code.trim().is_empty() {
+                return 0;
+            }
+            let Some(original) = original_line(i) else {
+                return 0;
+            };
+            original.trim_end().strip_suffix(code.trim_end()).map_or(0, str::len)
+        })
+        .collect()
+}
+
 pub(crate) trait Tester {
     fn add_test(&mut self, test: String, config: LangString, line: usize);
     fn get_line(&self) -> usize {
@@ -915,6 +1057,8 @@ pub(crate) struct Collector {
     visited_tests: FxHashMap<(String, usize), usize>,
     unused_extern_reports: Arc<Mutex<Vec<UnusedExterns>>>,
     compiling_test_count: AtomicUsize,
+    /// The doctests which are compiled together when `--merge-doctests` is passed, per edition.
+    merged_doctests: FxHashMap<Edition, Arc<MergedDoctests>>,
 }
 
 impl Collector {
@@ -941,6 +1085,7 @@ pub(crate) fn new(
             visited_tests: FxHashMap::default(),
             unused_extern_reports: Default::default(),
             compiling_test_count: AtomicUsize::new(0),
+            merged_doctests: FxHashMap::default(),
         }
     }
 
@@ -957,6 +1102,66 @@ pub(crate) fn set_position(&mut self, position: Span) {
         self.position = position;
     }
 
+    /// Adds the test found at `line` of `path` to the binary shared by the doctests of its
+    /// edition if it doesn't need to be compiled on its own. Returns the bundle and the name of
+    /// the test in it.
+    fn try_merge_test(
+        &mut self,
+        test: &str,
+        config: &LangString,
+        edition: Edition,
+        test_id: &str,
+        path: &Path,
+        line: usize,
+    ) -> Option<(Arc<MergedDoctests>, String)> {
+        if !self.rustdoc_options.merge_doctests
+            || config.compile_fail
+            || config.test_harness
+            || config.ignore != Ignore::None
+            // Each test reports its unused externs through its own compiler invocation.
+            || self.rustdoc_options.json_unused_externs.is_enabled()
+            // Coverage mappings can only point back into the documentation for tests compiled on
+            // their own.
+            || self.rustdoc_options.doctest_coverage.is_some()
+        {
+            return None;
+        }
+        let (test, supports_color) = merged::make_mergeable_test(
+            test,
+            Some(&self.crate_name),
+            edition,
+            config.should_panic,
+            test_id,
+        )?;
+        if !self.merged_doctests.contains_key(&edition) {
+            let outdir = make_outdir(&self.rustdoc_options, &format!("merged_{edition}"));
+            let merged = MergedDoctests::new(
+                edition,
+                self.crate_name.clone(),
+                self.opts.clone(),
+                self.rustdoc_options.clone(),
+                supports_color,
+                outdir,
+            );
+            self.merged_doctests.insert(edition, Arc::new(merged));
+        }
+        let merged = self.merged_doctests[&edition].clone();
+        let name = merged.push(test, path.to_owned(), line);
+        Some((merged, name))
+    }
+
+    /// Returns the byte column offsets of each line of the doctest starting after `line`, see
+    /// [`col_offsets`].
+    fn get_col_offsets(&self, test: &str, line: usize) -> Vec<usize> {
+        let Some(ref source_map) = self.source_map else {
+            return Vec::new();
+        };
+        let file = source_map.lookup_source_file(self.position.lo());
+        // `line` is the 1-based number of the line with the opening fence, so it's also the
+        // 0-based index of the first line of code.
+        col_offsets(test, |i| file.get_line(line + i))
+    }
+
     fn get_filename(&self) -> FileName {
         if let Some(ref source_map) = self.source_map {
             let filename = source_map.span_to_filename(self.position);
@@ -1023,22 +1228,14 @@ fn add_test(&mut self, test: String, config: LangString, line: usize) {
                 self.visited_tests.entry((file.clone(), line)).and_modify(|v| *v += 1).or_insert(0)
             },
         );
-        let outdir = if let Some(mut path) = rustdoc_options.persist_doctests.clone() {
-            path.push(&test_id);
-
-            if let Err(err) = std::fs::create_dir_all(&path) {
-                eprintln!("Couldn't create directory for doctest executables: {err}");
-                panic::resume_unwind(Box::new(()));
-            }
-
-            DirState::Perm(path)
+        let merged = self.try_merge_test(&test, &config, edition, &test_id, &path, line);
+        // Merged tests only need a directory of their own if their bundle fails to compile.
+        let outdir = merged.is_none().then(|| make_outdir(&rustdoc_options, &test_id));
+        // The columns are only needed for coverage mappings.
+        let cols = if rustdoc_options.instrument_coverage() {
+            self.get_col_offsets(&test, line)
         } else {
-            DirState::Temp(
-                TempFileBuilder::new()
-                    .prefix("rustdoctest")
-                    .tempdir()
-                    .expect("rustdoc needs a tempdir"),
-            )
+            Vec::new()
         };
 
         debug!("creating test {name}: {test}");
@@ -1066,23 +1263,54 @@ fn add_test(&mut self, test: String, config: LangString, line: usize) {
                 let report_unused_externs = |uext| {
                     unused_externs.lock().unwrap().push(uext);
                 };
-                let res = run_test(
-                    &test,
-                    &crate_name,
-                    line,
-                    rustdoc_options,
-                    config,
-                    no_run,
-                    runtool,
-                    runtool_args,
-                    target,
-                    &opts,
-                    edition,
-                    outdir,
-                    path,
-                    &test_id,
-                    report_unused_externs,
-                );
+                let merged_binary = merged
+                    .as_ref()
+                    .and_then(|(bundle, name)| Some((bundle.binary()?.to_path_buf(), name)));
+                let res = match merged_binary {
+                    Some(_) if no_run => Ok(()),
+                    Some((binary, name)) => {
+                        // The harness of the bundle checks that the test panics itself.
+                        let config = LangString { should_panic: false, ..config.clone() };
+                        let mut args =
+                            vec![name.clone(), "--exact".to_owned(), "--test-threads=1".to_owned()];
+                        if rustdoc_options.nocapture {
+                            args.push("--nocapture".to_owned());
+                        }
+                        run_test_binary(
+                            binary,
+                            &args,
+                            &config,
+                            &rustdoc_options,
+                            runtool,
+                            runtool_args,
+                            &test_id,
+                        )
+                    }
+                    // Either the test has to be compiled on its own, or the binary it was merged
+                    // into failed to compile and its tests are compiled separately instead.
+                    None => {
+                        let outdir =
+                            outdir.unwrap_or_else(|| make_outdir(&rustdoc_options, &test_id));
+                        run_test(
+                            &test,
+                            &crate_name,
+                            line,
+                            cols,
+                            rustdoc_options,
+                            config,
+                            no_run,
+                            runtool,
+                            runtool_args,
+                            target,
+                            &opts,
+                            edition,
+                            outdir,
+                            path,
+                            &test_id,
+                            report_unused_externs,
+                        )
+                    }
+                };
 
                 if let Err(err) = res {
                     match err {
@@ -1200,6 +1428,8 @@ struct HirCollector<'a, 'hir, 'tcx> {
     map: Map<'hir>,
     codes: ErrorCodes,
     tcx: TyCtxt<'tcx>,
+    /// The documentation to lint with `--lint-examples`, which replaces collecting the tests.
+    examples: Option<Vec<DocumentedItem>>,
 }
 
 impl<'a, 'hir, 'tcx> HirCollector<'a, 'hir, 'tcx> {
@@ -1232,19 +1462,35 @@ fn visit_testable<F: FnOnce(&mut Self)>(
                 .find(|attr| attr.doc_str().is_some())
                 .map(|attr| attr.span.ctxt().outer_expn().expansion_cause().unwrap_or(attr.span))
                 .unwrap_or(DUMMY_SP);
-            self.collector.set_position(span);
-            markdown::find_testable_code(
-                &doc,
-                self.collector,
-                self.codes,
-                self.collector.enable_per_target_ignores,
-                Some(&crate::html::markdown::ExtraInfo::new(
-                    self.tcx,
-                    def_id.to_def_id(),
-                    span_of_fragments(&attrs.doc_strings).unwrap_or(sp),
-                )),
-                self.tcx.features().custom_code_classes_in_docs,
-            );
+            let doc_span = span_of_fragments(&attrs.doc_strings).unwrap_or(sp);
+            let extra_info =
+                crate::html::markdown::ExtraInfo::new(self.tcx, def_id.to_def_id(), doc_span);
+            if let Some(examples) = &mut self.examples {
+                let code_blocks = markdown::rust_code_blocks(
+                    &doc,
+                    &extra_info,
+                    self.tcx.features().custom_code_classes_in_docs,
+                );
+                if !code_blocks.is_empty() {
+                    examples.push(DocumentedItem {
+                        def_id,
+                        span: doc_span,
+                        dox: doc,
+                        doc_strings: attrs.doc_strings,
+                        code_blocks,
+                    });
+                }
+            } else {
+                self.collector.set_position(span);
+                markdown::find_testable_code(
+                    &doc,
+                    self.collector,
+                    self.codes,
+                    self.collector.enable_per_target_ignores,
+                    Some(&extra_info),
+                    self.tcx.features().custom_code_classes_in_docs,
+                );
+            }
         }
 
         nested(self);
@@ -1306,5 +1552,7 @@ fn visit_field_def(&mut self, f: &'hir hir::FieldDef<'_>) {
     }
 }
 
+mod lint_examples;
+mod merged;
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,277 @@
+//! Support for `--lint-examples`, which reports the lint warnings triggered by the examples in the
+//! documentation of a crate instead of running them.
+//!
+//! Every example is compiled on its own with `--emit=metadata` and JSON diagnostics. The lints it
+//! triggers are then reported through the `rustdoc::example_lints` lint, pointing into the doc
+//! comment the example was written in, like the lints of `passes::lint`.
+//!
+//! `make_test` moves the crate attributes and `extern crate` items of an example in front of the
+//! code it wraps into `fn main`, so the lines of the generated program are matched back to the
+//! lines of the example by their content.
+
+use std::io::Write;
+use std::ops::Range;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use rustc_errors::emitter::HumanReadableErrorType;
+use rustc_errors::ColorConfig;
+use rustc_hir::def_id::LocalDefId;
+use rustc_middle::ty::TyCtxt;
+use rustc_resolve::rustdoc::{source_span_for_markdown_range, DocFragment};
+use rustc_session::config::ErrorOutputType;
+use rustc_span::edition::Edition;
+use rustc_span::Span;
+use serde::Deserialize;
+use tempfile::Builder as TempFileBuilder;
+
+use super::{make_rustc_command, make_test, Collector, GlobalTestOptions};
+use crate::config::Options as RustdocOptions;
+use crate::html::markdown::{self, Ignore, RustCodeBlock};
+use crate::lint::EXAMPLE_LINTS;
+
+/// The documentation of an item, along with the examples in it which need to be linted.
+pub(crate) struct DocumentedItem {
+    pub(crate) def_id: LocalDefId,
+    /// Where to report the diagnostics which can't be mapped back into the documentation.
+    pub(crate) span: Span,
+    pub(crate) dox: String,
+    pub(crate) doc_strings: Vec<DocFragment>,
+    pub(crate) code_blocks: Vec<RustCodeBlock>,
+}
+
+/// Returns whether the example is compiled when running the doctests. Examples which aren't, or
+/// are expected to fail to compile, are not linted.
+fn should_lint(code_block: &RustCodeBlock) -> bool {
+    !code_block.lang_string.compile_fail
+        && code_block.lang_string.ignore == Ignore::None
+        && !code_block.code.is_empty()
+}
+
+/// The subset of the JSON diagnostics emitted by `rustc` needed to report them again.
+#[derive(Deserialize)]
+struct Diagnostic {
+    message: String,
+    code: Option<DiagnosticCode>,
+    level: String,
+    spans: Vec<DiagnosticSpan>,
+    children: Vec<Diagnostic>,
+}
+
+#[derive(Deserialize)]
+struct DiagnosticCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct DiagnosticSpan {
+    file_name: String,
+    byte_start: usize,
+    byte_end: usize,
+    is_primary: bool,
+    suggested_replacement: Option<String>,
+}
+
+impl Diagnostic {
+    /// Returns the name of the lint which emitted this diagnostic, if it was emitted by a lint.
+    fn lint_name(&self) -> Option<&str> {
+        let code = &self.code.as_ref()?.code;
+        // Errors have codes like `E0308`, lints use their name.
+        let is_error_code =
+            code.strip_prefix('E').is_some_and(|n| n.bytes().all(|b| b.is_ascii_digit()));
+        (!is_error_code && matches!(&*self.level, "warning" | "error")).then_some(code)
+    }
+}
+
+struct Example<'a> {
+    item: &'a DocumentedItem,
+    code_block: &'a RustCodeBlock,
+    /// The code of the example, with the hidden lines uncovered.
+    test: String,
+    /// The program generated from `test`.
+    program: String,
+    /// The number of lines `make_test` added before the code of `test`.
+    line_offset: usize,
+    edition: Edition,
+}
+
+impl<'a> Example<'a> {
+    fn new(
+        item: &'a DocumentedItem,
+        code_block: &'a RustCodeBlock,
+        crate_name: &str,
+        opts: &GlobalTestOptions,
+        default_edition: Edition,
+    ) -> Example<'a> {
+        let test = markdown::code_block_source(&item.dox[code_block.code.clone()]);
+        let edition = code_block.lang_string.edition.unwrap_or(default_edition);
+        let (program, line_offset, _) = make_test(
+            &test,
+            Some(crate_name),
+            code_block.lang_string.test_harness,
+            opts,
+            edition,
+            None,
+        );
+        Example { item, code_block, test, program, line_offset, edition }
+    }
+
+    /// Maps a byte offset in the generated program to a byte offset in the documentation.
+    fn doc_offset(&self, offset: usize) -> Option<usize> {
+        let before = self.program.get(..offset)?;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.program[offset..].find('\n').map_or(self.program.len(), |i| offset + i);
+        let line_index = before[..line_start].matches('\n').count();
+        let line = self.program[line_start..line_end].trim_end();
+        let col = offset - line_start;
+
+        // Look at the line of the example the generated line would be if no lines had been
+        // moved first, then at all the others.
+        let test_lines = self.test.lines().collect::<Vec<_>>();
+        let (test_index, test_col) = line_index
+            .checked_sub(self.line_offset)
+            .into_iter()
+            .chain(0..test_lines.len())
+            .find_map(|index| {
+                let test_line = test_lines.get(index)?.trim_end();
+                let prefix = test_line.strip_suffix(line)?.len();
+                Some((index, prefix + col))
+            })?;
+
+        // Hidden lines are the only ones changed when uncovering them, and only lose a prefix.
+        let code = &self.item.dox[self.code_block.code.clone()];
+        let mut doc_line_start = self.code_block.code.start;
+        for (index, doc_line) in code.split_inclusive('\n').enumerate() {
+            if index == test_index {
+                let test_line = test_lines[test_index].trim_end();
+                let prefix = doc_line.trim_end().strip_suffix(test_line)?.len();
+                return Some(doc_line_start + prefix + test_col.min(test_line.len()));
+            }
+            doc_line_start += doc_line.len();
+        }
+        None
+    }
+
+    /// Maps the primary span of a diagnostic to a range in the documentation.
+    fn doc_range(&self, diag: &Diagnostic) -> Option<Range<usize>> {
+        let span = diag.spans.iter().find(|span| span.is_primary && span.file_name == "<anon>")?;
+        let start = self.doc_offset(span.byte_start)?;
+        let end = self.doc_offset(span.byte_end)?;
+        (start <= end).then_some(start..end)
+    }
+
+    /// Compiles the example and returns the diagnostics emitted by lints.
+    fn compile(&self, rustdoc_options: &RustdocOptions) -> Vec<Diagnostic> {
+        let outdir = TempFileBuilder::new()
+            .prefix("rustdoctest")
+            .tempdir()
+            .expect("rustdoc needs a tempdir");
+        let mut compiler = make_rustc_command(
+            rustdoc_options,
+            self.edition,
+            &rustdoc_options.target,
+            &outdir.path().join("rust_out"),
+            false,
+        );
+        if self.code_block.lang_string.test_harness {
+            compiler.arg("--test");
+        }
+        compiler.arg("--emit=metadata");
+        compiler.arg("--error-format=json");
+        compiler.arg("-");
+        compiler.stdin(Stdio::piped());
+        compiler.stdout(Stdio::null());
+        compiler.stderr(Stdio::piped());
+
+        debug!("compiler invocation for linting doctest: {compiler:?}");
+
+        let mut child = compiler.spawn().expect("Failed to spawn rustc process");
+        {
+            let stdin = child.stdin.as_mut().expect("Failed to open stdin");
+            stdin.write_all(self.program.as_bytes()).expect("could write out test sources");
+        }
+        let output = child.wait_with_output().expect("Failed to read stdout");
+        String::from_utf8_lossy(&output.stderr)
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Diagnostic>(line).ok())
+            .filter(|diag| diag.lint_name().is_some())
+            .collect()
+    }
+}
+
+/// Compiles the examples of `items` and reports the lints they trigger.
+pub(crate) fn lint_examples(tcx: TyCtxt<'_>, collector: &Collector, items: &[DocumentedItem]) {
+    // `make_test` allows the `unused` lints when there are no test attributes, but they are the
+    // ones examples trigger the most.
+    let mut opts = collector.opts.clone();
+    if opts.attrs.is_empty() {
+        opts.attrs.push("warn(unused)".to_owned());
+    }
+    let examples = items
+        .iter()
+        .flat_map(|item| item.code_blocks.iter().map(move |code_block| (item, code_block)))
+        .filter(|(_, code_block)| should_lint(code_block))
+        .map(|(item, code_block)| {
+            let edition = collector.rustdoc_options.edition;
+            Example::new(item, code_block, &collector.crate_name, &opts, edition)
+        })
+        .collect::<Vec<_>>();
+
+    // The examples have to be compiled with JSON diagnostics, which `make_rustc_command` leaves
+    // to the caller.
+    let mut rustdoc_options = collector.rustdoc_options.clone();
+    rustdoc_options.error_format = ErrorOutputType::Json {
+        pretty: false,
+        json_rendered: HumanReadableErrorType::Default(ColorConfig::Never),
+    };
+
+    // Compiling the examples is what takes time, so do it in parallel, but report the
+    // diagnostics in the order of the examples.
+    let diagnostics = examples.iter().map(|_| Mutex::new(Vec::new())).collect::<Vec<_>>();
+    let next = AtomicUsize::new(0);
+    let threads = thread::available_parallelism().map_or(1, |n| n.get()).min(examples.len());
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(example) = examples.get(index) else { break };
+                    *diagnostics[index].lock().unwrap() = example.compile(&rustdoc_options);
+                }
+            });
+        }
+    });
+
+    for (example, diagnostics) in examples.iter().zip(diagnostics) {
+        for diag in diagnostics.into_inner().unwrap() {
+            report_diagnostic(tcx, example, diag);
+        }
+    }
+}
+
+fn report_diagnostic(tcx: TyCtxt<'_>, example: &Example<'_>, diag: Diagnostic) {
+    let item = example.item;
+    let lint_name = diag.lint_name().unwrap_or_default().to_owned();
+    let range = example.doc_range(&diag).unwrap_or_else(|| example.code_block.range.clone());
+    let sp = source_span_for_markdown_range(tcx, &item.dox, &range, &item.doc_strings)
+        .unwrap_or(item.span);
+
+    let hir_id = tcx.local_def_id_to_hir_id(item.def_id);
+    tcx.node_span_lint(EXAMPLE_LINTS, hir_id, sp, diag.message, |lint| {
+        lint.note(format!("this example triggers the `{lint_name}` lint"));
+        // The notes pointing into the generated program, like where the lint level is set,
+        // would be confusing without their span.
+        for child in diag.children.iter().filter(|child| child.level == "help") {
+            match child.spans.iter().find_map(|span| span.suggested_replacement.as_ref()) {
+                Some(replacement) if !replacement.is_empty() => {
+                    lint.help(format!("{}: `{replacement}`", child.message));
+                }
+                _ => {
+                    lint.help(child.message.clone());
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,312 @@
+//! Support for compiling several doctests into a single executable.
+//!
+//! Compiling every doctest on its own means spawning one `rustc` per code block, which dominates
+//! the time spent running the doctests of large crates. With `--merge-doctests`, doctests which
+//! don't need to be compiled on their own are instead turned into `#[test]` functions, each in
+//! their own module file, of a single "bundle" crate (one per edition) which is compiled once with
+//! the libtest harness. Each test still runs in its own process, which runs only that test by
+//! passing its name as an exact filter to the harness.
+//!
+//! The module file of each test is named after the doctest it contains, with its lines offset like
+//! for the doctests compiled on their own, so that diagnostics and panic locations point into the
+//! documentation.
+//!
+//! A doctest has to be compiled on its own if it:
+//!
+//! * is a `compile_fail` test or uses `test_harness`,
+//! * has its own `fn main`,
+//! * has crate-level attributes or `#[macro_use] extern crate` items,
+//! * is `#![no_std]`,
+//! * is a 2015 edition test with `use` declarations or `extern crate` items, whose paths are
+//!   relative to the crate root and would resolve differently from the module of the test.
+//!
+//! If the bundle fails to compile, all the tests it contains fall back to being compiled on their
+//! own, so that compilation errors are reported for the test which caused them.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Mutex, OnceLock};
+
+use rustc_span::edition::Edition;
+use rustc_target::spec::TargetTriple;
+
+use super::{
+    add_exe_suffix, check_for_main_and_extern_crate, make_rustc_command, partition_source,
+    DirState, GlobalTestOptions,
+};
+use crate::config::Options as RustdocOptions;
+
+/// A doctest split into the parts needed to put it into a bundle.
+pub(crate) struct MergeableDoctest {
+    /// The `extern crate` items at the start of the test.
+    crates: String,
+    /// The rest of the test, which becomes the body of the test function.
+    body: String,
+    /// The index of the line of the test on which `body` starts.
+    body_start: usize,
+    /// Whether the test ends with `Ok(())`, and therefore needs to return a `Result`.
+    returns_result: bool,
+    /// Whether the test mentions the documented crate, which then needs to be injected.
+    uses_crate: bool,
+    should_panic: bool,
+    test_id: String,
+}
+
+impl MergeableDoctest {
+    fn fn_name(&self) -> String {
+        format!("_doctest_main_{}", self.test_id)
+    }
+
+    /// The source of the module containing the test. All the attributes of the test function are
+    /// put on the same line as its signature, so that the lines of the test are only preceded by
+    /// those of its `extern crate` items.
+    fn module_source(&self) -> String {
+        let mut source = self.crates.clone();
+        source.push_str("#[test] ");
+        if self.should_panic {
+            source.push_str("#[should_panic] ");
+        }
+        let return_ty =
+            if self.returns_result { " -> Result<(), impl core::fmt::Debug>" } else { "" };
+        source.push_str(&format!(
+            "#[allow(non_snake_case)] pub(crate) fn {}(){return_ty} {{\n",
+            self.fn_name()
+        ));
+        source.push_str(&self.body);
+        source.push_str("\n}\n");
+        source
+    }
+
+    /// The offset between the lines of the module containing the test and the lines of the file
+    /// the test comes from, given the number of the line with the opening fence of the test.
+    fn line_offset(&self, line: usize) -> isize {
+        let lines_before_body = self.crates.lines().count() + 1;
+        (line + self.body_start) as isize - lines_before_body as isize
+    }
+}
+
+/// Splits `s` into a [`MergeableDoctest`], or returns `None` if the doctest has to be compiled on
+/// its own. Also returns whether the output stream supports colors.
+pub(crate) fn make_mergeable_test(
+    s: &str,
+    crate_name: Option<&str>,
+    edition: Edition,
+    should_panic: bool,
+    test_id: &str,
+) -> Option<(MergeableDoctest, bool)> {
+    let (crate_attrs, everything_else, crates) = partition_source(s, edition);
+    // `partition_source` also puts the blank and comment lines found before the first item with
+    // the crate attributes, so look for actual attributes.
+    if crate_attrs.lines().any(|line| line.trim_start().starts_with("#!")) {
+        return None;
+    }
+    // `#[macro_use]` is only allowed on `extern crate` items at the crate root.
+    if crates.contains("#[macro_use]") || s.contains("![no_std]") {
+        return None;
+    }
+    if edition == Edition::Edition2015
+        && (crates.contains("extern crate")
+            || everything_else.lines().any(|line| {
+                let line = line.trim_start();
+                line.starts_with("use ") || line.starts_with("pub use ")
+            }))
+    {
+        return None;
+    }
+
+    let everything_else = everything_else.trim();
+    let mut supports_color = false;
+    let (already_has_main, already_has_extern_crate) = check_for_main_and_extern_crate(
+        crate_name,
+        s,
+        crates.clone(),
+        everything_else,
+        edition,
+        &mut supports_color,
+    )
+    .ok()?;
+    if already_has_main {
+        return None;
+    }
+
+    let first_line = everything_else.lines().next().unwrap_or_default().trim();
+    let body_start = s.lines().position(|line| line.trim() == first_line).unwrap_or(0);
+    let uses_crate = !already_has_extern_crate
+        && crate_name.is_some_and(|crate_name| crate_name != "std" && s.contains(crate_name));
+    let test = MergeableDoctest {
+        crates,
+        body: everything_else.to_owned(),
+        body_start,
+        returns_result: everything_else.trim_end().ends_with("(())"),
+        uses_crate,
+        should_panic,
+        test_id: test_id.to_owned(),
+    };
+    Some((test, supports_color))
+}
+
+/// Generates the source of the root module of a bundle containing `tests`. The test at index `i`
+/// is in the module `__doctest_{i}`, whose source is read from `doctest_{i}.rs`.
+pub(crate) fn make_merged_source(
+    tests: &[&MergeableDoctest],
+    crate_name: Option<&str>,
+    opts: &GlobalTestOptions,
+) -> String {
+    let mut prog = String::new();
+
+    // See `make_test` for why this is only added when no test attributes were supplied.
+    if opts.attrs.is_empty() {
+        prog.push_str("#![allow(unused)]\n");
+    }
+    for attr in &opts.attrs {
+        prog.push_str(&format!("#![{attr}]\n"));
+    }
+    if !opts.no_crate_inject
+        && let Some(crate_name) = crate_name
+        && tests.iter().any(|test| test.uses_crate)
+    {
+        prog.push_str("#[allow(unused_extern_crates)]\n");
+        prog.push_str(&format!("extern crate r#{crate_name};\n"));
+    }
+
+    for index in 0..tests.len() {
+        prog.push_str(&format!("\n#[path = \"doctest_{index}.rs\"]\nmod __doctest_{index};\n"));
+    }
+
+    debug!("merged doctests:\n{prog}");
+
+    prog
+}
+
+/// A test added to a bundle, along with where it comes from.
+struct MergedTest {
+    test: MergeableDoctest,
+    /// The file containing the test, as passed to rustc for the tests compiled on their own.
+    path: PathBuf,
+    line: usize,
+}
+
+/// All the doctests sharing an edition which are compiled into the same bundle.
+pub(crate) struct MergedDoctests {
+    edition: Edition,
+    crate_name: String,
+    opts: GlobalTestOptions,
+    rustdoc_options: RustdocOptions,
+    target: TargetTriple,
+    supports_color: bool,
+    outdir: DirState,
+    tests: Mutex<Vec<MergedTest>>,
+    /// The compiled bundle, or `None` if it failed to compile.
+    binary: OnceLock<Option<PathBuf>>,
+}
+
+impl MergedDoctests {
+    pub(crate) fn new(
+        edition: Edition,
+        crate_name: String,
+        opts: GlobalTestOptions,
+        rustdoc_options: RustdocOptions,
+        supports_color: bool,
+        outdir: DirState,
+    ) -> MergedDoctests {
+        let target = rustdoc_options.target.clone();
+        MergedDoctests {
+            edition,
+            crate_name,
+            opts,
+            rustdoc_options,
+            target,
+            supports_color,
+            outdir,
+            tests: Mutex::new(Vec::new()),
+            binary: OnceLock::new(),
+        }
+    }
+
+    /// Adds a test found at `line` of `path` to the bundle, and returns the name of the test in
+    /// the harness of the bundle.
+    pub(crate) fn push(&self, test: MergeableDoctest, path: PathBuf, line: usize) -> String {
+        let mut tests = self.tests.lock().unwrap();
+        let name = format!("__doctest_{}::{}", tests.len(), test.fn_name());
+        tests.push(MergedTest { test, path, line });
+        name
+    }
+
+    /// Returns the path of the compiled bundle, compiling it the first time this is called.
+    ///
+    /// Tests are only run once all of them have been collected, so the bundle contains every test
+    /// pushed into it by then.
+    pub(crate) fn binary(&self) -> Option<&Path> {
+        self.binary.get_or_init(|| self.compile()).as_deref()
+    }
+
+    /// Writes the sources of the bundle, and the list of its module files with the doctests they
+    /// are named after. Returns the path of the root module and of the list.
+    fn write_sources(&self, name: &str) -> std::io::Result<(PathBuf, PathBuf)> {
+        let tests = self.tests.lock().unwrap();
+        let dir = self.outdir.path();
+        let mut files = String::new();
+        for (index, MergedTest { test, path, line }) in tests.iter().enumerate() {
+            let module_file = dir.join(format!("doctest_{index}.rs"));
+            fs::write(&module_file, test.module_source())?;
+            writeln!(
+                files,
+                "{}\t{}\t{}",
+                module_file.display(),
+                test.line_offset(*line),
+                path.display()
+            )
+            .unwrap();
+        }
+        let files_list = dir.join(format!("{name}.files"));
+        fs::write(&files_list, files)?;
+
+        let tests = tests.iter().map(|merged| &merged.test).collect::<Vec<_>>();
+        let source = make_merged_source(&tests, Some(&self.crate_name), &self.opts);
+        let source_file = dir.join(format!("{name}.rs"));
+        fs::write(&source_file, source)?;
+        Ok((source_file, files_list))
+    }
+
+    fn compile(&self) -> Option<PathBuf> {
+        let name = format!("doctest_bundle_{}", self.edition);
+        let (source_file, files_list) = match self.write_sources(&name) {
+            Ok(paths) => paths,
+            Err(err) => {
+                debug!("couldn't write merged doctests to {}: {err}", self.outdir.path().display());
+                return None;
+            }
+        };
+        let output_file = self.outdir.path().join(add_exe_suffix(name, &self.target));
+
+        let mut compiler = make_rustc_command(
+            &self.rustdoc_options,
+            self.edition,
+            &self.target,
+            &output_file,
+            self.supports_color,
+        );
+        compiler.env("UNSTABLE_RUSTDOC_TEST_FILES", files_list);
+        compiler.arg("--test");
+        compiler.arg(&source_file);
+        compiler.stdout(Stdio::null());
+        compiler.stderr(Stdio::piped());
+
+        debug!("compiler invocation for merged doctests: {compiler:?}");
+
+        let output = compiler.output().expect("Failed to spawn rustc process");
+        if output.status.success() {
+            Some(output_file)
+        } else {
+            // The tests will be compiled on their own instead, which reports the error against
+            // the test that caused it.
+            debug!(
+                "merged doctests failed to compile:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            None
+        }
+    }
+}
@@ -1,5 +1,6 @@
-use super::{make_test, GlobalTestOptions};
-use rustc_span::edition::DEFAULT_EDITION;
+use super::merged::{make_mergeable_test, make_merged_source};
+use super::{col_offsets, make_test, GlobalTestOptions};
+use rustc_span::edition::{Edition, DEFAULT_EDITION};
 
 #[test]
 fn make_test_basic() {
@@ -302,3 +303,150 @@ fn main() { #[allow(non_snake_case)] fn _doctest_main__some_unique_name() {
         make_test(input, None, false, &opts, DEFAULT_EDITION, Some("_some_unique_name"));
     assert_eq!((output, len), (expected, 2));
 }
+
+#[test]
+fn merge_tests_basic() {
+    // Each test gets its own module, and the documented crate is injected once at the root if
+    // any of the tests use it.
+    let opts = GlobalTestOptions::default();
+    let first = "use asdf::qwop;
+assert_eq!(2+2, 4);";
+    let second = "extern crate core;
+let x: u8 = \"1\".parse()?;
+Ok::<(), core::num::ParseIntError>(())";
+    let tests = [(first, "first"), (second, "second")]
+        .into_iter()
+        .map(|(input, id)| {
+            make_mergeable_test(input, Some("asdf"), DEFAULT_EDITION, false, id).unwrap().0
+        })
+        .collect::<Vec<_>>();
+    let expected = "#![allow(unused)]
+#[allow(unused_extern_crates)]
+extern crate r#asdf;
+
+#[path = \"doctest_0.rs\"]
+mod __doctest_0;
+
+#[path = \"doctest_1.rs\"]
+mod __doctest_1;
+";
+    assert_eq!(
+        make_merged_source(&tests.iter().collect::<Vec<_>>(), Some("asdf"), &opts),
+        expected
+    );
+
+    let expected = "#[test] #[allow(non_snake_case)] pub(crate) fn _doctest_main_first() {
+use asdf::qwop;
+assert_eq!(2+2, 4);
+}
+";
+    assert_eq!(tests[0].module_source(), expected);
+    let expected = "extern crate core;
+#[test] #[allow(non_snake_case)] pub(crate) fn _doctest_main_second() -> Result<(), impl core::fmt::Debug> {
+let x: u8 = \"1\".parse()?;
+Ok::<(), core::num::ParseIntError>(())
+}
+";
+    assert_eq!(tests[1].module_source(), expected);
+}
+
+#[test]
+fn merge_tests_line_offset() {
+    // The lines of the test keep their place in the documentation, even though the `extern crate`
+    // items are moved before the test function. Here, the opening fence is at line 10.
+    let input = "
+
+extern crate core;
+let x = 1;
+panic!();";
+    let (test, _) = make_mergeable_test(input, None, DEFAULT_EDITION, true, "id").unwrap();
+    let expected = "extern crate core;
+#[test] #[should_panic] #[allow(non_snake_case)] pub(crate) fn _doctest_main_id() {
+let x = 1;
+panic!();
+}
+";
+    assert_eq!(test.module_source(), expected);
+    // `let x = 1;` is at line 3 of the module and line 14 of the documentation.
+    assert_eq!(test.line_offset(10), 11);
+}
+
+#[test]
+fn merge_tests_need_separate_compilation() {
+    // Tests with their own `main`, crate attributes, `#[macro_use] extern crate` or `no_std`
+    // can't share a binary with other tests.
+    let inputs = [
+        "fn main() {
+    assert_eq!(2+2, 4);
+}",
+        "#![feature(sure)]
+assert_eq!(2+2, 4);",
+        "#[macro_use] extern crate asdf;
+assert_eq!(2+2, 4);",
+        "#![no_std]
+pub fn f() {}",
+    ];
+    for input in inputs {
+        assert!(make_mergeable_test(input, Some("asdf"), DEFAULT_EDITION, false, "id").is_none());
+    }
+}
+
+#[test]
+fn merge_tests_2015_paths() {
+    // In the 2015 edition, paths are relative to the crate root, so tests with `use` declarations
+    // or `extern crate` items would break in the module of a bundle.
+    let inputs = [
+        "mod m { pub fn f() {} }
+use m::f;
+f();",
+        "extern crate asdf;
+asdf::f();",
+    ];
+    for input in inputs {
+        let mergeable =
+            |edition| make_mergeable_test(input, Some("asdf"), edition, false, "id").is_some();
+        assert!(!mergeable(Edition::Edition2015));
+        assert!(mergeable(Edition::Edition2018));
+    }
+    let input = "let x = ::std::mem::size_of::<u8>();";
+    assert!(make_mergeable_test(input, None, Edition::Edition2015, false, "id").is_some());
+}
+
+#[test]
+fn merge_tests_attrs() {
+    // Attributes from `#![doc(test(attr(...)))]` apply to the whole binary.
+    let opts =
+        GlobalTestOptions { no_crate_inject: false, attrs: vec!["deny(warnings)".to_string()] };
+    let (test, _) = make_mergeable_test(
+        "// Comments are fine.\nlet x = 1;",
+        Some("asdf"),
+        DEFAULT_EDITION,
+        false,
+        "id",
+    )
+    .unwrap();
+    let expected = "#![deny(warnings)]
+
+#[path = \"doctest_0.rs\"]
+mod __doctest_0;
+";
+    assert_eq!(make_merged_source(&[&test], Some("asdf"), &opts), expected);
+}
+
+#[test]
+fn col_offsets_per_line() {
+    // Each line of code has its own prefix in the documentation: hidden lines, indented code and
+    // doc comments which are themselves indented differently.
+    let source = [
+        "/// ```",
+        "/// # fn f() {}",
+        "/// let x = 1;",
+        "///",
+        "///     f();",
+        "    /// let y = 2;",
+        "/// ```",
+    ];
+    let test = "fn f() {}\nlet x = 1;\n\n    f();\nlet y = 2;";
+    let offsets = col_offsets(test, |i| source.get(i + 1).map(|line| (*line).into()));
+    assert_eq!(offsets, [6, 4, 0, 4, 8]);
+}
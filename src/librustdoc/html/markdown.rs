@@ -181,6 +181,22 @@ fn map_line(s: &str) -> Line<'_> {
     }
 }
 
+/// Returns the code of a code block as it is compiled when running doctests, with the leading
+/// `#` of hidden lines removed.
+pub(crate) fn code_block_source(code: &str) -> String {
+    code.lines().map(|l| map_line(l).for_code()).collect::<Vec<Cow<'_, str>>>().join("\n")
+}
+
+/// Returns the code of a code block with the given language string as it is shown in the
+/// documentation, without the hidden lines of Rust code blocks.
+pub(crate) fn shown_code_block_source(lang: &str, code: &str) -> String {
+    if !lang.is_empty() && !LangString::parse_without_check(lang, ErrorCodes::No, false, false).rust
+    {
+        return code.to_owned();
+    }
+    code.lines().filter_map(|l| map_line(l).for_html()).collect::<Vec<Cow<'_, str>>>().join("\n")
+}
+
 /// Convert chars from a title for an id.
 ///
 /// "Hello, world!" -> "hello-world"
@@ -784,11 +800,7 @@ pub(crate) fn find_codes<T: doctest::Tester>(
                 while let Some((Event::Text(s), _)) = parser.next() {
                     test_s.push_str(&s);
                 }
-                let text = test_s
-                    .lines()
-                    .map(|l| map_line(l).for_code())
-                    .collect::<Vec<Cow<'_, str>>>()
-                    .join("\n");
+                let text = code_block_source(&test_s);
 
                 nb_lines += doc[prev_offset..offset.start].lines().count();
                 // If there are characters between the preceding line ending and
@@ -180,6 +180,8 @@ pub(crate) enum RenderTypeId {
     Primitive(clean::PrimitiveType),
     AssociatedType(Symbol),
     Index(isize),
+    /// The mutability of a `&mut` reference, written as the first generic of the reference.
+    Mut,
 }
 
 impl RenderTypeId {
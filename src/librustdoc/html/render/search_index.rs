@@ -4,7 +4,7 @@
 use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
 use rustc_middle::ty::TyCtxt;
 use rustc_span::def_id::DefId;
-use rustc_span::symbol::Symbol;
+use rustc_span::symbol::{kw, Symbol};
 use serde::ser::{Serialize, SerializeSeq, SerializeStruct, Serializer};
 use thin_vec::ThinVec;
 
@@ -142,6 +142,14 @@ fn convert_render_type_id(
                         &[sym],
                     ))
                 }
+                RenderTypeId::Mut => Some(insert_into_map(
+                    primitives,
+                    kw::Mut,
+                    lastpathid,
+                    crate_paths,
+                    ItemType::Keyword,
+                    &[kw::Mut],
+                )),
                 RenderTypeId::Index(_) => Some(id),
                 RenderTypeId::AssociatedType(sym) => Some(insert_into_map(
                     associated_types,
@@ -560,9 +568,8 @@ fn get_index_type_id(
             bounds.get(0).map(|b| RenderTypeId::DefId(b.trait_.def_id()))
         }
         clean::Primitive(p) => Some(RenderTypeId::Primitive(p)),
-        clean::BorrowedRef { ref type_, .. } | clean::RawPointer(_, ref type_) => {
-            get_index_type_id(type_, rgen)
-        }
+        clean::BorrowedRef { .. } => Some(RenderTypeId::Primitive(clean::PrimitiveType::Reference)),
+        clean::RawPointer(_, ref type_) => get_index_type_id(type_, rgen),
         // The type parameters are converted to generics in `simplify_fn_type`
         clean::Slice(_) => Some(RenderTypeId::Primitive(clean::PrimitiveType::Slice)),
         clean::Array(_, _) => Some(RenderTypeId::Primitive(clean::PrimitiveType::Array)),
@@ -628,7 +635,7 @@ fn simplify_fn_type<'tcx, 'a>(
 
     // First, check if it's "Self".
     let mut is_self = false;
-    let mut arg = if let Some(self_) = self_ {
+    let arg = if let Some(self_) = self_ {
         match &*arg {
             Type::BorrowedRef { type_, .. } if type_.is_self_type() => {
                 is_self = true;
@@ -644,11 +651,6 @@ fn simplify_fn_type<'tcx, 'a>(
         arg
     };
 
-    // strip references from the argument type
-    while let Type::BorrowedRef { type_, .. } = &*arg {
-        arg = &*type_;
-    }
-
     // If this argument is a type parameter and not a trait bound or a type, we need to look
     // for its bounds.
     if let Type::Generic(arg_s) = *arg {
@@ -741,6 +743,30 @@ fn simplify_fn_type<'tcx, 'a>(
                 bindings: None,
             });
         }
+    } else if let Type::BorrowedRef { mutability, ref type_, .. } = *arg {
+        // References are written as the `reference` primitive, with the referenced type as its
+        // generic. `&mut T` is `reference<mut, T>`: the search engine checks that the `mut`
+        // keyword is there if and only if the query has it.
+        let mut ty_generics = Vec::new();
+        if mutability.is_mut() {
+            ty_generics.push(RenderType {
+                id: Some(RenderTypeId::Mut),
+                generics: None,
+                bindings: None,
+            });
+        }
+        simplify_fn_type(
+            self_,
+            generics,
+            &type_,
+            tcx,
+            recurse + 1,
+            &mut ty_generics,
+            rgen,
+            is_return,
+            cache,
+        );
+        res.push(get_index_type(arg, ty_generics, rgen));
     } else if let Type::Slice(ref ty) = *arg {
         let mut ty_generics = Vec::new();
         simplify_fn_type(
@@ -230,6 +230,10 @@ function editDistance(a, b, limit) {
 function initSearch(rawSearchIndex) {
     const MAX_RESULTS = 200;
     const NO_TYPE_FILTER = -1;
+    /**
+     * The number of times types can be unboxed to match a query, like `u8` matching `Vec<u8>`.
+     */
+    const UNBOXING_LIMIT = 10;
     /**
      *  @type {Array<Row>}
      */
@@ -272,6 +276,14 @@ function initSearch(rawSearchIndex) {
      * Special type name IDs for searching by both tuple and unit (`()` syntax).
      */
     let typeNameIdOfTupleOrUnit;
+    /**
+     * Special type name IDs for searching by reference (`&` syntax).
+     */
+    let typeNameIdOfReference;
+    /**
+     * Special type name IDs for the mutability of references (`&mut` syntax).
+     */
+    let typeNameIdOfMut;
 
     /**
      * Add an item to the type Name->ID map, or, if one already exists, use it.
@@ -670,7 +682,9 @@ function initSearch(rawSearchIndex) {
         skipWhitespace(parserState);
         let start = parserState.pos;
         let end;
-        if ("[(".indexOf(parserState.userQuery[parserState.pos]) !== -1) {
+        if (parserState.userQuery[parserState.pos] === "&") {
+            getReferenceElem(query, parserState, elems, isInGenerics);
+        } else if ("[(".indexOf(parserState.userQuery[parserState.pos]) !== -1) {
 let endChar = ")";
 let name = "()";
 let friendlyName = "tuple";
@@ -780,6 +794,80 @@ if (parserState.userQuery[parserState.pos] === "[") {
         }
     }
 
+    /**
+     * Parses a reference, like `&str` or `&mut Vec<u8>`. It is searched for as the `reference`
+     * primitive, with the referenced type as its generic, and a `mut` keyword as its first
+     * generic if it is mutable.
+     *
+     * @param {ParsedQuery} query
+     * @param {ParserState} parserState
+     * @param {Array<QueryElement>} elems - This is where the new {QueryElement} will be added.
+     * @param {boolean} isInGenerics
+     */
+    function getReferenceElem(query, parserState, elems, isInGenerics) {
+        const typeFilter = parserState.typeFilter;
+        const isInBinding = parserState.isInBinding;
+        if (typeFilter !== null && typeFilter !== "primitive") {
+            throw [
+                "Invalid search type: primitive ",
+                "&",
+                " and ",
+                typeFilter,
+                " both specified",
+            ];
+        }
+        parserState.typeFilter = null;
+        parserState.isInBinding = null;
+        parserState.pos += 1;
+        skipWhitespace(parserState);
+
+        const generics = [];
+        if (parserState.userQuery.startsWith("mut", parserState.pos) &&
+            (parserState.pos + 3 >= parserState.length ||
+                !isIdentCharacter(parserState.userQuery[parserState.pos + 3]))
+        ) {
+            parserState.pos += 3;
+            generics.push({
+                name: "mut",
+                id: null,
+                fullPath: ["mut"],
+                pathWithoutLast: [],
+                pathLast: "mut",
+                normalizedPathLast: "mut",
+                generics: [],
+                bindings: new Map(),
+                typeFilter: "keyword",
+                bindingName: null,
+            });
+            skipWhitespace(parserState);
+        }
+        if (parserState.pos < parserState.length &&
+            !isEndCharacter(parserState.userQuery[parserState.pos]) &&
+            !isSeparatorCharacter(parserState.userQuery[parserState.pos])
+        ) {
+            getNextElem(query, parserState, generics, true);
+            if (parserState.isInBinding !== null) {
+                throw ["Type parameter ", "=", " cannot be within reference ", "&"];
+            }
+        }
+        parserState.totalElems += 1;
+        if (isInGenerics) {
+            parserState.genericsElems += 1;
+        }
+        elems.push({
+            name: "reference",
+            id: null,
+            fullPath: ["reference"],
+            pathWithoutLast: [],
+            pathLast: "reference",
+            normalizedPathLast: "reference",
+            generics,
+            bindings: new Map(),
+            typeFilter: "primitive",
+            bindingName: isInBinding,
+        });
+    }
+
     /**
      * This function parses the next query element until it finds `endChar`, calling `getNextElem`
      * to collect each element.
@@ -1377,22 +1465,35 @@ if (parserState.userQuery[parserState.pos] === "[") {
          * then this function will try with a different solution, or bail with false if it
          * runs out of candidates.
          *
+         * The callback is also given the unboxing budget left by the solution, which is the
+         * smallest budget any of its types was matched with. The more types need to be unboxed,
+         * the less budget is left, so the cheapest solution is the one leaving the most.
+         *
          * @param {Array<FunctionType>} fnTypesIn - The objects to check.
          * @param {Array<QueryElement>} queryElems - The elements from the parsed query.
          * @param {[FunctionType]} whereClause - Trait bounds for generic items.
          * @param {Map<number,number>|null} mgensIn
          *     - Map functions generics to query generics (never modified).
-         * @param {null|Map<number,number> -> bool} solutionCb - Called for each `mgens` solution.
+         * @param {null|(Map<number,number>, number) -> bool} solutionCb
+         *     - Called for each `mgens` solution, with the unboxing budget it leaves.
+         * @param {number} unboxingBudget - The number of times types can still be unboxed.
          *
          * @return {boolean} - Returns true if a match, false otherwise.
          */
-        function unifyFunctionTypes(fnTypesIn, queryElems, whereClause, mgensIn, solutionCb) {
+        function unifyFunctionTypes(
+            fnTypesIn,
+            queryElems,
+            whereClause,
+            mgensIn,
+            solutionCb,
+            unboxingBudget
+        ) {
             /**
              * @type Map<integer, integer>|null
              */
             const mgens = mgensIn === null ? null : new Map(mgensIn);
             if (queryElems.length === 0) {
-                return !solutionCb || solutionCb(mgens);
+                return !solutionCb || solutionCb(mgens, unboxingBudget);
             }
             if (!fnTypesIn || fnTypesIn.length === 0) {
                 return false;
@@ -1415,16 +1516,24 @@ if (parserState.userQuery[parserState.pos] === "[") {
                         }
                         const mgensScratch = new Map(mgens);
                         mgensScratch.set(fnType.id, queryElem.id);
-                        if (!solutionCb || solutionCb(mgensScratch)) {
+                        if (!solutionCb || solutionCb(mgensScratch, unboxingBudget)) {
                             return true;
                         }
-                    } else if (!solutionCb || solutionCb(mgens ? new Map(mgens) : null)) {
+                    } else if (
+                        !solutionCb || solutionCb(mgens ? new Map(mgens) : null, unboxingBudget)
+                    ) {
                         // unifyFunctionTypeIsMatchCandidate already checks that ids match
                         return true;
                     }
                 }
                 for (const fnType of fnTypesIn) {
-                    if (!unifyFunctionTypeIsUnboxCandidate(fnType, queryElem, whereClause, mgens)) {
+                    if (!unifyFunctionTypeIsUnboxCandidate(
+                        fnType,
+                        queryElem,
+                        whereClause,
+                        mgens,
+                        unboxingBudget
+                    )) {
                         continue;
                     }
                     if (fnType.id < 0) {
@@ -1439,7 +1548,8 @@ if (parserState.userQuery[parserState.pos] === "[") {
                             queryElems,
                             whereClause,
                             mgensScratch,
-                            solutionCb
+                            solutionCb,
+                            unboxingBudget - 1
                         )) {
                             return true;
                         }
@@ -1448,7 +1558,8 @@ if (parserState.userQuery[parserState.pos] === "[") {
                         queryElems,
                         whereClause,
                         mgens ? new Map(mgens) : null,
-                        solutionCb
+                        solutionCb,
+                        unboxingBudget - 1
                     )) {
                         return true;
                     }
@@ -1512,35 +1623,43 @@ if (parserState.userQuery[parserState.pos] === "[") {
                     queryElemsTmp,
                     whereClause,
                     mgensScratch,
-                    mgensScratch => {
+                    (mgensScratch, restBudget) => {
+                        // `queryElem` itself was matched with the current budget.
+                        const budget = Math.min(restBudget, unboxingBudget);
                         if (fnType.generics.length === 0 && queryElem.generics.length === 0
                             && fnType.bindings.size === 0 && queryElem.bindings.size === 0) {
-                            return !solutionCb || solutionCb(mgensScratch);
+                            return !solutionCb || solutionCb(mgensScratch, budget);
                         }
                         const solution = unifyFunctionTypeCheckBindings(
                             fnType,
                             queryElem,
                             whereClause,
-                            mgensScratch
+                            mgensScratch,
+                            unboxingBudget
                         );
                         if (!solution) {
                             return false;
                         }
                         const simplifiedGenerics = solution.simplifiedGenerics;
-                        for (const simplifiedMgens of solution.mgens) {
+                        for (const [simplifiedMgens, bindingsBudget] of solution.solutions) {
                             const passesUnification = unifyFunctionTypes(
                                 simplifiedGenerics,
                                 queryElem.generics,
                                 whereClause,
                                 simplifiedMgens,
-                                solutionCb
+                                solutionCb && ((mgensGenerics, genericsBudget) => solutionCb(
+                                    mgensGenerics,
+                                    Math.min(budget, bindingsBudget, genericsBudget)
+                                )),
+                                unboxingBudget
                             );
                             if (passesUnification) {
                                 return true;
                             }
                         }
                         return false;
-                    }
+                    },
+                    unboxingBudget
                 );
                 if (passesUnification) {
                     return true;
@@ -1552,7 +1671,13 @@ if (parserState.userQuery[parserState.pos] === "[") {
             }
             for (let i = flast; i >= 0; i -= 1) {
                 const fnType = fnTypes[i];
-                if (!unifyFunctionTypeIsUnboxCandidate(fnType, queryElem, whereClause, mgens)) {
+                if (!unifyFunctionTypeIsUnboxCandidate(
+                    fnType,
+                    queryElem,
+                    whereClause,
+                    mgens,
+                    unboxingBudget
+                )) {
                     continue;
                 }
                 let mgensScratch;
@@ -1576,7 +1701,8 @@ if (parserState.userQuery[parserState.pos] === "[") {
                     queryElems,
                     whereClause,
                     mgensScratch,
-                    solutionCb
+                    solutionCb,
+                    unboxingBudget - 1
                 );
                 if (passesUnification) {
                     return true;
@@ -1638,6 +1764,12 @@ if (parserState.userQuery[parserState.pos] === "[") {
                 } else if (fnType.id !== queryElem.id || queryElem.id === null) {
                     return false;
                 }
+                // `&T` and `&mut T` are different types: the `mut` is their first generic.
+                if (queryElem.id === typeNameIdOfReference &&
+                    isMutableReference(fnType) !== isMutableReference(queryElem)
+                ) {
+                    return false;
+                }
                 // If the query elem has generics, and the function doesn't,
                 // it can't match.
                 if ((fnType.generics.length + fnType.bindings.size) === 0 &&
@@ -1677,6 +1809,15 @@ if (parserState.userQuery[parserState.pos] === "[") {
                 return true;
             }
         }
+        /**
+         * Returns `true` if `type` is a `&mut` reference.
+         *
+         * @param {FunctionType|QueryElement} type
+         * @returns {boolean}
+         */
+        function isMutableReference(type) {
+            return type.generics.length > 0 && type.generics[0].id === typeNameIdOfMut;
+        }
         /**
          * This function checks the associated type bindings. Any that aren't matched get converted
          * to generics, and this function returns an array of the function's generics with these
@@ -1694,15 +1835,25 @@ if (parserState.userQuery[parserState.pos] === "[") {
          * @param {[FunctionType]} whereClause - Trait bounds for generic items.
          * @param {Map<number,number>} mgensIn - Map functions generics to query generics.
          *                                            Never modified.
-         * @returns {false|{mgens: [Map<number,number>], simplifiedGenerics: [FunctionType]}}
+         * @param {number} unboxingBudget - The number of times types can still be unboxed.
+         * @returns {false|{
+         *     solutions: [[Map<number,number>, number]],
+         *     simplifiedGenerics: [FunctionType]
+         * }} - The solutions come with the unboxing budget they leave.
          */
-        function unifyFunctionTypeCheckBindings(fnType, queryElem, whereClause, mgensIn) {
+        function unifyFunctionTypeCheckBindings(
+            fnType,
+            queryElem,
+            whereClause,
+            mgensIn,
+            unboxingBudget
+        ) {
             if (fnType.bindings.size < queryElem.bindings.size) {
                 return false;
             }
             let simplifiedGenerics = fnType.generics || [];
             if (fnType.bindings.size > 0) {
-                let mgensSolutionSet = [mgensIn];
+                let mgensSolutionSet = [[mgensIn, unboxingBudget]];
                 for (const [name, constraints] of queryElem.bindings.entries()) {
                     if (mgensSolutionSet.length === 0) {
                         return false;
@@ -1711,19 +1862,20 @@ if (parserState.userQuery[parserState.pos] === "[") {
                         return false;
                     }
                     const fnTypeBindings = fnType.bindings.get(name);
-                    mgensSolutionSet = mgensSolutionSet.flatMap(mgens => {
+                    mgensSolutionSet = mgensSolutionSet.flatMap(([mgens, budget]) => {
                         const newSolutions = [];
                         unifyFunctionTypes(
                             fnTypeBindings,
                             constraints,
                             whereClause,
                             mgens,
-                            newMgens => {
-                                newSolutions.push(newMgens);
+                            (newMgens, newBudget) => {
+                                newSolutions.push([newMgens, Math.min(budget, newBudget)]);
                                 // return `false` makes unifyFunctionTypes return the full set of
                                 // possible solutions
                                 return false;
-                            }
+                            },
+                            unboxingBudget
                         );
                         return newSolutions;
                     });
@@ -1744,18 +1896,28 @@ if (parserState.userQuery[parserState.pos] === "[") {
                 } else {
                     simplifiedGenerics = binds;
                 }
-                return { simplifiedGenerics, mgens: mgensSolutionSet };
+                return { simplifiedGenerics, solutions: mgensSolutionSet };
             }
-            return { simplifiedGenerics, mgens: [mgensIn] };
+            return { simplifiedGenerics, solutions: [[mgensIn, unboxingBudget]] };
         }
         /**
          * @param {FunctionType} fnType
          * @param {QueryElement} queryElem
          * @param {[FunctionType]} whereClause - Trait bounds for generic items.
          * @param {Map<number,number>|null} mgens - Map functions generics to query generics.
+         * @param {number} unboxingBudget - The number of times types can still be unboxed.
          * @returns {boolean}
          */
-        function unifyFunctionTypeIsUnboxCandidate(fnType, queryElem, whereClause, mgens) {
+        function unifyFunctionTypeIsUnboxCandidate(
+            fnType,
+            queryElem,
+            whereClause,
+            mgens,
+            unboxingBudget
+        ) {
+            if (unboxingBudget <= 0) {
+                return false;
+            }
             if (fnType.id < 0 && queryElem.id >= 0) {
                 if (!whereClause) {
                     return false;
@@ -1777,14 +1939,21 @@ if (parserState.userQuery[parserState.pos] === "[") {
                     whereClause[(-fnType.id) - 1],
                     queryElem,
                     whereClause,
-                    mgensTmp
-                );
+                    mgensTmp,
+                    unboxingBudget - 1
+                ) !== null;
             } else if (fnType.generics.length > 0 || fnType.bindings.size > 0) {
                 const simplifiedGenerics = [
                     ...fnType.generics,
                     ...Array.from(fnType.bindings.values()).flat(),
                 ];
-                return checkIfInList(simplifiedGenerics, queryElem, whereClause, mgens);
+                return checkIfInList(
+                    simplifiedGenerics,
+                    queryElem,
+                    whereClause,
+                    mgens,
+                    unboxingBudget - 1
+                ) !== null;
             }
             return false;
         }
@@ -1797,16 +1966,23 @@ if (parserState.userQuery[parserState.pos] === "[") {
           * @param {QueryElement} elem          - The element from the parsed query.
           * @param {[FunctionType]} whereClause - Trait bounds for generic items.
          * @param {Map<number,number>|null} mgens - Map functions generics to query generics.
+         * @param {number} unboxingBudget - The number of times types can still be unboxed.
           *
-          * @return {boolean} - Returns true if found, false otherwise.
+          * @return {number|null} - Returns the unboxing budget left by the cheapest match, or
+          *                          null if not found.
           */
-        function checkIfInList(list, elem, whereClause, mgens) {
+        function checkIfInList(list, elem, whereClause, mgens, unboxingBudget) {
+            let best = null;
             for (const entry of list) {
-                if (checkType(entry, elem, whereClause, mgens)) {
-                    return true;
+                const budget = checkType(entry, elem, whereClause, mgens, unboxingBudget);
+                if (budget !== null && (best === null || budget > best)) {
+                    best = budget;
+                    if (best === unboxingBudget) {
+                        break;
+                    }
                 }
             }
-            return false;
+            return best;
         }
 
         /**
@@ -1817,28 +1993,45 @@ if (parserState.userQuery[parserState.pos] === "[") {
           * @param {QueryElement} elem          - The element from the parsed query.
           * @param {[FunctionType]} whereClause - Trait bounds for generic items.
          * @param {Map<number,number>|null} mgens - Map functions generics to query generics.
+         * @param {number} unboxingBudget - The number of times types can still be unboxed.
           *
-          * @return {boolean} - Returns true if the type matches, false otherwise.
+          * @return {number|null} - Returns the unboxing budget left by the cheapest match, or
+          *                          null if the type doesn't match.
           */
-        function checkType(row, elem, whereClause, mgens) {
+        function checkType(row, elem, whereClause, mgens, unboxingBudget) {
             if (row.bindings.size === 0 && elem.bindings.size === 0) {
                 if (elem.id < 0) {
-                    return row.id < 0 || checkIfInList(row.generics, elem, whereClause, mgens);
+                    if (row.id < 0) {
+                        return unboxingBudget;
+                    }
+                    return unboxingBudget > 0 ?
+                        checkIfInList(row.generics, elem, whereClause, mgens, unboxingBudget - 1) :
+                        null;
                 }
                 if (row.id > 0 && elem.id > 0 && elem.pathWithoutLast.length === 0 &&
                     typePassesFilter(elem.typeFilter, row.ty) && elem.generics.length === 0 &&
                     // special case
-                    elem.id !== typeNameIdOfArrayOrSlice && elem.id !== typeNameIdOfTupleOrUnit
+                    elem.id !== typeNameIdOfArrayOrSlice && elem.id !== typeNameIdOfTupleOrUnit &&
+                    elem.id !== typeNameIdOfReference
                 ) {
-                    return row.id === elem.id || checkIfInList(
-                        row.generics,
-                        elem,
-                        whereClause,
-                        mgens
-                    );
+                    if (row.id === elem.id) {
+                        return unboxingBudget;
+                    }
+                    return unboxingBudget > 0 ?
+                        checkIfInList(row.generics, elem, whereClause, mgens, unboxingBudget - 1) :
+                        null;
                 }
             }
-            return unifyFunctionTypes([row], [elem], whereClause, mgens);
+            return cheapestSolution(unboxingBudget, solutionCb => {
+                return unifyFunctionTypes(
+                    [row],
+                    [elem],
+                    whereClause,
+                    mgens,
+                    solutionCb,
+                    unboxingBudget
+                );
+            });
         }
 
         /**
@@ -2015,6 +2208,46 @@ if (parserState.userQuery[parserState.pos] === "[") {
             }
         }
 
+        /**
+         * Finds the solution of a unification which unboxes the fewest types, that is the one
+         * leaving the most unboxing budget.
+         *
+         * The search goes on after the first solution, but only as far as it would if there was
+         * none: this costs no more than checking an item that doesn't match.
+         *
+         * @param {number} unboxingBudget - The budget `unify` runs the unification with.
+         * @param {function(function(Map<number,number>|null, number): boolean): boolean} unify
+         *     - Runs the unification, with a callback to give each solution to.
+         *
+         * @return {number|null} - The budget left by the cheapest solution, or `null` if there is
+         *                         none.
+         */
+        function cheapestSolution(unboxingBudget, unify) {
+            let best = null;
+            unify((mgens, budget) => {
+                if (best === null || budget > best) {
+                    best = budget;
+                }
+                // Nothing is cheaper than a solution which doesn't unbox anything.
+                return best === unboxingBudget;
+            });
+            return best;
+        }
+
+        /**
+         * Computes the unification cost of a match, which is the smallest number of times types
+         * need to be unboxed for it. For example, matching `u8` against `u8` costs nothing, but
+         * matching it against `Option<&u8>` costs 2.
+         *
+         * @param {number|null} budget - The budget left by a unification which started with
+         *                               `UNBOXING_LIMIT`, or `null` if there is no match.
+         *
+         * @return {number|null} - The unification cost, or `null` if there is no match.
+         */
+        function unificationCost(budget) {
+            return budget === null ? null : UNBOXING_LIMIT - budget;
+        }
+
         /**
          * This function is called in case the query is only one element (with or without generics).
          * This element will be compared to arguments' and returned values' items and also to items.
@@ -2046,29 +2279,41 @@ if (parserState.userQuery[parserState.pos] === "[") {
             const fullId = row.id;
 
             // fpDist is a minimum possible type distance, where "type distance" is the number of
-            // atoms in the function not present in the query
+            // atoms in the function not present in the query, plus the unification cost
             const tfpDist = compareTypeFingerprints(
                 fullId,
                 parsedQuery.typeFingerprint
             );
-            if (tfpDist !== null) {
-                const in_args = row.type && row.type.inputs
-                    && checkIfInList(row.type.inputs, elem, row.type.where_clause);
-                const returned = row.type && row.type.output
-                    && checkIfInList(row.type.output, elem, row.type.where_clause);
-                if (in_args) {
-                    results_in_args.max_dist = Math.max(results_in_args.max_dist || 0, tfpDist);
+            if (tfpDist !== null && row.type) {
+                const in_args = unificationCost(checkIfInList(
+                    row.type.inputs,
+                    elem,
+                    row.type.where_clause,
+                    null,
+                    UNBOXING_LIMIT
+                ));
+                const returned = unificationCost(checkIfInList(
+                    row.type.output,
+                    elem,
+                    row.type.where_clause,
+                    null,
+                    UNBOXING_LIMIT
+                ));
+                if (in_args !== null) {
+                    const dist = tfpDist + in_args;
+                    results_in_args.max_dist = Math.max(results_in_args.max_dist || 0, dist);
                     const maxDist = results_in_args.size < MAX_RESULTS ?
-                        (tfpDist + 1) :
+                        (dist + 1) :
                         results_in_args.max_dist;
-                    addIntoResults(results_in_args, fullId, pos, -1, tfpDist, 0, maxDist);
+                    addIntoResults(results_in_args, fullId, pos, -1, dist, 0, maxDist);
                 }
-                if (returned) {
-                    results_returned.max_dist = Math.max(results_returned.max_dist || 0, tfpDist);
+                if (returned !== null) {
+                    const dist = tfpDist + returned;
+                    results_returned.max_dist = Math.max(results_returned.max_dist || 0, dist);
                     const maxDist = results_returned.size < MAX_RESULTS ?
-                        (tfpDist + 1) :
+                        (dist + 1) :
                         results_returned.max_dist;
-                    addIntoResults(results_returned, fullId, pos, -1, tfpDist, 0, maxDist);
+                    addIntoResults(results_returned, fullId, pos, -1, dist, 0, maxDist);
                 }
             }
 
@@ -2131,25 +2376,34 @@ if (parserState.userQuery[parserState.pos] === "[") {
             }
 
             // If the result is too "bad", we return false and it ends this search.
-            if (!unifyFunctionTypes(
-                row.type.inputs,
-                parsedQuery.elems,
-                row.type.where_clause,
-                null,
-                mgens => {
-                    return unifyFunctionTypes(
-                        row.type.output,
-                        parsedQuery.returned,
-                        row.type.where_clause,
-                        mgens
-                    );
-                }
-            )) {
+            const cost = unificationCost(cheapestSolution(UNBOXING_LIMIT, solutionCb => {
+                return unifyFunctionTypes(
+                    row.type.inputs,
+                    parsedQuery.elems,
+                    row.type.where_clause,
+                    null,
+                    (mgens, inputsBudget) => {
+                        return unifyFunctionTypes(
+                            row.type.output,
+                            parsedQuery.returned,
+                            row.type.where_clause,
+                            mgens,
+                            (mgens, outputBudget) => {
+                                return solutionCb(mgens, Math.min(inputsBudget, outputBudget));
+                            },
+                            UNBOXING_LIMIT
+                        );
+                    },
+                    UNBOXING_LIMIT
+                );
+            }));
+            if (cost === null) {
                 return;
             }
 
-            results.max_dist = Math.max(results.max_dist || 0, tfpDist);
-            addIntoResults(results, row.id, pos, 0, tfpDist, 0, Number.MAX_VALUE);
+            const dist = tfpDist + cost;
+            results.max_dist = Math.max(results.max_dist || 0, dist);
+            addIntoResults(results, row.id, pos, 0, dist, 0, Number.MAX_VALUE);
         }
 
         function innerRunQuery() {
@@ -3103,6 +3357,10 @@ ${item.displayPath}<span class="${type}">${name}</span>\
         typeNameIdOfUnit = buildTypeMapIndex("unit");
         typeNameIdOfArrayOrSlice = buildTypeMapIndex("[]");
         typeNameIdOfTupleOrUnit = buildTypeMapIndex("()");
+        // Initialize type map indexes for references, so that `&` and `&mut` in a query don't
+        // get corrected into other types when the crates have no references.
+        typeNameIdOfReference = buildTypeMapIndex("reference");
+        typeNameIdOfMut = buildTypeMapIndex("mut");
 
         // Function type fingerprints are 128-bit bloom filters that are used to
         // estimate the distance between function and query.
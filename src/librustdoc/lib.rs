@@ -118,6 +118,7 @@ macro_rules! map {
 pub mod html;
 mod json;
 pub(crate) mod lint;
+mod man;
 mod markdown;
 mod passes;
 mod scrape_examples;
@@ -505,6 +506,15 @@ fn opts() -> Vec<RustcOptGroup> {
                 "PATH",
             )
         }),
+        unstable("doctest-coverage", |o| {
+            o.optopt(
+                "",
+                "doctest-coverage",
+                "Instrument doctests for coverage and write their coverage data and executables \
+                 into the given directory",
+                "PATH",
+            )
+        }),
         unstable("show-coverage", |o| {
             o.optflagmulti(
                 "",
@@ -563,6 +573,20 @@ fn opts() -> Vec<RustcOptGroup> {
         unstable("nocapture", |o| {
             o.optflag("", "nocapture", "Don't capture stdout and stderr of tests")
         }),
+        unstable("merge-doctests", |o| {
+            o.optflag(
+                "",
+                "merge-doctests",
+                "Compile doctests that do not need to be compiled separately into a single binary",
+            )
+        }),
+        unstable("lint-examples", |o| {
+            o.optflag(
+                "",
+                "lint-examples",
+                "Report the lint warnings triggered by doctests instead of running them",
+            )
+        }),
         unstable("generate-link-to-definition", |o| {
             o.optflag(
                 "",
@@ -730,6 +754,9 @@ fn main_args(
         core::new_dcx(options.error_format, None, options.diagnostic_width, &options.unstable_opts);
 
     match (options.should_test, options.markdown_input()) {
+        (true, true) if options.lint_examples => {
+            diag.fatal("`--lint-examples` cannot be used with Markdown files")
+        }
         (true, true) => return wrap_return(&diag, markdown::test(options)),
         (true, false) => return doctest::run(options),
         (false, true) => {
@@ -820,6 +847,9 @@ fn main_args(
                     config::OutputFormat::Json => sess.time("render_json", || {
                         run_renderer::<json::JsonRenderer<'_>>(krate, render_opts, cache, tcx)
                     }),
+                    config::OutputFormat::Man => sess.time("render_man", || {
+                        run_renderer::<man::ManRenderer<'_>>(krate, render_opts, cache, tcx)
+                    }),
                 }
             })
         })
@@ -196,6 +196,17 @@ macro_rules! declare_rustdoc_lint {
     "detects redundant explicit links in doc comments"
 }
 
+declare_rustdoc_lint! {
+    /// The `example_lints` lint reports the warnings emitted by `rustc` when compiling
+    /// documentation examples with `--lint-examples`. This is a `rustdoc` only lint, see the
+    /// documentation in the [rustdoc book].
+    ///
+    /// [rustdoc book]: ../../../rustdoc/lints.html#example_lints
+    EXAMPLE_LINTS,
+    Warn,
+    "detects code in documentation examples which triggers lint warnings"
+}
+
 pub(crate) static RUSTDOC_LINTS: Lazy<Vec<&'static Lint>> = Lazy::new(|| {
     vec![
         BROKEN_INTRA_DOC_LINKS,
@@ -209,6 +220,7 @@ macro_rules! declare_rustdoc_lint {
         MISSING_CRATE_LEVEL_DOCS,
         UNESCAPED_BACKTICKS,
         REDUNDANT_EXPLICIT_LINKS,
+        EXAMPLE_LINTS,
     ]
 });
 
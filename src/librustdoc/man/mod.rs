@@ -0,0 +1,194 @@
+//! Rustdoc's man page backend
+//!
+//! This module renders the documentation of a crate as a single man page written in roff. It is
+//! meant for binary crates, whose documentation describes how to use the program rather than an
+//! API: the crate documentation becomes the description of the page, and every other documented
+//! item becomes a section of it, with its documented fields and variants as tagged paragraphs.
+
+mod roff;
+
+use std::cell::RefCell;
+use std::fs::{self, create_dir_all};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_span::def_id::LOCAL_CRATE;
+
+use crate::clean::{self, ItemKind};
+use crate::config::RenderOptions;
+use crate::docfs::PathError;
+use crate::error::Error;
+use crate::formats::cache::Cache;
+use crate::formats::FormatRenderer;
+use crate::html::markdown::plain_text_summary;
+use crate::try_err;
+
+use self::roff::{escape, markdown_to_roff, Placement};
+
+#[derive(Clone)]
+pub(crate) struct ManRenderer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    /// The directory where the man page will be written to.
+    out_path: PathBuf,
+    cache: Rc<Cache>,
+    /// The man page section: 1 for programs, 3 for libraries.
+    section: u8,
+    /// The roff source of the page, filled when rendering the crate root.
+    page: Rc<RefCell<String>>,
+}
+
+impl<'tcx> ManRenderer<'tcx> {
+    /// Writes the header of the page, and the documentation of the crate as its description.
+    fn render_crate(&self, krate: &clean::Item, page: &mut String) {
+        let name = krate.name.unwrap().to_string();
+        let doc = krate.doc_value();
+        let links = krate.link_names(&self.cache);
+        let source = match &self.cache.crate_version {
+            Some(version) => format!("{name} {version}"),
+            None => name.clone(),
+        };
+        let manual = if self.section == 1 { "User Commands" } else { "Library Functions" };
+        page.push_str(&format!(
+            ".TH \"{}\" \"{}\" \"\" \"{}\" \"{manual}\"\n",
+            escape(&name.to_uppercase()),
+            self.section,
+            escape(&source),
+        ));
+
+        page.push_str(".SH NAME\n");
+        let summary = plain_text_summary(&doc, &links);
+        if summary.is_empty() {
+            page.push_str(&format!("{}\n", escape(&name)));
+        } else {
+            page.push_str(&format!("{} \\- {}\n", escape(&name), escape(&summary)));
+        }
+
+        if !doc.is_empty() {
+            page.push_str(".SH DESCRIPTION\n");
+            page.push_str(&markdown_to_roff(&doc, &links, Placement::Page));
+        }
+    }
+
+    /// Writes the sections of `item` and of the items it contains. `path` is the path of the
+    /// module containing `item`, relative to the crate root.
+    fn render_item(&self, item: &clean::Item, path: &mut Vec<String>, page: &mut String) {
+        let Some(name) = item.name else { return };
+        match *item.kind {
+            ItemKind::ModuleItem(ref module) => {
+                if !item.is_crate() {
+                    path.push(name.to_string());
+                    self.render_section(item, &path.join("::"), page);
+                }
+                for item in &module.items {
+                    self.render_item(item, path, page);
+                }
+                if !item.is_crate() {
+                    path.pop();
+                }
+            }
+            // Implementations and re-exports don't add anything to the interface of a program,
+            // and the fields and variants of items are written in their section.
+            ItemKind::ImplItem(_)
+            | ItemKind::ImportItem(_)
+            | ItemKind::ExternCrateItem { .. }
+            | ItemKind::StrippedItem(_)
+            | ItemKind::PrimitiveItem(_)
+            | ItemKind::KeywordItem => {}
+            _ => {
+                let title = path.iter().map(String::as_str).chain([name.as_str()]);
+                self.render_section(item, &title.collect::<Vec<_>>().join("::"), page);
+            }
+        }
+    }
+
+    /// Writes a section for `item` if it or its fields and variants are documented.
+    fn render_section(&self, item: &clean::Item, title: &str, page: &mut String) {
+        let doc = item.opt_doc_value();
+        let documented_fields = item
+            .kind
+            .inner_items()
+            .filter(|field| {
+                matches!(*field.kind, ItemKind::StructFieldItem(_) | ItemKind::VariantItem(_))
+                    && field.name.is_some()
+                    && field.opt_doc_value().is_some()
+            })
+            .collect::<Vec<_>>();
+        if doc.is_none() && documented_fields.is_empty() {
+            return;
+        }
+
+        page.push_str(&format!(".SH {}\n", escape(&title.to_uppercase())));
+        if let Some(doc) = doc {
+            let links = item.link_names(&self.cache);
+            page.push_str(&markdown_to_roff(&doc, &links, Placement::Section));
+        }
+        for field in documented_fields {
+            let links = field.link_names(&self.cache);
+            page.push_str(".TP\n");
+            page.push_str(&format!("\\fB{}\\fR\n", escape(field.name.unwrap().as_str())));
+            page.push_str(&markdown_to_roff(&field.doc_value(), &links, Placement::Indented));
+        }
+    }
+}
+
+impl<'tcx> FormatRenderer<'tcx> for ManRenderer<'tcx> {
+    fn descr() -> &'static str {
+        "man"
+    }
+
+    const RUN_ON_MODULE: bool = false;
+
+    fn init(
+        krate: clean::Crate,
+        options: RenderOptions,
+        cache: Cache,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<(Self, clean::Crate), Error> {
+        debug!("Initializing man renderer");
+
+        let section = if options.bin_crate { 1 } else { 3 };
+        Ok((
+            ManRenderer {
+                tcx,
+                out_path: options.output,
+                cache: Rc::new(cache),
+                section,
+                page: Rc::new(RefCell::new(String::new())),
+            },
+            krate,
+        ))
+    }
+
+    fn make_child_renderer(&self) -> Self {
+        self.clone()
+    }
+
+    /// Renders the whole page, as this is only called for the crate root.
+    fn item(&mut self, item: clean::Item) -> Result<(), Error> {
+        let mut page = self.page.borrow_mut();
+        self.render_crate(&item, &mut page);
+        self.render_item(&item, &mut Vec::new(), &mut page);
+        Ok(())
+    }
+
+    fn mod_item_in(&mut self, _item: &clean::Item) -> Result<(), Error> {
+        unreachable!("RUN_ON_MODULE = false should never call mod_item_in")
+    }
+
+    fn after_krate(&mut self) -> Result<(), Error> {
+        let out_dir = self.out_path.clone();
+        try_err!(create_dir_all(&out_dir), out_dir);
+
+        let mut p = out_dir;
+        p.push(self.tcx.crate_name(LOCAL_CRATE).as_str());
+        p.set_extension(self.section.to_string());
+        try_err!(fs::write(&p, &*self.page.borrow()), p);
+
+        Ok(())
+    }
+
+    fn cache(&self) -> &Cache {
+        &self.cache
+    }
+}
@@ -0,0 +1,221 @@
+//! Conversion of Markdown documentation into roff, the typesetting language of man pages.
+
+use pulldown_cmark::{BrokenLink, CodeBlockKind, Event, HeadingLevel, Parser, Tag};
+
+use crate::clean::RenderedLink;
+use crate::html::markdown::{main_body_opts, shown_code_block_source};
+
+#[cfg(test)]
+mod tests;
+
+/// Where the converted documentation is placed in the man page.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Placement {
+    /// Directly in the page: top-level headings start new sections.
+    Page,
+    /// In the section of an item: headings start subsections.
+    Section,
+    /// In the tagged paragraph of a field or variant, whose indentation has to be kept.
+    Indented,
+}
+
+/// Escapes `text` so that it's printed as is, even at the start of a line.
+pub(crate) fn escape(text: &str) -> String {
+    escape_from(text, true)
+}
+
+/// Escapes `text`, which is written at the start of a line if `line_start` is true.
+fn escape_from(text: &str, line_start: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for (i, line) in text.split_inclusive('\n').enumerate() {
+        // Lines starting with a dot or an apostrophe are requests.
+        if (i > 0 || line_start) && line.starts_with(['.', '\'']) {
+            escaped.push_str("\\&");
+        }
+        for c in line.chars() {
+            match c {
+                '\\' => escaped.push_str("\\e"),
+                '-' => escaped.push_str("\\-"),
+                c => escaped.push(c),
+            }
+        }
+    }
+    escaped
+}
+
+struct RoffWriter {
+    out: String,
+    placement: Placement,
+    /// Whether nothing was written since a request starting a new block, so that the next
+    /// paragraph doesn't need to start one itself.
+    block_start: bool,
+    /// The number of the next item for each list being written, or `None` for bullet lists.
+    lists: Vec<Option<u64>>,
+}
+
+impl RoffWriter {
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with('\n')
+    }
+
+    fn request(&mut self, request: &str) {
+        if !self.at_line_start() {
+            self.out.push('\n');
+        }
+        self.out.push_str(request);
+        self.out.push('\n');
+    }
+
+    fn text(&mut self, text: &str) {
+        let escaped = escape_from(text, self.at_line_start());
+        self.out.push_str(&escaped);
+        self.block_start = false;
+    }
+
+    fn raw(&mut self, roff: &str) {
+        self.out.push_str(roff);
+        self.block_start = false;
+    }
+
+    /// Starts a new paragraph, keeping the indentation of lists and tagged paragraphs.
+    fn paragraph(&mut self) {
+        if self.block_start {
+            self.block_start = false;
+        } else if !self.lists.is_empty() || self.placement == Placement::Indented {
+            self.request(".IP");
+        } else {
+            self.request(".PP");
+        }
+    }
+}
+
+/// Converts the Markdown documentation `md` into roff. `links` are the intra-doc links of the
+/// documentation, which are written as plain text.
+///
+/// The caller is expected to have started the section or tagged paragraph the documentation
+/// belongs to, so the first paragraph doesn't start a new one.
+pub(crate) fn markdown_to_roff(md: &str, links: &[RenderedLink], placement: Placement) -> String {
+    let mut replacer = |broken_link: BrokenLink<'_>| {
+        links
+            .iter()
+            .find(|link| &*link.original_text == &*broken_link.reference)
+            .map(|link| (link.href.as_str().into(), link.tooltip.as_str().into()))
+    };
+    let parser = Parser::new_with_broken_link_callback(md, main_body_opts(), Some(&mut replacer));
+
+    let mut w = RoffWriter { out: String::new(), placement, block_start: true, lists: Vec::new() };
+    // The text of the heading or code block being written, which is written all at once.
+    let mut buffer: Option<String> = None;
+
+    for event in parser {
+        match event {
+            Event::Start(Tag::Paragraph) => w.paragraph(),
+            Event::Start(Tag::Heading(..)) | Event::Start(Tag::CodeBlock(_)) => {
+                buffer = Some(String::new());
+            }
+            Event::End(Tag::Heading(level, ..)) => {
+                let heading = buffer.take().unwrap_or_default();
+                match placement {
+                    Placement::Page if level == HeadingLevel::H1 => {
+                        w.request(&format!(".SH {}", escape(&heading.to_uppercase())));
+                    }
+                    Placement::Page | Placement::Section => {
+                        w.request(&format!(".SS {}", escape(&heading)));
+                    }
+                    Placement::Indented => {
+                        w.paragraph();
+                        w.raw(&format!("\\fB{}\\fR\n", escape(&heading)));
+                        w.request(".br");
+                    }
+                }
+                w.block_start = true;
+            }
+            Event::End(Tag::CodeBlock(kind)) => {
+                let lang = match kind {
+                    CodeBlockKind::Fenced(ref lang) => &**lang,
+                    CodeBlockKind::Indented => "",
+                };
+                let code = shown_code_block_source(lang, &buffer.take().unwrap_or_default());
+                w.paragraph();
+                w.request(".RS 4");
+                w.request(".nf");
+                w.text(&code);
+                w.request(".fi");
+                w.request(".RE");
+            }
+            Event::Text(text) | Event::Code(text) if let Some(buffer) = &mut buffer => {
+                buffer.push_str(&text);
+            }
+            // Only the text of headings and code blocks is kept.
+            _ if buffer.is_some() => {}
+            Event::Text(text) => w.text(&text),
+            Event::Code(code) => {
+                w.raw("\\fB");
+                w.text(&code);
+                w.raw("\\fR");
+            }
+            Event::Start(Tag::Emphasis) => w.raw("\\fI"),
+            Event::Start(Tag::Strong) => w.raw("\\fB"),
+            Event::End(Tag::Emphasis) | Event::End(Tag::Strong) => w.raw("\\fR"),
+            Event::End(Tag::Link(_, dest, _)) => {
+                // Intra-doc links have no destination in a man page, only write the URL of
+                // external ones.
+                if dest.contains("://") {
+                    w.text(&format!(" <{dest}>"));
+                }
+            }
+            Event::Start(Tag::List(start)) => {
+                if !w.lists.is_empty() {
+                    w.request(".RS 2");
+                }
+                w.lists.push(start);
+            }
+            Event::End(Tag::List(_)) => {
+                w.lists.pop();
+                if !w.lists.is_empty() {
+                    w.request(".RE");
+                }
+            }
+            Event::Start(Tag::Item) => {
+                let request = match w.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!(".IP {}. 4", *number - 1)
+                    }
+                    _ => ".IP \\(bu 2".to_owned(),
+                };
+                w.request(&request);
+                w.block_start = true;
+            }
+            Event::Start(Tag::BlockQuote) => {
+                w.paragraph();
+                w.request(".RS 4");
+                w.block_start = true;
+            }
+            Event::End(Tag::BlockQuote) => w.request(".RE"),
+            Event::Start(Tag::Table(_)) => {
+                w.paragraph();
+                w.request(".nf");
+            }
+            Event::End(Tag::TableCell) => w.raw("\t"),
+            Event::End(Tag::TableHead) | Event::End(Tag::TableRow) => w.raw("\n"),
+            Event::End(Tag::Table(_)) => w.request(".fi"),
+            Event::Start(Tag::FootnoteDefinition(name)) => {
+                w.paragraph();
+                w.text(&format!("[{name}]: "));
+            }
+            Event::FootnoteReference(name) => w.text(&format!("[{name}]")),
+            Event::TaskListMarker(checked) => w.text(if checked { "[x] " } else { "[ ] " }),
+            Event::SoftBreak => w.raw("\n"),
+            Event::HardBreak => w.request(".br"),
+            Event::Rule => w.paragraph(),
+            // Other tags only change how their text looks in HTML, and HTML can't be rendered.
+            _ => {}
+        }
+    }
+
+    if !w.at_line_start() {
+        w.out.push('\n');
+    }
+    w.out
+}
@@ -0,0 +1,77 @@
+use super::{escape, markdown_to_roff, Placement};
+use crate::clean::RenderedLink;
+
+#[test]
+fn test_escape() {
+    assert_eq!(
+        escape(".hidden\n'quoted\nfoo-bar \\ baz"),
+        "\\&.hidden\n\\&'quoted\nfoo\\-bar \\e baz"
+    );
+}
+
+#[test]
+fn test_page() {
+    let md = "Prints greetings.
+
+# Options
+
+Use `--name` to pick *who* to greet:
+
+* **Alice**
+* Bob
+
+```
+# fn main() {
+let x = 1;
+# }
+```";
+    let expected = "Prints greetings.
+.SH OPTIONS
+Use \\fB\\-\\-name\\fR to pick \\fIwho\\fR to greet:
+.IP \\(bu 2
+\\fBAlice\\fR
+.IP \\(bu 2
+Bob
+.PP
+.RS 4
+.nf
+let x = 1;
+.fi
+.RE
+";
+    assert_eq!(markdown_to_roff(md, &[], Placement::Page), expected);
+}
+
+#[test]
+fn test_section_headings() {
+    let md = "# Usage\n\nRun it.\n\n## Details\n\n1. First\n2. Second";
+    let expected = ".SS Usage
+Run it.
+.SS Details
+.IP 1. 4
+First
+.IP 2. 4
+Second
+";
+    assert_eq!(markdown_to_roff(md, &[], Placement::Section), expected);
+}
+
+#[test]
+fn test_indented() {
+    let md = "The name.\n\nDefaults to `world`.";
+    let expected = "The name.\n.IP\nDefaults to \\fBworld\\fR.\n";
+    assert_eq!(markdown_to_roff(md, &[], Placement::Indented), expected);
+}
+
+#[test]
+fn test_links() {
+    let links = [RenderedLink {
+        original_text: "`Args`".into(),
+        new_text: "Args".into(),
+        href: String::new(),
+        tooltip: String::new(),
+    }];
+    let md = "See [the book](https://example.com/book) and [`Args`].";
+    let expected = "See the book <https://example.com/book> and \\fBArgs\\fR.\n";
+    assert_eq!(markdown_to_roff(md, &links, Placement::Section), expected);
+}
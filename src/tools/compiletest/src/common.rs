@@ -69,6 +69,7 @@ pub enum Mode {
         Assembly => "assembly",
         CoverageMap => "coverage-map",
         CoverageRun => "coverage-run",
+        Fuzz => "fuzz",
     }
 }
 
@@ -200,6 +201,10 @@ pub struct Config {
     /// The jsondoclint executable.
     pub jsondoclint_path: Option<String>,
 
+    /// The `run_make_support` library that the `rmake.rs` recipes of `run-make` tests are linked
+    /// against.
+    pub run_make_support_path: Option<PathBuf>,
+
     /// The LLVM `FileCheck` binary path.
     pub llvm_filecheck: Option<PathBuf>,
 
@@ -346,6 +351,10 @@ pub struct Config {
     /// created in `/<build_base>/rustfix_missing_coverage.txt`
     pub rustfix_coverage: bool,
 
+    /// If true, UI tests that fail are reduced to a minimal test case that fails the same way,
+    /// which is written next to the test as `<name>.reduced.rs`.
+    pub reduce: bool,
+
     /// whether to run `tidy` when a rustdoc test fails
     pub has_tidy: bool,
 
@@ -391,6 +400,12 @@ pub struct Config {
     /// True if the profiler runtime is enabled for this target.
     /// Used by the "needs-profiler-support" header in test files.
     pub profiler_support: bool,
+
+    /// The number of mutants to generate from every seed program in `fuzz` mode.
+    pub fuzz_iterations: u32,
+
+    /// The seed of the random number generator choosing the mutations in `fuzz` mode.
+    pub fuzz_seed: u64,
 }
 
 impl Config {
@@ -713,6 +728,7 @@ pub fn expected_output_path(
     UI_STDERR_16,
     UI_COVERAGE,
     UI_COVERAGE_MAP,
+    UI_DIAGNOSTICS_JSON,
 ];
 pub const UI_STDERR: &str = "stderr";
 pub const UI_STDOUT: &str = "stdout";
@@ -724,6 +740,7 @@ pub fn expected_output_path(
 pub const UI_STDERR_16: &str = "16bit.stderr";
 pub const UI_COVERAGE: &str = "coverage";
 pub const UI_COVERAGE_MAP: &str = "cov-map";
+pub const UI_DIAGNOSTICS_JSON: &str = "diagnostics.json";
 
 /// Absolute path to the directory where all output for all tests in the given
 /// `relative_dir` group should reside. Example:
@@ -168,6 +168,9 @@ pub struct TestProps {
     pub run_rustfix: bool,
     // If true, `rustfix` will only apply `MachineApplicable` suggestions.
     pub rustfix_only_machine_applicable: bool,
+    // For UI tests, compare the structured JSON diagnostics (spans, labels, suggestions and
+    // their applicability) against the `.diagnostics.json` file.
+    pub check_diagnostics_json: bool,
     pub assembly_output: Option<String>,
     // If true, the test is expected to ICE
     pub should_ice: bool,
@@ -212,6 +215,7 @@ mod directives {
     pub const DONT_CHECK_FAILURE_STATUS: &'static str = "dont-check-failure-status";
     pub const RUN_RUSTFIX: &'static str = "run-rustfix";
     pub const RUSTFIX_ONLY_MACHINE_APPLICABLE: &'static str = "rustfix-only-machine-applicable";
+    pub const CHECK_DIAGNOSTICS_JSON: &'static str = "check-diagnostics-json";
     pub const ASSEMBLY_OUTPUT: &'static str = "assembly-output";
     pub const STDERR_PER_BITWIDTH: &'static str = "stderr-per-bitwidth";
     pub const INCREMENTAL: &'static str = "incremental";
@@ -264,6 +268,7 @@ pub fn new() -> Self {
             dont_check_failure_status: false,
             run_rustfix: false,
             rustfix_only_machine_applicable: false,
+            check_diagnostics_json: false,
             assembly_output: None,
             should_ice: false,
             stderr_per_bitwidth: false,
@@ -455,6 +460,11 @@ fn split_flags(flags: &str) -> Vec<String> {
                     RUSTFIX_ONLY_MACHINE_APPLICABLE,
                     &mut self.rustfix_only_machine_applicable,
                 );
+                config.set_name_directive(
+                    ln,
+                    CHECK_DIAGNOSTICS_JSON,
+                    &mut self.check_diagnostics_json,
+                );
                 config.set_name_value_directive(
                     ln,
                     ASSEMBLY_OUTPUT,
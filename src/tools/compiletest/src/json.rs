@@ -3,7 +3,7 @@
 
 use crate::errors::{Error, ErrorKind};
 use crate::runtest::ProcRes;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
@@ -41,6 +41,7 @@ struct DiagnosticSpan {
     is_primary: bool,
     label: Option<String>,
     suggested_replacement: Option<String>,
+    suggestion_applicability: Option<String>,
     expansion: Option<Box<DiagnosticSpanMacroExpansion>>,
 }
 
@@ -91,6 +92,77 @@ pub fn rustfix_diagnostics_only(output: &str) -> String {
         .collect()
 }
 
+/// The parts of a diagnostic that `// check-diagnostics-json` tests compare against the
+/// expected `.diagnostics.json` file. The rendered message is left out, as it is already
+/// checked by the `.stderr` file.
+#[derive(Serialize)]
+struct DiagnosticExpectation {
+    level: String,
+    code: Option<String>,
+    message: String,
+    spans: Vec<SpanExpectation>,
+    children: Vec<DiagnosticExpectation>,
+}
+
+#[derive(Serialize)]
+struct SpanExpectation {
+    file: String,
+    line_start: usize,
+    column_start: usize,
+    line_end: usize,
+    column_end: usize,
+    is_primary: bool,
+    label: Option<String>,
+    suggested_replacement: Option<String>,
+    suggestion_applicability: Option<String>,
+}
+
+impl From<Diagnostic> for DiagnosticExpectation {
+    fn from(diagnostic: Diagnostic) -> Self {
+        DiagnosticExpectation {
+            level: diagnostic.level,
+            code: diagnostic.code.map(|code| code.code),
+            message: diagnostic.message,
+            spans: diagnostic.spans.into_iter().map(SpanExpectation::from).collect(),
+            children: diagnostic.children.into_iter().map(DiagnosticExpectation::from).collect(),
+        }
+    }
+}
+
+impl From<DiagnosticSpan> for SpanExpectation {
+    fn from(span: DiagnosticSpan) -> Self {
+        SpanExpectation {
+            file: span.file_name,
+            line_start: span.line_start,
+            column_start: span.column_start,
+            line_end: span.line_end,
+            column_end: span.column_end,
+            is_primary: span.is_primary,
+            label: span.label,
+            suggested_replacement: span.suggested_replacement,
+            suggestion_applicability: span.suggestion_applicability,
+        }
+    }
+}
+
+/// Extracts the structured diagnostics from the compiler's JSON output and pretty-prints them,
+/// so they can be compared against a `.diagnostics.json` file. Other lines, such as artifact
+/// notifications and future incompatibility reports, are skipped.
+pub fn extract_diagnostics_json(output: &str) -> String {
+    let diagnostics: Vec<DiagnosticExpectation> = output
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .filter_map(|line| serde_json::from_str::<Diagnostic>(line).ok())
+        .map(DiagnosticExpectation::from)
+        .collect();
+    if diagnostics.is_empty() {
+        return String::new();
+    }
+    let mut json = serde_json::to_string_pretty(&diagnostics).unwrap();
+    json.push('\n');
+    json
+}
+
 pub fn extract_rendered(output: &str) -> String {
     output
         .lines()
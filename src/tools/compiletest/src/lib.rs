@@ -52,6 +52,7 @@ pub fn parse_config(args: Vec<String>) -> Config {
         .reqopt("", "python", "path to python to use for doc tests", "PATH")
         .optopt("", "jsondocck-path", "path to jsondocck to use for doc tests", "PATH")
         .optopt("", "jsondoclint-path", "path to jsondoclint to use for doc tests", "PATH")
+        .optopt("", "run-make-support-path", "path to the run_make_support library", "PATH")
         .optopt("", "valgrind-path", "path to Valgrind executable for Valgrind tests", "PROGRAM")
         .optflag("", "force-valgrind", "fail if Valgrind tests cannot be run under Valgrind")
         .optopt("", "run-clang-based-tests-with", "path to Clang executable", "PATH")
@@ -139,6 +140,11 @@ pub fn parse_config(args: Vec<String>) -> Config {
             "enable this to generate a Rustfix coverage file, which is saved in \
             `./<build_base>/rustfix_missing_coverage.txt`",
         )
+        .optflag(
+            "",
+            "reduce",
+            "reduce failing ui tests to a minimal test case that fails the same way",
+        )
         .optflag("", "force-rerun", "rerun tests even if the inputs are unchanged")
         .optflag("", "only-modified", "only run tests that result been modified")
         .optflag("", "nocapture", "")
@@ -228,6 +234,7 @@ fn make_absolute(path: PathBuf) -> PathBuf {
         python: matches.opt_str("python").unwrap(),
         jsondocck_path: matches.opt_str("jsondocck-path"),
         jsondoclint_path: matches.opt_str("jsondoclint-path"),
+        run_make_support_path: matches.opt_str("run-make-support-path").map(PathBuf::from),
         valgrind_path: matches.opt_str("valgrind-path"),
         force_valgrind: matches.opt_present("force-valgrind"),
         run_clang_based_tests_with: matches.opt_str("run-clang-based-tests-with"),
@@ -292,6 +299,7 @@ fn make_absolute(path: PathBuf) -> PathBuf {
             .opt_str("compare-mode")
             .map(|s| s.parse().expect("invalid --compare-mode provided")),
         rustfix_coverage: matches.opt_present("rustfix-coverage"),
+        reduce: matches.opt_present("reduce"),
         has_tidy,
         channel: matches.opt_str("channel").unwrap(),
         git_hash: matches.opt_present("git-hash"),
@@ -318,6 +326,13 @@ fn make_absolute(path: PathBuf) -> PathBuf {
         nightly_branch: matches.opt_str("nightly-branch").unwrap(),
 
         profiler_support: matches.opt_present("profiler-support"),
+
+        fuzz_iterations: env::var("COMPILETEST_FUZZ_ITERATIONS")
+            .map(|n| n.parse().expect("COMPILETEST_FUZZ_ITERATIONS must be a number"))
+            .unwrap_or(100),
+        fuzz_seed: env::var("COMPILETEST_FUZZ_SEED")
+            .map(|n| n.parse().expect("COMPILETEST_FUZZ_SEED must be a number"))
+            .unwrap_or(0),
     }
 }
 
@@ -329,6 +344,7 @@ pub fn log_config(config: &Config) {
     logv(c, format!("rustc_path: {:?}", config.rustc_path.display()));
     logv(c, format!("rustdoc_path: {:?}", config.rustdoc_path));
     logv(c, format!("rust_demangler_path: {:?}", config.rust_demangler_path));
+    logv(c, format!("run_make_support_path: {:?}", config.run_make_support_path));
     logv(c, format!("src_base: {:?}", config.src_base.display()));
     logv(c, format!("build_base: {:?}", config.build_base.display()));
     logv(c, format!("stage_id: {}", config.stage_id));
@@ -386,6 +402,13 @@ pub fn run_tests(config: Arc<Config>) {
         }
     }
 
+    if config.reduce && config.mode != Mode::Ui {
+        eprintln!(
+            "warning: `--reduce` only reduces ui tests, failing {} tests are not reduced",
+            config.mode
+        );
+    }
+
     // sadly osx needs some file descriptor limits raised for running tests in
     // parallel (especially when we have lots and lots of child processes).
     // For context, see #8904
@@ -609,6 +632,10 @@ fn common_inputs_stamp(config: &Config) -> Stamp {
         stamp.add_path(&rust_src_dir.join("src/etc/htmldocck.py"));
     }
 
+    if let Some(ref run_make_support_path) = config.run_make_support_path {
+        stamp.add_path(&run_make_support_path);
+    }
+
     // Compiletest itself.
     stamp.add_dir(&rust_src_dir.join("src/tools/compiletest/"));
 
@@ -655,7 +682,9 @@ fn collect_tests_from_dir(
         return Ok(());
     }
 
-    if config.mode == Mode::RunMake && dir.join("Makefile").exists() {
+    if config.mode == Mode::RunMake
+        && (dir.join("Makefile").exists() || dir.join("rmake.rs").exists())
+    {
         let paths = TestPaths {
             file: dir.to_path_buf(),
             relative_dir: relative_dir_path.parent().unwrap().to_path_buf(),
@@ -718,6 +747,11 @@ pub fn is_test(file_name: &OsString) -> bool {
         return false;
     }
 
+    // Test cases written by `--reduce` are not tests of their own.
+    if file_name.ends_with(".reduced.rs") {
+        return false;
+    }
+
     // `.`, `#`, and `~` are common temp-file prefixes.
     let invalid_prefixes = &[".", "#", "~"];
     !invalid_prefixes.iter().any(|p| file_name.starts_with(p))
@@ -731,8 +765,9 @@ fn make_test(
     poisoned: &mut bool,
 ) -> Vec<test::TestDescAndFn> {
     let test_path = if config.mode == Mode::RunMake {
-        // Parse directives in the Makefile
-        testpaths.file.join("Makefile")
+        // Parse directives in the `rmake.rs` recipe, or in the Makefile for older tests
+        let recipe = testpaths.file.join("rmake.rs");
+        if recipe.exists() { recipe } else { testpaths.file.join("Makefile") }
     } else {
         PathBuf::from(&testpaths.file)
     };
@@ -6,8 +6,9 @@
 use crate::common::{Codegen, CodegenUnits, DebugInfo, Debugger, Rustdoc};
 use crate::common::{CompareMode, FailMode, PassMode};
 use crate::common::{Config, TestPaths};
-use crate::common::{CoverageMap, CoverageRun, Pretty, RunPassValgrind};
-use crate::common::{UI_COVERAGE, UI_COVERAGE_MAP, UI_RUN_STDERR, UI_RUN_STDOUT};
+use crate::common::{CoverageMap, CoverageRun, Fuzz, Pretty, RunPassValgrind};
+use crate::common::{UI_COVERAGE, UI_COVERAGE_MAP, UI_DIAGNOSTICS_JSON};
+use crate::common::{UI_RUN_STDERR, UI_RUN_STDOUT};
 use crate::compute_diff::{write_diff, write_filtered_diff};
 use crate::errors::{self, Error, ErrorKind};
 use crate::header::TestProps;
@@ -27,6 +28,7 @@
 use std::io::prelude::*;
 use std::io::{self, BufReader};
 use std::iter;
+use std::panic::{self, AssertUnwindSafe};
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, ExitStatus, Output, Stdio};
 use std::str;
@@ -43,6 +45,9 @@
 mod debugger;
 use debugger::DebuggerCommands;
 
+mod fuzz;
+mod reduce;
+
 #[cfg(test)]
 mod tests;
 
@@ -156,6 +161,12 @@ pub fn run(config: Arc<Config>, testpaths: &TestPaths, revision: Option<&str>) {
             };
             rev_cx.run_revision();
         }
+    } else if config.reduce && config.mode == Ui {
+        // Reduce the test if it fails, then fail as usual.
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| cx.run_revision())) {
+            cx.reduce_failed_test();
+            panic::resume_unwind(payload);
+        }
     } else {
         cx.run_revision();
     }
@@ -193,6 +204,11 @@ pub fn compute_stamp_hash(config: &Config) -> String {
         config.force_pass_mode.hash(&mut hash);
     }
 
+    if let Fuzz = config.mode {
+        config.fuzz_iterations.hash(&mut hash);
+        config.fuzz_seed.hash(&mut hash);
+    }
+
     format!("{:x}", hash.finish())
 }
 
@@ -256,6 +272,7 @@ fn run_revision(&self) {
             JsDocTest => self.run_js_doc_test(),
             CoverageMap => self.run_coverage_map_test(),
             CoverageRun => self.run_coverage_run_test(),
+            Fuzz => self.run_fuzz_test(),
         }
     }
 
@@ -611,38 +628,30 @@ fn run_doctests_for_coverage(
         profraw_paths: &mut Vec<PathBuf>,
         bin_paths: &mut Vec<PathBuf>,
     ) {
-        // Put .profraw files and doctest executables in dedicated directories,
+        // Put .profraw files and doctest executables in a dedicated directory,
         // to make it easier to glob them all later.
-        let profraws_dir = self.output_base_dir().join("doc_profraws");
-        let bins_dir = self.output_base_dir().join("doc_bins");
+        let coverage_dir = self.output_base_dir().join("doc_coverage");
 
-        // Remove existing directories to prevent cross-run interference.
-        if profraws_dir.try_exists().unwrap() {
-            std::fs::remove_dir_all(&profraws_dir).unwrap();
-        }
-        if bins_dir.try_exists().unwrap() {
-            std::fs::remove_dir_all(&bins_dir).unwrap();
+        // Remove the existing directory to prevent cross-run interference.
+        if coverage_dir.try_exists().unwrap() {
+            std::fs::remove_dir_all(&coverage_dir).unwrap();
         }
 
         let mut rustdoc_cmd =
             Command::new(self.config.rustdoc_path.as_ref().expect("--rustdoc-path not passed"));
 
-        // In general there will be multiple doctest binaries running, so we
-        // tell the profiler runtime to write their coverage data into separate
-        // profraw files.
-        rustdoc_cmd.env("LLVM_PROFILE_FILE", profraws_dir.join("%p-%m.profraw"));
-
-        rustdoc_cmd.args(["--test", "-Cinstrument-coverage"]);
+        rustdoc_cmd.arg("--test");
 
         // Without this, the doctests complain about not being able to find
         // their enclosing file's crate for some reason.
         rustdoc_cmd.args(["--crate-name", "workaround_for_79771"]);
 
-        // Persist the doctest binaries so that `llvm-cov show` can read their
-        // embedded coverage mappings later.
+        // Build the doctests with coverage instrumentation, and keep both the
+        // doctest binaries (whose embedded coverage mappings are needed by
+        // `llvm-cov show`) and the profraw files they write.
         rustdoc_cmd.arg("-Zunstable-options");
-        rustdoc_cmd.arg("--persist-doctests");
-        rustdoc_cmd.arg(&bins_dir);
+        rustdoc_cmd.arg("--doctest-coverage");
+        rustdoc_cmd.arg(&coverage_dir);
 
         rustdoc_cmd.arg("-L");
         rustdoc_cmd.arg(self.aux_output_dir_name());
@@ -660,15 +669,15 @@ fn glob_iter(path: impl AsRef<Path>) -> impl Iterator<Item = PathBuf> {
             iter.map(Result::unwrap)
         }
 
-        // Find all profraw files in the profraw directory.
-        for p in glob_iter(profraws_dir.join("*.profraw")) {
+        // Find all profraw files in the coverage directory.
+        for p in glob_iter(coverage_dir.join("*.profraw")) {
             profraw_paths.push(p);
         }
-        // Find all executables in the `--persist-doctests` directory, while
+        // Find all executables in the coverage directory, while
         // avoiding other file types (e.g. `.pdb` on Windows). This doesn't
         // need to be perfect, as long as it can handle the files actually
         // produced by `rustdoc --test`.
-        for p in glob_iter(bins_dir.join("**/*")) {
+        for p in glob_iter(coverage_dir.join("**/*")) {
             let is_bin = p.is_file()
                 && match p.extension() {
                     None => true,
@@ -2505,7 +2514,7 @@ fn make_compile_args(
                 rustc.arg("-Copt-level=2");
             }
             RunPassValgrind | Pretty | DebugInfo | Codegen | Rustdoc | RustdocJson | RunMake
-            | CodegenUnits | JsDocTest | Assembly => {
+            | CodegenUnits | JsDocTest | Assembly | Fuzz => {
                 // do not use JSON output
             }
         }
@@ -3570,8 +3579,13 @@ fn run_rmake_test(&self) {
         }
         create_dir_all(&tmpdir).unwrap();
 
+        // Tests are either written as an `rmake.rs` recipe, which is compiled against the
+        // `run_make_support` library and then run, or as a Makefile.
+        let recipe = self.testpaths.file.join("rmake.rs");
         let host = &self.config.host;
-        let make = if host.contains("dragonfly")
+        let runner = if recipe.exists() {
+            "rmake"
+        } else if host.contains("dragonfly")
             || host.contains("freebsd")
             || host.contains("netbsd")
             || host.contains("openbsd")
@@ -3582,7 +3596,11 @@ fn run_rmake_test(&self) {
             "make"
         };
 
-        let mut cmd = Command::new(make);
+        let mut cmd = if recipe.exists() {
+            Command::new(self.compile_rmake_recipe(&recipe))
+        } else {
+            Command::new(runner)
+        };
         cmd.current_dir(&self.testpaths.file)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -3690,8 +3708,8 @@ fn run_rmake_test(&self) {
             }
         }
 
-        let (output, truncated) =
-            self.read2_abbreviated(cmd.spawn().expect("failed to spawn `make`"));
+        let child = cmd.spawn().unwrap_or_else(|e| panic!("failed to spawn `{runner}`: {e:?}"));
+        let (output, truncated) = self.read2_abbreviated(child);
         if !output.status.success() {
             let res = ProcRes {
                 status: output.status,
@@ -3700,8 +3718,41 @@ fn run_rmake_test(&self) {
                 truncated,
                 cmdline: format!("{:?}", cmd),
             };
-            self.fatal_proc_rec("make failed", &res);
+            self.fatal_proc_rec(&format!("{runner} failed"), &res);
+        }
+    }
+
+    /// Compiles the `rmake.rs` recipe of a `run-make` test for the host, linking it against the
+    /// `run_make_support` library, and returns the path to the resulting executable.
+    fn compile_rmake_recipe(&self, recipe: &Path) -> PathBuf {
+        let Some(support_lib) = &self.config.run_make_support_path else {
+            self.fatal("`rmake.rs` recipes need the `--run-make-support-path` flag");
+        };
+        // The recipe lives next to the test's `TMPDIR`, so it doesn't show up among the files
+        // the test produces.
+        let recipe_bin = self.output_base_dir().join(if self.config.host.contains("windows") {
+            "rmake.exe"
+        } else {
+            "rmake"
+        });
+
+        let mut rustc = Command::new(&self.config.rustc_path);
+        rustc
+            .arg(recipe)
+            .arg("--edition=2021")
+            .arg("-o")
+            .arg(&recipe_bin)
+            .arg("--extern")
+            .arg(format!("run_make_support={}", support_lib.display()))
+            .arg("-L")
+            .arg(format!("dependency={}", support_lib.parent().unwrap().display()))
+            .env_remove("RUSTFLAGS");
+        let proc_res =
+            self.compose_and_run(rustc, self.config.compile_lib_path.to_str().unwrap(), None, None);
+        if !proc_res.status.success() {
+            self.fatal_proc_rec("compiling the `rmake.rs` recipe failed", &proc_res);
         }
+        recipe_bin
     }
 
     fn aggressive_rm_rf(&self, path: &Path) -> io::Result<()> {
@@ -3879,6 +3930,31 @@ fn run_ui_test(&self) {
         let mut errors = self.load_compare_outputs(&proc_res, TestOutput::Compile, explicit);
         let rustfix_input = json::rustfix_diagnostics_only(&proc_res.stderr);
 
+        let expected_diagnostics_json = self.load_expected_output(UI_DIAGNOSTICS_JSON);
+        if self.props.check_diagnostics_json {
+            if explicit {
+                self.fatal(
+                    "the `// check-diagnostics-json` directive cannot be combined with \
+                     an explicit `--error-format`",
+                );
+            }
+            let diagnostics_json = self.normalize_output(
+                &json::extract_diagnostics_json(&proc_res.stderr),
+                &self.props.normalize_stderr,
+            );
+            errors += self.compare_output(
+                UI_DIAGNOSTICS_JSON,
+                &diagnostics_json,
+                &expected_diagnostics_json,
+                self.props.compare_output_lines_by_subset,
+            );
+        } else if !expected_diagnostics_json.is_empty() {
+            panic!(
+                "the `// check-diagnostics-json` directive wasn't found but a \
+                 `*.diagnostics.json` file was found"
+            );
+        }
+
         if self.config.compare_mode.is_some() {
             // don't test rustfix with nll right now
         } else if self.config.rustfix_coverage {
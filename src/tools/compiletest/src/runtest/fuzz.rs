@@ -0,0 +1,715 @@
+//! The `fuzz` test mode: looking for compiler crashes with generated programs.
+//!
+//! Every test file is a seed program. The seed is split into token trees, which are mutated a
+//! number of times (deleting, duplicating, swapping and replacing trees, or transplanting the
+//! contents of one delimited group into another), and rustc is run on each mutant. Mutants that
+//! make rustc crash (an ICE, including delayed bugs) are reduced to a small program that still
+//! crashes in the same place, and written out in the format of `tests/crashes`.
+//!
+//! The mutations work on token trees rather than on the AST of `rustc_parse`. compiletest is built
+//! by the bootstrap compiler as an ordinary (non-`rustc_private`) tool, so it cannot link the
+//! parser of the compiler under test, and linking the bootstrap compiler's parser instead would
+//! mutate programs with a different grammar than the one being tested. The compiler under test
+//! is used for everything that needs the AST instead: mutants are only compiled if they still
+//! parse with `-Zparse-only` (unless parsing already crashes), and the reduced programs are
+//! formatted with `-Zunpretty=normal`, i.e. by `rustc_ast_pretty`.
+//!
+//! The number of mutants per seed and the seed of the random number generator are set with the
+//! `COMPILETEST_FUZZ_ITERATIONS` and `COMPILETEST_FUZZ_SEED` environment variables.
+
+use std::collections::HashSet;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::TestCx;
+use crate::util::add_dylib_path;
+
+/// How long rustc may take on a single mutant before it is considered to hang.
+const RUSTC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The maximal number of rustc invocations spent on reducing a single crash.
+const MAX_REDUCTION_RUNS: usize = 1000;
+
+/// How often to mutate the seed before giving up on finding a mutant that parses.
+const MAX_MUTATION_ATTEMPTS: usize = 10;
+
+/// Expressions that tend to hit edge cases, to replace literals with.
+const INTERESTING_LITERALS: &[&str] = &[
+    "0",
+    "1",
+    "-1",
+    "255u8",
+    "-128i8",
+    "340282366920938463463374607431768211455u128",
+    "0usize",
+    "1.0e308",
+    "f64::NAN",
+    "\"\"",
+    "'\\0'",
+    "b'a'",
+    "true",
+    "()",
+];
+
+/// Tokens that are inserted at random places, since they change the meaning of the code around
+/// them a lot.
+const INTERESTING_TOKENS: &[&str] = &[
+    "&", "*", "?", "!", "_", "..", "'static", "dyn", "impl", "async", "await", "const", "unsafe",
+    "move", "mut", "ref", "Self", "self", "where", "for<'a>", "[]", "{}", "()", ";", "loop {}",
+];
+
+impl TestCx<'_> {
+    pub(super) fn run_fuzz_test(&self) {
+        let src = fs::read_to_string(&self.testpaths.file).unwrap();
+        let seed_program = match parse(&src) {
+            Ok(program) => program,
+            Err(err) => self.fatal(&format!("failed to split the seed into token trees: {err}")),
+        };
+
+        let mut hasher = DefaultHasher::new();
+        self.testpaths.file.file_name().hash(&mut hasher);
+        let mut rng = Rng::new(self.config.fuzz_seed ^ hasher.finish());
+
+        let work_dir = self.output_base_dir();
+        let _ = fs::remove_dir_all(&work_dir);
+        fs::create_dir_all(&work_dir).unwrap();
+
+        let mut signatures = HashSet::new();
+        let mut crashes = Vec::new();
+        // The first run is on the seed itself, so crashes it already has are reported as well.
+        for iteration in 0..=self.config.fuzz_iterations {
+            let program = if iteration == 0 {
+                seed_program.clone()
+            } else {
+                let mutant = (0..MAX_MUTATION_ATTEMPTS).find_map(|_| {
+                    let mut program = seed_program.clone();
+                    for _ in 0..rng.below(3) + 1 {
+                        mutate(&mut program, &mut rng);
+                    }
+                    self.parses(&print(&program), &work_dir).then_some(program)
+                });
+                let Some(mutant) = mutant else { continue };
+                mutant
+            };
+            let Some(signature) = self.crash_signature(&print(&program), &work_dir) else {
+                continue;
+            };
+            if !signatures.insert(signature.clone()) {
+                continue;
+            }
+            let reduced = self.reduce(program, &signature, &work_dir);
+            let path = self.write_crash(&reduced, &signature, iteration, &work_dir);
+            crashes.push(format!("{}\n    {}", path.display(), signature));
+        }
+
+        if !crashes.is_empty() {
+            self.fatal(&format!(
+                "found {} compiler crash(es), reduced test cases were written to:\n{}",
+                crashes.len(),
+                crashes.join("\n")
+            ));
+        }
+    }
+
+    /// Run rustc on `src`, and return what identifies the crash if it crashes.
+    fn crash_signature(&self, src: &str, work_dir: &Path) -> Option<String> {
+        let input = work_dir.join("mutant.rs");
+        fs::write(&input, src).unwrap();
+        let mut rustc = self.fuzz_rustc(&input);
+        rustc.arg("--crate-type=lib").arg("--emit=mir").arg("--out-dir").arg(work_dir);
+        let stderr = run_with_timeout(rustc, &work_dir.join("mutant.stderr"))?;
+        ice_signature(&stderr)
+    }
+
+    /// Whether `src` parses, or crashes the parser.
+    fn parses(&self, src: &str, work_dir: &Path) -> bool {
+        let input = work_dir.join("mutant.rs");
+        fs::write(&input, src).unwrap();
+        let mut rustc = self.fuzz_rustc(&input);
+        rustc.arg("--crate-type=lib").arg("-Zparse-only");
+        let Some(stderr) = run_with_timeout(rustc, &work_dir.join("mutant.stderr")) else {
+            return false;
+        };
+        ice_signature(&stderr).is_some() || !stderr.lines().any(|line| line.starts_with("error"))
+    }
+
+    /// A rustc command for `input`, with the flags all invocations in this mode share.
+    fn fuzz_rustc(&self, input: &Path) -> Command {
+        let mut rustc = Command::new(&self.config.rustc_path);
+        rustc
+            .arg(input)
+            .arg("-Zthreads=1")
+            .args(&["--target", &self.config.target])
+            .arg("-A")
+            .arg("warnings")
+            .arg("-A")
+            .arg("internal_features")
+            .args(&self.props.compile_flags)
+            .envs(self.props.rustc_env.clone())
+            .env("RUST_BACKTRACE", "0");
+        self.maybe_add_external_args(&mut rustc, &self.config.target_rustcflags);
+        add_dylib_path(&mut rustc, iter::once(&self.config.compile_lib_path));
+        rustc
+    }
+
+    /// Remove as much of `program` as possible while it still crashes with `signature`.
+    fn reduce(&self, program: Vec<TokenTree>, signature: &str, work_dir: &Path) -> Vec<TokenTree> {
+        let mut runs_left = MAX_REDUCTION_RUNS;
+        reduce_token_trees(program, &mut runs_left, |candidate| {
+            self.crash_signature(&print(candidate), work_dir).as_deref() == Some(signature)
+        })
+    }
+
+    /// Write a reduced crash in the format of `tests/crashes`: to that directory when blessing,
+    /// and to the output directory of this test otherwise.
+    fn write_crash(
+        &self,
+        program: &[TokenTree],
+        signature: &str,
+        iteration: u32,
+        work_dir: &Path,
+    ) -> PathBuf {
+        let mut src = print(program);
+        // Let the compiler format the reduced program, if it still crashes afterwards.
+        let input = work_dir.join("reduced.rs");
+        let output = work_dir.join("reduced.pretty.rs");
+        fs::write(&input, &src).unwrap();
+        let _ = fs::remove_file(&output);
+        let mut rustc = self.fuzz_rustc(&input);
+        rustc.arg("-Zunpretty=normal").arg("-o").arg(&output);
+        if run_with_timeout(rustc, &work_dir.join("reduced.stderr")).is_some() {
+            if let Ok(pretty) = fs::read_to_string(&output) {
+                if !pretty.trim().is_empty()
+                    && self.crash_signature(&pretty, work_dir).as_deref() == Some(signature)
+                {
+                    src = pretty;
+                }
+            }
+        }
+
+        let mut hasher = DefaultHasher::new();
+        signature.hash(&mut hasher);
+        let dir = if self.config.bless {
+            self.config.src_base.parent().unwrap().join("crashes")
+        } else {
+            work_dir.join("crashes")
+        };
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!(
+            "{}-{:08x}.rs",
+            self.testpaths.file.file_stem().unwrap().to_str().unwrap(),
+            hasher.finish() as u32
+        ));
+        let contents = format!(
+            "// known-bug: unknown\n\
+             // {signature}\n\
+             // Found by the fuzz test `{}`, iteration {iteration} with seed {}.\n\
+             {}\n",
+            self.testpaths.relative_dir.join(self.testpaths.file.file_name().unwrap()).display(),
+            self.config.fuzz_seed,
+            src.trim_end(),
+        );
+        fs::write(&path, contents).unwrap();
+        path
+    }
+}
+
+/// Remove as much of `program` as possible while `is_interesting` still holds for it: first
+/// large runs of token trees, then smaller ones, then delimiters, until nothing can be removed
+/// anymore or `runs_left` candidates have been tried.
+pub(super) fn reduce_token_trees(
+    mut program: Vec<TokenTree>,
+    runs_left: &mut usize,
+    mut is_interesting: impl FnMut(&[TokenTree]) -> bool,
+) -> Vec<TokenTree> {
+    let mut changed = true;
+    while changed {
+        changed = false;
+        let mut index = 0;
+        while let Some(path) = sequences(&program).into_iter().nth(index) {
+            let mut chunk = (sequence(&mut program, &path).len() / 2).max(1);
+            loop {
+                let mut start = 0;
+                while start < sequence(&mut program, &path).len() {
+                    if *runs_left == 0 {
+                        return program;
+                    }
+                    *runs_left -= 1;
+                    let mut candidate = program.clone();
+                    let trees = sequence(&mut candidate, &path);
+                    trees.drain(start..(start + chunk).min(trees.len()));
+                    if is_interesting(&candidate) {
+                        program = candidate;
+                        changed = true;
+                    } else {
+                        start += chunk;
+                    }
+                }
+                if chunk == 1 {
+                    break;
+                }
+                chunk /= 2;
+            }
+            // Then try to replace groups by their contents.
+            let mut start = 0;
+            while start < sequence(&mut program, &path).len() {
+                let mut candidate = program.clone();
+                let trees = sequence(&mut candidate, &path);
+                let TokenTree::Group(_, contents) = trees[start].clone() else {
+                    start += 1;
+                    continue;
+                };
+                if *runs_left == 0 {
+                    return program;
+                }
+                *runs_left -= 1;
+                trees.splice(start..start + 1, contents);
+                if is_interesting(&candidate) {
+                    program = candidate;
+                    changed = true;
+                } else {
+                    start += 1;
+                }
+            }
+            index += 1;
+        }
+    }
+    program
+}
+
+/// Run `command`, and return its stderr if it terminated before the timeout.
+pub(super) fn run_with_timeout(mut command: Command, stderr_path: &Path) -> Option<String> {
+    let stderr = fs::File::create(stderr_path).unwrap();
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(stderr)
+        .spawn()
+        .unwrap_or_else(|e| panic!("failed to exec `{command:?}`: {e:?}"));
+    let deadline = Instant::now() + RUSTC_TIMEOUT;
+    loop {
+        if child.try_wait().unwrap().is_some() {
+            break;
+        }
+        if Instant::now() > deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return None;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    Some(fs::read_to_string(stderr_path).unwrap_or_default())
+}
+
+/// If rustc crashed, return a description of where it crashed. That is the location in the
+/// compiler source if the message has one, so that reducing the program does not count as a
+/// different crash just because the message mentions different names.
+pub(super) fn ice_signature(stderr: &str) -> Option<String> {
+    let mut lines = stderr.lines();
+    let message = loop {
+        let line = lines.next()?;
+        if let Some(message) = line.strip_prefix("error: internal compiler error: ") {
+            break message.to_string();
+        }
+        if let Some(location) = line.strip_prefix("thread 'rustc' panicked at ") {
+            break format!("{location} {}", lines.next().unwrap_or_default());
+        }
+    };
+    let location = message.split_whitespace().find(|word| {
+        word.starts_with("compiler/") && word.trim_end_matches(':').rsplit(':').count() >= 3
+    });
+    Some(location.map_or(message.clone(), |location| location.trim_end_matches(':').to_string()))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Delimiter {
+    Parenthesis,
+    Bracket,
+    Brace,
+}
+
+impl Delimiter {
+    fn open(self) -> char {
+        match self {
+            Delimiter::Parenthesis => '(',
+            Delimiter::Bracket => '[',
+            Delimiter::Brace => '{',
+        }
+    }
+
+    fn close(self) -> char {
+        match self {
+            Delimiter::Parenthesis => ')',
+            Delimiter::Bracket => ']',
+            Delimiter::Brace => '}',
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum TokenKind {
+    Ident,
+    Lifetime,
+    Literal,
+    Punct,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) enum TokenTree {
+    Token(TokenKind, String),
+    Group(Delimiter, Vec<TokenTree>),
+}
+
+/// Punctuation that has to stay together, longest first.
+const MULTI_CHAR_PUNCT: &[&str] = &[
+    "<<=", ">>=", "...", "..=", "::", "->", "=>", "==", "!=", "<=", ">=", "&&", "||", "+=", "-=",
+    "*=", "/=", "%=", "^=", "&=", "|=", "<<", ">>", "..",
+];
+
+fn is_ident_start(c: char) -> bool {
+    c == '_' || c.is_alphabetic()
+}
+
+fn is_ident_continue(c: char) -> bool {
+    c == '_' || c.is_alphanumeric()
+}
+
+/// Split a program into token trees. Comments are dropped.
+pub(super) fn parse(src: &str) -> Result<Vec<TokenTree>, String> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut pos = 0;
+    // The token trees of the groups that are currently open, innermost last.
+    let mut stack: Vec<(Delimiter, Vec<TokenTree>)> = Vec::new();
+    let mut trees = Vec::new();
+    let text = |from: usize, to: usize| chars[from..to].iter().collect::<String>();
+    while pos < chars.len() {
+        let c = chars[pos];
+        let rest = &chars[pos..];
+        let start = pos;
+        let token = if c.is_whitespace() {
+            pos += 1;
+            None
+        } else if rest.starts_with(&['/', '/']) {
+            while pos < chars.len() && chars[pos] != '\n' {
+                pos += 1;
+            }
+            None
+        } else if rest.starts_with(&['/', '*']) {
+            let mut depth = 0;
+            loop {
+                if pos >= chars.len() {
+                    return Err("unterminated block comment".to_string());
+                } else if chars[pos..].starts_with(&['/', '*']) {
+                    depth += 1;
+                    pos += 2;
+                } else if chars[pos..].starts_with(&['*', '/']) {
+                    depth -= 1;
+                    pos += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    pos += 1;
+                }
+            }
+            None
+        } else if let Some(delimiter) =
+            [Delimiter::Parenthesis, Delimiter::Bracket, Delimiter::Brace]
+                .into_iter()
+                .find(|delimiter| delimiter.open() == c)
+        {
+            pos += 1;
+            stack.push((delimiter, std::mem::take(&mut trees)));
+            None
+        } else if matches!(c, ')' | ']' | '}') {
+            pos += 1;
+            let Some((delimiter, outer)) = stack.pop() else {
+                return Err(format!("unexpected `{c}`"));
+            };
+            if delimiter.close() != c {
+                return Err(format!("`{}` closed by `{c}`", delimiter.open()));
+            }
+            let group = TokenTree::Group(delimiter, std::mem::replace(&mut trees, outer));
+            trees.push(group);
+            None
+        } else if let Some(end) = lex_string(&chars, pos)? {
+            pos = end;
+            Some((TokenKind::Literal, text(start, pos)))
+        } else if c == '\'' {
+            // A character literal, or a lifetime.
+            if chars.get(pos + 1) == Some(&'\\') || chars.get(pos + 2) == Some(&'\'') {
+                pos = lex_quoted(&chars, pos + 1, '\'')?;
+                Some((TokenKind::Literal, text(start, pos)))
+            } else {
+                pos += 1;
+                while pos < chars.len() && is_ident_continue(chars[pos]) {
+                    pos += 1;
+                }
+                Some((TokenKind::Lifetime, text(start, pos)))
+            }
+        } else if c.is_ascii_digit() {
+            pos += 1;
+            while pos < chars.len() {
+                let c = chars[pos];
+                if is_ident_continue(c) {
+                    pos += 1;
+                } else if c == '.' && chars.get(pos + 1).is_some_and(|c| c.is_ascii_digit()) {
+                    pos += 2;
+                } else if matches!(c, '+' | '-') && matches!(chars[pos - 1], 'e' | 'E') {
+                    pos += 1;
+                } else {
+                    break;
+                }
+            }
+            Some((TokenKind::Literal, text(start, pos)))
+        } else if is_ident_start(c) {
+            // This includes raw identifiers.
+            if rest.starts_with(&['r', '#'])
+                && chars.get(pos + 2).is_some_and(|&c| is_ident_start(c))
+            {
+                pos += 2;
+            }
+            pos += 1;
+            while pos < chars.len() && is_ident_continue(chars[pos]) {
+                pos += 1;
+            }
+            Some((TokenKind::Ident, text(start, pos)))
+        } else {
+            let len = MULTI_CHAR_PUNCT
+                .iter()
+                .find(|punct| {
+                    rest.len() >= punct.len() && punct.chars().zip(rest).all(|(a, &b)| a == b)
+                })
+                .map_or(1, |punct| punct.len());
+            pos += len;
+            Some((TokenKind::Punct, text(start, pos)))
+        };
+        if let Some((kind, text)) = token {
+            trees.push(TokenTree::Token(kind, text));
+        }
+    }
+    if let Some((delimiter, _)) = stack.last() {
+        return Err(format!("unclosed `{}`", delimiter.open()));
+    }
+    Ok(trees)
+}
+
+/// If a string literal (of any kind) starts at `pos`, return where it ends.
+fn lex_string(chars: &[char], pos: usize) -> Result<Option<usize>, String> {
+    let mut prefix_end = pos;
+    while prefix_end < chars.len() && matches!(chars[prefix_end], 'b' | 'c' | 'r') {
+        prefix_end += 1;
+    }
+    if prefix_end - pos > 2 {
+        return Ok(None);
+    }
+    let prefix: String = chars[pos..prefix_end].iter().collect();
+    if prefix.contains('r') {
+        if !matches!(prefix.as_str(), "r" | "br" | "cr") {
+            return Ok(None);
+        }
+        let mut hashes = 0;
+        while chars.get(prefix_end + hashes) == Some(&'#') {
+            hashes += 1;
+        }
+        if chars.get(prefix_end + hashes) != Some(&'"') {
+            return Ok(None);
+        }
+        let mut end = prefix_end + hashes + 1;
+        loop {
+            if end >= chars.len() {
+                return Err("unterminated raw string".to_string());
+            }
+            if chars[end] == '"' && (1..=hashes).all(|offset| chars.get(end + offset) == Some(&'#'))
+            {
+                return Ok(Some(end + 1 + hashes));
+            }
+            end += 1;
+        }
+    }
+    match (prefix.as_str(), chars.get(prefix_end)) {
+        ("" | "b" | "c", Some('"')) => lex_quoted(chars, prefix_end + 1, '"').map(Some),
+        ("b", Some('\'')) => lex_quoted(chars, prefix_end + 1, '\'').map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Find the end of a quoted literal whose contents start at `pos`.
+fn lex_quoted(chars: &[char], mut pos: usize, quote: char) -> Result<usize, String> {
+    loop {
+        match chars.get(pos) {
+            None => return Err("unterminated literal".to_string()),
+            Some('\\') => pos += 2,
+            Some(&c) if c == quote => return Ok(pos + 1),
+            Some(_) => pos += 1,
+        }
+    }
+}
+
+/// Turn token trees back into source code.
+pub(super) fn print(trees: &[TokenTree]) -> String {
+    fn print_into(trees: &[TokenTree], out: &mut String) {
+        for tree in trees {
+            match tree {
+                TokenTree::Token(_, text) => out.push_str(text),
+                TokenTree::Group(delimiter, trees) => {
+                    out.push(delimiter.open());
+                    print_into(trees, out);
+                    out.push(delimiter.close());
+                }
+            }
+            // Semicolons and braces end items and statements, which makes for a readable layout.
+            let newline = matches!(tree, TokenTree::Token(_, text) if text == ";")
+                || matches!(tree, TokenTree::Group(Delimiter::Brace, _));
+            out.push(if newline { '\n' } else { ' ' });
+        }
+    }
+    let mut out = String::new();
+    print_into(trees, &mut out);
+    out
+}
+
+/// The paths (indices of groups) of all token tree sequences, i.e. the top level and the
+/// contents of every group, outer ones first.
+fn sequences(trees: &[TokenTree]) -> Vec<Vec<usize>> {
+    fn collect(trees: &[TokenTree], path: &mut Vec<usize>, out: &mut Vec<Vec<usize>>) {
+        out.push(path.clone());
+        for (index, tree) in trees.iter().enumerate() {
+            if let TokenTree::Group(_, trees) = tree {
+                path.push(index);
+                collect(trees, path, out);
+                path.pop();
+            }
+        }
+    }
+    let mut out = Vec::new();
+    collect(trees, &mut Vec::new(), &mut out);
+    out
+}
+
+fn sequence<'a>(trees: &'a mut Vec<TokenTree>, path: &[usize]) -> &'a mut Vec<TokenTree> {
+    match path.split_first() {
+        None => trees,
+        Some((&index, rest)) => match &mut trees[index] {
+            TokenTree::Group(_, trees) => sequence(trees, rest),
+            TokenTree::Token(..) => unreachable!("paths only lead through groups"),
+        },
+    }
+}
+
+/// Apply a random mutation to `program`.
+pub(super) fn mutate(program: &mut Vec<TokenTree>, rng: &mut Rng) {
+    let paths = sequences(program);
+    let path = &paths[rng.below(paths.len())];
+    let snapshot = program.clone();
+    let trees = sequence(program, path);
+    let len = trees.len();
+    match rng.below(7) {
+        // Delete a tree.
+        0 if len > 0 => {
+            trees.remove(rng.below(len));
+        }
+        // Duplicate a tree.
+        1 if len > 0 => {
+            let index = rng.below(len);
+            let tree = trees[index].clone();
+            trees.insert(index, tree);
+        }
+        // Swap two trees.
+        2 if len > 1 => {
+            let (a, b) = (rng.below(len), rng.below(len));
+            trees.swap(a, b);
+        }
+        // Replace an identifier with another one from the program.
+        3 => {
+            let idents = all_tokens(&snapshot, TokenKind::Ident);
+            let positions: Vec<usize> = (0..len)
+                .filter(|&i| matches!(&trees[i], TokenTree::Token(TokenKind::Ident, _)))
+                .collect();
+            if !idents.is_empty() && !positions.is_empty() {
+                let ident = idents[rng.below(idents.len())].clone();
+                trees[positions[rng.below(positions.len())]] =
+                    TokenTree::Token(TokenKind::Ident, ident);
+            }
+        }
+        // Replace a literal with an interesting one.
+        4 => {
+            let positions: Vec<usize> = (0..len)
+                .filter(|&i| matches!(&trees[i], TokenTree::Token(TokenKind::Literal, _)))
+                .collect();
+            if !positions.is_empty() {
+                let literal = INTERESTING_LITERALS[rng.below(INTERESTING_LITERALS.len())];
+                let index = positions[rng.below(positions.len())];
+                trees.splice(index..index + 1, parse(literal).unwrap());
+            }
+        }
+        // Insert interesting tokens.
+        5 => {
+            let token = INTERESTING_TOKENS[rng.below(INTERESTING_TOKENS.len())];
+            let new = parse(token).unwrap();
+            let index = rng.below(len + 1);
+            trees.splice(index..index, new);
+        }
+        // Replace the contents of a group with those of another group with the same delimiter.
+        _ => {
+            let groups: Vec<usize> =
+                (0..len).filter(|&i| matches!(&trees[i], TokenTree::Group(..))).collect();
+            if groups.is_empty() {
+                return;
+            }
+            let index = groups[rng.below(groups.len())];
+            let TokenTree::Group(delimiter, _) = trees[index] else { unreachable!() };
+            let donors = all_groups(&snapshot, delimiter);
+            let donor = donors[rng.below(donors.len())].clone();
+            trees[index] = TokenTree::Group(delimiter, donor);
+        }
+    }
+}
+
+pub(super) fn all_tokens(trees: &[TokenTree], kind: TokenKind) -> Vec<String> {
+    let mut out = Vec::new();
+    for tree in trees {
+        match tree {
+            TokenTree::Token(k, text) if *k == kind => out.push(text.clone()),
+            TokenTree::Token(..) => {}
+            TokenTree::Group(_, trees) => out.extend(all_tokens(trees, kind)),
+        }
+    }
+    out
+}
+
+fn all_groups(trees: &[TokenTree], delimiter: Delimiter) -> Vec<Vec<TokenTree>> {
+    let mut out = Vec::new();
+    for tree in trees {
+        if let TokenTree::Group(d, trees) = tree {
+            if *d == delimiter {
+                out.push(trees.clone());
+            }
+            out.extend(all_groups(trees, delimiter));
+        }
+    }
+    out
+}
+
+/// A small xorshift random number generator, so that runs with the same seed are reproducible.
+pub(super) struct Rng(u64);
+
+impl Rng {
+    pub(super) fn new(seed: u64) -> Self {
+        // The state must not be zero.
+        Rng(seed | 1)
+    }
+
+    /// A random number below `n`, which must not be zero.
+    pub(super) fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
@@ -0,0 +1,256 @@
+//! Reducing failing tests with `--reduce`.
+//!
+//! When a UI test fails, its source is shrunk to a small program that still fails the same way,
+//! and written next to the test as `<name>.reduced.rs`, or `<name>.<revision>.reduced.rs` for
+//! each failing revision of a test with revisions. What "the same way" means is captured by a
+//! [`FailureSignature`]: the location of an ICE, the first error of a test that should compile,
+//! or a line of the compiler output that is not in the expected output.
+//!
+//! The reduction follows established test-case reducers: first runs of whole lines are removed,
+//! halving the size of the runs down to single lines (delta debugging). Then items, statements
+//! and expressions are removed as token trees, and delimited groups are replaced by their
+//! contents (see `fuzz::reduce_token_trees`). Both passes are repeated until neither makes
+//! progress. The comments at the start of the file, which hold the directives, are always kept,
+//! and candidates are compiled like the test itself, with the same flags, environment and
+//! auxiliary crates.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use super::fuzz::{ice_signature, parse, print, reduce_token_trees, run_with_timeout};
+use super::{AllowUnused, LinkToAux, TargetLocation, TestCx};
+use crate::common::{PassMode, UI_STDERR};
+use crate::json;
+use crate::util::add_dylib_path;
+
+/// The maximal number of compiler invocations spent on reducing a single test.
+const MAX_REDUCTION_RUNS: usize = 2000;
+
+/// What identifies the way a test fails.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum FailureSignature {
+    /// The compiler crashed at this location, see `fuzz::ice_signature`.
+    Ice(String),
+    /// The test should compile, but the compiler reports an error with this code, or with this
+    /// message if the error has no code.
+    Error(String),
+    /// This line of the compiler output is not in the expected output.
+    UnexpectedOutput(String),
+}
+
+impl FailureSignature {
+    pub(super) fn matches(&self, output: &CompilerOutput) -> bool {
+        let ice = ice_signature(&output.raw);
+        match self {
+            FailureSignature::Ice(location) => ice.as_ref() == Some(location),
+            FailureSignature::Error(error) => {
+                ice.is_none() && output.errors().any(|e| e == error.as_str())
+            }
+            FailureSignature::UnexpectedOutput(line) => {
+                ice.is_none() && output.rendered.lines().any(|l| l == line.as_str())
+            }
+        }
+    }
+}
+
+/// The stderr of the compiler for a candidate.
+pub(super) struct CompilerOutput {
+    /// As the compiler printed it.
+    pub(super) raw: String,
+    /// The rendered diagnostics, normalized like the stderr of UI tests.
+    pub(super) rendered: String,
+}
+
+impl CompilerOutput {
+    /// The code of every error, or its message if it has none.
+    fn errors(&self) -> impl Iterator<Item = &str> {
+        self.rendered.lines().filter_map(|line| {
+            let rest = line.strip_prefix("error")?;
+            match rest.strip_prefix('[') {
+                Some(code) => code.split(']').next(),
+                None => rest.strip_prefix(": ").filter(|message| !message.starts_with("aborting")),
+            }
+        })
+    }
+}
+
+impl TestCx<'_> {
+    /// Reduce the source of a UI test that just failed, and write the result next to the test.
+    pub(super) fn reduce_failed_test(&self) {
+        let src = fs::read_to_string(&self.testpaths.file).unwrap();
+        let work_dir = self.output_base_dir().join("reduce");
+        let _ = fs::remove_dir_all(&work_dir);
+        fs::create_dir_all(work_dir.join("out")).unwrap();
+
+        // Build the auxiliary crates once, and remember how to link them.
+        let mut aux_flags = Command::new(&self.config.rustc_path);
+        let aux_dir = self.build_all_auxiliary(&mut aux_flags);
+        let compile = |src: &str| self.compile_candidate(src, &work_dir, &aux_flags, &aux_dir);
+
+        let revision =
+            self.revision.map_or(String::new(), |revision| format!(" revision `{revision}`"));
+        let Some(signature) = compile(&src).and_then(|output| self.failure_signature(&output))
+        else {
+            println!(
+                "\nnot reducing the test{revision}: the compiler output does not show how it fails"
+            );
+            return;
+        };
+        println!("\nreducing the test{revision}, preserving the failure {signature:?}");
+
+        let (header, body) = split_header(&src);
+        let is_interesting = |body: &str| {
+            compile(&format!("{header}{body}")).is_some_and(|output| signature.matches(&output))
+        };
+        let mut runs_left = MAX_REDUCTION_RUNS;
+        let mut lines: Vec<String> = body.lines().map(String::from).collect();
+        loop {
+            let before = lines.clone();
+            lines = reduce_lines(lines, &mut runs_left, |lines| is_interesting(&lines.join("\n")));
+            if let Ok(trees) = parse(&lines.join("\n")) {
+                let trees = reduce_token_trees(trees, &mut runs_left, |trees| {
+                    is_interesting(&print(trees))
+                });
+                // Printing the token trees drops comments and changes the layout, so only take
+                // the result if it still fails the same way.
+                let reduced = print(&trees);
+                if runs_left > 0 {
+                    runs_left -= 1;
+                    if is_interesting(&reduced) {
+                        lines = reduced.lines().map(String::from).collect();
+                    }
+                }
+            }
+            if lines == before || runs_left == 0 {
+                break;
+            }
+        }
+
+        let extension = match self.revision {
+            Some(revision) => format!("{revision}.reduced.rs"),
+            None => "reduced.rs".to_string(),
+        };
+        let path = self.testpaths.file.with_extension(extension);
+        fs::write(&path, format!("{header}{}\n", lines.join("\n"))).unwrap();
+        println!("reduced test case written to {}", path.display());
+    }
+
+    /// Compile `src` like the test, and return the compiler's stderr, or `None` if the compiler
+    /// hangs.
+    fn compile_candidate(
+        &self,
+        src: &str,
+        work_dir: &Path,
+        aux_flags: &Command,
+        aux_dir: &Path,
+    ) -> Option<CompilerOutput> {
+        // Keep the name of the test, since it determines the crate name.
+        let input = work_dir.join(self.testpaths.file.file_name().unwrap());
+        fs::write(&input, src).unwrap();
+        let allow_unused =
+            if !self.is_rustdoc() && self.props.local_pass_mode() != Some(PassMode::Run) {
+                AllowUnused::Yes
+            } else {
+                AllowUnused::No
+            };
+        let mut rustc = self.make_compile_args(
+            &input,
+            TargetLocation::ThisDirectory(work_dir.join("out")),
+            self.should_emit_metadata(self.pass_mode()),
+            allow_unused,
+            LinkToAux::Yes,
+            Vec::new(),
+        );
+        rustc.args(aux_flags.get_args());
+        self.props.unset_rustc_env.iter().fold(&mut rustc, Command::env_remove);
+        rustc.envs(self.props.rustc_env.clone());
+        add_dylib_path(&mut rustc, [self.config.compile_lib_path.as_path(), aux_dir].into_iter());
+
+        let raw = run_with_timeout(rustc, &work_dir.join("candidate.stderr"))?;
+        let explicit = self.props.compile_flags.iter().any(|s| s.contains("--error-format"));
+        let rendered = if explicit { raw.clone() } else { json::extract_rendered(&raw) };
+        // Candidates live in the work directory, but should have the same output as the test.
+        let rendered = rendered.replace(
+            &work_dir.display().to_string(),
+            &self.testpaths.file.parent().unwrap().display().to_string(),
+        );
+        let rendered = self.normalize_output(&rendered, &self.props.normalize_stderr);
+        Some(CompilerOutput { raw, rendered })
+    }
+
+    /// Work out how the test fails from the compiler output for its original source.
+    fn failure_signature(&self, output: &CompilerOutput) -> Option<FailureSignature> {
+        if let Some(location) = ice_signature(&output.raw) {
+            return Some(FailureSignature::Ice(location));
+        }
+        if self.should_compile_successfully(self.pass_mode()) {
+            if let Some(error) = output.errors().next() {
+                return Some(FailureSignature::Error(error.to_string()));
+            }
+        }
+        let expected = self.load_expected_output(UI_STDERR);
+        let expected: HashSet<&str> = expected.lines().collect();
+        let unexpected: Vec<&str> = output
+            .rendered
+            .lines()
+            .filter(|line| !matches!(line.trim(), "" | "|") && !expected.contains(line))
+            .collect();
+        // Prefer the first line of a diagnostic, which does not show any source code that the
+        // reduction would change.
+        let line = unexpected
+            .iter()
+            .find(|line| line.starts_with("error") || line.starts_with("warning"))
+            .or(unexpected.first())?;
+        Some(FailureSignature::UnexpectedOutput(line.to_string()))
+    }
+}
+
+/// Split `src` into the comments and blank lines at its start, which hold the directives, and
+/// the rest.
+pub(super) fn split_header(src: &str) -> (String, String) {
+    let lines: Vec<&str> = src.lines().collect();
+    let header_len = lines
+        .iter()
+        .take_while(|line| {
+            let line = line.trim();
+            line.is_empty() || (line.starts_with("//") && !line.starts_with("//~"))
+        })
+        .count();
+    let mut header = lines[..header_len].join("\n");
+    if header_len > 0 {
+        header.push('\n');
+    }
+    (header, lines[header_len..].join("\n"))
+}
+
+/// Remove as many of `lines` as possible while `is_interesting` still holds for the rest: runs
+/// of lines of decreasing length, down to single lines.
+pub(super) fn reduce_lines(
+    mut lines: Vec<String>,
+    runs_left: &mut usize,
+    mut is_interesting: impl FnMut(&[String]) -> bool,
+) -> Vec<String> {
+    let mut chunk = (lines.len() / 2).max(1);
+    loop {
+        let mut start = 0;
+        while start < lines.len() {
+            if *runs_left == 0 {
+                return lines;
+            }
+            *runs_left -= 1;
+            let mut candidate = lines.clone();
+            candidate.drain(start..(start + chunk).min(lines.len()));
+            if is_interesting(&candidate) {
+                lines = candidate;
+            } else {
+                start += chunk;
+            }
+        }
+        if chunk == 1 {
+            return lines;
+        }
+        chunk /= 2;
+    }
+}
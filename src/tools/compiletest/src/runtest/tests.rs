@@ -120,3 +120,233 @@ fn anonymize_coverage_line_numbers() {
 
     assert_eq!(anon(input), expected);
 }
+
+#[test]
+fn fuzz_token_trees_round_trip() {
+    use fuzz::{parse, print, Delimiter, TokenKind, TokenTree};
+
+    let src = r####"
+        // A comment, /* and */ a /* nested /* block */ comment.
+        fn f<'a>(x: &'a [u8; 3]) -> Option<char> {
+            let s = r#"raw "string""#; let b = b'\''; let c = '\\';
+            let _ = (1.5e-3, 0x1f_u8, ..=2, a::b, r#match);
+            if x[0] >= 2 { Some('x') } else { None }
+        }
+    "####;
+    let trees = parse(src).unwrap();
+    assert_eq!(parse(&print(&trees)).unwrap(), trees);
+
+    let tokens = fuzz::all_tokens(&trees, TokenKind::Literal);
+    assert_eq!(
+        tokens,
+        [
+            "3",
+            r###"r#"raw "string""#"###,
+            r"b'\''",
+            r"'\\'",
+            "1.5e-3",
+            "0x1f_u8",
+            "2",
+            "0",
+            "2",
+            "'x'"
+        ],
+    );
+    assert_eq!(fuzz::all_tokens(&trees, TokenKind::Lifetime), ["'a", "'a"]);
+    assert!(fuzz::all_tokens(&trees, TokenKind::Ident).contains(&"r#match".to_string()));
+    assert!(fuzz::all_tokens(&trees, TokenKind::Punct).contains(&"..=".to_string()));
+
+    assert_eq!(
+        parse("f(x)[0]").unwrap(),
+        [
+            TokenTree::Token(TokenKind::Ident, "f".into()),
+            TokenTree::Group(
+                Delimiter::Parenthesis,
+                vec![TokenTree::Token(TokenKind::Ident, "x".into())]
+            ),
+            TokenTree::Group(
+                Delimiter::Bracket,
+                vec![TokenTree::Token(TokenKind::Literal, "0".into())]
+            ),
+        ]
+    );
+
+    assert_eq!(parse("fn f() {").unwrap_err(), "unclosed `{`");
+    assert_eq!(parse("(]").unwrap_err(), "`(` closed by `]`");
+    assert_eq!(parse("x }").unwrap_err(), "unexpected `}`");
+    assert_eq!(parse("\"abc").unwrap_err(), "unterminated literal");
+    assert_eq!(parse("/* /* */").unwrap_err(), "unterminated block comment");
+}
+
+#[test]
+fn fuzz_mutations() {
+    use fuzz::{mutate, parse, print, Rng};
+
+    let seed =
+        parse("fn f(x: u32) -> u32 { let y = [x, 1]; match y[0] { 0 => 1, n => n } }").unwrap();
+    let mutants = |seed_value| {
+        let mut rng = Rng::new(seed_value);
+        (0..200)
+            .map(|_| {
+                let mut program = seed.clone();
+                for _ in 0..3 {
+                    mutate(&mut program, &mut rng);
+                }
+                program
+            })
+            .collect::<Vec<_>>()
+    };
+    let first = mutants(1);
+    // The same seed produces the same mutants.
+    assert_eq!(first, mutants(1));
+    assert_ne!(first, mutants(2));
+    // Mutants keep their delimiters balanced, so they print to something that splits into the
+    // same token trees again.
+    for mutant in &first {
+        assert_eq!(&parse(&print(mutant)).unwrap(), mutant);
+    }
+    // Most mutants differ from the seed, and from each other.
+    let different = first.iter().filter(|mutant| **mutant != seed).count();
+    assert!(different > 150, "only {different} of 200 mutants differ from the seed");
+    let mut distinct = first.clone();
+    distinct.sort_by_key(|mutant| print(mutant));
+    distinct.dedup();
+    assert!(distinct.len() > 100, "only {} of 200 mutants are distinct", distinct.len());
+}
+
+#[test]
+fn fuzz_reduce_token_trees() {
+    use fuzz::{parse, print, reduce_token_trees, TokenKind};
+
+    let program = parse("fn f() { let x = [1, 2]; if x[0] > 0 { crash(x); } } struct S;").unwrap();
+    let crashes =
+        |trees: &[_]| fuzz::all_tokens(trees, TokenKind::Ident).contains(&"crash".to_string());
+
+    let mut runs_left = 1000;
+    let reduced = reduce_token_trees(program.clone(), &mut runs_left, crashes);
+    assert_eq!(print(&reduced).trim(), "crash");
+    assert!(runs_left > 0);
+
+    // Requiring the call arguments keeps the group.
+    let mut runs_left = 1000;
+    let reduced = reduce_token_trees(program.clone(), &mut runs_left, |trees| {
+        crashes(trees) && print(trees).contains("crash (x )")
+    });
+    assert_eq!(print(&reduced).trim(), "crash (x )");
+
+    // The reducer stops when it runs out of runs.
+    let mut runs_left = 0;
+    assert_eq!(reduce_token_trees(program.clone(), &mut runs_left, crashes), program);
+}
+
+#[test]
+fn fuzz_ice_signature() {
+    use fuzz::ice_signature;
+
+    assert_eq!(ice_signature("error[E0308]: mismatched types\n"), None);
+    assert_eq!(
+        ice_signature(
+            "error: internal compiler error: compiler/rustc_middle/src/ty/mod.rs:12:5: \
+             no type for `x`\n"
+        )
+        .as_deref(),
+        Some("compiler/rustc_middle/src/ty/mod.rs:12:5"),
+    );
+    assert_eq!(
+        ice_signature(
+            "thread 'rustc' panicked at compiler/rustc_mir_build/src/build/mod.rs:7:9:\n\
+             assertion failed: x\n"
+        )
+        .as_deref(),
+        Some("compiler/rustc_mir_build/src/build/mod.rs:7:9"),
+    );
+    assert_eq!(
+        ice_signature("error: internal compiler error: unexpected panic\n").as_deref(),
+        Some("unexpected panic"),
+    );
+}
+
+#[test]
+fn reduce_split_header() {
+    use reduce::split_header;
+
+    let (header, body) = split_header(
+        "//@ revisions: a b\n// A comment.\n\n//~? ERROR nothing\nfn main() {} //~ ERROR x\n",
+    );
+    assert_eq!(header, "//@ revisions: a b\n// A comment.\n\n");
+    assert_eq!(body, "//~? ERROR nothing\nfn main() {} //~ ERROR x");
+
+    assert_eq!(
+        split_header("fn main() {}\n// end\n"),
+        (String::new(), "fn main() {}\n// end".into())
+    );
+    assert_eq!(split_header("//@ check-pass\n"), ("//@ check-pass\n".into(), String::new()));
+}
+
+#[test]
+fn reduce_lines() {
+    let lines: Vec<String> = (0..10).map(|i| i.to_string()).collect();
+    let keeps_3_and_7 =
+        |lines: &[String]| lines.contains(&"3".into()) && lines.contains(&"7".into());
+
+    let mut runs_left = 100;
+    assert_eq!(reduce::reduce_lines(lines.clone(), &mut runs_left, keeps_3_and_7), ["3", "7"]);
+    assert!(runs_left < 100);
+
+    // Lines that only matter together are kept together.
+    let mut runs_left = 100;
+    let reduced = reduce::reduce_lines(lines.clone(), &mut runs_left, |lines| {
+        lines.windows(2).any(|pair| pair == ["4", "5"])
+    });
+    assert_eq!(reduced, ["4", "5"]);
+
+    let mut runs_left = 0;
+    assert_eq!(reduce::reduce_lines(lines.clone(), &mut runs_left, keeps_3_and_7), lines);
+}
+
+#[test]
+fn reduce_failure_signature() {
+    use reduce::{CompilerOutput, FailureSignature};
+
+    let output = |raw: &str, rendered: &str| CompilerOutput {
+        raw: raw.to_string(),
+        rendered: rendered.to_string(),
+    };
+    let ice = output(
+        "error: internal compiler error: compiler/rustc_hir_typeck/src/lib.rs:10:5: no type\n",
+        "error: internal compiler error: compiler/rustc_hir_typeck/src/lib.rs:10:5: no type\n",
+    );
+    let mismatch = output(
+        "{\"rendered\":...}",
+        "error[E0308]: mismatched types\n  --> $DIR/a.rs:2:5\n\
+         error: expected `;`, found `}`\n\
+         warning: unused variable: `x`\n\
+         error: aborting due to 2 previous errors\n",
+    );
+
+    let location = FailureSignature::Ice("compiler/rustc_hir_typeck/src/lib.rs:10:5".into());
+    assert!(location.matches(&ice));
+    assert!(!location.matches(&mismatch));
+    assert!(
+        !FailureSignature::Ice("compiler/rustc_hir_typeck/src/lib.rs:11:5".into()).matches(&ice)
+    );
+
+    assert!(FailureSignature::Error("E0308".into()).matches(&mismatch));
+    assert!(FailureSignature::Error("expected `;`, found `}`".into()).matches(&mismatch));
+    assert!(!FailureSignature::Error("E0277".into()).matches(&mismatch));
+    assert!(
+        !FailureSignature::Error("aborting due to 2 previous errors".into()).matches(&mismatch)
+    );
+    // A crash is never the same failure as an error.
+    assert!(
+        !FailureSignature::Error(
+            "internal compiler error: compiler/rustc_hir_typeck/src/lib.rs:10:5: no type".into()
+        )
+        .matches(&ice)
+    );
+
+    let unexpected = FailureSignature::UnexpectedOutput("warning: unused variable: `x`".into());
+    assert!(unexpected.matches(&mismatch));
+    assert!(!FailureSignature::UnexpectedOutput("warning: unused".into()).matches(&mismatch));
+    assert!(!unexpected.matches(&ice));
+}
@@ -63,6 +63,7 @@ fn is_test_test() {
     assert_eq!(false, is_test(&OsString::from("a_cat.gif")));
     assert_eq!(false, is_test(&OsString::from("#a_dog_gif")));
     assert_eq!(false, is_test(&OsString::from("~a_temp_file")));
+    assert_eq!(false, is_test(&OsString::from("a_test.reduced.rs")));
 }
 
 #[test]
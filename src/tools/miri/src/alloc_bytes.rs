@@ -0,0 +1,103 @@
+use std::alloc::{self, Layout};
+use std::borrow::Cow;
+use std::hash::{Hash, Hasher};
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+use std::slice;
+
+use rustc_middle::mir::interpret::AllocBytes;
+use rustc_target::abi::{Align, Size};
+
+/// The storage of the bytes of an allocation. Unlike a `Box<[u8]>`, this is aligned like the
+/// allocation it belongs to, and never empty, so that its host address can be used as the
+/// address of the allocation when native code gets to access it (see `intptrcast`).
+#[derive(Debug)]
+pub struct MiriAllocBytes {
+    /// The layout used for the host allocation. Its size is `max(len, 1)`.
+    layout: Layout,
+    /// The number of bytes of the interpreter allocation, which may be 0.
+    len: usize,
+    ptr: NonNull<u8>,
+}
+
+impl MiriAllocBytes {
+    /// Allocates zeroed host memory for `size` bytes aligned to `align`, or returns `None` if the
+    /// host is out of memory.
+    fn alloc_zeroed(size: Size, align: Align) -> Option<Self> {
+        let len = size.bytes_usize();
+        // Zero-sized allocations still need a unique address.
+        let layout = Layout::from_size_align(len.max(1), align.bytes_usize()).ok()?;
+        // SAFETY: `layout` has a non-zero size.
+        let ptr = NonNull::new(unsafe { alloc::alloc_zeroed(layout) })?;
+        Some(MiriAllocBytes { layout, len, ptr })
+    }
+
+    /// The alignment of the host memory.
+    pub fn align(&self) -> Align {
+        Align::from_bytes(self.layout.align().try_into().unwrap()).unwrap()
+    }
+}
+
+impl Drop for MiriAllocBytes {
+    fn drop(&mut self) {
+        // SAFETY: `ptr` was allocated with `layout` and is not used any more.
+        unsafe { alloc::dealloc(self.ptr.as_ptr(), self.layout) }
+    }
+}
+
+impl Clone for MiriAllocBytes {
+    fn clone(&self) -> Self {
+        let mut bytes = MiriAllocBytes::alloc_zeroed(Size::from_bytes(self.len), self.align())
+            .unwrap_or_else(|| alloc::handle_alloc_error(self.layout));
+        bytes.copy_from_slice(self);
+        bytes
+    }
+}
+
+impl Deref for MiriAllocBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: `ptr` points to at least `len` initialized bytes that we own.
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl DerefMut for MiriAllocBytes {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        // SAFETY: `ptr` points to at least `len` initialized bytes that we own.
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl PartialEq for MiriAllocBytes {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for MiriAllocBytes {}
+
+impl Hash for MiriAllocBytes {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl AllocBytes for MiriAllocBytes {
+    fn from_bytes<'a>(slice: impl Into<Cow<'a, [u8]>>, align: Align) -> Self {
+        let slice = slice.into();
+        let mut bytes = MiriAllocBytes::alloc_zeroed(Size::from_bytes(slice.len()), align)
+            .unwrap_or_else(|| {
+                alloc::handle_alloc_error(
+                    Layout::from_size_align(slice.len(), align.bytes_usize()).unwrap(),
+                )
+            });
+        bytes.copy_from_slice(&slice);
+        bytes
+    }
+
+    fn zeroed(size: Size, align: Align) -> Option<Self> {
+        MiriAllocBytes::alloc_zeroed(size, align)
+    }
+}
@@ -618,6 +618,29 @@ fn main() {
             };
 
             miri_config.page_size = Some(page_size);
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-record=") {
+            if miri_config.replay_trace.is_some() {
+                show_error!("-Zmiri-record cannot be used along with -Zmiri-replay");
+            }
+            miri_config.record_trace = Some(param.into());
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-replay=") {
+            if miri_config.record_trace.is_some() {
+                show_error!("-Zmiri-replay cannot be used along with -Zmiri-record");
+            }
+            if !std::path::Path::new(param).exists() {
+                show_error!("-Zmiri-replay `{}` does not exist", param);
+            }
+            miri_config.replay_trace = Some(param.into());
+        } else if arg == "-Zmiri-explore" {
+            miri_config.explore = Some(2);
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-explore=") {
+            let bound = match param.parse::<u32>() {
+                Ok(i) => i,
+                Err(err) => show_error!("-Zmiri-explore requires a `u32`: {}", err),
+            };
+            miri_config.explore = Some(bound);
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-coverage=") {
+            miri_config.coverage = Some(param.into());
         } else {
             // Forward to rustc.
             rustc_args.push(arg);
@@ -632,6 +655,12 @@ fn main() {
         );
     }
 
+    if miri_config.explore.is_some() && miri_config.replay_trace.is_some() {
+        show_error!(
+            "-Zmiri-explore cannot be used along with -Zmiri-replay, which replays explored executions on its own"
+        );
+    }
+
     debug!("rustc arguments: {:?}", rustc_args);
     debug!("crate arguments: {:?}", miri_config.args);
     run_compiler(
@@ -1,5 +1,8 @@
 use std::cell::Cell;
-use std::time::{Duration, Instant as StdInstant};
+use std::rc::Rc;
+use std::time::{Duration, Instant as StdInstant, SystemTime};
+
+use crate::Trace;
 
 /// When using a virtual clock, this defines how many nanoseconds we pretend are passing for each
 /// basic block.
@@ -49,6 +52,8 @@ pub fn duration_since(&self, earlier: Instant) -> Duration {
 #[derive(Debug)]
 pub struct Clock {
     kind: ClockKind,
+    /// Readings of the host's clocks are recorded to, or replayed from, this trace.
+    trace: Rc<Trace>,
 }
 
 #[derive(Debug)]
@@ -65,14 +70,14 @@ enum ClockKind {
 
 impl Clock {
     /// Create a new clock based on the availability of communication with the host.
-    pub fn new(communicate: bool) -> Self {
+    pub fn new(communicate: bool, trace: Rc<Trace>) -> Self {
         let kind = if communicate {
             ClockKind::Host { time_anchor: StdInstant::now() }
         } else {
             ClockKind::Virtual { nanoseconds: 0.into() }
         };
 
-        Self { kind }
+        Self { kind, trace }
     }
 
     /// Let the time pass for a small interval.
@@ -90,7 +95,11 @@ pub fn tick(&self) {
     /// Sleep for the desired duration.
     pub fn sleep(&self, duration: Duration) {
         match &self.kind {
-            ClockKind::Host { .. } => std::thread::sleep(duration),
+            ClockKind::Host { .. } =>
+                if !self.trace.is_replaying() {
+                    // When replaying, the time after waking up is taken from the trace.
+                    std::thread::sleep(duration)
+                },
             ClockKind::Virtual { nanoseconds } => {
                 // Just pretend that we have slept for some time.
                 let nanos: u64 = duration.as_nanos().try_into().unwrap();
@@ -109,9 +118,20 @@ pub fn anchor(&self) -> Instant {
 
     pub fn now(&self) -> Instant {
         match &self.kind {
-            ClockKind::Host { .. } => Instant { kind: InstantKind::Host(StdInstant::now()) },
+            ClockKind::Host { time_anchor } => {
+                let elapsed = self.trace.time(|| time_anchor.elapsed());
+                Instant { kind: InstantKind::Host(*time_anchor + elapsed) }
+            }
             ClockKind::Virtual { nanoseconds } =>
                 Instant { kind: InstantKind::Virtual { nanoseconds: nanoseconds.get() } },
         }
     }
+
+    /// Return the current time of the host's system clock.
+    pub fn system_now(&self) -> SystemTime {
+        let since_epoch = self.trace.system_time(|| {
+            SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default()
+        });
+        SystemTime::UNIX_EPOCH + since_epoch
+    }
 }
@@ -692,7 +692,6 @@ fn atomic_compare_exchange_scalar(
         fail: AtomicReadOrd,
         can_fail_spuriously: bool,
     ) -> InterpResult<'tcx, Immediate<Provenance>> {
-        use rand::Rng as _;
         let this = self.eval_context_mut();
         this.atomic_access_check(place, AtomicAccessType::Rmw)?;
 
@@ -708,7 +707,7 @@ fn atomic_compare_exchange_scalar(
         let success_rate = 1.0 - this.machine.cmpxchg_weak_failure_rate;
         let cmpxchg_success = eq.to_scalar().to_bool()?
             && if can_fail_spuriously {
-                this.machine.rng.get_mut().gen_bool(success_rate)
+                this.machine.rng.get_mut().compare_exchange_weak_succeeds(success_rate)
             } else {
                 true
             };
@@ -737,6 +736,7 @@ fn atomic_compare_exchange_scalar(
     /// Update the data-race detector for an atomic fence on the current thread.
     fn atomic_fence(&mut self, atomic: AtomicFenceOrd) -> InterpResult<'tcx> {
         let this = self.eval_context_mut();
+        this.exploration_visible_operation();
         let current_span = this.machine.current_span();
         if let Some(data_race) = &mut this.machine.data_race {
             data_race.maybe_perform_sync_operation(
@@ -1171,6 +1171,7 @@ fn atomic_access_check(
                 }
             }
         }
+        this.exploration_visible_operation();
         Ok(())
     }
 
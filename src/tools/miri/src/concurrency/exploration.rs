@@ -0,0 +1,267 @@
+//! Systematic exploration of the nondeterministic choices of a concurrent program.
+//!
+//! Normally Miri makes every nondeterministic choice (which thread to run, which store a weak
+//! memory load reads from, whether a `compare_exchange_weak` fails spuriously) at random, so a
+//! single run only covers a single execution. With `-Zmiri-explore`, the program is run over and
+//! over again instead, and every run makes a different sequence of choices, until all executions
+//! have been covered or one of them fails.
+//!
+//! The executions are enumerated by a depth-first search: every run records the choices it made
+//! and how many alternatives there were, and the next run makes the same choices up to the last
+//! one that still has an alternative left, then takes that alternative. After that, the first
+//! alternative is always taken, which means not preempting the active thread and reading from
+//! the latest store.
+//!
+//! To keep the number of executions manageable, two standard techniques are used:
+//! - Bounded preemption: executions where the active thread is preempted (or a
+//!   `compare_exchange_weak` fails spuriously) more than a fixed number of times are not explored.
+//!   Switching threads because the active one blocked or terminated does not count.
+//! - Partial-order reduction: a thread is only preempted right after an operation that another
+//!   thread can observe, i.e. an atomic access or fence, or an operation on one of Miri's
+//!   synchronization primitives (mutexes, reader-writer locks, condition variables and futexes).
+//!   All other steps only touch memory that is not shared (or is shared in a racy way, which the
+//!   data race detector reports no matter which order the accesses happen in), so the order in
+//!   which threads execute them does not matter. Furthermore, only the atomic operations of the
+//!   crates being tested count: the ones in the standard library are assumed to be correct.
+//!   Instead, returning from a function of the `sync` module of `core`, `alloc` or `std` (which
+//!   includes the atomic types, `Arc` and `Mutex`) to the code being tested counts, since e.g.
+//!   locking a `Mutex` that nobody else holds does not involve any synchronization primitive.
+//!
+//! Every execution is checked for leaks, so a leak that only happens in some interleavings is
+//! found as well.
+//!
+//! The non-default choices of an execution that failed are reported, and when recording with
+//! `-Zmiri-record`, they are written to the trace, so that `-Zmiri-replay` can run exactly that
+//! execution again.
+
+use rustc_hir::definitions::DefPathData;
+use rustc_middle::ty;
+use rustc_span::sym;
+
+use crate::*;
+
+/// The kinds of nondeterministic choices that are explored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Choice {
+    /// Whether to keep running the active thread after an atomic operation, or which other thread
+    /// to preempt it for.
+    Preemption,
+    /// Which thread to run after the active thread blocked, yielded or terminated.
+    Schedule,
+    /// Which store a weak memory load reads from.
+    WeakLoad,
+    /// Whether a `compare_exchange_weak` that could succeed fails spuriously.
+    SpuriousFailure,
+}
+
+impl Choice {
+    /// Whether taking any alternative but the first counts against the preemption bound.
+    fn is_bounded(self) -> bool {
+        matches!(self, Choice::Preemption | Choice::SpuriousFailure)
+    }
+}
+
+#[derive(Debug)]
+struct ChoicePoint {
+    kind: Choice,
+    /// The number of alternatives that were available here.
+    alternatives: usize,
+    /// The alternative that was taken.
+    chosen: usize,
+    /// What taking that alternative meant, if it was not the default one.
+    description: Option<String>,
+}
+
+/// The state of the exploration while executing a single run.
+#[derive(Debug)]
+pub struct Exploration {
+    /// The maximal number of preemptions per execution.
+    preemption_bound: u32,
+    /// The number of runs before this one.
+    runs: u64,
+    /// The alternatives to take at the first choice points of this run.
+    prefix: Vec<usize>,
+    /// The choice points this run has passed so far.
+    trail: Vec<ChoicePoint>,
+    /// The number of preemptions this run has made so far.
+    preemptions: u32,
+    /// Whether the active thread executed an operation that other threads can observe since the
+    /// last preemption point.
+    visible_operation: bool,
+    /// Whether this run replays an execution from a trace, so there is nothing to backtrack to.
+    replaying: bool,
+}
+
+impl Exploration {
+    pub fn new(preemption_bound: u32) -> Self {
+        Exploration {
+            preemption_bound,
+            runs: 0,
+            prefix: Vec::new(),
+            trail: Vec::new(),
+            preemptions: 0,
+            visible_operation: false,
+            replaying: false,
+        }
+    }
+
+    /// Create the state for replaying a single execution that was explored with the given bound.
+    /// The choices are taken from the trace.
+    pub fn replay(preemption_bound: u32) -> Self {
+        Exploration { replaying: true, ..Exploration::new(preemption_bound) }
+    }
+
+    pub fn preemption_bound(&self) -> u32 {
+        self.preemption_bound
+    }
+
+    /// The number of choice points this run has passed so far.
+    pub fn choice_points(&self) -> usize {
+        self.trail.len()
+    }
+
+    /// Pick one out of `alternatives` choices. The first alternative is the default one. If
+    /// `replayed` is given, that alternative is taken instead of the one the search dictates.
+    /// `describe` explains what a non-default alternative means, for reporting the choices of a
+    /// failing execution.
+    pub fn choose(
+        &mut self,
+        kind: Choice,
+        alternatives: usize,
+        replayed: Option<usize>,
+        describe: impl FnOnce(usize) -> String,
+    ) -> usize {
+        assert!(alternatives > 0, "nothing to choose from");
+        // Once we ran out of preemptions, there is nothing to backtrack to at this point anymore.
+        let alternatives = if kind.is_bounded() && self.preemptions >= self.preemption_bound {
+            1
+        } else {
+            alternatives
+        };
+        // Miri is deterministic, so we see the same alternatives as the run we took the prefix
+        // from. Clamping is just for robustness.
+        let chosen = replayed
+            .or_else(|| self.prefix.get(self.trail.len()).copied())
+            .map_or(0, |chosen| chosen.min(alternatives - 1));
+        if kind.is_bounded() && chosen > 0 {
+            self.preemptions += 1;
+        }
+        let description = (chosen > 0).then(|| describe(chosen));
+        self.trail.push(ChoicePoint { kind, alternatives, chosen, description });
+        chosen
+    }
+
+    /// Note that the active thread executed an operation that other threads can observe.
+    pub fn visible_operation(&mut self) {
+        self.visible_operation = true;
+    }
+
+    /// Returns whether the active thread executed an operation that other threads can observe
+    /// since the last call, and should therefore be considered for preemption.
+    pub fn take_visible_operation(&mut self) -> bool {
+        std::mem::take(&mut self.visible_operation)
+    }
+
+    /// The number of runs so far, including this one.
+    pub fn runs(&self) -> u64 {
+        self.runs + 1
+    }
+
+    /// Set up the next run after this one finished successfully, or return `None` if all
+    /// executions have been explored.
+    pub fn next_run(mut self) -> Option<Exploration> {
+        if self.replaying {
+            return None;
+        }
+        while let Some(point) = self.trail.pop() {
+            if point.chosen + 1 < point.alternatives {
+                let mut prefix: Vec<usize> = self.trail.iter().map(|point| point.chosen).collect();
+                prefix.push(point.chosen + 1);
+                return Some(Exploration {
+                    runs: self.runs + 1,
+                    prefix,
+                    ..Exploration::new(self.preemption_bound)
+                });
+            }
+        }
+        None
+    }
+
+    /// Describe the choices this run made that differ from the default ones, one per line, in
+    /// the order in which they were made.
+    pub fn describe_choices(&self) -> String {
+        let choices = self
+            .trail
+            .iter()
+            .enumerate()
+            .filter_map(|(index, point)| {
+                let description = point.description.as_ref()?;
+                Some(format!("  choice {index}: {description}"))
+            })
+            .collect::<Vec<_>>();
+        if choices.is_empty() {
+            "  no preemptions, always reading the latest store".to_string()
+        } else {
+            choices.join("\n")
+        }
+    }
+}
+
+impl<'mir, 'tcx: 'mir> EvalContextExt<'mir, 'tcx> for crate::MiriInterpCx<'mir, 'tcx> {}
+pub trait EvalContextExt<'mir, 'tcx: 'mir>: crate::MiriInterpCxExt<'mir, 'tcx> {
+    /// Called on every atomic operation. If it was executed by the code being tested, the active
+    /// thread may be preempted at the next preemption point.
+    fn exploration_visible_operation(&self) {
+        let this = self.eval_context_ref();
+        let mut rng = this.machine.rng.borrow_mut();
+        let Some(exploration) = rng.exploration() else { return };
+        if this.exploration_caller_is_user_relevant() {
+            exploration.visible_operation();
+        }
+    }
+
+    /// Called on every operation on a mutex, reader-writer lock, condition variable or futex that
+    /// another thread can observe. The active thread may be preempted at the next preemption
+    /// point, no matter who executed the operation.
+    fn exploration_sync_operation(&self) {
+        let this = self.eval_context_ref();
+        if let Some(exploration) = this.machine.rng.borrow_mut().exploration() {
+            exploration.visible_operation();
+        }
+    }
+
+    /// Called whenever `callee` returns. Returning from a function of the `sync` module of
+    /// `core`, `alloc` or `std` to the code being tested counts as an operation other threads can
+    /// observe, since the synchronization primitives of the standard library do not always
+    /// involve Miri's (e.g. when a `Mutex` is not contended), and their atomic operations do not
+    /// count.
+    fn exploration_returned_from(&self, callee: ty::Instance<'tcx>) {
+        let this = self.eval_context_ref();
+        let mut rng = this.machine.rng.borrow_mut();
+        let Some(exploration) = rng.exploration() else { return };
+        let def_id = callee.def_id();
+        if ![sym::core, sym::alloc, sym::std].contains(&this.tcx.crate_name(def_id.krate)) {
+            return;
+        }
+        let in_sync = this
+            .tcx
+            .def_path(def_id)
+            .data
+            .first()
+            .is_some_and(|data| data.data == DefPathData::TypeNs(sym::sync));
+        if in_sync && this.exploration_caller_is_user_relevant() {
+            exploration.visible_operation();
+        }
+    }
+
+    /// Whether the innermost frame of the active thread outside of `core` (which contains the
+    /// atomic wrappers and drop glue) belongs to the code being tested.
+    fn exploration_caller_is_user_relevant(&self) -> bool {
+        let this = self.eval_context_ref();
+        this.active_thread_stack()
+            .iter()
+            .rev()
+            .find(|frame| this.tcx.crate_name(frame.instance.def_id().krate) != sym::core)
+            .is_some_and(|frame| frame.extra.is_user_relevant)
+    }
+}
@@ -1,8 +1,9 @@
 pub mod data_race;
+pub mod exploration;
 mod range_object_map;
 #[macro_use]
 pub mod sync;
 pub mod init_once;
 pub mod thread;
-mod vector_clock;
+pub mod vector_clock;
 pub mod weak_memory;
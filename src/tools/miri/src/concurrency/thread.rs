@@ -298,7 +298,7 @@ fn get_wait_time(&self, clock: &Clock) -> Duration {
         match self {
             Time::Monotonic(instant) => instant.duration_since(clock.now()),
             Time::RealTime(time) =>
-                time.duration_since(SystemTime::now()).unwrap_or(Duration::new(0, 0)),
+                time.duration_since(clock.system_now()).unwrap_or(Duration::new(0, 0)),
         }
     }
 }
@@ -337,6 +337,8 @@ pub struct ThreadManager<'mir, 'tcx> {
     thread_local_alloc_ids: RefCell<FxHashMap<(DefId, ThreadId), Pointer<Provenance>>>,
     /// A flag that indicates that we should change the active thread.
     yield_active_thread: bool,
+    /// The thread to switch to when the active thread yields, if it was chosen already.
+    next_thread: Option<ThreadId>,
     /// Callbacks that are called once the specified time passes.
     timeout_callbacks: FxHashMap<ThreadId, TimeoutCallbackInfo<'mir, 'tcx>>,
 }
@@ -349,6 +351,7 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
             timeout_callbacks,
             active_thread: _,
             yield_active_thread: _,
+            next_thread: _,
             sync,
         } = self;
 
@@ -376,6 +379,7 @@ fn default() -> Self {
             sync: SynchronizationState::default(),
             thread_local_alloc_ids: Default::default(),
             yield_active_thread: false,
+            next_thread: None,
             timeout_callbacks: FxHashMap::default(),
         }
     }
@@ -599,6 +603,27 @@ fn yield_active_thread(&mut self) {
         self.yield_active_thread = true;
     }
 
+    /// Change the active thread to the given thread, which must be enabled.
+    fn yield_to(&mut self, thread: ThreadId) {
+        self.yield_active_thread = true;
+        self.next_thread = Some(thread);
+    }
+
+    /// The enabled threads other than the active one, in the order in which the scheduler
+    /// considers them.
+    fn other_enabled_threads(&self) -> Vec<ThreadId> {
+        // `skip(N)` means we start iterating at thread N, so we skip 1 more to start just *after*
+        // the active thread. Then after that we look at `take(N)`, i.e., the threads *before* the
+        // active thread.
+        self.threads
+            .iter_enumerated()
+            .skip(self.active_thread.index() + 1)
+            .chain(self.threads.iter_enumerated().take(self.active_thread.index()))
+            .filter(|(_, thread)| thread.state == ThreadState::Enabled)
+            .map(|(id, _)| id)
+            .collect()
+    }
+
     /// Register the given `callback` to be called once the `call_time` passes.
     ///
     /// The callback will be called with `thread` being the active thread, and
@@ -688,7 +713,11 @@ fn thread_terminated(
     /// used in stateless model checkers such as Loom: run the active thread as
     /// long as we can and switch only when we have to (the active thread was
     /// blocked, terminated, or has explicitly asked to be preempted).
-    fn schedule(&mut self, clock: &Clock) -> InterpResult<'tcx, SchedulingAction> {
+    fn schedule(
+        &mut self,
+        clock: &Clock,
+        rng: &mut MiriRng,
+    ) -> InterpResult<'tcx, SchedulingAction> {
         // This thread and the program can keep going.
         if self.threads[self.active_thread].state == ThreadState::Enabled
             && !self.yield_active_thread
@@ -711,21 +740,22 @@ fn schedule(&mut self, clock: &Clock) -> InterpResult<'tcx, SchedulingAction> {
         // The active thread blocked or yielded. So we go search for another enabled thread.
         // Crucially, we start searching at the current active thread ID, rather than at 0, since we
         // want to avoid always scheduling threads 0 and 1 without ever making progress in thread 2.
-        //
-        // `skip(N)` means we start iterating at thread N, so we skip 1 more to start just *after*
-        // the active thread. Then after that we look at `take(N)`, i.e., the threads *before* the
-        // active thread.
-        let threads = self
-            .threads
-            .iter_enumerated()
-            .skip(self.active_thread.index() + 1)
-            .chain(self.threads.iter_enumerated().take(self.active_thread.index()));
-        for (id, thread) in threads {
-            debug_assert_ne!(self.active_thread, id);
-            if thread.state == ThreadState::Enabled {
-                self.active_thread = id;
-                break;
-            }
+        let threads = self.other_enabled_threads();
+        if let Some(next_thread) = self.next_thread.take() {
+            debug_assert!(threads.contains(&next_thread));
+            self.active_thread = next_thread;
+        } else if !threads.is_empty()
+            && let Some(chosen) = rng.explore_choose(Choice::Schedule, threads.len(), |chosen| {
+                format!(
+                    "thread {} ran next instead of thread {}",
+                    threads[chosen].to_u32(),
+                    threads[0].to_u32()
+                )
+            })
+        {
+            self.active_thread = threads[chosen];
+        } else if let Some(&next_thread) = threads.first() {
+            self.active_thread = next_thread;
         }
         self.yield_active_thread = false;
         if self.threads[self.active_thread].state == ThreadState::Enabled {
@@ -1005,10 +1035,39 @@ fn yield_active_thread(&mut self) {
 
     #[inline]
     fn maybe_preempt_active_thread(&mut self) {
-        use rand::Rng as _;
-
         let this = self.eval_context_mut();
-        if this.machine.rng.get_mut().gen_bool(this.machine.preemption_rate) {
+        let rng = this.machine.rng.get_mut();
+        if let Some(exploration) = rng.exploration() {
+            // Only preempt after operations that other threads can observe, since the order in
+            // which threads execute all other operations does not matter.
+            if !exploration.take_visible_operation() {
+                return;
+            }
+            let threads = this.machine.threads.other_enabled_threads();
+            if threads.is_empty() {
+                return;
+            }
+            let active = this.get_active_thread();
+            let span = this.machine.current_span();
+            let source_map = this.tcx.sess.source_map();
+            // Not preempting the active thread is the first alternative.
+            let choice = this
+                .machine
+                .rng
+                .get_mut()
+                .explore_choose(Choice::Preemption, threads.len() + 1, |choice| {
+                    format!(
+                        "thread {} was preempted at {}, and thread {} ran instead",
+                        active.to_u32(),
+                        source_map.span_to_embeddable_string(span),
+                        threads[choice - 1].to_u32()
+                    )
+                })
+                .unwrap();
+            if choice > 0 {
+                this.machine.threads.yield_to(threads[choice - 1]);
+            }
+        } else if rng.preempt(this.machine.preemption_rate) {
             this.yield_active_thread();
         }
     }
@@ -1054,7 +1113,7 @@ fn run_threads(&mut self) -> InterpResult<'tcx, !> {
                 this.machine.handle_abnormal_termination();
                 std::process::exit(1);
             }
-            match this.machine.threads.schedule(&this.machine.clock)? {
+            match this.machine.threads.schedule(&this.machine.clock, this.machine.rng.get_mut())? {
                 SchedulingAction::ExecuteStep => {
                     if !this.step()? {
                         // See if this thread can do something else.
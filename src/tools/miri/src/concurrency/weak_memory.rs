@@ -280,7 +280,7 @@ fn buffered_read(
         global: &DataRaceState,
         thread_mgr: &ThreadManager<'_, '_>,
         is_seqcst: bool,
-        rng: &mut (impl rand::Rng + ?Sized),
+        rng: &mut MiriRng,
         validate: impl FnOnce() -> InterpResult<'tcx>,
     ) -> InterpResult<'tcx, (Scalar<Provenance>, LoadRecency)> {
         // Having a live borrow to store_buffer while calling validate_atomic_load is fine
@@ -291,7 +291,7 @@ fn buffered_read(
             // as the race detector will update it
             let (.., clocks) = global.current_thread_state(thread_mgr);
             // Load from a valid entry in the store buffer
-            self.fetch_store(is_seqcst, &clocks, &mut *rng)
+            self.fetch_store(is_seqcst, &clocks, rng)
         };
 
         // Unlike in buffered_atomic_write, thread clock updates have to be done
@@ -320,13 +320,12 @@ fn buffered_write(
 
     #[allow(clippy::if_same_then_else, clippy::needless_bool)]
     /// Selects a valid store element in the buffer.
-    fn fetch_store<R: rand::Rng + ?Sized>(
+    fn fetch_store(
         &self,
         is_seqcst: bool,
         clocks: &ThreadClockSet,
-        rng: &mut R,
+        rng: &mut MiriRng,
     ) -> (&StoreElement, LoadRecency) {
-        use rand::seq::IteratorRandom;
         let mut found_sc = false;
         // FIXME: we want an inclusive take_while (stops after a false predicate, but
         // includes the element that gave the false), but such function doesn't yet
@@ -337,7 +336,8 @@ fn fetch_store<R: rand::Rng + ?Sized>(
             .buffer
             .iter()
             .rev()
-            .take_while(move |&store_elem| {
+            .enumerate()
+            .take_while(move |&(_, store_elem)| {
                 if !keep_searching {
                     return false;
                 }
@@ -385,7 +385,7 @@ fn fetch_store<R: rand::Rng + ?Sized>(
 
                 true
             })
-            .filter(|&store_elem| {
+            .filter(|&(_, store_elem)| {
                 if is_seqcst && store_elem.is_seqcst {
                     // An SC load needs to ignore all but last store maked SC (stores not marked SC are not
                     // affected)
@@ -395,14 +395,14 @@ fn fetch_store<R: rand::Rng + ?Sized>(
                 } else {
                     true
                 }
-            });
+            })
+            // The choice is made in terms of how far behind the latest store the chosen one is, so
+            // that it can be recorded and replayed.
+            .map(|(offset, _)| offset);
 
-        let chosen = candidates.choose(rng).expect("store buffer cannot be empty");
-        if std::ptr::eq(chosen, self.buffer.back().expect("store buffer cannot be empty")) {
-            (chosen, LoadRecency::Latest)
-        } else {
-            (chosen, LoadRecency::Outdated)
-        }
+        let offset = rng.weak_load(self.buffer.len(), candidates);
+        let chosen = &self.buffer[self.buffer.len() - 1 - offset];
+        if offset == 0 { (chosen, LoadRecency::Latest) } else { (chosen, LoadRecency::Outdated) }
     }
 
     /// ATOMIC STORE IMPL in the paper (except we don't need the location's vector clock)
@@ -0,0 +1,252 @@
+//! Collecting a coverage report of the code the interpreted program executed.
+//!
+//! With `-Zmiri-coverage`, Miri counts how often each basic block of the functions in local
+//! crates was executed, and how often control flow went from one basic block to another. At the
+//! end of the execution, these counts are mapped to source lines and written out as text in the
+//! format of `llvm-cov show -show-branches=count`, which is also what the `.coverage` files of
+//! rustc's `tests/coverage` suite contain, so the reports can be compared with the coverage of
+//! natively instrumented builds.
+//!
+//! A line counts as executed as often as the most frequently executed basic block that has a
+//! statement on that line. Every `SwitchInt` terminator on a `bool` (i.e. `if` and loop
+//! conditions) is reported as a branch, with how often the true and the false side were taken.
+//! Cleanup blocks are ignored, since they only run when unwinding and would otherwise show up as
+//! uncovered code all over the place.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::{DefId, LOCAL_CRATE};
+use rustc_middle::mir;
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_span::{FileName, Span};
+
+use crate::*;
+
+#[derive(Debug, Default)]
+struct FunctionCoverage {
+    /// How often each basic block was executed.
+    blocks: FxHashMap<mir::BasicBlock, u64>,
+    /// How often control flow went from the first basic block to the second one.
+    edges: FxHashMap<(mir::BasicBlock, mir::BasicBlock), u64>,
+}
+
+/// The coverage collected so far.
+#[derive(Debug)]
+pub struct Coverage {
+    /// The directory to write the report to.
+    dir: PathBuf,
+    functions: FxHashMap<DefId, FunctionCoverage>,
+}
+
+/// The part of the report that belongs to a single source file.
+#[derive(Default)]
+struct FileReport {
+    /// Execution count of every line that has code on it.
+    lines: BTreeMap<usize, u64>,
+    /// Column and how often the true and the false side were taken, for every branch on a line.
+    branches: BTreeMap<usize, Vec<(usize, u64, u64)>>,
+}
+
+impl Coverage {
+    pub fn new(dir: PathBuf) -> Self {
+        Coverage { dir, functions: FxHashMap::default() }
+    }
+
+    /// Record that `block` of the body of `def_id` was executed, after `pred`.
+    fn record_block(
+        &mut self,
+        def_id: DefId,
+        block: mir::BasicBlock,
+        pred: Option<mir::BasicBlock>,
+    ) {
+        let function = self.functions.entry(def_id).or_default();
+        *function.blocks.entry(block).or_default() += 1;
+        if let Some(pred) = pred {
+            *function.edges.entry((pred, block)).or_default() += 1;
+        }
+    }
+
+    /// Write the report for the current crate to a new file in the coverage directory. All
+    /// functions of the current crate are included, whether they were executed or not; functions
+    /// of other local crates only if they were executed.
+    pub fn write(&self, tcx: TyCtxt<'_>) -> io::Result<PathBuf> {
+        let mut def_ids: Vec<DefId> = tcx
+            .mir_keys(())
+            .iter()
+            .map(|def_id| def_id.to_def_id())
+            .filter(|&def_id| {
+                matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn | DefKind::Closure)
+                    && tcx.is_mir_available(def_id)
+            })
+            .chain(self.functions.keys().copied())
+            .collect();
+        def_ids.sort_by_key(|&def_id| (tcx.def_span(def_id), def_id));
+        def_ids.dedup();
+
+        let empty = FunctionCoverage::default();
+        let mut files: BTreeMap<String, FileReport> = BTreeMap::new();
+        for def_id in def_ids {
+            let function = self.functions.get(&def_id).unwrap_or(&empty);
+            add_function(tcx, def_id, function, &mut files);
+        }
+
+        let mut out = String::new();
+        for (file, report) in &files {
+            // The source is only used for display, so a file that cannot be read anymore still
+            // gets its counts reported.
+            let source = fs::read_to_string(file).unwrap_or_default();
+            report.write(file, &source, &mut out).unwrap();
+        }
+        fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!(
+            "{}-{}.coverage",
+            tcx.crate_name(LOCAL_CRATE),
+            std::process::id()
+        ));
+        fs::write(&path, out)?;
+        Ok(path)
+    }
+}
+
+/// Find the file, line and column (both starting at 1) a span begins at. Code from macro
+/// expansions is attributed to the macro invocation.
+fn source_pos(tcx: TyCtxt<'_>, span: Span) -> Option<(String, usize, usize)> {
+    let span = span.source_callsite();
+    if span.is_dummy() {
+        return None;
+    }
+    let loc = tcx.sess.source_map().lookup_char_pos(span.lo());
+    let FileName::Real(name) = &loc.file.name else { return None };
+    Some((name.local_path_if_available().display().to_string(), loc.line, loc.col.0 + 1))
+}
+
+fn add_function(
+    tcx: TyCtxt<'_>,
+    def_id: DefId,
+    function: &FunctionCoverage,
+    files: &mut BTreeMap<String, FileReport>,
+) {
+    let body = tcx.optimized_mir(def_id);
+    let count = |block| function.blocks.get(&block).copied().unwrap_or(0);
+
+    for (block, data) in body.basic_blocks.iter_enumerated() {
+        if data.is_cleanup {
+            continue;
+        }
+        let executed = count(block);
+        let spans = data
+            .statements
+            .iter()
+            .filter(|stmt| {
+                !matches!(
+                    stmt.kind,
+                    mir::StatementKind::StorageLive(_)
+                        | mir::StatementKind::StorageDead(_)
+                        | mir::StatementKind::Nop
+                )
+            })
+            .map(|stmt| stmt.source_info.span)
+            .chain(std::iter::once(data.terminator().source_info.span));
+        for span in spans {
+            let Some((file, line, _)) = source_pos(tcx, span) else { continue };
+            let lines = &mut files.entry(file).or_default().lines;
+            let line_count = lines.entry(line).or_default();
+            *line_count = (*line_count).max(executed);
+        }
+
+        let mir::TerminatorKind::SwitchInt { discr, targets } = &data.terminator().kind else {
+            continue;
+        };
+        if !discr.ty(body, tcx).is_bool() {
+            continue;
+        }
+        let Some((file, line, col)) = source_pos(tcx, data.terminator().source_info.span) else {
+            continue;
+        };
+        let taken = |value: u128| {
+            let target = targets
+                .iter()
+                .find(|&(v, _)| v == value)
+                .map_or(targets.otherwise(), |(_, target)| target);
+            function.edges.get(&(block, target)).copied().unwrap_or(0)
+        };
+        let branches = files.entry(file).or_default().branches.entry(line).or_default();
+        branches.push((col, taken(1), taken(0)));
+    }
+}
+
+/// Format an execution count the way `llvm-cov` does: with at most three significant digits and
+/// a metric suffix for large counts.
+fn format_count(count: u64) -> String {
+    let digits = count.to_string();
+    if digits.len() <= 3 {
+        return digits;
+    }
+    let int_len = match digits.len() % 3 {
+        0 => 3,
+        n => n,
+    };
+    let mut result = digits[..int_len].to_string();
+    if int_len != 3 {
+        result.push('.');
+        result.push_str(&digits[int_len..3]);
+    }
+    result.push(" kMGTPEZY".as_bytes()[(digits.len() - 1) / 3] as char);
+    result
+}
+
+impl FileReport {
+    /// Write the report in the format of `llvm-cov show`: every source line with its execution
+    /// count (empty for lines without code), followed by the branches that start on it.
+    fn write(&self, file: &str, source: &str, out: &mut String) -> std::fmt::Result {
+        writeln!(out, "{file}:")?;
+        let last_line = self.lines.keys().chain(self.branches.keys()).max().copied().unwrap_or(0);
+        let mut source_lines = source.lines();
+        for line in 1.. {
+            let text = source_lines.next();
+            if text.is_none() && line > last_line {
+                break;
+            }
+            let count = self.lines.get(&line).map(|&count| format_count(count));
+            writeln!(out, "{line:>5}|{:>7}|{}", count.unwrap_or_default(), text.unwrap_or(""))?;
+            if let Some(branches) = self.branches.get(&line) {
+                writeln!(out, "  ------------------")?;
+                for (col, taken, not_taken) in branches {
+                    writeln!(
+                        out,
+                        "  |  Branch ({line}:{col}): [True: {}, False: {}]",
+                        format_count(*taken),
+                        format_count(*not_taken),
+                    )?;
+                }
+                writeln!(out, "  ------------------")?;
+            }
+        }
+        writeln!(out)
+    }
+}
+
+impl<'mir, 'tcx: 'mir> EvalContextExt<'mir, 'tcx> for crate::MiriInterpCx<'mir, 'tcx> {}
+pub trait EvalContextExt<'mir, 'tcx: 'mir>: crate::MiriInterpCxExt<'mir, 'tcx> {
+    /// Called before every terminator, to record that its basic block was executed.
+    fn record_coverage(&mut self) {
+        let this = self.eval_context_mut();
+        if this.machine.coverage.is_none() {
+            return;
+        }
+        let frame = this.frame_mut();
+        let block = frame.loc.left().expect("executing a terminator without a location").block;
+        let pred = frame.extra.coverage_last_block.replace(block);
+        let ty::InstanceDef::Item(def_id) = frame.instance.def else { return };
+        if !this.machine.local_crates.contains(&def_id.krate) {
+            return;
+        }
+        this.machine.coverage.as_mut().unwrap().record_block(def_id, block, pred);
+    }
+}
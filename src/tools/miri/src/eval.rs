@@ -153,6 +153,15 @@ pub struct MiriConfig {
     pub page_size: Option<u64>,
     /// Whether to collect a backtrace when each allocation is created, just in case it leaks.
     pub collect_leak_backtraces: bool,
+    /// If `Some`, record all nondeterministic choices of the execution to this file.
+    pub record_trace: Option<PathBuf>,
+    /// If `Some`, replay the nondeterministic choices recorded in this file.
+    pub replay_trace: Option<PathBuf>,
+    /// If `Some`, run the program once for every combination of nondeterministic choices, with at
+    /// most this many preemptions per execution, instead of making the choices at random.
+    pub explore: Option<u32>,
+    /// If `Some`, write a coverage report of the executed code to this directory.
+    pub coverage: Option<PathBuf>,
 }
 
 impl Default for MiriConfig {
@@ -189,6 +198,10 @@ fn default() -> MiriConfig {
             num_cpus: 1,
             page_size: None,
             collect_leak_backtraces: true,
+            record_trace: None,
+            replay_trace: None,
+            explore: None,
+            coverage: None,
         }
     }
 }
@@ -425,40 +438,121 @@ pub fn eval_entry<'tcx>(
     // Copy setting before we move `config`.
     let ignore_leaks = config.ignore_leaks;
 
-    let mut ecx = match create_ecx(tcx, entry_id, entry_type, &config) {
-        Ok(v) => v,
-        Err(err) => {
-            let (kind, backtrace) = err.into_parts();
-            backtrace.print_backtrace();
-            panic!("Miri initialization error: {kind:?}")
+    // When exploring, the program is run once per execution, until one of them fails.
+    let mut exploration = config.explore.map(Exploration::new);
+    let mut exploration_note = None;
+    // The coverage is collected across all executions.
+    let mut coverage = None;
+    let (mut ecx, res) = loop {
+        let mut ecx = match create_ecx(tcx, entry_id, entry_type, &config) {
+            Ok(v) => v,
+            Err(err) => {
+                let (kind, backtrace) = err.into_parts();
+                backtrace.print_backtrace();
+                panic!("Miri initialization error: {kind:?}")
+            }
+        };
+        if let Some(exploration) = exploration.take() {
+            ecx.machine.rng.get_mut().explore(exploration);
+        } else if let Some(bound) = ecx.machine.trace.replayed_exploration() {
+            // The replayed trace was recorded while exploring, so we make the same choices.
+            ecx.machine.rng.get_mut().explore(Exploration::replay(bound));
+        }
+        if let Some(coverage) = coverage.take() {
+            ecx.machine.coverage = Some(coverage);
+        }
+
+        // Perform the main execution.
+        let res: thread::Result<InterpResult<'_, !>> =
+            panic::catch_unwind(AssertUnwindSafe(|| ecx.run_threads()));
+        let res = res.unwrap_or_else(|panic_payload| {
+            ecx.handle_ice();
+            panic::resume_unwind(panic_payload)
+        });
+        let res = match res {
+            Err(res) => res,
+            // `Ok` can never happen
+            Ok(never) => match never {},
+        };
+
+        // Machine cleanup. Only do this if all threads have terminated; threads that are still
+        // running might cause Stacked Borrows errors (https://github.com/rust-lang/miri/issues/2396).
+        if ecx.have_all_terminated() {
+            // Even if all threads have terminated, we have to beware of data races since some
+            // threads might not have joined the main thread
+            // (https://github.com/rust-lang/miri/issues/2020,
+            // https://github.com/rust-lang/miri/issues/2508).
+            ecx.allow_data_races_all_threads_done();
+            EnvVars::cleanup(&mut ecx).expect("error during env var cleanup");
         }
-    };
 
-    // Perform the main execution.
-    let res: thread::Result<InterpResult<'_, !>> =
-        panic::catch_unwind(AssertUnwindSafe(|| ecx.run_threads()));
-    let res = res.unwrap_or_else(|panic_payload| {
-        ecx.handle_ice();
-        panic::resume_unwind(panic_payload)
-    });
-    let res = match res {
-        Err(res) => res,
-        // `Ok` can never happen
-        Ok(never) => match never {},
+        let Some(explored) = ecx.machine.rng.get_mut().take_exploration() else {
+            break (ecx, res);
+        };
+        // An execution that leaks fails just like one that exits with an error, so that the leak
+        // is reported for it rather than for the last execution.
+        let succeeded = match res.kind() {
+            InterpError::MachineStop(info) =>
+                match info.downcast_ref::<TerminationInfo>() {
+                    Some(&TerminationInfo::Exit { code: 0, leak_check }) =>
+                        !leak_check
+                            || ignore_leaks
+                            || (ecx.have_all_terminated()
+                                && ecx
+                                    .find_leaked_allocations(&ecx.machine.static_roots)
+                                    .is_empty()),
+                    _ => false,
+                },
+            _ => false,
+        };
+        if !succeeded {
+            if config.explore.is_some() {
+                let replay = match &config.record_trace {
+                    Some(path) =>
+                        format!("the trace of this execution was written to `{}`", path.display()),
+                    None => "pass `-Zmiri-record=<path>` to write a trace of this execution".into(),
+                };
+                exploration_note = Some(format!(
+                    "this happened in execution {} of the exploration, which made these choices:\n\
+                    {}\n{replay}, which `-Zmiri-replay=<path>` runs again",
+                    explored.runs(),
+                    explored.describe_choices(),
+                ));
+            }
+            break (ecx, res);
+        }
+        match explored.next_run() {
+            Some(next) => {
+                exploration = Some(next);
+                coverage = ecx.machine.coverage.take();
+            }
+            // Every execution succeeded, so we report the last one as usual.
+            None => break (ecx, res),
+        }
     };
 
-    // Machine cleanup. Only do this if all threads have terminated; threads that are still running
-    // might cause Stacked Borrows errors (https://github.com/rust-lang/miri/issues/2396).
-    if ecx.have_all_terminated() {
-        // Even if all threads have terminated, we have to beware of data races since some threads
-        // might not have joined the main thread (https://github.com/rust-lang/miri/issues/2020,
-        // https://github.com/rust-lang/miri/issues/2508).
-        ecx.allow_data_races_all_threads_done();
-        EnvVars::cleanup(&mut ecx).expect("error during env var cleanup");
+    // Make sure the recorded trace ends up on disk, even if the execution failed: that is when it
+    // is most useful.
+    if let Err(err) = ecx.machine.trace.finish() {
+        tcx.dcx().err(format!("failed to write the trace file: {err}"));
+    }
+    if let Some(coverage) = &ecx.machine.coverage {
+        match coverage.write(tcx) {
+            Ok(path) => {
+                tcx.dcx().note(format!("coverage report written to `{}`", path.display()));
+            }
+            Err(err) => {
+                tcx.dcx().err(format!("failed to write the coverage report: {err}"));
+            }
+        }
     }
 
     // Process the result.
-    let (return_code, leak_check) = report_error(&ecx, res)?;
+    let result = report_error(&ecx, res);
+    if let Some(note) = exploration_note {
+        tcx.dcx().note(note);
+    }
+    let (return_code, leak_check) = result?;
     if leak_check && !ignore_leaks {
         // Check for thread leaks.
         if !ecx.have_all_terminated() {
@@ -355,8 +355,11 @@ fn gen_random(&mut self, ptr: Pointer<Option<Provenance>>, len: u64) -> InterpRe
         let mut data = vec![0; usize::try_from(len).unwrap()];
 
         if this.machine.communicate() {
-            // Fill the buffer using the host's rng.
-            getrandom::getrandom(&mut data)
+            // Fill the buffer using the host's rng. This still goes through the trace, so that the
+            // bytes can be replayed later.
+            this.machine
+                .trace
+                .random(&mut data, getrandom::getrandom)
                 .map_err(|err| err_unsup_format!("host getrandom failed: {}", err))?;
         } else {
             let rng = this.machine.rng.get_mut();
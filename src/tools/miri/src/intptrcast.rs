@@ -7,7 +7,7 @@
 
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_span::Span;
-use rustc_target::abi::{HasDataLayout, Size};
+use rustc_target::abi::{Align, HasDataLayout, Size};
 
 use crate::*;
 
@@ -44,6 +44,10 @@ pub struct GlobalStateInner {
     next_base_addr: u64,
     /// The provenance to use for int2ptr casts
     provenance_mode: ProvenanceMode,
+    /// When native code can access memory, the address of an allocation is the host address of
+    /// its bytes. Global allocations can be given an address before their bytes are copied into
+    /// memory, so their bytes are stored here until then, see `alloc_bytes_for`.
+    prepared_alloc_bytes: FxHashMap<AllocId, MiriAllocBytes>,
 }
 
 impl VisitProvenance for GlobalStateInner {
@@ -54,6 +58,7 @@ fn visit_provenance(&self, _visit: &mut VisitWith<'_>) {
             exposed: _,
             next_base_addr: _,
             provenance_mode: _,
+            prepared_alloc_bytes: _,
         } = self;
         // Though base_addr, int_to_ptr_map, and exposed contain AllocIds, we do not want to visit them.
         // int_to_ptr_map and exposed must contain only live allocations, and those
@@ -72,6 +77,7 @@ pub fn new(config: &MiriConfig, stack_addr: u64) -> Self {
             exposed: FxHashSet::default(),
             next_base_addr: stack_addr,
             provenance_mode: config.provenance_mode,
+            prepared_alloc_bytes: FxHashMap::default(),
         }
     }
 
@@ -148,44 +154,85 @@ fn addr_from_alloc_id(&self, alloc_id: AllocId) -> InterpResult<'tcx, u64> {
                 // information was removed.
                 assert!(!matches!(kind, AllocKind::Dead));
 
-                // This allocation does not have a base address yet, pick one.
-                // Leave some space to the previous allocation, to give it some chance to be less aligned.
-                let slack = {
-                    let mut rng = ecx.machine.rng.borrow_mut();
-                    // This means that `(global_state.next_base_addr + slack) % 16` is uniformly distributed.
-                    rng.gen_range(0..16)
+                let base_addr = if ecx.machine.external_so_lib.is_some() {
+                    // Native code accesses the memory of the allocation directly, so its address
+                    // is where its bytes are stored on the host.
+                    match kind {
+                        AllocKind::LiveData =>
+                            match ecx.memory.alloc_map().get(alloc_id) {
+                                Some((_kind, alloc)) =>
+                                    alloc
+                                        .inspect_with_uninit_and_ptr_outside_interpreter(
+                                            0..alloc.len(),
+                                        )
+                                        .as_ptr() as u64,
+                                None => {
+                                    // A global allocation that is not in memory yet. Its bytes
+                                    // will be copied to the storage we prepare now.
+                                    let bytes = MiriAllocBytes::zeroed(size, align)
+                                        .ok_or_else(|| err_exhaust!(MemoryExhausted))?;
+                                    let base_addr = bytes.as_ptr() as u64;
+                                    global_state.prepared_alloc_bytes.insert(alloc_id, bytes);
+                                    base_addr
+                                }
+                            },
+                        AllocKind::Function | AllocKind::VTable => {
+                            // There are no bytes, but the address still has to be unique, so we
+                            // take it from a host allocation that is never freed.
+                            let bytes = MiriAllocBytes::zeroed(Size::from_bytes(1), Align::ONE)
+                                .ok_or_else(|| err_exhaust!(MemoryExhausted))?;
+                            let base_addr = bytes.as_ptr() as u64;
+                            std::mem::forget(bytes);
+                            base_addr
+                        }
+                        AllocKind::Dead => unreachable!(),
+                    }
+                } else {
+                    // This allocation does not have a base address yet, pick one.
+                    // Leave some space to the previous allocation, to give it some chance to be less aligned.
+                    let slack = {
+                        let mut rng = ecx.machine.rng.borrow_mut();
+                        // This means that `(global_state.next_base_addr + slack) % 16` is uniformly distributed.
+                        rng.gen_range(0..16)
+                    };
+                    // From next_base_addr + slack, round up to adjust for alignment.
+                    let base_addr = global_state
+                        .next_base_addr
+                        .checked_add(slack)
+                        .ok_or_else(|| err_exhaust!(AddressSpaceFull))?;
+                    let base_addr = align_addr(base_addr, align.bytes());
+                    trace!(
+                        "Assigning base address {:#x} to allocation {:?} (size: {}, align: {}, slack: {})",
+                        base_addr,
+                        alloc_id,
+                        size.bytes(),
+                        align.bytes(),
+                        slack,
+                    );
+
+                    // Remember next base address.  If this allocation is zero-sized, leave a gap
+                    // of at least 1 to avoid two allocations having the same base address.
+                    // (The logic in `alloc_id_from_addr` assumes unique addresses, and different
+                    // function/vtable pointers need to be distinguishable!)
+                    global_state.next_base_addr = base_addr
+                        .checked_add(max(size.bytes(), 1))
+                        .ok_or_else(|| err_exhaust!(AddressSpaceFull))?;
+                    // Even if `Size` didn't overflow, we might still have filled up the address space.
+                    if global_state.next_base_addr > ecx.target_usize_max() {
+                        throw_exhaust!(AddressSpaceFull);
+                    }
+                    base_addr
                 };
-                // From next_base_addr + slack, round up to adjust for alignment.
-                let base_addr = global_state
-                    .next_base_addr
-                    .checked_add(slack)
-                    .ok_or_else(|| err_exhaust!(AddressSpaceFull))?;
-                let base_addr = align_addr(base_addr, align.bytes());
                 entry.insert(base_addr);
-                trace!(
-                    "Assigning base address {:#x} to allocation {:?} (size: {}, align: {}, slack: {})",
-                    base_addr,
-                    alloc_id,
-                    size.bytes(),
-                    align.bytes(),
-                    slack,
-                );
-
-                // Remember next base address.  If this allocation is zero-sized, leave a gap
-                // of at least 1 to avoid two allocations having the same base address.
-                // (The logic in `alloc_id_from_addr` assumes unique addresses, and different
-                // function/vtable pointers need to be distinguishable!)
-                global_state.next_base_addr = base_addr
-                    .checked_add(max(size.bytes(), 1))
-                    .ok_or_else(|| err_exhaust!(AddressSpaceFull))?;
-                // Even if `Size` didn't overflow, we might still have filled up the address space.
-                if global_state.next_base_addr > ecx.target_usize_max() {
-                    throw_exhaust!(AddressSpaceFull);
-                }
-                // Also maintain the opposite mapping in `int_to_ptr_map`.
-                // Given that `next_base_addr` increases in each allocation, pushing the
-                // corresponding tuple keeps `int_to_ptr_map` sorted
-                global_state.int_to_ptr_map.push((base_addr, alloc_id));
+
+                // Also maintain the opposite mapping in `int_to_ptr_map`, keeping it sorted.
+                // Usually `next_base_addr` increases in each allocation, so this is a push, but host
+                // addresses can come in any order.
+                let pos = global_state
+                    .int_to_ptr_map
+                    .binary_search_by_key(&base_addr, |(addr, _)| *addr)
+                    .unwrap_err();
+                global_state.int_to_ptr_map.insert(pos, (base_addr, alloc_id));
 
                 base_addr
             }
@@ -216,6 +263,27 @@ fn expose_ptr(&mut self, alloc_id: AllocId, tag: BorTag) -> InterpResult<'tcx> {
         Ok(())
     }
 
+    /// Creates the storage for the bytes of the allocation `id`, with the given contents. When
+    /// native code can access memory, a global allocation might already have an address, which
+    /// is that of the storage prepared for it then.
+    fn alloc_bytes_for(
+        &self,
+        id: AllocId,
+        bytes: &[u8],
+        align: Align,
+    ) -> InterpResult<'tcx, MiriAllocBytes> {
+        let ecx = self.eval_context_ref();
+        if ecx.machine.external_so_lib.is_some() && ecx.tcx.try_get_global_alloc(id).is_some() {
+            // Make sure there is prepared storage, if the address was not needed before.
+            ecx.addr_from_alloc_id(id)?;
+            let mut prepared =
+                ecx.machine.intptrcast.borrow_mut().prepared_alloc_bytes.remove(&id).unwrap();
+            prepared.copy_from_slice(bytes);
+            return Ok(prepared);
+        }
+        Ok(MiriAllocBytes::from_bytes(bytes, align))
+    }
+
     fn ptr_from_addr_cast(&self, addr: u64) -> InterpResult<'tcx, Pointer<Option<Provenance>>> {
         trace!("Casting {:#x} to a pointer", addr);
 
@@ -68,9 +68,11 @@
 #[allow(unused_extern_crates)]
 extern crate rustc_driver;
 
+mod alloc_bytes;
 mod borrow_tracker;
 mod clock;
 mod concurrency;
+mod coverage;
 mod diagnostics;
 mod eval;
 mod helpers;
@@ -80,6 +82,7 @@
 mod operator;
 mod provenance_gc;
 mod range_map;
+mod replay;
 mod shims;
 
 // Establish a "crate-wide prelude": we often import `crate::*`.
@@ -97,6 +100,7 @@
 pub use crate::shims::time::EvalContextExt as _;
 pub use crate::shims::tls::TlsData;
 
+pub use crate::alloc_bytes::MiriAllocBytes;
 pub use crate::borrow_tracker::stacked_borrows::{
     EvalContextExt as _, Item, Permission, Stack, Stacks,
 };
@@ -107,6 +111,7 @@
 pub use crate::clock::{Clock, Instant};
 pub use crate::concurrency::{
     data_race::{AtomicFenceOrd, AtomicReadOrd, AtomicRwOrd, AtomicWriteOrd, EvalContextExt as _},
+    exploration::{Choice, EvalContextExt as _, Exploration},
     init_once::{EvalContextExt as _, InitOnceId},
     sync::{CondvarId, EvalContextExt as _, MutexId, RwLockId, SyncId},
     thread::{EvalContextExt as _, StackEmptyCallback, ThreadId, ThreadManager, Time},
@@ -127,6 +132,8 @@
 pub use crate::operator::EvalContextExt as _;
 pub use crate::provenance_gc::{EvalContextExt as _, LiveAllocs, VisitProvenance, VisitWith};
 pub use crate::range_map::RangeMap;
+pub use crate::coverage::{Coverage, EvalContextExt as _};
+pub use crate::replay::{MiriRng, Trace};
 
 /// Insert rustc arguments at the beginning of the argument list that Miri wants to be
 /// set per default, for maximal validation power.
@@ -7,6 +7,7 @@
 use std::fmt;
 use std::path::Path;
 use std::process;
+use std::rc::Rc;
 
 use either::Either;
 use rand::rngs::StdRng;
@@ -78,13 +79,23 @@ pub struct FrameExtra<'tcx> {
     /// an additional bit of "salt" into the cache key. This salt is fixed per-frame
     /// so that within a call, a const will have a stable address.
     salt: usize,
+
+    /// The basic block whose terminator this frame executed last. Only tracked when collecting
+    /// coverage, to find out which way branches went.
+    pub coverage_last_block: Option<mir::BasicBlock>,
 }
 
 impl<'tcx> std::fmt::Debug for FrameExtra<'tcx> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         // Omitting `timing`, it does not support `Debug`.
-        let FrameExtra { borrow_tracker, catch_unwind, timing: _, is_user_relevant: _, salt: _ } =
-            self;
+        let FrameExtra {
+            borrow_tracker,
+            catch_unwind,
+            timing: _,
+            is_user_relevant: _,
+            salt: _,
+            coverage_last_block: _,
+        } = self;
         f.debug_struct("FrameData")
             .field("borrow_tracker", borrow_tracker)
             .field("catch_unwind", catch_unwind)
@@ -94,8 +105,14 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 
 impl VisitProvenance for FrameExtra<'_> {
     fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
-        let FrameExtra { catch_unwind, borrow_tracker, timing: _, is_user_relevant: _, salt: _ } =
-            self;
+        let FrameExtra {
+            catch_unwind,
+            borrow_tracker,
+            timing: _,
+            is_user_relevant: _,
+            salt: _,
+            coverage_last_block: _,
+        } = self;
 
         catch_unwind.visit_provenance(visit);
         borrow_tracker.visit_provenance(visit);
@@ -467,6 +484,8 @@ pub struct MiriMachine<'mir, 'tcx> {
     pub(crate) file_handler: shims::unix::FileHandler,
     /// The table of directory descriptors.
     pub(crate) dir_handler: shims::unix::DirHandler,
+    /// The emulated loopback network.
+    pub(crate) network: shims::unix::Network,
 
     /// This machine's monotone clock.
     pub(crate) clock: Clock,
@@ -507,7 +526,13 @@ pub struct MiriMachine<'mir, 'tcx> {
 
     /// The random number generator used for resolving non-determinism.
     /// Needs to be queried by ptr_to_int, hence needs interior mutability.
-    pub(crate) rng: RefCell<StdRng>,
+    pub(crate) rng: RefCell<MiriRng>,
+
+    /// The trace of nondeterministic choices that is being recorded or replayed, if any.
+    pub(crate) trace: Rc<Trace>,
+
+    /// The coverage collected so far, if `-Zmiri-coverage` is set.
+    pub(crate) coverage: Option<Coverage>,
 
     /// The allocation IDs to report when they are being allocated
     /// (helps for debugging memory leaks and use after free bugs).
@@ -596,7 +621,24 @@ pub(crate) fn new(config: &MiriConfig, layout_cx: LayoutCx<'tcx, TyCtxt<'tcx>>)
             let path = Path::new(out).join(filename);
             measureme::Profiler::new(path).expect("Couldn't create `measureme` profiler")
         });
-        let rng = StdRng::seed_from_u64(config.seed.unwrap_or(0));
+        let trace = if let Some(path) = &config.record_trace {
+            Trace::record(path).unwrap_or_else(|err| {
+                layout_cx
+                    .tcx
+                    .dcx()
+                    .fatal(format!("failed to create the trace file `{}`: {err}", path.display()))
+            })
+        } else if let Some(path) = &config.replay_trace {
+            Trace::replay(path).unwrap_or_else(|err| {
+                layout_cx
+                    .tcx
+                    .dcx()
+                    .fatal(format!("failed to read the trace file `{}`: {err}", path.display()))
+            })
+        } else {
+            Trace::off()
+        };
+        let rng = MiriRng::new(StdRng::seed_from_u64(config.seed.unwrap_or(0)), trace.clone());
         let borrow_tracker = config.borrow_tracker.map(|bt| bt.instantiate_global_state(config));
         let data_race = config.data_race_detector.then(|| data_race::GlobalState::new(config));
         // Determine page size, stack address, and stack size.
@@ -642,6 +684,7 @@ pub(crate) fn new(config: &MiriConfig, layout_cx: LayoutCx<'tcx, TyCtxt<'tcx>>)
             enforce_abi: config.check_abi,
             file_handler: FileHandler::new(config.mute_stdout_stderr),
             dir_handler: Default::default(),
+            network: Default::default(),
             layouts,
             threads: ThreadManager::default(),
             static_roots: Vec::new(),
@@ -653,6 +696,8 @@ pub(crate) fn new(config: &MiriConfig, layout_cx: LayoutCx<'tcx, TyCtxt<'tcx>>)
             local_crates,
             extern_statics: FxHashMap::default(),
             rng: RefCell::new(rng),
+            trace: trace.clone(),
+            coverage: config.coverage.clone().map(Coverage::new),
             tracked_alloc_ids: config.tracked_alloc_ids.clone(),
             check_alignment: config.check_alignment,
             cmpxchg_weak_failure_rate: config.cmpxchg_weak_failure_rate,
@@ -661,7 +706,7 @@ pub(crate) fn new(config: &MiriConfig, layout_cx: LayoutCx<'tcx, TyCtxt<'tcx>>)
             preemption_rate: config.preemption_rate,
             report_progress: config.report_progress,
             basic_block_count: 0,
-            clock: Clock::new(config.isolated_op == IsolatedOp::Allow),
+            clock: Clock::new(config.isolated_op == IsolatedOp::Allow, trace),
             #[cfg(target_os = "linux")]
             external_so_lib: config.external_so_file.as_ref().map(|lib_file_path| {
                 let target_triple = layout_cx.tcx.sess.opts.target_triple.triple();
@@ -774,6 +819,7 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
             cmd_line,
             extern_statics,
             dir_handler,
+            network,
             borrow_tracker,
             data_race,
             intptrcast,
@@ -792,6 +838,8 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
             backtrace_style: _,
             local_crates: _,
             rng: _,
+            trace: _,
+            coverage: _,
             tracked_alloc_ids: _,
             check_alignment: _,
             cmpxchg_weak_failure_rate: _,
@@ -817,6 +865,7 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
         tls.visit_provenance(visit);
         env_vars.visit_provenance(visit);
         dir_handler.visit_provenance(visit);
+        network.visit_provenance(visit);
         file_handler.visit_provenance(visit);
         data_race.visit_provenance(visit);
         borrow_tracker.visit_provenance(visit);
@@ -860,7 +909,7 @@ impl<'mir, 'tcx> Machine<'mir, 'tcx> for MiriMachine<'mir, 'tcx> {
 
     type Provenance = Provenance;
     type ProvenanceExtra = ProvenanceExtra;
-    type Bytes = Box<[u8]>;
+    type Bytes = MiriAllocBytes;
 
     type MemoryMap = MonoHashMap<
         AllocId,
@@ -1092,7 +1141,8 @@ fn adjust_allocation<'b>(
         id: AllocId,
         alloc: Cow<'b, Allocation>,
         kind: Option<MemoryKind<Self::MemoryKind>>,
-    ) -> InterpResult<'tcx, Cow<'b, Allocation<Self::Provenance, Self::AllocExtra>>> {
+    ) -> InterpResult<'tcx, Cow<'b, Allocation<Self::Provenance, Self::AllocExtra, Self::Bytes>>>
+    {
         let kind = kind.expect("we set our STATIC_KIND so this cannot be None");
         if ecx.machine.tracked_alloc_ids.contains(&id) {
             ecx.emit_diagnostic(NonHaltingDiagnostic::CreatedAlloc(
@@ -1130,7 +1180,7 @@ fn adjust_allocation<'b>(
             Some(ecx.generate_stacktrace())
         };
 
-        let alloc: Allocation<Provenance, Self::AllocExtra> = alloc.adjust_from_tcx(
+        let alloc: Allocation<Provenance, Self::AllocExtra, Self::Bytes> = alloc.adjust_from_tcx(
             &ecx.tcx,
             AllocExtra {
                 borrow_tracker,
@@ -1138,6 +1188,7 @@ fn adjust_allocation<'b>(
                 weak_memory: buffer_alloc,
                 backtrace,
             },
+            |bytes, align| ecx.alloc_bytes_for(id, bytes, align),
             |ptr| ecx.global_base_pointer(ptr),
         )?;
 
@@ -1372,6 +1423,7 @@ fn init_frame_extra(
             timing,
             is_user_relevant: ecx.machine.is_user_relevant(&frame),
             salt: ecx.machine.rng.borrow_mut().gen::<usize>() % ADDRS_PER_CONST,
+            coverage_last_block: None,
         };
 
         Ok(frame.with_extra(extra))
@@ -1410,6 +1462,13 @@ fn before_terminator(ecx: &mut InterpCx<'mir, 'tcx, Self>) -> InterpResult<'tcx>
             ecx.run_provenance_gc();
         }
 
+        ecx.record_coverage();
+
+        // Stop if we are replaying a trace that does not match this execution anymore.
+        if let Some(msg) = ecx.machine.trace.take_divergence() {
+            throw_machine_stop!(TerminationInfo::ReplayDiverged(msg));
+        }
+
         // These are our preemption points.
         ecx.maybe_preempt_active_thread();
 
@@ -1455,6 +1514,7 @@ fn after_stack_pop(
             // user-relevant frame and restore that here.)
             ecx.active_thread_mut().recompute_top_user_relevant_frame();
         }
+        ecx.exploration_returned_from(frame.instance);
         let timing = frame.extra.timing.take();
         let res = ecx.handle_stack_pop_unwind(frame.extra, unwinding);
         if let Some(profiler) = ecx.machine.profiler.as_ref() {
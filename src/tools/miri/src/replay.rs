@@ -0,0 +1,493 @@
+//! Recording and replaying the nondeterministic choices of an execution.
+//!
+//! With `-Zmiri-record=<path>`, every choice that Miri makes on its own (preempting a thread,
+//! picking the store a weak memory load reads from, and any random number it generates) is
+//! written to a trace file, together with every reading of the host's clocks and random number
+//! generator. `-Zmiri-replay=<path>` then makes the same choices and returns the same readings,
+//! in the same order, so a failing run can be reproduced exactly even if it depended on the
+//! timing of the host.
+//!
+//! When exploring executions with `-Zmiri-explore`, the trace file ends up containing the last
+//! execution, i.e. the one that failed, if any. Instead of preemptions and weak memory loads, it
+//! then contains the choices the exploration made, which are replayed in the same way.
+//!
+//! The trace is a text file with one event per line. Nothing else the program observes from the
+//! host (e.g. the contents of files) is recorded, so replaying only works with the same program,
+//! the same flags, and the same environment. If the execution asks for something different than
+//! what the trace contains at that point, the replay is stopped with an error.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, BufWriter, Write as _};
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::seq::IteratorRandom;
+use rand::{Rng as _, RngCore};
+
+use crate::{Choice, Exploration};
+
+/// The first line of every trace file.
+const TRACE_HEADER: &str = "miri-trace v1";
+
+/// A single nondeterministic choice or host reading.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TraceEvent {
+    /// The active thread was preempted at the given preemption point. Preemption points where the
+    /// active thread kept running are not recorded; they are only counted.
+    Preempt(u64),
+    /// A weak memory load read from the store that is this many stores behind the latest one.
+    WeakLoad(usize),
+    /// A reading of the host's monotone clock, in nanoseconds since the clock's anchor.
+    Time(u64),
+    /// A reading of the host's system clock, in nanoseconds since the Unix epoch.
+    SystemTime(u64),
+    /// Random bytes, generated either by Miri's random number generator or by the host.
+    Random(Vec<u8>),
+    /// The execution was explored with the given preemption bound, so its scheduling decisions
+    /// and weak memory loads are given by `Choice` events instead.
+    Explore(u32),
+    /// The exploration took the given non-default alternative at the choice point with the given
+    /// index. Choice points where the default alternative was taken are not recorded.
+    Choice(usize, usize),
+}
+
+impl TraceEvent {
+    fn parse(line: &str) -> Option<TraceEvent> {
+        let (kind, value) = line.split_once(' ')?;
+        Some(match kind {
+            "preempt" => TraceEvent::Preempt(value.parse().ok()?),
+            "weak-load" => TraceEvent::WeakLoad(value.parse().ok()?),
+            "time" => TraceEvent::Time(value.parse().ok()?),
+            "system-time" => TraceEvent::SystemTime(value.parse().ok()?),
+            "explore" => TraceEvent::Explore(value.parse().ok()?),
+            "choice" => {
+                let (index, chosen) = value.split_once(':')?;
+                TraceEvent::Choice(index.parse().ok()?, chosen.parse().ok()?)
+            }
+            "random" => {
+                if value.len() % 2 != 0 {
+                    return None;
+                }
+                let bytes = (0..value.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+                    .collect::<Option<_>>()?;
+                TraceEvent::Random(bytes)
+            }
+            _ => return None,
+        })
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            TraceEvent::Preempt(_) => "preempt",
+            TraceEvent::WeakLoad(_) => "weak-load",
+            TraceEvent::Time(_) => "time",
+            TraceEvent::SystemTime(_) => "system-time",
+            TraceEvent::Random(_) => "random",
+            TraceEvent::Explore(_) => "explore",
+            TraceEvent::Choice(..) => "choice",
+        }
+    }
+}
+
+impl std::fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ", self.kind())?;
+        match self {
+            TraceEvent::Preempt(n) | TraceEvent::Time(n) | TraceEvent::SystemTime(n) =>
+                write!(f, "{n}"),
+            TraceEvent::WeakLoad(n) => write!(f, "{n}"),
+            TraceEvent::Explore(bound) => write!(f, "{bound}"),
+            TraceEvent::Choice(index, chosen) => write!(f, "{index}:{chosen}"),
+            TraceEvent::Random(bytes) => {
+                let mut hex = String::with_capacity(bytes.len() * 2);
+                for byte in bytes {
+                    write!(hex, "{byte:02x}").unwrap();
+                }
+                write!(f, "{hex}")
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+enum TraceMode {
+    /// Nothing is recorded or replayed.
+    Off,
+    Record {
+        out: BufWriter<File>,
+        /// The first error that happened while writing the trace, reported by `Trace::finish`.
+        error: Option<io::Error>,
+    },
+    Replay {
+        events: VecDeque<TraceEvent>,
+    },
+}
+
+/// The trace of an execution that is being recorded or replayed. It is shared by Miri's random
+/// number generator and its clock, since both of them need to consult it.
+#[derive(Debug)]
+pub struct Trace {
+    mode: RefCell<TraceMode>,
+    /// The number of preemption points we have passed so far.
+    preemption_points: Cell<u64>,
+    /// Set when the execution asked for something the replayed trace does not contain. We cannot
+    /// raise an error from where that happens (e.g. inside the random number generator), so this
+    /// is checked at the end of every basic block instead.
+    divergence: RefCell<Option<String>>,
+}
+
+impl Trace {
+    pub fn off() -> Rc<Trace> {
+        Trace::with_mode(TraceMode::Off)
+    }
+
+    /// Create a trace that records to the given file.
+    pub fn record(path: &Path) -> io::Result<Rc<Trace>> {
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(out, "{TRACE_HEADER}")?;
+        Ok(Trace::with_mode(TraceMode::Record { out, error: None }))
+    }
+
+    /// Create a trace that replays the given file.
+    pub fn replay(path: &Path) -> io::Result<Rc<Trace>> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let contents = std::fs::read_to_string(path)?;
+        let mut lines = contents.lines();
+        if lines.next() != Some(TRACE_HEADER) {
+            return Err(invalid(format!("the file does not start with `{TRACE_HEADER}`")));
+        }
+        let events = lines
+            .enumerate()
+            .map(|(i, line)| {
+                TraceEvent::parse(line)
+                    .ok_or_else(|| invalid(format!("invalid event on line {}: `{line}`", i + 2)))
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Trace::with_mode(TraceMode::Replay { events }))
+    }
+
+    fn with_mode(mode: TraceMode) -> Rc<Trace> {
+        Rc::new(Trace {
+            mode: RefCell::new(mode),
+            preemption_points: Cell::new(0),
+            divergence: RefCell::new(None),
+        })
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        matches!(*self.mode.borrow(), TraceMode::Replay { .. })
+    }
+
+    /// Returns a description of how the execution diverged from the replayed trace, if it did.
+    pub fn take_divergence(&self) -> Option<String> {
+        self.divergence.borrow_mut().take()
+    }
+
+    /// Flush the recorded trace, and report any error that happened while writing it.
+    pub fn finish(&self) -> io::Result<()> {
+        match &mut *self.mode.borrow_mut() {
+            TraceMode::Record { out, error } => {
+                if let Some(error) = error.take() {
+                    return Err(error);
+                }
+                out.flush()
+            }
+            TraceMode::Off | TraceMode::Replay { .. } => Ok(()),
+        }
+    }
+
+    fn diverge(&self, msg: String) {
+        let mut divergence = self.divergence.borrow_mut();
+        // Only the first divergence is interesting, everything after that is a consequence of it.
+        if divergence.is_none() {
+            *divergence = Some(msg);
+        }
+    }
+
+    fn push(&self, event: TraceEvent) {
+        if let TraceMode::Record { out, error } = &mut *self.mode.borrow_mut() {
+            if error.is_none() {
+                if let Err(err) = writeln!(out, "{event}") {
+                    *error = Some(err);
+                }
+            }
+        }
+    }
+
+    /// Take the next event out of the replayed trace, if it is of the same kind as `expected`.
+    fn pop(&self, expected: &'static str) -> Option<TraceEvent> {
+        let mut mode = self.mode.borrow_mut();
+        let TraceMode::Replay { events } = &mut *mode else { return None };
+        match events.front() {
+            Some(event) if event.kind() == expected => events.pop_front(),
+            Some(event) => {
+                let msg = format!(
+                    "the execution diverged from the replayed trace: \
+                    expected a `{expected}` event, but the trace contains `{event}`"
+                );
+                drop(mode);
+                self.diverge(msg);
+                None
+            }
+            None => {
+                drop(mode);
+                self.diverge(format!(
+                    "the execution diverged from the replayed trace: \
+                    expected a `{expected}` event, but the trace has ended"
+                ));
+                None
+            }
+        }
+    }
+
+    /// If the replayed trace was recorded while exploring, returns the preemption bound of the
+    /// exploration. This has to be called right before the execution starts.
+    pub fn replayed_exploration(&self) -> Option<u32> {
+        let mut mode = self.mode.borrow_mut();
+        let TraceMode::Replay { events } = &mut *mode else { return None };
+        let Some(&TraceEvent::Explore(bound)) = events.front() else { return None };
+        events.pop_front();
+        Some(bound)
+    }
+
+    /// Replay the alternative taken at the choice point with the given index, or return `None`
+    /// if we are not replaying.
+    fn replay_choice(&self, index: usize) -> Option<usize> {
+        let mut mode = self.mode.borrow_mut();
+        let TraceMode::Replay { events } = &mut *mode else { return None };
+        match events.front() {
+            Some(&TraceEvent::Choice(recorded, chosen)) if recorded <= index => {
+                events.pop_front();
+                drop(mode);
+                if recorded < index {
+                    self.diverge(format!(
+                        "the execution diverged from the replayed trace: \
+                        expected choice point {recorded}, but it was skipped"
+                    ));
+                }
+                Some(chosen)
+            }
+            // Any other event has to happen before the next non-default choice.
+            _ => Some(0),
+        }
+    }
+
+    /// Record or replay a reading of the host's monotone clock, relative to its anchor.
+    pub fn time(&self, read: impl FnOnce() -> Duration) -> Duration {
+        self.duration(TraceEvent::Time, "time", read)
+    }
+
+    /// Record or replay a reading of the host's system clock, relative to the Unix epoch.
+    pub fn system_time(&self, read: impl FnOnce() -> Duration) -> Duration {
+        self.duration(TraceEvent::SystemTime, "system-time", read)
+    }
+
+    fn duration(
+        &self,
+        event: fn(u64) -> TraceEvent,
+        kind: &'static str,
+        read: impl FnOnce() -> Duration,
+    ) -> Duration {
+        if self.is_replaying() {
+            if let Some(TraceEvent::Time(nanos) | TraceEvent::SystemTime(nanos)) = self.pop(kind) {
+                return Duration::from_nanos(nanos);
+            }
+        }
+        let duration = read();
+        // Durations that do not fit into a `u64` are more than 500 years long.
+        self.push(event(u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)));
+        duration
+    }
+
+    /// Record or replay random bytes, which are otherwise generated by `fill`.
+    pub fn random<E>(
+        &self,
+        dest: &mut [u8],
+        fill: impl FnOnce(&mut [u8]) -> Result<(), E>,
+    ) -> Result<(), E> {
+        if self.is_replaying() {
+            match self.pop("random") {
+                Some(TraceEvent::Random(bytes)) if bytes.len() == dest.len() => {
+                    dest.copy_from_slice(&bytes);
+                    return Ok(());
+                }
+                Some(event) =>
+                    self.diverge(format!(
+                        "the execution diverged from the replayed trace: \
+                        expected {} random bytes, but the trace contains `{event}`",
+                        dest.len()
+                    )),
+                None => {}
+            }
+        }
+        fill(dest)?;
+        self.push(TraceEvent::Random(dest.to_vec()));
+        Ok(())
+    }
+}
+
+/// Miri's random number generator. All randomness it produces is recorded and replayed as part
+/// of the execution's trace. When systematically exploring executions, the choices that are
+/// explored are not made randomly at all.
+#[derive(Debug)]
+pub struct MiriRng {
+    rng: StdRng,
+    trace: Rc<Trace>,
+    exploration: Option<Exploration>,
+}
+
+impl MiriRng {
+    pub fn new(rng: StdRng, trace: Rc<Trace>) -> Self {
+        MiriRng { rng, trace, exploration: None }
+    }
+
+    /// Make the choices of this run according to the given exploration state.
+    pub fn explore(&mut self, exploration: Exploration) {
+        self.trace.push(TraceEvent::Explore(exploration.preemption_bound()));
+        self.exploration = Some(exploration);
+    }
+
+    /// Make a choice out of `alternatives` according to the exploration state, and record it to
+    /// or replay it from the trace. Returns `None` if we are not exploring. See
+    /// [`Exploration::choose`] for the meaning of `describe`.
+    pub fn explore_choose(
+        &mut self,
+        kind: Choice,
+        alternatives: usize,
+        describe: impl FnOnce(usize) -> String,
+    ) -> Option<usize> {
+        let exploration = self.exploration.as_mut()?;
+        let index = exploration.choice_points();
+        let replayed = self.trace.replay_choice(index);
+        let chosen = exploration.choose(kind, alternatives, replayed, describe);
+        if chosen > 0 {
+            self.trace.push(TraceEvent::Choice(index, chosen));
+        }
+        Some(chosen)
+    }
+
+    pub fn exploration(&mut self) -> Option<&mut Exploration> {
+        self.exploration.as_mut()
+    }
+
+    pub fn take_exploration(&mut self) -> Option<Exploration> {
+        self.exploration.take()
+    }
+
+    /// Decide whether to preempt the active thread at this preemption point.
+    pub fn preempt(&mut self, preemption_rate: f64) -> bool {
+        let point = self.trace.preemption_points.get();
+        self.trace.preemption_points.set(point + 1);
+        if self.trace.is_replaying() {
+            let mode = self.trace.mode.borrow();
+            let TraceMode::Replay { events } = &*mode else { unreachable!() };
+            return match events.front() {
+                Some(&TraceEvent::Preempt(recorded)) if recorded <= point => {
+                    drop(mode);
+                    self.trace.pop("preempt");
+                    if recorded < point {
+                        self.trace.diverge(format!(
+                            "the execution diverged from the replayed trace: \
+                            expected to be preempted at preemption point {recorded}, \
+                            but it was skipped"
+                        ));
+                    }
+                    true
+                }
+                // Any other event has to happen before the next preemption.
+                _ => false,
+            };
+        }
+        let preempt = self.rng.gen_bool(preemption_rate);
+        if preempt {
+            self.trace.push(TraceEvent::Preempt(point));
+        }
+        preempt
+    }
+
+    /// Choose which store a weak memory load reads from, out of a store buffer with `len`
+    /// elements. The stores that may be read from are given by how many stores they are behind
+    /// the latest one, and so is the result.
+    pub fn weak_load(&mut self, len: usize, candidates: impl Iterator<Item = usize>) -> usize {
+        if self.exploration.is_some() {
+            let candidates: Vec<usize> = candidates.collect();
+            let chosen = self
+                .explore_choose(Choice::WeakLoad, candidates.len(), |chosen| {
+                    format!(
+                        "a weak memory load read from the store {} behind the latest one",
+                        candidates[chosen]
+                    )
+                })
+                .unwrap();
+            return candidates[chosen];
+        }
+        if len > 1 && self.trace.is_replaying() {
+            match self.trace.pop("weak-load") {
+                Some(TraceEvent::WeakLoad(offset)) if offset < len => return offset,
+                Some(event) =>
+                    self.trace.diverge(format!(
+                        "the execution diverged from the replayed trace: \
+                        the store buffer has only {len} elements, but the trace contains `{event}`"
+                    )),
+                None => {}
+            }
+        }
+        let offset = candidates.choose(&mut self.rng).expect("store buffer cannot be empty");
+        // Reading the latest store is the only option most of the time, so we only record the
+        // loads where there actually was a choice.
+        if len > 1 {
+            self.trace.push(TraceEvent::WeakLoad(offset));
+        }
+        offset
+    }
+
+    /// Decide whether a `compare_exchange_weak` that could succeed actually does.
+    pub fn compare_exchange_weak_succeeds(&mut self, success_rate: f64) -> bool {
+        if success_rate < 1.0
+            && let Some(chosen) = self.explore_choose(Choice::SpuriousFailure, 2, |_| {
+                "a `compare_exchange_weak` failed spuriously".to_string()
+            })
+        {
+            return chosen == 0;
+        }
+        self.gen_bool(success_rate)
+    }
+}
+
+impl RngCore for MiriRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0; 4];
+        let _ = self.trace.random(&mut bytes, |bytes| {
+            bytes.copy_from_slice(&self.rng.next_u32().to_le_bytes());
+            Ok::<_, !>(())
+        });
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0; 8];
+        let _ = self.trace.random(&mut bytes, |bytes| {
+            bytes.copy_from_slice(&self.rng.next_u64().to_le_bytes());
+            Ok::<_, !>(())
+        });
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let _ = self.trace.random(dest, |dest| {
+            self.rng.fill_bytes(dest);
+            Ok::<_, !>(())
+        });
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.trace.random(dest, |dest| self.rng.try_fill_bytes(dest))
+    }
+}
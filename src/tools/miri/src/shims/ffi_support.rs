@@ -1,9 +1,11 @@
 use libffi::{high::call as ffi, low::CodePtr};
+use std::ffi::c_void;
 use std::ops::Deref;
 
+use rustc_data_structures::fx::FxHashSet;
 use rustc_middle::ty::{self as ty, IntTy, Ty, UintTy};
 use rustc_span::Symbol;
-use rustc_target::abi::HasDataLayout;
+use rustc_target::abi::{HasDataLayout, Size};
 
 use crate::*;
 
@@ -65,6 +67,143 @@ fn scalar_to_carg(
         );
     }
 
+    /// Convert a pointer argument to a `CArg`. When native code can access memory, the address
+    /// of an allocation is that of its bytes on the host (see `intptrcast`), so the native code
+    /// gets the pointer as it is. The allocation the pointer points to, if any, is added to
+    /// `roots`.
+    ///
+    /// The native code may access the part of the allocation from the pointer to the end of the
+    /// allocation, or just the pointee for a reference. This counts as a read through the
+    /// pointer, and if the pointer is mutable, the part is remembered in `writes`, so that changes
+    /// to it count as writes through the pointer once the call returned.
+    fn pointer_to_carg(
+        &mut self,
+        ptr: Pointer<Option<Provenance>>,
+        ptr_ty: Ty<'tcx>,
+        roots: &mut Vec<AllocId>,
+        writes: &mut Vec<NativeWrite>,
+    ) -> InterpResult<'tcx, CArg> {
+        let this = self.eval_context_mut();
+        let carg = CArg::RawPtr(ptr.addr().bytes_usize() as *mut c_void);
+        let Ok((alloc_id, offset, _prov_extra)) = this.ptr_try_get_alloc_id(ptr) else {
+            // Without provenance, the native code can only use the pointer as an integer.
+            return Ok(carg);
+        };
+        let (alloc_size, _align, kind) = this.get_alloc_info(alloc_id);
+        if kind != AllocKind::LiveData {
+            throw_unsup_format!(
+                "passing a pointer to a function, vtable or dead allocation to an external C function is not supported"
+            );
+        }
+        if let Some(Provenance::Concrete { alloc_id, tag }) = ptr.provenance {
+            this.expose_ptr(alloc_id, tag)?;
+        }
+        roots.push(alloc_id);
+
+        let pointee = ptr_ty.builtin_deref(true).unwrap();
+        let size = if ptr_ty.is_ref() {
+            this.layout_of(pointee.ty)?.size
+        } else {
+            Size::from_bytes(alloc_size.bytes().saturating_sub(offset.bytes()))
+        };
+        if size.bytes() != 0 {
+            this.get_ptr_alloc(ptr, size)?;
+            if pointee.mutbl.is_mut() {
+                let old = this
+                    .get_alloc_raw(alloc_id)?
+                    .inspect_with_uninit_and_ptr_outside_interpreter(
+                        offset.bytes_usize()..(offset + size).bytes_usize(),
+                    )
+                    .to_vec();
+                writes.push(NativeWrite { ptr, alloc_id, offset, old });
+            }
+        }
+        Ok(carg)
+    }
+
+    /// Expose the provenance of all pointers stored in memory reachable from `roots`, since the
+    /// native code can follow them, and keep them around for later calls. Returns all the
+    /// reachable allocations.
+    fn expose_reachable_memory(&mut self, roots: Vec<AllocId>) -> InterpResult<'tcx, Vec<AllocId>> {
+        let this = self.eval_context_mut();
+        let mut reachable = FxHashSet::default();
+        let mut todo = roots;
+        while let Some(alloc_id) = todo.pop() {
+            if !reachable.insert(alloc_id) {
+                continue;
+            }
+            if this.get_alloc_info(alloc_id).2 != AllocKind::LiveData {
+                continue;
+            }
+            let provenances =
+                this.get_alloc_raw(alloc_id)?.provenance().provenances().collect::<Vec<_>>();
+            for prov in provenances {
+                // Wildcard pointers can only point to exposed allocations, which native code was
+                // given access to before.
+                if let Provenance::Concrete { alloc_id, tag } = prov {
+                    this.expose_ptr(alloc_id, tag)?;
+                    todo.push(alloc_id);
+                }
+            }
+        }
+        Ok(reachable.into_iter().collect())
+    }
+
+    /// Account for what the native code might have done to the memory it could reach, after
+    /// the call returned.
+    ///
+    /// The parts behind mutable pointer arguments that changed count as written through those
+    /// pointers, so the write is checked like any other. Beyond that, we cannot know what the
+    /// native code did, so all the bytes of mutable reachable allocations become initialized,
+    /// with wildcard provenance, since the native code might have stored any pointer it had
+    /// access to there.
+    fn finish_native_access(
+        &mut self,
+        reachable: Vec<AllocId>,
+        writes: Vec<NativeWrite>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        for NativeWrite { ptr, alloc_id, offset, old } in writes {
+            let start = offset.bytes_usize();
+            let new = this
+                .get_alloc_raw(alloc_id)?
+                .inspect_with_uninit_and_ptr_outside_interpreter(start..start + old.len());
+            let Some(first) = (0..old.len()).find(|&i| old[i] != new[i]) else { continue };
+            let last = (0..old.len()).rfind(|&i| old[i] != new[i]).unwrap();
+            let ptr = ptr.wrapping_offset(Size::from_bytes(first), this);
+            this.get_ptr_alloc_mut(ptr, Size::from_bytes(last + 1 - first))?;
+        }
+
+        let tcx = this.tcx;
+        let ptr_size = tcx.data_layout.pointer_size;
+        for alloc_id in reachable {
+            if this.get_alloc_info(alloc_id).2 != AllocKind::LiveData
+                || this.get_alloc_mutability(alloc_id)?.is_not()
+            {
+                continue;
+            }
+            let (alloc, _machine) = this.get_alloc_raw_mut(alloc_id)?;
+            let bytes =
+                alloc.inspect_with_uninit_and_ptr_outside_interpreter(0..alloc.len()).to_vec();
+            let mut offset = 0;
+            while offset < bytes.len() {
+                let (val, size) = if let Some(chunk) = bytes[offset..].get(..ptr_size.bytes_usize())
+                {
+                    let addr = read_target_uint(tcx.data_layout.endian, chunk).unwrap();
+                    let ptr = Pointer::new(Provenance::Wildcard, Size::from_bytes(addr));
+                    (Scalar::from_pointer(ptr, &tcx), ptr_size)
+                } else {
+                    (Scalar::from_u8(bytes[offset]), Size::from_bytes(1))
+                };
+                alloc
+                    .write_scalar(&tcx, alloc_range(Size::from_bytes(offset), size), val)
+                    .map_err(|e| e.to_interp_error(alloc_id))?;
+                offset += size.bytes_usize();
+            }
+        }
+        Ok(())
+    }
+
     /// Call external C function and
     /// store output, depending on return type in the function signature.
     fn call_external_c_and_store_return<'a>(
@@ -221,12 +360,17 @@ fn call_external_c_fct(
 
         // Get the function arguments, and convert them to `libffi`-compatible form.
         let mut libffi_args = Vec::<CArg>::with_capacity(args.len());
+        let mut roots = Vec::new();
+        let mut writes = Vec::new();
         for cur_arg in args.iter() {
-            libffi_args.push(Self::scalar_to_carg(
-                this.read_scalar(cur_arg)?,
-                cur_arg.layout.ty,
-                this,
-            )?);
+            let carg = match cur_arg.layout.ty.kind() {
+                ty::RawPtr(..) | ty::Ref(..) => {
+                    let ptr = this.read_pointer(cur_arg)?;
+                    this.pointer_to_carg(ptr, cur_arg.layout.ty, &mut roots, &mut writes)?
+                }
+                _ => Self::scalar_to_carg(this.read_scalar(cur_arg)?, cur_arg.layout.ty, this)?,
+            };
+            libffi_args.push(carg);
         }
 
         // Convert them to `libffi::high::Arg` type.
@@ -235,12 +379,25 @@ fn call_external_c_fct(
             .map(|cur_arg| cur_arg.arg_downcast())
             .collect::<Vec<libffi::high::Arg<'_>>>();
 
+        let reachable = this.expose_reachable_memory(roots)?;
         // Call the function and store output, depending on return type in the function signature.
-        self.call_external_c_and_store_return(link_name, dest, code_ptr, libffi_args)?;
+        this.call_external_c_and_store_return(link_name, dest, code_ptr, libffi_args)?;
+        this.finish_native_access(reachable, writes)?;
         Ok(true)
     }
 }
 
+/// The part of an allocation that native code may write through a mutable pointer argument.
+pub struct NativeWrite {
+    /// The pointer argument, which points to the start of the part.
+    ptr: Pointer<Option<Provenance>>,
+    alloc_id: AllocId,
+    /// The offset of the part in the allocation.
+    offset: Size,
+    /// The bytes of the part before the call.
+    old: Vec<u8>,
+}
+
 #[derive(Debug, Clone)]
 /// Enum of supported arguments to external C functions.
 // We introduce this enum instead of just calling `ffi::arg` and storing a list
@@ -268,6 +425,8 @@ pub enum CArg {
     UInt64(u64),
     /// usize.
     USize(usize),
+    /// Raw pointer.
+    RawPtr(*mut c_void),
 }
 
 impl<'a> CArg {
@@ -284,6 +443,7 @@ fn arg_downcast(&'a self) -> libffi::high::Arg<'a> {
             CArg::UInt32(i) => ffi::arg(i),
             CArg::UInt64(i) => ffi::arg(i),
             CArg::USize(i) => ffi::arg(i),
+            CArg::RawPtr(i) => ffi::arg(i),
         }
     }
 }
@@ -103,6 +103,11 @@ fn emulate_intrinsic_by_name(
             "const_deallocate" => {
                 // complete NOP
             }
+            "const_make_global" => {
+                let [ptr] = check_arg_count(args)?;
+                let ptr = this.read_pointer(ptr)?;
+                this.write_pointer(ptr, dest)?;
+            }
 
             // Raw memory accesses
             "volatile_load" => {
@@ -17,6 +17,8 @@
 use crate::shims::os_str::bytes_to_os_str;
 use crate::*;
 use shims::time::system_time_to_duration;
+use shims::unix::linux::fd::epoll::{Epoll, EpollReadyEvents};
+use shims::unix::linux::fd::EvalContextExt as _;
 
 #[derive(Debug)]
 pub struct FileHandle {
@@ -65,6 +67,11 @@ fn close<'tcx>(
     fn is_tty(&self, _communicate_allowed: bool) -> bool {
         false
     }
+
+    /// Check the readiness of this file description for `epoll_wait`.
+    fn get_epoll_ready_events<'tcx>(&self) -> InterpResult<'tcx, EpollReadyEvents> {
+        throw_unsup_format!("cannot use {} with epoll", self.name());
+    }
 }
 
 impl dyn FileDescriptor {
@@ -262,8 +269,14 @@ pub struct FileHandler {
 }
 
 impl VisitProvenance for FileHandler {
-    fn visit_provenance(&self, _visit: &mut VisitWith<'_>) {
-        // All our FileDescriptor do not have any tags.
+    fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
+        // Only epoll instances hold on to tags: the registered user data, and the buffers of
+        // threads blocked in `epoll_wait`.
+        for handle in self.handles.values() {
+            if let Some(epoll) = handle.downcast_ref::<Epoll>() {
+                epoll.visit_provenance(visit);
+            }
+        }
     }
 }
 
@@ -711,6 +724,9 @@ fn close(&mut self, fd_op: &OpTy<'tcx, Provenance>) -> InterpResult<'tcx, Scalar
         Ok(Scalar::from_i32(
             if let Some(file_descriptor) = this.machine.file_handler.handles.remove(&fd) {
                 let result = file_descriptor.close(this.machine.communicate())?;
+                // Closing a file descriptor can make its peer ready, and removes it from the
+                // interest list of all epoll instances.
+                this.epoll_notify(Some(fd))?;
                 this.try_unwrap_io_result(result)?
             } else {
                 this.handle_not_found()?
@@ -766,7 +782,13 @@ fn read(
             match result {
                 Ok(read_bytes) => {
                     // If reading to `bytes` did not fail, we write those bytes to the buffer.
-                    this.write_bytes_ptr(buf, bytes)?;
+                    // Remember that we were asked to read `count` bytes, but maybe got fewer.
+                    let read_len = usize::try_from(read_bytes).unwrap();
+                    this.write_bytes_ptr(buf, bytes[..read_len].iter().copied())?;
+                    // The read might have made room for writes, e.g. by resetting the counter of
+                    // an eventfd, so threads blocked in `epoll_wait` on `EPOLLOUT` might have to
+                    // wake up.
+                    this.epoll_notify(None)?;
                     Ok(read_bytes)
                 }
                 Err(e) => {
@@ -805,6 +827,9 @@ fn write(
             let result = file_descriptor
                 .write(communicate, bytes, *this.tcx)?
                 .map(|c| i64::try_from(c).unwrap());
+            // The write might have made some file descriptor ready, so threads blocked in
+            // `epoll_wait` might have to wake up.
+            this.epoll_notify(None)?;
             this.try_unwrap_io_result(result)
         } else {
             this.handle_not_found()
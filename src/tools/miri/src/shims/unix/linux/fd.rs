@@ -1,16 +1,21 @@
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+use std::time::Duration;
 
 use rustc_middle::ty::ScalarInt;
 
+use crate::concurrency::thread::MachineCallback;
 use crate::*;
-use epoll::{Epoll, EpollEvent};
+use epoll::{Epoll, EpollEvent, EpollState, EpollWaiter};
 use event::Event;
 use socketpair::SocketPair;
 
 use shims::unix::fs::EvalContextExt as _;
+use shims::unix::linux::net::EvalContextExt as _;
 
 pub mod epoll;
 pub mod event;
+pub mod socket;
 pub mod socketpair;
 
 impl<'mir, 'tcx: 'mir> EvalContextExt<'mir, 'tcx> for crate::MiriInterpCx<'mir, 'tcx> {}
@@ -70,36 +75,64 @@ fn epoll_ctl(
         let epoll_ctl_mod = this.eval_libc_i32("EPOLL_CTL_MOD");
         let epoll_ctl_del = this.eval_libc_i32("EPOLL_CTL_DEL");
 
+        let Some(epoll) = this.machine.file_handler.handles.get(&epfd) else {
+            return Ok(Scalar::from_i32(this.handle_not_found()?));
+        };
+        let state = epoll
+            .downcast_ref::<Epoll>()
+            .ok_or_else(|| err_unsup_format!("non-epoll FD passed to `epoll_ctl`"))?
+            .state
+            .clone();
+        if epfd == fd {
+            let einval = this.eval_libc("EINVAL");
+            this.set_last_error(einval)?;
+            return Ok(Scalar::from_i32(-1));
+        }
+        let Some(file_descriptor) = this.machine.file_handler.handles.get(&fd) else {
+            return Ok(Scalar::from_i32(this.handle_not_found()?));
+        };
+
         if op == epoll_ctl_add || op == epoll_ctl_mod {
+            // Make sure we can actually tell when this file descriptor is ready.
+            file_descriptor.get_epoll_ready_events()?;
+
             let event = this.deref_pointer_as(event, this.libc_ty_layout("epoll_event"))?;
 
             let events = this.project_field(&event, 0)?;
             let events = this.read_scalar(&events)?.to_u32()?;
             let data = this.project_field(&event, 1)?;
             let data = this.read_scalar(&data)?;
-            let event = EpollEvent { events, data };
-
-            if let Some(epfd) = this.machine.file_handler.handles.get_mut(&epfd) {
-                let epfd = epfd
-                    .downcast_mut::<Epoll>()
-                    .ok_or_else(|| err_unsup_format!("non-epoll FD passed to `epoll_ctl`"))?;
-
-                epfd.file_descriptors.insert(fd, event);
-                Ok(Scalar::from_i32(0))
-            } else {
-                Ok(Scalar::from_i32(this.handle_not_found()?))
+            // The current readiness gets reported even for edge-triggered interests.
+            let event = EpollEvent { events, data, edge_pending: true, disabled: false };
+
+            let result = {
+                let interest_list = &mut state.borrow_mut().interest_list;
+                if op == epoll_ctl_add {
+                    interest_list.try_insert(fd, event).map(|_| ()).map_err(|_| "EEXIST")
+                } else if let Some(interest) = interest_list.get_mut(&fd) {
+                    *interest = event;
+                    Ok(())
+                } else {
+                    Err("ENOENT")
+                }
+            };
+            if let Err(errno) = result {
+                let errno = this.eval_libc(errno);
+                this.set_last_error(errno)?;
+                return Ok(Scalar::from_i32(-1));
             }
+
+            // The file descriptor might already be ready, in which case threads blocked on this
+            // instance have to wake up.
+            this.epoll_notify(None)?;
+            Ok(Scalar::from_i32(0))
         } else if op == epoll_ctl_del {
-            if let Some(epfd) = this.machine.file_handler.handles.get_mut(&epfd) {
-                let epfd = epfd
-                    .downcast_mut::<Epoll>()
-                    .ok_or_else(|| err_unsup_format!("non-epoll FD passed to `epoll_ctl`"))?;
-
-                epfd.file_descriptors.remove(&fd);
-                Ok(Scalar::from_i32(0))
-            } else {
-                Ok(Scalar::from_i32(this.handle_not_found()?))
+            if state.borrow_mut().interest_list.remove(&fd).is_none() {
+                let enoent = this.eval_libc("ENOENT");
+                this.set_last_error(enoent)?;
+                return Ok(Scalar::from_i32(-1));
             }
+            Ok(Scalar::from_i32(0))
         } else {
             let einval = this.eval_libc("EINVAL");
             this.set_last_error(einval)?;
@@ -142,24 +175,202 @@ fn epoll_wait(
         events: &OpTy<'tcx, Provenance>,
         maxevents: &OpTy<'tcx, Provenance>,
         timeout: &OpTy<'tcx, Provenance>,
-    ) -> InterpResult<'tcx, Scalar<Provenance>> {
+        dest: &PlaceTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx> {
         let this = self.eval_context_mut();
 
         let epfd = this.read_scalar(epfd)?.to_i32()?;
-        let _events = this.read_scalar(events)?.to_pointer(this)?;
-        let _maxevents = this.read_scalar(maxevents)?.to_i32()?;
-        let _timeout = this.read_scalar(timeout)?.to_i32()?;
+        let events = this.read_scalar(events)?.to_pointer(this)?;
+        let maxevents = this.read_scalar(maxevents)?.to_i32()?;
+        let timeout = this.read_scalar(timeout)?.to_i32()?;
 
-        if let Some(epfd) = this.machine.file_handler.handles.get_mut(&epfd) {
-            let _epfd = epfd
-                .downcast_mut::<Epoll>()
-                .ok_or_else(|| err_unsup_format!("non-epoll FD passed to `epoll_wait`"))?;
+        if maxevents <= 0 {
+            let einval = this.eval_libc("EINVAL");
+            this.set_last_error(einval)?;
+            this.write_scalar(Scalar::from_i32(-1), dest)?;
+            return Ok(());
+        }
 
-            // FIXME return number of events ready when scheme for marking events ready exists
-            throw_unsup_format!("returning ready events from epoll_wait is not yet implemented");
-        } else {
-            Ok(Scalar::from_i32(this.handle_not_found()?))
+        let Some(epoll) = this.machine.file_handler.handles.get(&epfd) else {
+            let result = this.handle_not_found()?;
+            this.write_scalar(Scalar::from_i32(result), dest)?;
+            return Ok(());
+        };
+        let state = epoll
+            .downcast_ref::<Epoll>()
+            .ok_or_else(|| err_unsup_format!("non-epoll FD passed to `epoll_wait`"))?
+            .state
+            .clone();
+
+        let ready = this.epoll_collect_ready_events(&state, maxevents)?;
+        if !ready.is_empty() || timeout == 0 {
+            if !ready.is_empty()
+                && let Some(data_race) = &this.machine.data_race
+            {
+                data_race.validate_lock_acquire(&state.borrow().clock, this.get_active_thread());
+            }
+            let count = this.epoll_write_events(events, &ready)?;
+            this.write_scalar(Scalar::from_i32(count), dest)?;
+            return Ok(());
+        }
+
+        // Nothing is ready yet, so we block until `epoll_notify` hands us some events. The result
+        // is written once we wake up, so it has to live in memory.
+        let thread = this.get_active_thread();
+        let dest = this.force_allocation(dest)?.ptr();
+        state.borrow_mut().waiters.push_back(EpollWaiter { thread, events, maxevents, dest });
+        this.block_thread(thread);
+        // A negative timeout means that we wait indefinitely.
+        if let Ok(timeout) = u64::try_from(timeout) {
+            let duration = Duration::from_millis(timeout);
+            let timeout_time = this.machine.clock.now().checked_add(duration).unwrap();
+            this.register_timeout_callback(
+                thread,
+                Time::Monotonic(timeout_time),
+                Box::new(EpollTimeoutCallback { thread, state: Rc::downgrade(&state), dest }),
+            );
+        }
+        Ok(())
+    }
+
+    /// Collects the events of up to `maxevents` ready file descriptors in the interest list of an
+    /// epoll instance. Edge-triggered and one-shot interests are consumed by being reported.
+    fn epoll_collect_ready_events(
+        &mut self,
+        state: &RefCell<EpollState>,
+        maxevents: i32,
+    ) -> InterpResult<'tcx, Vec<(u32, Scalar<Provenance>)>> {
+        let this = self.eval_context_mut();
+
+        let epollet = this.eval_libc_u32("EPOLLET");
+        let epolloneshot = this.eval_libc_u32("EPOLLONESHOT");
+        let epollhup = this.eval_libc_u32("EPOLLHUP");
+        let epollerr = this.eval_libc_u32("EPOLLERR");
+        let maxevents = usize::try_from(maxevents).unwrap();
+
+        let mut ready = Vec::new();
+        for (fd, interest) in state.borrow_mut().interest_list.iter_mut() {
+            if ready.len() == maxevents {
+                break;
+            }
+            if interest.disabled {
+                continue;
+            }
+            let file_descriptor = this
+                .machine
+                .file_handler
+                .handles
+                .get(fd)
+                .expect("closed file descriptors are removed from all interest lists");
+            let readiness = file_descriptor.get_epoll_ready_events()?.get_event_bitmask(this);
+            // Hang ups and errors are always reported, even if they were not asked for.
+            let events = readiness & (interest.events | epollhup | epollerr);
+            if events == 0 {
+                continue;
+            }
+            if interest.events & epollet != 0 {
+                if !interest.edge_pending {
+                    continue;
+                }
+                interest.edge_pending = false;
+            }
+            if interest.events & epolloneshot != 0 {
+                interest.disabled = true;
+            }
+            ready.push((events, interest.data));
+        }
+        Ok(ready)
+    }
+
+    /// Writes `ready` events to the `epoll_event` array at `events`, and returns how many there
+    /// were.
+    fn epoll_write_events(
+        &mut self,
+        events: Pointer<Option<Provenance>>,
+        ready: &[(u32, Scalar<Provenance>)],
+    ) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let event_layout = this.libc_ty_layout("epoll_event");
+        for (i, &(events_bitmask, data)) in ready.iter().enumerate() {
+            let offset = event_layout.size * u64::try_from(i).unwrap();
+            let event = this.ptr_to_mplace(events.offset(offset, this)?, event_layout);
+            let events_field = this.project_field(&event, 0)?;
+            this.write_scalar(Scalar::from_u32(events_bitmask), &events_field)?;
+            let data_field = this.project_field(&event, 1)?;
+            this.write_scalar(data, &data_field)?;
+        }
+        Ok(i32::try_from(ready.len()).unwrap())
+    }
+
+    /// Must be called whenever file descriptors might have become ready, i.e. after every read,
+    /// write and close. Threads blocked in `epoll_wait` on an instance that now has events ready are
+    /// woken up, in the order they started waiting. If `closed_fd` is given, that file descriptor
+    /// was just closed and gets removed from all interest lists.
+    ///
+    /// Threads blocked in socket calls get to retry them as well.
+    fn epoll_notify(&mut self, closed_fd: Option<i32>) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        this.socket_notify()?;
+
+        // `dup`ed epoll file descriptors share their state, so every instance is collected once.
+        let mut instances: Vec<Rc<RefCell<EpollState>>> = Vec::new();
+        for file_descriptor in this.machine.file_handler.handles.values() {
+            if let Some(epoll) = file_descriptor.downcast_ref::<Epoll>()
+                && !instances.iter().any(|state| Rc::ptr_eq(state, &epoll.state))
+            {
+                instances.push(epoll.state.clone());
+            }
+        }
+
+        let current_thread = this.get_active_thread();
+        let current_span = this.machine.current_span();
+        for state in instances {
+            {
+                let mut state = state.borrow_mut();
+                if let Some(fd) = closed_fd {
+                    state.interest_list.remove(&fd);
+                }
+                // We do not know which file descriptors changed, so we assume they all did.
+                for interest in state.interest_list.values_mut() {
+                    interest.edge_pending = true;
+                }
+                // For the same reason, this thread synchronizes with every later report of
+                // events by this instance.
+                if let Some(data_race) = &this.machine.data_race {
+                    data_race.validate_lock_release_shared(
+                        &mut state.clock,
+                        current_thread,
+                        current_span,
+                    );
+                }
+            }
+            loop {
+                let waiter = state.borrow().waiters.front().copied();
+                let Some(EpollWaiter { thread, events, maxevents, dest }) = waiter else {
+                    break;
+                };
+                let ready = this.epoll_collect_ready_events(&state, maxevents)?;
+                if ready.is_empty() {
+                    break;
+                }
+                state.borrow_mut().waiters.pop_front();
+                if let Some(data_race) = &this.machine.data_race {
+                    data_race.validate_lock_acquire(&state.borrow().clock, thread);
+                }
+                // The events are written by the woken up thread itself, so we replace its timeout
+                // with a callback that fires right away.
+                this.unregister_timeout_callback_if_exists(thread);
+                let now = this.machine.clock.now();
+                this.register_timeout_callback(
+                    thread,
+                    Time::Monotonic(now),
+                    Box::new(EpollReadyCallback { thread, events, dest, ready }),
+                );
+            }
         }
+        Ok(())
     }
 
     /// This function creates an `Event` that is used as an event wait/notify mechanism by
@@ -171,14 +382,12 @@ fn epoll_wait(
     /// `select`, and `close` operations can be performed on the file descriptor. For more
     /// information on these operations, see the man page linked below.
     ///
-    /// The `flags` are not currently implemented for eventfd.
     /// The `flags` may be bitwise ORed to change the behavior of `eventfd`:
     /// `EFD_CLOEXEC` - Set the close-on-exec (`FD_CLOEXEC`) flag on the new file descriptor.
     /// `EFD_NONBLOCK` - Set the `O_NONBLOCK` file status flag on the new open file description.
     /// `EFD_SEMAPHORE` - miri does not support semaphore-like semantics.
     ///
     /// <https://linux.die.net/man/2/eventfd>
-    #[expect(clippy::needless_if)]
     fn eventfd(
         &mut self,
         val: &OpTy<'tcx, Provenance>,
@@ -187,32 +396,31 @@ fn eventfd(
         let this = self.eval_context_mut();
 
         let val = this.read_scalar(val)?.to_u32()?;
-        let flags = this.read_scalar(flags)?.to_i32()?;
+        let mut flags = this.read_scalar(flags)?.to_i32()?;
 
         let efd_cloexec = this.eval_libc_i32("EFD_CLOEXEC");
         let efd_nonblock = this.eval_libc_i32("EFD_NONBLOCK");
         let efd_semaphore = this.eval_libc_i32("EFD_SEMAPHORE");
 
-        if flags & (efd_cloexec | efd_nonblock | efd_semaphore) == 0 {
-            throw_unsup_format!("{flags} is unsupported");
-        }
-        // FIXME handle the cloexec and nonblock flags
-        if flags & efd_cloexec == efd_cloexec {}
-        if flags & efd_nonblock == efd_nonblock {}
         if flags & efd_semaphore == efd_semaphore {
             throw_unsup_format!("EFD_SEMAPHORE is unsupported");
         }
+        // Miri does not support exec, so the cloexec flag has no effect.
+        flags &= !efd_cloexec;
+        let is_nonblock = flags & efd_nonblock == efd_nonblock;
+        flags &= !efd_nonblock;
+        if flags != 0 {
+            throw_unsup_format!("eventfd flags {flags:#x} are unsupported");
+        }
 
         let fh = &mut this.machine.file_handler;
-        let fd = fh.insert_fd(Box::new(Event { val: Cell::new(val.into()) }));
+        let fd = fh.insert_fd(Box::new(Event { val: Rc::new(Cell::new(val.into())), is_nonblock }));
         Ok(Scalar::from_i32(fd))
     }
 
-    /// Currently this function creates new `SocketPair`s without specifying the domain, type, or
-    /// protocol of the new socket and these are stored in the socket values `sv` argument.
-    ///
     /// This function creates an unnamed pair of connected sockets in the specified domain, of the
-    /// specified type, and using the optionally specified protocol.
+    /// specified type, and using the optionally specified protocol. The file descriptors of the
+    /// new sockets are stored in the socket values `sv` argument.
     ///
     /// The `domain` argument specified a communication domain; this selects the protocol family
     /// used for communication. The socket `type` specifies the communication semantics.
@@ -221,6 +429,9 @@ fn eventfd(
     /// family, in which case `protocol` can be specified as 0. It is possible that many protocols
     /// exist and in that case, a particular protocol must be specified.
     ///
+    /// Only `AF_UNIX` stream sockets are currently supported. The `type` may be bitwise ORed with
+    /// `SOCK_NONBLOCK` and `SOCK_CLOEXEC`.
+    ///
     /// For more information on the arguments see the socket manpage:
     /// <https://linux.die.net/man/2/socket>
     ///
@@ -234,15 +445,34 @@ fn socketpair(
     ) -> InterpResult<'tcx, Scalar<Provenance>> {
         let this = self.eval_context_mut();
 
-        let _domain = this.read_scalar(domain)?.to_i32()?;
-        let _type_ = this.read_scalar(type_)?.to_i32()?;
-        let _protocol = this.read_scalar(protocol)?.to_i32()?;
+        let domain = this.read_scalar(domain)?.to_i32()?;
+        let mut type_ = this.read_scalar(type_)?.to_i32()?;
+        let protocol = this.read_scalar(protocol)?.to_i32()?;
         let sv = this.deref_pointer(sv)?;
 
+        let af_unix = this.eval_libc_i32("AF_UNIX");
+        let sock_stream = this.eval_libc_i32("SOCK_STREAM");
+        let sock_nonblock = this.eval_libc_i32("SOCK_NONBLOCK");
+        let sock_cloexec = this.eval_libc_i32("SOCK_CLOEXEC");
+
+        if domain != af_unix {
+            throw_unsup_format!("socketpair: domain {domain:#x} is unsupported, only AF_UNIX is");
+        }
+        if protocol != 0 {
+            throw_unsup_format!("socketpair: non-zero protocol {protocol:#x} is unsupported");
+        }
+        let is_nonblock = type_ & sock_nonblock == sock_nonblock;
+        // Miri does not support exec, so the cloexec flag has no effect.
+        type_ &= !(sock_nonblock | sock_cloexec);
+        if type_ != sock_stream {
+            throw_unsup_format!("socketpair: type {type_:#x} is unsupported, only SOCK_STREAM is");
+        }
+
+        let (end0, end1) = SocketPair::new_pair(/*is_socket*/ true, is_nonblock);
         let fh = &mut this.machine.file_handler;
-        let sv0 = fh.insert_fd(Box::new(SocketPair));
+        let sv0 = fh.insert_fd(Box::new(end0));
         let sv0 = ScalarInt::try_from_int(sv0, sv.layout.size).unwrap();
-        let sv1 = fh.insert_fd(Box::new(SocketPair));
+        let sv1 = fh.insert_fd(Box::new(end1));
         let sv1 = ScalarInt::try_from_int(sv1, sv.layout.size).unwrap();
 
         this.write_scalar(sv0, &sv)?;
@@ -250,4 +480,104 @@ fn socketpair(
 
         Ok(Scalar::from_i32(0))
     }
+
+    /// This function creates a pipe, a unidirectional data channel. The file descriptor of the
+    /// read end of the pipe is stored in `pipefd[0]`, the one of the write end in `pipefd[1]`.
+    ///
+    /// The `flags` may be bitwise ORed from `O_NONBLOCK` and `O_CLOEXEC`. `pipe` is the same as
+    /// `pipe2` with `flags` set to 0.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/pipe.2.html>
+    fn pipe2(
+        &mut self,
+        pipefd: &OpTy<'tcx, Provenance>,
+        flags: Option<&OpTy<'tcx, Provenance>>,
+    ) -> InterpResult<'tcx, Scalar<Provenance>> {
+        let this = self.eval_context_mut();
+
+        let pipefd = this.deref_pointer(pipefd)?;
+        let mut flags = match flags {
+            Some(flags) => this.read_scalar(flags)?.to_i32()?,
+            None => 0,
+        };
+
+        let o_nonblock = this.eval_libc_i32("O_NONBLOCK");
+        let o_cloexec = this.eval_libc_i32("O_CLOEXEC");
+
+        let is_nonblock = flags & o_nonblock == o_nonblock;
+        // Miri does not support exec, so the cloexec flag has no effect.
+        flags &= !(o_nonblock | o_cloexec);
+        if flags != 0 {
+            throw_unsup_format!("pipe2: flags {flags:#x} are unsupported");
+        }
+
+        let (read_end, write_end) = SocketPair::new_pair(/*is_socket*/ false, is_nonblock);
+        let fh = &mut this.machine.file_handler;
+        let read_end = fh.insert_fd(Box::new(read_end));
+        let read_end = ScalarInt::try_from_int(read_end, pipefd.layout.size).unwrap();
+        let write_end = fh.insert_fd(Box::new(write_end));
+        let write_end = ScalarInt::try_from_int(write_end, pipefd.layout.size).unwrap();
+
+        this.write_scalar(read_end, &pipefd)?;
+        this.write_scalar(write_end, &pipefd.offset(pipefd.layout.size, pipefd.layout, this)?)?;
+
+        Ok(Scalar::from_i32(0))
+    }
+}
+
+/// Returns from a blocked `epoll_wait` on the thread that called it, once `epoll_notify` handed
+/// it some ready events.
+struct EpollReadyCallback {
+    thread: ThreadId,
+    events: Pointer<Option<Provenance>>,
+    dest: Pointer<Option<Provenance>>,
+    ready: Vec<(u32, Scalar<Provenance>)>,
+}
+
+impl VisitProvenance for EpollReadyCallback {
+    fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
+        let EpollReadyCallback { thread: _, events, dest, ready } = self;
+        events.visit_provenance(visit);
+        dest.visit_provenance(visit);
+        for (_, data) in ready {
+            data.visit_provenance(visit);
+        }
+    }
+}
+
+impl<'mir, 'tcx: 'mir> MachineCallback<'mir, 'tcx> for EpollReadyCallback {
+    fn call(&self, this: &mut MiriInterpCx<'mir, 'tcx>) -> InterpResult<'tcx> {
+        this.unblock_thread(self.thread);
+        let count = this.epoll_write_events(self.events, &self.ready)?;
+        let dest = this.ptr_to_mplace(self.dest, this.machine.layouts.i32);
+        this.write_scalar(Scalar::from_i32(count), &dest)?;
+        Ok(())
+    }
+}
+
+/// Returns zero events from a blocked `epoll_wait` once its timeout expired.
+struct EpollTimeoutCallback {
+    thread: ThreadId,
+    state: Weak<RefCell<EpollState>>,
+    dest: Pointer<Option<Provenance>>,
+}
+
+impl VisitProvenance for EpollTimeoutCallback {
+    fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
+        let EpollTimeoutCallback { thread: _, state: _, dest } = self;
+        dest.visit_provenance(visit);
+    }
+}
+
+impl<'mir, 'tcx: 'mir> MachineCallback<'mir, 'tcx> for EpollTimeoutCallback {
+    fn call(&self, this: &mut MiriInterpCx<'mir, 'tcx>) -> InterpResult<'tcx> {
+        // The epoll instance might have been closed in the meantime.
+        if let Some(state) = self.state.upgrade() {
+            state.borrow_mut().waiters.retain(|waiter| waiter.thread != self.thread);
+        }
+        this.unblock_thread(self.thread);
+        let dest = this.ptr_to_mplace(self.dest, this.machine.layouts.i32);
+        this.write_scalar(Scalar::from_i32(0), &dest)?;
+        Ok(())
+    }
 }
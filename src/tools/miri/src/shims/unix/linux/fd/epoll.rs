@@ -1,19 +1,36 @@
 use crate::*;
 
+use crate::concurrency::vector_clock::VClock;
 use crate::shims::unix::fs::FileDescriptor;
 
-use rustc_data_structures::fx::FxHashMap;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
 use std::io;
+use std::rc::Rc;
 
 /// An `Epoll` file descriptor connects file handles and epoll events
 #[derive(Clone, Debug, Default)]
 pub struct Epoll {
+    /// The state of this epoll instance. It is shared by all file descriptors that were
+    /// `dup`ed from the one returned by `epoll_create1`.
+    pub state: Rc<RefCell<EpollState>>,
+}
+
+/// The interest list and the waiting threads of an `Epoll` instance.
+#[derive(Debug, Default)]
+pub struct EpollState {
     /// The file descriptors we are watching, and what we are watching for.
-    pub file_descriptors: FxHashMap<i32, EpollEvent>,
+    /// This is ordered by file descriptor so that ready events are reported deterministically.
+    pub interest_list: BTreeMap<i32, EpollEvent>,
+    /// The threads blocked in `epoll_wait` on this instance, in the order they started waiting.
+    pub waiters: VecDeque<EpollWaiter>,
+    /// The clocks of the threads that might have made file descriptors ready. Reporting events
+    /// acquires them, so that whatever happened before a file descriptor became ready
+    /// happens-before `epoll_wait` returns.
+    pub clock: VClock,
 }
 
 /// Epoll Events associate events with data.
-/// These fields are currently unused by miri.
 /// This matches the `epoll_event` struct defined
 /// by the epoll_ctl man page. For more information
 /// see the man page:
@@ -25,6 +42,87 @@ pub struct EpollEvent {
     /// `Scalar<Provenance>` is used to represent the
     /// `epoll_data` type union.
     pub data: Scalar<Provenance>,
+    /// For edge-triggered (`EPOLLET`) interests: whether the file description may have changed
+    /// since its readiness was last reported. We do not track which file description a write
+    /// affects, so every write or close sets this for all interests. This may cause spurious
+    /// wakeups, which edge-triggered users have to deal with anyway.
+    pub edge_pending: bool,
+    /// For `EPOLLONESHOT` interests: whether an event was already reported, which disables the
+    /// interest until it is re-armed with `EPOLL_CTL_MOD`.
+    pub disabled: bool,
+}
+
+/// A thread blocked in `epoll_wait`. The pointers are stored without their layout since file
+/// descriptors cannot hold on to anything with a `'tcx` lifetime.
+#[derive(Clone, Copy, Debug)]
+pub struct EpollWaiter {
+    pub thread: ThreadId,
+    /// The buffer the ready events are written to.
+    pub events: Pointer<Option<Provenance>>,
+    /// The capacity of `events`, always positive.
+    pub maxevents: i32,
+    /// The `i32` place that `epoll_wait` returns its result to.
+    pub dest: Pointer<Option<Provenance>>,
+}
+
+/// The readiness of a file description, which `epoll_wait` reports as events.
+#[derive(Debug, Default)]
+pub struct EpollReadyEvents {
+    /// The associated file is available for read operations (`EPOLLIN`).
+    pub epollin: bool,
+    /// The associated file is available for write operations (`EPOLLOUT`).
+    pub epollout: bool,
+    /// The peer closed its end of the connection, or shut down its writing half (`EPOLLRDHUP`).
+    pub epollrdhup: bool,
+    /// Hang up happened on the associated file descriptor (`EPOLLHUP`).
+    /// This is always reported, whether or not it was requested.
+    pub epollhup: bool,
+    /// Error condition happened on the associated file descriptor (`EPOLLERR`).
+    /// This is always reported, whether or not it was requested.
+    pub epollerr: bool,
+}
+
+impl EpollReadyEvents {
+    pub fn get_event_bitmask<'tcx>(&self, ecx: &MiriInterpCx<'_, 'tcx>) -> u32 {
+        let epollin = ecx.eval_libc_u32("EPOLLIN");
+        let epollout = ecx.eval_libc_u32("EPOLLOUT");
+        let epollrdhup = ecx.eval_libc_u32("EPOLLRDHUP");
+        let epollhup = ecx.eval_libc_u32("EPOLLHUP");
+        let epollerr = ecx.eval_libc_u32("EPOLLERR");
+
+        let mut bitmask = 0;
+        if self.epollin {
+            bitmask |= epollin;
+        }
+        if self.epollout {
+            bitmask |= epollout;
+        }
+        if self.epollrdhup {
+            bitmask |= epollrdhup;
+        }
+        if self.epollhup {
+            bitmask |= epollhup;
+        }
+        if self.epollerr {
+            bitmask |= epollerr;
+        }
+        bitmask
+    }
+}
+
+impl VisitProvenance for Epoll {
+    fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
+        let state = self.state.borrow();
+        let EpollState { interest_list, waiters } = &*state;
+
+        for event in interest_list.values() {
+            event.data.visit_provenance(visit);
+        }
+        for EpollWaiter { thread: _, events, maxevents: _, dest } in waiters {
+            events.visit_provenance(visit);
+            dest.visit_provenance(visit);
+        }
+    }
 }
 
 impl FileDescriptor for Epoll {
@@ -1,4 +1,5 @@
 use crate::shims::unix::fs::FileDescriptor;
+use crate::shims::unix::linux::fd::epoll::EpollReadyEvents;
 
 use rustc_const_eval::interpret::InterpResult;
 use rustc_middle::ty::TyCtxt;
@@ -6,9 +7,12 @@
 
 use std::cell::Cell;
 use std::io;
+use std::rc::Rc;
+
+/// The maximum value that may be stored in the counter of an `Event`.
+const MAX_COUNTER: u64 = u64::MAX - 1;
 
 /// A kind of file descriptor created by `eventfd`.
-/// The `Event` type isn't currently written to by `eventfd`.
 /// The interface is meant to keep track of objects associated
 /// with a file descriptor. For more information see the man
 /// page below:
@@ -18,7 +22,28 @@
 pub struct Event {
     /// The object contains an unsigned 64-bit integer (uint64_t) counter that is maintained by the
     /// kernel. This counter is initialized with the value specified in the argument initval.
-    pub val: Cell<u64>,
+    /// It is shared by all file descriptors that were `dup`ed from the same `eventfd`.
+    pub val: Rc<Cell<u64>>,
+    /// Whether reads and writes fail with `EAGAIN` instead of blocking.
+    pub is_nonblock: bool,
+}
+
+impl Event {
+    /// Converts from target endianess to host endianess.
+    fn from_target_bytes(bytes: [u8; 8], tcx: TyCtxt<'_>) -> u64 {
+        match tcx.sess.target.endian {
+            Endian::Little => u64::from_le_bytes(bytes),
+            Endian::Big => u64::from_be_bytes(bytes),
+        }
+    }
+
+    /// Converts from host endianess to target endianess.
+    fn to_target_bytes(val: u64, tcx: TyCtxt<'_>) -> [u8; 8] {
+        match tcx.sess.target.endian {
+            Endian::Little => val.to_le_bytes(),
+            Endian::Big => val.to_be_bytes(),
+        }
+    }
 }
 
 impl FileDescriptor for Event {
@@ -27,7 +52,7 @@ fn name(&self) -> &'static str {
     }
 
     fn dup(&mut self) -> io::Result<Box<dyn FileDescriptor>> {
-        Ok(Box::new(Event { val: self.val.clone() }))
+        Ok(Box::new(Event { val: Rc::clone(&self.val), is_nonblock: self.is_nonblock }))
     }
 
     fn close<'tcx>(
@@ -37,6 +62,37 @@ fn close<'tcx>(
         Ok(Ok(0))
     }
 
+    /// A read call returns the 8-byte value of the counter in
+    /// its buffer (in native endianess) and resets the counter to zero.
+    /// If the counter is zero at the time of the read, then the
+    /// read either blocks until the counter becomes nonzero, or
+    /// fails with the error EAGAIN if the file descriptor has
+    /// been made nonblocking.
+
+    /// A read fails with the error EINVAL if the size of the
+    /// supplied buffer is less than 8 bytes.
+    fn read<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+        bytes: &mut [u8],
+        tcx: TyCtxt<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<usize>> {
+        let Some(bytes) = bytes.first_chunk_mut::<8>() else {
+            return Ok(Err(io::ErrorKind::InvalidInput.into()));
+        };
+        let val = self.val.get();
+        if val == 0 {
+            if self.is_nonblock {
+                return Ok(Err(io::ErrorKind::WouldBlock.into()));
+            }
+            // FIXME block until the counter becomes nonzero.
+            throw_unsup_format!("blocking reads from an eventfd are not supported");
+        }
+        *bytes = Event::to_target_bytes(val, tcx);
+        self.val.set(0);
+        Ok(Ok(8))
+    }
+
     /// A write call adds the 8-byte integer value supplied in
     /// its buffer (in native endianess) to the counter.  The maximum value that may be
     /// stored in the counter is the largest unsigned 64-bit value
@@ -55,18 +111,30 @@ fn write<'tcx>(
         bytes: &[u8],
         tcx: TyCtxt<'tcx>,
     ) -> InterpResult<'tcx, io::Result<usize>> {
-        let v1 = self.val.get();
-        let bytes: [u8; 8] = bytes.try_into().unwrap(); // FIXME fail gracefully when this has the wrong size
-        // Convert from target endianess to host endianess.
-        let num = match tcx.sess.target.endian {
-            Endian::Little => u64::from_le_bytes(bytes),
-            Endian::Big => u64::from_be_bytes(bytes),
+        let Some(bytes) = bytes.first_chunk::<8>() else {
+            return Ok(Err(io::ErrorKind::InvalidInput.into()));
         };
-        // FIXME handle blocking when addition results in exceeding the max u64 value
-        // or fail with EAGAIN if the file descriptor is nonblocking.
-        let v2 = v1.checked_add(num).unwrap();
-        self.val.set(v2);
-        assert_eq!(8, bytes.len());
+        let num = Event::from_target_bytes(*bytes, tcx);
+        if num == u64::MAX {
+            return Ok(Err(io::ErrorKind::InvalidInput.into()));
+        }
+        match self.val.get().checked_add(num).filter(|&val| val <= MAX_COUNTER) {
+            Some(val) => self.val.set(val),
+            None => {
+                if self.is_nonblock {
+                    return Ok(Err(io::ErrorKind::WouldBlock.into()));
+                }
+                // FIXME block until a read makes room for the addition.
+                throw_unsup_format!("blocking writes to an eventfd are not supported");
+            }
+        }
         Ok(Ok(8))
     }
+
+    /// An eventfd is readable if the counter is nonzero, and writable if a value
+    /// of at least 1 can be added without exceeding the maximum.
+    fn get_epoll_ready_events<'tcx>(&self) -> InterpResult<'tcx, EpollReadyEvents> {
+        let val = self.val.get();
+        Ok(EpollReadyEvents { epollin: val > 0, epollout: val < MAX_COUNTER, ..Default::default() })
+    }
 }
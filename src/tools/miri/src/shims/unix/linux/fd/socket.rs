@@ -0,0 +1,139 @@
+use crate::*;
+
+use crate::shims::unix::fs::FileDescriptor;
+use crate::shims::unix::linux::fd::epoll::EpollReadyEvents;
+use crate::shims::unix::linux::fd::socketpair::SocketPair;
+
+use rustc_middle::ty::TyCtxt;
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io;
+use std::net::SocketAddr;
+use std::rc::Rc;
+
+/// An emulated `AF_INET` or `AF_INET6` socket. These only ever talk to other sockets of the same
+/// program over the loopback interface; see `shims::unix::linux::net` for how they are bound and
+/// connected.
+#[derive(Clone, Debug)]
+pub struct InetSocket {
+    /// The state of this socket. It is shared by all file descriptors that were `dup`ed from the
+    /// one returned by `socket` or `accept`.
+    pub state: Rc<RefCell<SocketState>>,
+}
+
+#[derive(Debug)]
+pub struct SocketState {
+    /// Whether this is an `AF_INET6` socket, rather than an `AF_INET` one.
+    pub is_ipv6: bool,
+    /// Whether `accept`, `send` and `recv` fail with `EAGAIN` instead of blocking.
+    pub is_nonblock: bool,
+    /// The address this socket is bound to, if any.
+    pub local_addr: Option<SocketAddr>,
+    /// The address this socket is connected to, if any.
+    pub peer_addr: Option<SocketAddr>,
+    /// Whether this is a TCP or UDP socket, and what state it is in.
+    pub kind: SocketKind,
+}
+
+#[derive(Debug)]
+pub enum SocketKind {
+    /// A `SOCK_STREAM` socket that is neither listening nor connected.
+    Stream,
+    /// A `SOCK_STREAM` socket that `listen` was called on.
+    Listener {
+        /// The connections that are waiting to be accepted: the address of the connecting socket,
+        /// and our end of the connection.
+        backlog: VecDeque<(SocketAddr, SocketPair)>,
+    },
+    /// A connected `SOCK_STREAM` socket. Streams behave exactly like a `socketpair`, so that is
+    /// what we use for them.
+    Connected(SocketPair),
+    /// A `SOCK_DGRAM` socket.
+    Datagram {
+        /// The datagrams that were sent to this socket and not yet received, with the address
+        /// they were sent from.
+        queue: VecDeque<(SocketAddr, Vec<u8>)>,
+    },
+}
+
+impl SocketState {
+    pub fn is_stream(&self) -> bool {
+        !matches!(self.kind, SocketKind::Datagram { .. })
+    }
+}
+
+impl FileDescriptor for InetSocket {
+    fn name(&self) -> &'static str {
+        "socket"
+    }
+
+    fn dup(&mut self) -> io::Result<Box<dyn FileDescriptor>> {
+        Ok(Box::new(self.clone()))
+    }
+
+    fn close<'tcx>(
+        self: Box<Self>,
+        _communicate_allowed: bool,
+    ) -> InterpResult<'tcx, io::Result<i32>> {
+        // Once the last file descriptor is closed, the state is dropped, which makes the
+        // address available again and hangs up the connection.
+        Ok(Ok(0))
+    }
+
+    fn read<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+        bytes: &mut [u8],
+        _tcx: TyCtxt<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<usize>> {
+        let state = self.state.borrow();
+        let SocketKind::Connected(stream) = &state.kind else {
+            throw_unsup_format!("`read` is only supported on connected stream sockets, use `recv`");
+        };
+        match stream.recv(bytes, /*peek*/ false) {
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock && !state.is_nonblock => {
+                // Blocking is implemented by `recv`, which has access to the machine.
+                throw_unsup_format!("blocking `read` from a socket is not supported, use `recv`");
+            }
+            result => Ok(result),
+        }
+    }
+
+    fn write<'tcx>(
+        &self,
+        _communicate_allowed: bool,
+        bytes: &[u8],
+        _tcx: TyCtxt<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<usize>> {
+        let state = self.state.borrow();
+        match &state.kind {
+            SocketKind::Connected(stream) => Ok(stream.send(bytes)),
+            SocketKind::Stream | SocketKind::Listener { .. } =>
+                Ok(Err(io::ErrorKind::NotConnected.into())),
+            SocketKind::Datagram { .. } => {
+                // Sending a datagram needs to look up the receiving socket in the machine.
+                throw_unsup_format!("`write` to a datagram socket is not supported, use `send`");
+            }
+        }
+    }
+
+    fn get_epoll_ready_events<'tcx>(&self) -> InterpResult<'tcx, EpollReadyEvents> {
+        let state = self.state.borrow();
+        match &state.kind {
+            // Like on Linux, a socket that is not connected is writable, but hung up.
+            SocketKind::Stream =>
+                Ok(EpollReadyEvents { epollout: true, epollhup: true, ..Default::default() }),
+            SocketKind::Listener { backlog } =>
+                Ok(EpollReadyEvents { epollin: !backlog.is_empty(), ..Default::default() }),
+            SocketKind::Connected(stream) => stream.get_epoll_ready_events(),
+            // Sending never blocks, since datagrams are delivered right away.
+            SocketKind::Datagram { queue } =>
+                Ok(EpollReadyEvents {
+                    epollin: !queue.is_empty(),
+                    epollout: true,
+                    ..Default::default()
+                }),
+        }
+    }
+}
@@ -1,22 +1,168 @@
 use crate::*;
 
 use crate::shims::unix::fs::FileDescriptor;
+use crate::shims::unix::linux::fd::epoll::EpollReadyEvents;
 
+use rustc_middle::ty::TyCtxt;
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::io;
+use std::rc::{Rc, Weak};
 
-/// Pair of connected sockets.
+/// One end of a pair of connected sockets, or of a pipe.
+///
+/// Each end owns the buffer it reads from, and only holds a weak reference to the buffer of its
+/// peer. Once all file descriptors referring to one end are closed, its buffer is dropped, which
+/// is how the other end notices that its peer hung up. The write end of a pipe owns a buffer that
+/// nothing ever writes to, just so that the read end can notice when it gets closed.
 ///
-/// We currently don't allow sending any data through this pair, so this can be just a dummy.
+/// Each buffer holds at most `BUFFER_CAPACITY` bytes. Writes to a full buffer fail with `EAGAIN`
+/// until the peer reads some of the data.
 #[derive(Debug)]
-pub struct SocketPair;
+pub struct SocketPair {
+    /// The buffer this end reads from.
+    readbuf: Rc<RefCell<Buffer>>,
+    /// The buffer the peer reads from.
+    writebuf: Weak<RefCell<Buffer>>,
+    /// Whether this is a socket rather than the read or write end of a pipe. Only sockets support
+    /// both reading and writing.
+    is_socket: bool,
+    /// Whether this is the read end of a pipe. Meaningless for sockets.
+    is_pipe_read_end: bool,
+    /// Whether reads fail with `EAGAIN` instead of blocking.
+    is_nonblock: bool,
+}
+
+/// The number of bytes that can be in flight in one direction, which matches the default size of
+/// the send buffer of Unix sockets on Linux.
+const BUFFER_CAPACITY: usize = 212992;
+
+/// The data in flight from one end to the other.
+#[derive(Debug, Default)]
+struct Buffer {
+    data: VecDeque<u8>,
+    /// Whether the writing end shut down, so that no more data will arrive.
+    write_shutdown: bool,
+    /// Whether the reading end shut down, so that it reads the end of file once `data` is empty.
+    read_shutdown: bool,
+}
+
+impl SocketPair {
+    /// Creates two connected ends. The first one is the read end if this is a pipe.
+    pub fn new_pair(is_socket: bool, is_nonblock: bool) -> (SocketPair, SocketPair) {
+        let buf0 = Rc::new(RefCell::new(Buffer::default()));
+        let buf1 = Rc::new(RefCell::new(Buffer::default()));
+        let end0 = SocketPair {
+            writebuf: Rc::downgrade(&buf1),
+            readbuf: buf0,
+            is_socket,
+            is_pipe_read_end: true,
+            is_nonblock,
+        };
+        let end1 = SocketPair {
+            writebuf: Rc::downgrade(&end0.readbuf),
+            readbuf: buf1,
+            is_socket,
+            is_pipe_read_end: false,
+            is_nonblock,
+        };
+        (end0, end1)
+    }
+
+    fn is_readable(&self) -> bool {
+        self.is_socket || self.is_pipe_read_end
+    }
+
+    fn is_writable(&self) -> bool {
+        self.is_socket || !self.is_pipe_read_end
+    }
+
+    fn peer_closed(&self) -> bool {
+        self.writebuf.strong_count() == 0
+    }
+
+    /// Whether writing would not block, either because the buffer of the peer has room left, or
+    /// because the write fails right away.
+    fn can_write(&self) -> bool {
+        self.writebuf.upgrade().map_or(true, |writebuf| {
+            let writebuf = writebuf.borrow();
+            writebuf.write_shutdown || writebuf.data.len() < BUFFER_CAPACITY
+        })
+    }
+
+    /// Whether reading has reached the end of file once the buffered data is consumed.
+    fn read_eof(&self) -> bool {
+        let readbuf = self.readbuf.borrow();
+        readbuf.write_shutdown || readbuf.read_shutdown || self.peer_closed()
+    }
+
+    /// Reads buffered data into `bytes`. This never blocks: if there is no data yet, but more
+    /// might still arrive, this fails with `WouldBlock`. With `peek`, the data is left in the
+    /// buffer.
+    pub fn recv(&self, bytes: &mut [u8], peek: bool) -> io::Result<usize> {
+        let eof = self.read_eof();
+        let mut readbuf = self.readbuf.borrow_mut();
+        if readbuf.data.is_empty() && !bytes.is_empty() {
+            if eof {
+                return Ok(0);
+            }
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        let len = bytes.len().min(readbuf.data.len());
+        for (byte, &data) in bytes.iter_mut().zip(readbuf.data.iter()) {
+            *byte = data;
+        }
+        if !peek {
+            readbuf.data.drain(..len);
+        }
+        Ok(len)
+    }
+
+    /// Appends as much of `bytes` as fits to the buffer of the peer. If the buffer is full, this
+    /// fails with `WouldBlock`.
+    pub fn send(&self, bytes: &[u8]) -> io::Result<usize> {
+        let Some(writebuf) = self.writebuf.upgrade() else {
+            // The peer hung up. We do not support signals, so we just return `EPIPE`.
+            return Err(io::ErrorKind::BrokenPipe.into());
+        };
+        let mut writebuf = writebuf.borrow_mut();
+        if writebuf.write_shutdown {
+            return Err(io::ErrorKind::BrokenPipe.into());
+        }
+        let len = bytes.len().min(BUFFER_CAPACITY - writebuf.data.len());
+        if len == 0 && !bytes.is_empty() {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        writebuf.data.extend(&bytes[..len]);
+        Ok(len)
+    }
+
+    /// Shuts down the reading and/or writing half of a socket, for all file descriptors
+    /// referring to this end. The peer reads the end of file once we shut down writing.
+    pub fn shutdown(&self, read: bool, write: bool) {
+        if read {
+            self.readbuf.borrow_mut().read_shutdown = true;
+        }
+        if write && let Some(writebuf) = self.writebuf.upgrade() {
+            writebuf.borrow_mut().write_shutdown = true;
+        }
+    }
+}
 
 impl FileDescriptor for SocketPair {
     fn name(&self) -> &'static str {
-        "socketpair"
+        if self.is_socket { "socketpair" } else { "pipe" }
     }
 
     fn dup(&mut self) -> io::Result<Box<dyn FileDescriptor>> {
-        Ok(Box::new(SocketPair))
+        Ok(Box::new(SocketPair {
+            readbuf: Rc::clone(&self.readbuf),
+            writebuf: Weak::clone(&self.writebuf),
+            is_socket: self.is_socket,
+            is_pipe_read_end: self.is_pipe_read_end,
+            is_nonblock: self.is_nonblock,
+        }))
     }
 
     fn close<'tcx>(
@@ -25,4 +171,68 @@ fn close<'tcx>(
     ) -> InterpResult<'tcx, io::Result<i32>> {
         Ok(Ok(0))
     }
+
+    fn read<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+        bytes: &mut [u8],
+        _tcx: TyCtxt<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<usize>> {
+        if !self.is_readable() {
+            throw_unsup_format!("cannot read from the write end of a pipe");
+        }
+        match self.recv(bytes, /*peek*/ false) {
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock && !self.is_nonblock => {
+                // FIXME block until the peer writes something or hangs up.
+                throw_unsup_format!("blocking reads from a {} are not supported", self.name());
+            }
+            result => Ok(result),
+        }
+    }
+
+    fn write<'tcx>(
+        &self,
+        _communicate_allowed: bool,
+        bytes: &[u8],
+        _tcx: TyCtxt<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<usize>> {
+        if !self.is_writable() {
+            throw_unsup_format!("cannot write to the read end of a pipe");
+        }
+        match self.send(bytes) {
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock && !self.is_nonblock => {
+                // FIXME block until the peer reads something or hangs up.
+                throw_unsup_format!("blocking writes to a full {} are not supported", self.name());
+            }
+            result => Ok(result),
+        }
+    }
+
+    fn get_epoll_ready_events<'tcx>(&self) -> InterpResult<'tcx, EpollReadyEvents> {
+        let peer_closed = self.peer_closed();
+        if !self.is_socket {
+            // Once the peer hung up, the read end of a pipe reports `EPOLLHUP`, and the write end
+            // reports `EPOLLERR`. The write end is writable if there is room in the buffer, or if
+            // writing just fails with `EPIPE`.
+            return Ok(EpollReadyEvents {
+                epollin: self.is_pipe_read_end && !self.readbuf.borrow().data.is_empty(),
+                epollout: !self.is_pipe_read_end && self.can_write(),
+                epollhup: self.is_pipe_read_end && peer_closed,
+                epollerr: !self.is_pipe_read_end && peer_closed,
+                ..Default::default()
+            });
+        }
+        let read_eof = self.read_eof();
+        let write_shutdown =
+            self.writebuf.upgrade().map_or(true, |writebuf| writebuf.borrow().write_shutdown);
+        Ok(EpollReadyEvents {
+            // For sockets, hitting the end of file also counts as being readable.
+            epollin: !self.readbuf.borrow().data.is_empty() || read_eof,
+            epollout: self.can_write(),
+            epollrdhup: read_eof,
+            // Both directions are shut down.
+            epollhup: peer_closed || (read_eof && write_shutdown),
+            epollerr: false,
+        })
+    }
 }
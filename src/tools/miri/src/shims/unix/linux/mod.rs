@@ -1,4 +1,5 @@
 pub mod fd;
 pub mod foreign_items;
 pub mod mem;
+pub mod net;
 pub mod sync;
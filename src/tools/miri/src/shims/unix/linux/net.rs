@@ -0,0 +1,1169 @@
+//! An emulated loopback network. Sockets can be bound to the loopback addresses `127.0.0.1` and
+//! `::1` (or the unspecified addresses, which are treated the same), and connect only to other
+//! sockets of the interpreted program. Nothing ever reaches the host network, so this works in
+//! isolation and is deterministic.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
+use std::io;
+use std::mem;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::rc::{Rc, Weak};
+
+use rustc_target::abi::Size;
+
+use crate::concurrency::thread::MachineCallback;
+use crate::concurrency::vector_clock::VClock;
+use crate::*;
+use shims::unix::fs::EvalContextExt as _;
+use shims::unix::linux::fd::socket::{InetSocket, SocketKind, SocketState};
+use shims::unix::linux::fd::socketpair::SocketPair;
+use shims::unix::linux::fd::EvalContextExt as _;
+
+/// The first port handed out to sockets that are bound to port 0, or that connect or send before
+/// being bound. This is where the ephemeral port range of Linux starts by default.
+const FIRST_EPHEMERAL_PORT: u16 = 32768;
+
+/// The largest payload of a UDP datagram.
+const MAX_DATAGRAM_SIZE: usize = 65507;
+
+/// The most bytes a single `recv` reads. Reading less than requested is always allowed for
+/// streams, and no datagram is this large, so this just avoids huge host allocations.
+const MAX_RECV_SIZE: u64 = 1 << 16;
+
+/// TCP and UDP ports are separate namespaces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl Protocol {
+    fn of(state: &SocketState) -> Protocol {
+        if state.is_stream() { Protocol::Tcp } else { Protocol::Udp }
+    }
+}
+
+/// The machine-wide state of the emulated network.
+#[derive(Debug)]
+pub struct Network {
+    /// The sockets that are bound to a local address. A socket is unbound by closing all file
+    /// descriptors referring to it, which drops its state.
+    bound: BTreeMap<(Protocol, SocketAddr), Weak<RefCell<SocketState>>>,
+    /// The next port to try when we need an ephemeral port.
+    next_ephemeral_port: u16,
+    /// The threads blocked in `accept`, `send` or `recv`. They retry whenever a file descriptor
+    /// might have become ready, see `socket_notify`.
+    blocked: Vec<BlockedSocketOp>,
+    /// The clocks of the threads that might have made a socket ready. Completing a socket call
+    /// that might block acquires them, so that whatever happened before a send happens-before the
+    /// data is received, and likewise for connecting and accepting.
+    clock: VClock,
+}
+
+impl Default for Network {
+    fn default() -> Self {
+        Network {
+            bound: BTreeMap::new(),
+            next_ephemeral_port: FIRST_EPHEMERAL_PORT,
+            blocked: Vec::new(),
+            clock: VClock::default(),
+        }
+    }
+}
+
+impl VisitProvenance for Network {
+    fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
+        let Network { bound: _, next_ephemeral_port: _, blocked, clock: _ } = self;
+
+        for blocked in blocked {
+            blocked.visit_provenance(visit);
+        }
+    }
+}
+
+impl Network {
+    /// Finds the socket that receives what is sent to `addr`.
+    fn lookup(&self, protocol: Protocol, addr: SocketAddr) -> Option<Rc<RefCell<SocketState>>> {
+        // A socket bound to the unspecified address receives on all addresses.
+        let unspecified = SocketAddr::new(unspecified_ip(addr.is_ipv6()), addr.port());
+        [addr, unspecified]
+            .into_iter()
+            .find_map(|addr| self.bound.get(&(protocol, addr))?.upgrade())
+    }
+
+    /// Whether binding to `addr` would conflict with a socket that is already bound.
+    /// `AF_INET` and `AF_INET6` sockets never conflict, as if all IPv6 sockets were
+    /// `IPV6_V6ONLY`.
+    fn is_in_use(&self, protocol: Protocol, addr: SocketAddr) -> bool {
+        self.bound.iter().any(|(&(bound_protocol, bound_addr), state)| {
+            bound_protocol == protocol
+                && bound_addr.port() == addr.port()
+                && bound_addr.is_ipv6() == addr.is_ipv6()
+                && (bound_addr.ip() == addr.ip()
+                    || bound_addr.ip().is_unspecified()
+                    || addr.ip().is_unspecified())
+                && state.strong_count() > 0
+        })
+    }
+
+    /// Binds `state` to `addr`, picking an ephemeral port if its port is 0. Returns the address
+    /// that the socket is now bound to.
+    fn bind(
+        &mut self,
+        state: &Rc<RefCell<SocketState>>,
+        mut addr: SocketAddr,
+    ) -> io::Result<SocketAddr> {
+        let protocol = Protocol::of(&state.borrow());
+        if addr.port() == 0 {
+            addr.set_port(self.ephemeral_port(protocol, addr.ip())?);
+        } else if self.is_in_use(protocol, addr) {
+            return Err(io::ErrorKind::AddrInUse.into());
+        }
+        // Forget about the sockets that were closed in the meantime.
+        self.bound.retain(|_, state| state.strong_count() > 0);
+        self.bound.insert((protocol, addr), Rc::downgrade(state));
+        state.borrow_mut().local_addr = Some(addr);
+        Ok(addr)
+    }
+
+    /// Picks the next free port. Unlike Linux, we hand them out in order, to be deterministic.
+    fn ephemeral_port(&mut self, protocol: Protocol, ip: IpAddr) -> io::Result<u16> {
+        for _ in FIRST_EPHEMERAL_PORT..=u16::MAX {
+            let port = self.next_ephemeral_port;
+            self.next_ephemeral_port = port.checked_add(1).unwrap_or(FIRST_EPHEMERAL_PORT);
+            if !self.is_in_use(protocol, SocketAddr::new(ip, port)) {
+                return Ok(port);
+            }
+        }
+        Err(io::ErrorKind::AddrInUse.into())
+    }
+}
+
+/// A thread blocked in a socket call. The pointers are stored without their layout since the
+/// network state cannot hold on to anything with a `'tcx` lifetime.
+#[derive(Clone, Copy, Debug)]
+struct BlockedSocketOp {
+    thread: ThreadId,
+    op: SocketOp,
+    /// The place that the result of `op` is returned to.
+    dest: Pointer<Option<Provenance>>,
+}
+
+impl VisitProvenance for BlockedSocketOp {
+    fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
+        let BlockedSocketOp { thread: _, op, dest } = self;
+        op.visit_provenance(visit);
+        dest.visit_provenance(visit);
+    }
+}
+
+/// The arguments of a socket call that might block.
+#[derive(Clone, Copy, Debug)]
+enum SocketOp {
+    /// `accept4`. `addr` may be null.
+    Accept {
+        fd: i32,
+        addr: Pointer<Option<Provenance>>,
+        addrlen: Pointer<Option<Provenance>>,
+        flags: i32,
+    },
+    /// `sendto`. `addr` is null for `send`. Only sends to a stream socket ever block.
+    Send {
+        fd: i32,
+        buf: Pointer<Option<Provenance>>,
+        len: u64,
+        flags: i32,
+        addr: Pointer<Option<Provenance>>,
+        addrlen: u32,
+    },
+    /// `recvfrom`. `addr` is null for `recv`.
+    Recv {
+        fd: i32,
+        buf: Pointer<Option<Provenance>>,
+        len: u64,
+        flags: i32,
+        addr: Pointer<Option<Provenance>>,
+        addrlen: Pointer<Option<Provenance>>,
+    },
+}
+
+impl VisitProvenance for SocketOp {
+    fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
+        match self {
+            SocketOp::Accept { fd: _, addr, addrlen, flags: _ } => {
+                addr.visit_provenance(visit);
+                addrlen.visit_provenance(visit);
+            }
+            SocketOp::Send { fd: _, buf, len: _, flags: _, addr, addrlen: _ } => {
+                buf.visit_provenance(visit);
+                addr.visit_provenance(visit);
+            }
+            SocketOp::Recv { fd: _, buf, len: _, flags: _, addr, addrlen } => {
+                buf.visit_provenance(visit);
+                addr.visit_provenance(visit);
+                addrlen.visit_provenance(visit);
+            }
+        }
+    }
+}
+
+impl SocketOp {
+    /// `accept4` returns an `int`, `sendto` and `recvfrom` return an `ssize_t`.
+    fn result_layout<'tcx>(&self, ecx: &MiriInterpCx<'_, 'tcx>) -> TyAndLayout<'tcx> {
+        match self {
+            SocketOp::Accept { .. } => ecx.machine.layouts.i32,
+            SocketOp::Send { .. } | SocketOp::Recv { .. } => ecx.machine.layouts.isize,
+        }
+    }
+}
+
+fn loopback_ip(is_ipv6: bool) -> IpAddr {
+    if is_ipv6 { Ipv6Addr::LOCALHOST.into() } else { Ipv4Addr::LOCALHOST.into() }
+}
+
+fn unspecified_ip(is_ipv6: bool) -> IpAddr {
+    if is_ipv6 { Ipv6Addr::UNSPECIFIED.into() } else { Ipv4Addr::UNSPECIFIED.into() }
+}
+
+impl<'mir, 'tcx: 'mir> EvalContextExt<'mir, 'tcx> for crate::MiriInterpCx<'mir, 'tcx> {}
+pub trait EvalContextExt<'mir, 'tcx: 'mir>: crate::MiriInterpCxExt<'mir, 'tcx> {
+    /// Creates an `AF_INET` or `AF_INET6` socket of type `SOCK_STREAM` (TCP) or `SOCK_DGRAM`
+    /// (UDP). The `type` may be bitwise ORed with `SOCK_NONBLOCK` and `SOCK_CLOEXEC`.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/socket.2.html>
+    fn socket(
+        &mut self,
+        domain: &OpTy<'tcx, Provenance>,
+        type_: &OpTy<'tcx, Provenance>,
+        protocol: &OpTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx, Scalar<Provenance>> {
+        let this = self.eval_context_mut();
+
+        let domain = this.read_scalar(domain)?.to_i32()?;
+        let mut type_ = this.read_scalar(type_)?.to_i32()?;
+        let protocol = this.read_scalar(protocol)?.to_i32()?;
+
+        let sock_nonblock = this.eval_libc_i32("SOCK_NONBLOCK");
+        let sock_cloexec = this.eval_libc_i32("SOCK_CLOEXEC");
+
+        let is_ipv6 = if domain == this.eval_libc_i32("AF_INET") {
+            false
+        } else if domain == this.eval_libc_i32("AF_INET6") {
+            true
+        } else {
+            throw_unsup_format!(
+                "socket: domain {domain:#x} is unsupported, only AF_INET and AF_INET6 are"
+            );
+        };
+        let is_nonblock = type_ & sock_nonblock == sock_nonblock;
+        // Miri does not support exec, so the cloexec flag has no effect.
+        type_ &= !(sock_nonblock | sock_cloexec);
+        let kind = if type_ == this.eval_libc_i32("SOCK_STREAM")
+            && (protocol == 0 || protocol == this.eval_libc_i32("IPPROTO_TCP"))
+        {
+            SocketKind::Stream
+        } else if type_ == this.eval_libc_i32("SOCK_DGRAM")
+            && (protocol == 0 || protocol == this.eval_libc_i32("IPPROTO_UDP"))
+        {
+            SocketKind::Datagram { queue: VecDeque::new() }
+        } else {
+            throw_unsup_format!(
+                "socket: type {type_:#x} with protocol {protocol:#x} is unsupported, only TCP and UDP are"
+            );
+        };
+
+        let state = SocketState { is_ipv6, is_nonblock, local_addr: None, peer_addr: None, kind };
+        let fd = this
+            .machine
+            .file_handler
+            .insert_fd(Box::new(InetSocket { state: Rc::new(RefCell::new(state)) }));
+        Ok(Scalar::from_i32(fd))
+    }
+
+    /// Binds a socket to a loopback or unspecified address. Binding to port 0 picks a free port.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/bind.2.html>
+    fn bind(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        addr: &OpTy<'tcx, Provenance>,
+        addrlen: &OpTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let addr = this.read_pointer(addr)?;
+        let addrlen = this.read_scalar(addrlen)?.to_u32()?;
+
+        let Some(state) = this.socket_state(fd)? else {
+            return Ok(-1);
+        };
+        if state.borrow().local_addr.is_some() {
+            return this.socket_error("EINVAL");
+        }
+        let is_ipv6 = state.borrow().is_ipv6;
+        let addr = match this.read_socket_addr(addr, addrlen, is_ipv6)? {
+            Ok(addr) => addr,
+            Err(errno) => return this.socket_error(errno),
+        };
+        if !addr.ip().is_loopback() && !addr.ip().is_unspecified() {
+            // There are no other interfaces.
+            return this.socket_error("EADDRNOTAVAIL");
+        }
+
+        let result = this.machine.network.bind(&state, addr).map(|_| 0);
+        this.try_unwrap_io_result(result)
+    }
+
+    /// Marks a stream socket as accepting connections. Unbound sockets are bound to an ephemeral
+    /// port first. The `backlog` is ignored, there is no limit on pending connections.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/listen.2.html>
+    fn listen(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        backlog: &OpTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let _backlog = this.read_scalar(backlog)?.to_i32()?;
+
+        let Some(state) = this.socket_state(fd)? else {
+            return Ok(-1);
+        };
+        match state.borrow().kind {
+            SocketKind::Stream => {}
+            SocketKind::Listener { .. } => return Ok(0),
+            SocketKind::Connected(_) => return this.socket_error("EINVAL"),
+            SocketKind::Datagram { .. } => return this.socket_error("EOPNOTSUPP"),
+        }
+        let (local_addr, is_ipv6) = {
+            let state = state.borrow();
+            (state.local_addr, state.is_ipv6)
+        };
+        if local_addr.is_none() {
+            let addr = SocketAddr::new(unspecified_ip(is_ipv6), 0);
+            if let Err(err) = this.machine.network.bind(&state, addr) {
+                this.set_last_error_from_io_error(err.kind())?;
+                return Ok(-1);
+            }
+        }
+        state.borrow_mut().kind = SocketKind::Listener { backlog: VecDeque::new() };
+        Ok(0)
+    }
+
+    /// Connects a socket to `addr`, which must be a loopback address. Stream sockets connect
+    /// right away if some socket is listening on `addr`, and fail with `ECONNREFUSED` otherwise.
+    /// Datagram sockets just remember `addr` as the default destination of `send`.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/connect.2.html>
+    fn connect(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        addr: &OpTy<'tcx, Provenance>,
+        addrlen: &OpTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let addr = this.read_pointer(addr)?;
+        let addrlen = this.read_scalar(addrlen)?.to_u32()?;
+
+        let Some(state) = this.socket_state(fd)? else {
+            return Ok(-1);
+        };
+        let is_ipv6 = state.borrow().is_ipv6;
+        let addr = match this.read_destination_addr(addr, addrlen, is_ipv6)? {
+            Ok(addr) => addr,
+            Err(errno) => return this.socket_error(errno),
+        };
+        match state.borrow().kind {
+            SocketKind::Stream | SocketKind::Datagram { .. } => {}
+            SocketKind::Listener { .. } => return this.socket_error("EINVAL"),
+            SocketKind::Connected(_) => return this.socket_error("EISCONN"),
+        }
+
+        let listener = if state.borrow().is_stream() {
+            let listener =
+                this.machine.network.lookup(Protocol::Tcp, addr).filter(|listener| {
+                    matches!(listener.borrow().kind, SocketKind::Listener { .. })
+                });
+            let Some(listener) = listener else {
+                return this.socket_error("ECONNREFUSED");
+            };
+            Some(listener)
+        } else {
+            None
+        };
+
+        let local_addr = match this.socket_local_addr_or_bind(&state)? {
+            Ok(addr) => addr,
+            Err(err) => {
+                this.set_last_error_from_io_error(err.kind())?;
+                return Ok(-1);
+            }
+        };
+        let mut state = state.borrow_mut();
+        state.peer_addr = Some(addr);
+        if let Some(listener) = listener {
+            let (ours, theirs) =
+                SocketPair::new_pair(/*is_socket*/ true, /*is_nonblock*/ false);
+            state.kind = SocketKind::Connected(ours);
+            let mut listener = listener.borrow_mut();
+            let SocketKind::Listener { backlog } = &mut listener.kind else { unreachable!() };
+            backlog.push_back((local_addr, theirs));
+            drop(listener);
+            drop(state);
+            // The listener just became ready.
+            this.epoll_notify(None)?;
+        }
+        Ok(0)
+    }
+
+    /// Accepts a pending connection on a listening socket, blocking until there is one.
+    /// The `flags` may be bitwise ORed from `SOCK_NONBLOCK` and `SOCK_CLOEXEC`; `accept` is the
+    /// same as `accept4` with `flags` set to 0.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/accept.2.html>
+    fn accept4(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        addr: &OpTy<'tcx, Provenance>,
+        addrlen: &OpTy<'tcx, Provenance>,
+        flags: Option<&OpTy<'tcx, Provenance>>,
+        dest: &PlaceTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let addr = this.read_pointer(addr)?;
+        let addrlen = this.read_pointer(addrlen)?;
+        let flags = match flags {
+            Some(flags) => this.read_scalar(flags)?.to_i32()?,
+            None => 0,
+        };
+
+        this.socket_op(SocketOp::Accept { fd, addr, addrlen, flags }, dest)
+    }
+
+    /// Sends `len` bytes from `buf`, blocking until the peer of a stream socket has room for at
+    /// least some of them. For datagram sockets, `addr` is the destination, or null to send to
+    /// the address the socket is connected to; stream sockets ignore `addr`. Datagrams sent to an
+    /// address that no socket is bound to are silently dropped, like on a real network.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/send.2.html>
+    fn sendto(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        buf: &OpTy<'tcx, Provenance>,
+        len: &OpTy<'tcx, Provenance>,
+        flags: &OpTy<'tcx, Provenance>,
+        addr_and_len: Option<(&OpTy<'tcx, Provenance>, &OpTy<'tcx, Provenance>)>,
+        dest: &PlaceTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let buf = this.read_pointer(buf)?;
+        let len = this.read_target_usize(len)?;
+        let flags = this.read_scalar(flags)?.to_i32()?;
+        let (addr, addrlen) = match addr_and_len {
+            Some((addr, addrlen)) =>
+                (this.read_pointer(addr)?, this.read_scalar(addrlen)?.to_u32()?),
+            None => (Pointer::null(), 0),
+        };
+
+        this.socket_op(SocketOp::Send { fd, buf, len, flags, addr, addrlen }, dest)
+    }
+
+    /// Receives up to `len` bytes into `buf`, blocking until there is something to receive.
+    /// If `addr` is not null, the address of the sender is stored there for datagram sockets.
+    /// The `flags` may be bitwise ORed from `MSG_PEEK` and `MSG_DONTWAIT`.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/recv.2.html>
+    fn recvfrom(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        buf: &OpTy<'tcx, Provenance>,
+        len: &OpTy<'tcx, Provenance>,
+        flags: &OpTy<'tcx, Provenance>,
+        addr_and_len: Option<(&OpTy<'tcx, Provenance>, &OpTy<'tcx, Provenance>)>,
+        dest: &PlaceTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let buf = this.read_pointer(buf)?;
+        let len = this.read_target_usize(len)?;
+        let flags = this.read_scalar(flags)?.to_i32()?;
+        let (addr, addrlen) = match addr_and_len {
+            Some((addr, addrlen)) => (this.read_pointer(addr)?, this.read_pointer(addrlen)?),
+            None => (Pointer::null(), Pointer::null()),
+        };
+
+        this.socket_op(SocketOp::Recv { fd, buf, len, flags, addr, addrlen }, dest)
+    }
+
+    /// Shuts down the reading and/or writing half of a connected stream socket.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/shutdown.2.html>
+    fn shutdown(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        how: &OpTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let how = this.read_scalar(how)?.to_i32()?;
+
+        let (read, write) = if how == this.eval_libc_i32("SHUT_RD") {
+            (true, false)
+        } else if how == this.eval_libc_i32("SHUT_WR") {
+            (false, true)
+        } else if how == this.eval_libc_i32("SHUT_RDWR") {
+            (true, true)
+        } else {
+            return this.socket_error("EINVAL");
+        };
+
+        let Some(state) = this.socket_state(fd)? else {
+            return Ok(-1);
+        };
+        let state = state.borrow();
+        let SocketKind::Connected(stream) = &state.kind else {
+            return this.socket_error("ENOTCONN");
+        };
+        stream.shutdown(read, write);
+        drop(state);
+        // The peer might be blocked waiting for data that will now never come.
+        this.epoll_notify(None)?;
+        Ok(0)
+    }
+
+    /// Stores the local address of a socket (`getsockname`) or the address it is connected to
+    /// (`getpeername`) in `addr`.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/getsockname.2.html>
+    fn getsockname(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        addr: &OpTy<'tcx, Provenance>,
+        addrlen: &OpTy<'tcx, Provenance>,
+        peer: bool,
+    ) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let addr = this.read_pointer(addr)?;
+        let addrlen = this.read_pointer(addrlen)?;
+
+        let Some(state) = this.socket_state(fd)? else {
+            return Ok(-1);
+        };
+        let state = state.borrow();
+        let result = if peer {
+            let Some(peer_addr) = state.peer_addr else {
+                drop(state);
+                return this.socket_error("ENOTCONN");
+            };
+            peer_addr
+        } else {
+            // Unbound sockets report the unspecified address.
+            state.local_addr.unwrap_or(SocketAddr::new(unspecified_ip(state.is_ipv6), 0))
+        };
+        drop(state);
+        this.write_socket_addr(result, addr, addrlen)?;
+        Ok(0)
+    }
+
+    /// Sets a socket option. The options that `std` and `mio` set are accepted, but they have no
+    /// effect on the emulated network.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/setsockopt.2.html>
+    fn setsockopt(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        level: &OpTy<'tcx, Provenance>,
+        optname: &OpTy<'tcx, Provenance>,
+        optval: &OpTy<'tcx, Provenance>,
+        optlen: &OpTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let level = this.read_scalar(level)?.to_i32()?;
+        let optname = this.read_scalar(optname)?.to_i32()?;
+        let optval = this.read_pointer(optval)?;
+        let optlen = this.read_scalar(optlen)?.to_u32()?;
+
+        let ignored = if level == this.eval_libc_i32("SOL_SOCKET") {
+            ["SO_REUSEADDR", "SO_REUSEPORT", "SO_KEEPALIVE", "SO_BROADCAST"].as_slice()
+        } else if level == this.eval_libc_i32("IPPROTO_TCP") {
+            ["TCP_NODELAY"].as_slice()
+        } else if level == this.eval_libc_i32("IPPROTO_IPV6") {
+            ["IPV6_V6ONLY"].as_slice()
+        } else {
+            [].as_slice()
+        };
+        if !ignored.iter().any(|&name| optname == this.eval_libc_i32(name)) {
+            throw_unsup_format!(
+                "setsockopt: option {optname:#x} at level {level:#x} is unsupported"
+            );
+        }
+
+        if this.socket_state(fd)?.is_none() {
+            return Ok(-1);
+        }
+        // All of these options are `int`s.
+        if optlen < 4 {
+            return this.socket_error("EINVAL");
+        }
+        let optval = this.ptr_to_mplace(optval, this.machine.layouts.i32);
+        let _value = this.read_scalar(&optval)?.to_i32()?;
+        Ok(0)
+    }
+
+    /// Reads a socket option. Only `SO_ERROR` and `SO_TYPE` are supported. Since connecting never
+    /// fails asynchronously, there never is a pending error.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/getsockopt.2.html>
+    fn getsockopt(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        level: &OpTy<'tcx, Provenance>,
+        optname: &OpTy<'tcx, Provenance>,
+        optval: &OpTy<'tcx, Provenance>,
+        optlen: &OpTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let level = this.read_scalar(level)?.to_i32()?;
+        let optname = this.read_scalar(optname)?.to_i32()?;
+        let optval = this.read_pointer(optval)?;
+        let optlen = this.deref_pointer_as(optlen, this.libc_ty_layout("socklen_t"))?;
+
+        if level != this.eval_libc_i32("SOL_SOCKET") {
+            throw_unsup_format!("getsockopt: level {level:#x} is unsupported");
+        }
+        let Some(state) = this.socket_state(fd)? else {
+            return Ok(-1);
+        };
+        let value = if optname == this.eval_libc_i32("SO_ERROR") {
+            0
+        } else if optname == this.eval_libc_i32("SO_TYPE") {
+            if state.borrow().is_stream() {
+                this.eval_libc_i32("SOCK_STREAM")
+            } else {
+                this.eval_libc_i32("SOCK_DGRAM")
+            }
+        } else {
+            throw_unsup_format!("getsockopt: option {optname:#x} is unsupported");
+        };
+
+        if this.read_scalar(&optlen)?.to_u32()? < 4 {
+            return this.socket_error("EINVAL");
+        }
+        let optval = this.ptr_to_mplace(optval, this.machine.layouts.i32);
+        this.write_scalar(Scalar::from_i32(value), &optval)?;
+        this.write_int(4, &optlen)?;
+        Ok(0)
+    }
+
+    /// Handles `ioctl(fd, FIONBIO, &on)` on sockets, which is how `std` makes them nonblocking.
+    /// Nothing else is supported.
+    fn ioctl(&mut self, args: &[OpTy<'tcx, Provenance>]) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let [fd, request, arg, ..] = args else {
+            throw_ub_format!(
+                "incorrect number of arguments for `ioctl`: got {}, expected at least 3",
+                args.len()
+            );
+        };
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let request = this.read_scalar(request)?;
+        if request != this.eval_libc("FIONBIO") {
+            throw_unsup_format!("ioctl: request {request:?} is unsupported, only FIONBIO is");
+        }
+        let on = this.deref_pointer_as(arg, this.machine.layouts.i32)?;
+        let on = this.read_scalar(&on)?.to_i32()?;
+
+        let Some(state) = this.socket_state(fd)? else {
+            return Ok(-1);
+        };
+        state.borrow_mut().is_nonblock = on != 0;
+        Ok(0)
+    }
+
+    /// Must be called whenever file descriptors might have become ready. All threads blocked in
+    /// socket calls get to retry their call, and block again if it still cannot complete.
+    fn socket_notify(&mut self) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        // We do not know which sockets became ready, so this thread synchronizes with every
+        // socket call that completes later.
+        if let Some(data_race) = &this.machine.data_race {
+            let current_thread = this.get_active_thread();
+            let current_span = this.machine.current_span();
+            data_race.validate_lock_release_shared(
+                &mut this.machine.network.clock,
+                current_thread,
+                current_span,
+            );
+        }
+        for BlockedSocketOp { thread, op, dest } in mem::take(&mut this.machine.network.blocked) {
+            // The call is retried by the blocked thread itself, so we register a callback that
+            // fires right away.
+            let now = this.machine.clock.now();
+            this.register_timeout_callback(
+                thread,
+                Time::Monotonic(now),
+                Box::new(SocketRetryCallback { blocked: BlockedSocketOp { thread, op, dest } }),
+            );
+        }
+        Ok(())
+    }
+
+    /// Makes `thread` acquire the clocks of all threads that might have made a socket ready, see
+    /// `socket_notify`.
+    fn socket_acquire(&self, thread: ThreadId) {
+        let this = self.eval_context_ref();
+        if let Some(data_race) = &this.machine.data_race {
+            data_race.validate_lock_acquire(&this.machine.network.clock, thread);
+        }
+    }
+
+    /// Performs a socket call that might block, writing its result to `dest` or blocking the
+    /// active thread until `socket_notify` lets it retry.
+    fn socket_op(&mut self, op: SocketOp, dest: &PlaceTy<'tcx, Provenance>) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        match this.socket_try_op(op)? {
+            Some(result) => {
+                this.socket_acquire(this.get_active_thread());
+                this.write_int(result, dest)
+            }
+            None => {
+                // The result is written once we wake up, so it has to live in memory.
+                let dest = this.force_allocation(dest)?.ptr();
+                let thread = this.get_active_thread();
+                this.machine.network.blocked.push(BlockedSocketOp { thread, op, dest });
+                this.block_thread(thread);
+                Ok(())
+            }
+        }
+    }
+
+    /// Attempts a socket call that might block. Returns `None` if it would block, and the socket
+    /// is not nonblocking.
+    fn socket_try_op(&mut self, op: SocketOp) -> InterpResult<'tcx, Option<i64>> {
+        let this = self.eval_context_mut();
+
+        match op {
+            SocketOp::Accept { fd, addr, addrlen, flags } =>
+                this.socket_try_accept(fd, addr, addrlen, flags),
+            SocketOp::Send { fd, buf, len, flags, addr, addrlen } =>
+                this.socket_try_send(fd, buf, len, flags, addr, addrlen),
+            SocketOp::Recv { fd, buf, len, flags, addr, addrlen } =>
+                this.socket_try_recv(fd, buf, len, flags, addr, addrlen),
+        }
+    }
+
+    fn socket_try_accept(
+        &mut self,
+        fd: i32,
+        addr: Pointer<Option<Provenance>>,
+        addrlen: Pointer<Option<Provenance>>,
+        mut flags: i32,
+    ) -> InterpResult<'tcx, Option<i64>> {
+        let this = self.eval_context_mut();
+
+        let sock_nonblock = this.eval_libc_i32("SOCK_NONBLOCK");
+        let sock_cloexec = this.eval_libc_i32("SOCK_CLOEXEC");
+        let is_nonblock = flags & sock_nonblock == sock_nonblock;
+        // Miri does not support exec, so the cloexec flag has no effect.
+        flags &= !(sock_nonblock | sock_cloexec);
+        if flags != 0 {
+            return this.socket_error("EINVAL").map(Some);
+        }
+
+        let Some(listener) = this.socket_state(fd)? else {
+            return Ok(Some(-1));
+        };
+        let mut listener = listener.borrow_mut();
+        let SocketKind::Listener { backlog } = &mut listener.kind else {
+            drop(listener);
+            return this.socket_error("EINVAL").map(Some);
+        };
+        let Some((peer_addr, stream)) = backlog.pop_front() else {
+            let would_block = !listener.is_nonblock;
+            drop(listener);
+            if would_block {
+                return Ok(None);
+            }
+            return this.socket_error("EAGAIN").map(Some);
+        };
+        let mut local_addr = listener.local_addr.unwrap();
+        if local_addr.ip().is_unspecified() {
+            local_addr.set_ip(loopback_ip(local_addr.is_ipv6()));
+        }
+        let state = SocketState {
+            is_ipv6: listener.is_ipv6,
+            is_nonblock,
+            local_addr: Some(local_addr),
+            peer_addr: Some(peer_addr),
+            kind: SocketKind::Connected(stream),
+        };
+        drop(listener);
+
+        let fd = this
+            .machine
+            .file_handler
+            .insert_fd(Box::new(InetSocket { state: Rc::new(RefCell::new(state)) }));
+        this.write_socket_addr(peer_addr, addr, addrlen)?;
+        Ok(Some(fd.into()))
+    }
+
+    fn socket_try_send(
+        &mut self,
+        fd: i32,
+        buf: Pointer<Option<Provenance>>,
+        len: u64,
+        mut flags: i32,
+        addr: Pointer<Option<Provenance>>,
+        addrlen: u32,
+    ) -> InterpResult<'tcx, Option<i64>> {
+        let this = self.eval_context_mut();
+
+        // We do not support signals, so we never raise `SIGPIPE` anyway.
+        let msg_dontwait = this.eval_libc_i32("MSG_DONTWAIT");
+        let dontwait = flags & msg_dontwait == msg_dontwait;
+        flags &= !(this.eval_libc_i32("MSG_NOSIGNAL") | msg_dontwait);
+        if flags != 0 {
+            throw_unsup_format!("send: flags {flags:#x} are unsupported");
+        }
+
+        let Some(state) = this.socket_state(fd)? else {
+            return Ok(Some(-1));
+        };
+        let bytes = this.read_bytes_ptr_strip_provenance(buf, Size::from_bytes(len))?.to_vec();
+
+        let result = if state.borrow().is_stream() {
+            let state = state.borrow();
+            match &state.kind {
+                SocketKind::Connected(stream) =>
+                    match stream.send(&bytes) {
+                        Err(err)
+                            if err.kind() == io::ErrorKind::WouldBlock
+                                && !state.is_nonblock
+                                && !dontwait =>
+                            return Ok(None),
+                        result => result,
+                    },
+                _ => Err(io::ErrorKind::NotConnected.into()),
+            }
+        } else {
+            let dest = if this.ptr_is_null(addr)? {
+                state.borrow().peer_addr
+            } else {
+                let is_ipv6 = state.borrow().is_ipv6;
+                match this.read_destination_addr(addr, addrlen, is_ipv6)? {
+                    Ok(addr) => Some(addr),
+                    Err(errno) => return this.socket_error(errno).map(Some),
+                }
+            };
+            let Some(dest) = dest else {
+                return this.socket_error("EDESTADDRREQ").map(Some);
+            };
+            if bytes.len() > MAX_DATAGRAM_SIZE {
+                return this.socket_error("EMSGSIZE").map(Some);
+            }
+            let mut src = match this.socket_local_addr_or_bind(&state)? {
+                Ok(src) => src,
+                Err(err) => {
+                    this.set_last_error_from_io_error(err.kind())?;
+                    return Ok(Some(-1));
+                }
+            };
+            if src.ip().is_unspecified() {
+                src.set_ip(loopback_ip(src.is_ipv6()));
+            }
+            let len = bytes.len();
+            if let Some(receiver) = this.machine.network.lookup(Protocol::Udp, dest) {
+                let mut receiver = receiver.borrow_mut();
+                // Connected datagram sockets only receive from their peer.
+                if receiver.peer_addr.map_or(true, |peer| peer == src) {
+                    let SocketKind::Datagram { queue } = &mut receiver.kind else {
+                        unreachable!()
+                    };
+                    queue.push_back((src, bytes));
+                }
+            }
+            Ok(len)
+        };
+        // The receiver just became ready.
+        this.epoll_notify(None)?;
+        let result = result.map(|len| i64::try_from(len).unwrap());
+        this.try_unwrap_io_result(result).map(Some)
+    }
+
+    fn socket_try_recv(
+        &mut self,
+        fd: i32,
+        buf: Pointer<Option<Provenance>>,
+        len: u64,
+        mut flags: i32,
+        addr: Pointer<Option<Provenance>>,
+        addrlen: Pointer<Option<Provenance>>,
+    ) -> InterpResult<'tcx, Option<i64>> {
+        let this = self.eval_context_mut();
+
+        let msg_peek = this.eval_libc_i32("MSG_PEEK");
+        let msg_dontwait = this.eval_libc_i32("MSG_DONTWAIT");
+        let peek = flags & msg_peek == msg_peek;
+        let dontwait = flags & msg_dontwait == msg_dontwait;
+        flags &= !(msg_peek | msg_dontwait);
+        if flags != 0 {
+            throw_unsup_format!("recv: flags {flags:#x} are unsupported");
+        }
+
+        // Check that the *entire* buffer is actually valid memory.
+        this.check_ptr_access(buf, Size::from_bytes(len), CheckInAllocMsg::MemoryAccessTest)?;
+
+        let Some(state) = this.socket_state(fd)? else {
+            return Ok(Some(-1));
+        };
+        let mut bytes = vec![0; usize::try_from(len.min(MAX_RECV_SIZE)).unwrap()];
+        let mut state = state.borrow_mut();
+        let is_nonblock = state.is_nonblock || dontwait;
+        let (result, sender) = match &mut state.kind {
+            SocketKind::Connected(stream) => (stream.recv(&mut bytes, peek), None),
+            SocketKind::Datagram { queue } =>
+                match queue.front() {
+                    Some((sender, datagram)) => {
+                        // The rest of a datagram that does not fit is discarded.
+                        let len = bytes.len().min(datagram.len());
+                        bytes[..len].copy_from_slice(&datagram[..len]);
+                        let sender = *sender;
+                        if !peek {
+                            queue.pop_front();
+                        }
+                        (Ok(len), Some(sender))
+                    }
+                    None => (Err(io::ErrorKind::WouldBlock.into()), None),
+                },
+            SocketKind::Stream | SocketKind::Listener { .. } =>
+                (Err(io::ErrorKind::NotConnected.into()), None),
+        };
+        drop(state);
+
+        match result {
+            Ok(read) => {
+                this.write_bytes_ptr(buf, bytes[..read].iter().copied())?;
+                if !peek {
+                    // The read might have made room for the peer to send more.
+                    this.epoll_notify(None)?;
+                }
+                if let Some(sender) = sender
+                    && !this.ptr_is_null(addr)?
+                {
+                    this.write_socket_addr(sender, addr, addrlen)?;
+                }
+                Ok(Some(i64::try_from(read).unwrap()))
+            }
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock && !is_nonblock => Ok(None),
+            Err(err) => {
+                this.set_last_error_from_io_error(err.kind())?;
+                Ok(Some(-1))
+            }
+        }
+    }
+
+    /// Returns the state of the socket that `fd` refers to. If there is none, this sets the last
+    /// error to `EBADF` or `ENOTSOCK` and returns `None`.
+    fn socket_state(&mut self, fd: i32) -> InterpResult<'tcx, Option<Rc<RefCell<SocketState>>>> {
+        let this = self.eval_context_mut();
+
+        let Some(file_descriptor) = this.machine.file_handler.handles.get(&fd) else {
+            this.handle_not_found::<i32>()?;
+            return Ok(None);
+        };
+        if let Some(socket) = file_descriptor.downcast_ref::<InetSocket>() {
+            return Ok(Some(socket.state.clone()));
+        }
+        if file_descriptor.downcast_ref::<SocketPair>().is_some() {
+            throw_unsup_format!("socket calls on a {} are not supported", file_descriptor.name());
+        }
+        this.socket_error::<i32>("ENOTSOCK")?;
+        Ok(None)
+    }
+
+    /// Returns the local address of a socket, binding it to an ephemeral port on the loopback
+    /// address first if it is not bound yet.
+    fn socket_local_addr_or_bind(
+        &mut self,
+        state: &Rc<RefCell<SocketState>>,
+    ) -> InterpResult<'tcx, io::Result<SocketAddr>> {
+        let this = self.eval_context_mut();
+
+        let (local_addr, is_ipv6) = {
+            let state = state.borrow();
+            (state.local_addr, state.is_ipv6)
+        };
+        if let Some(local_addr) = local_addr {
+            return Ok(Ok(local_addr));
+        }
+        Ok(this.machine.network.bind(state, SocketAddr::new(loopback_ip(is_ipv6), 0)))
+    }
+
+    /// Sets the last error to the libc constant `errno` and returns -1.
+    fn socket_error<T: From<i32>>(&mut self, errno: &str) -> InterpResult<'tcx, T> {
+        let this = self.eval_context_mut();
+        let errno = this.eval_libc(errno);
+        this.set_last_error(errno)?;
+        Ok((-1).into())
+    }
+
+    /// Reads a `sockaddr_in` or `sockaddr_in6` of `addrlen` bytes at `addr`, which must belong
+    /// to the address family of the socket. Errors are returned as the name of an errno.
+    fn read_socket_addr(
+        &mut self,
+        addr: Pointer<Option<Provenance>>,
+        addrlen: u32,
+        is_ipv6: bool,
+    ) -> InterpResult<'tcx, Result<SocketAddr, &'static str>> {
+        let this = self.eval_context_mut();
+
+        let (layout, family) = if is_ipv6 {
+            (this.libc_ty_layout("sockaddr_in6"), this.eval_libc_i32("AF_INET6"))
+        } else {
+            (this.libc_ty_layout("sockaddr_in"), this.eval_libc_i32("AF_INET"))
+        };
+        let prefix = if is_ipv6 { "sin6" } else { "sin" };
+        let sockaddr = this.ptr_to_mplace(addr, layout);
+
+        // The family comes first in all socket address types.
+        let family_field = this.project_field_named(&sockaddr, &format!("{prefix}_family"))?;
+        if u64::from(addrlen) < family_field.layout.size.bytes() {
+            return Ok(Err("EINVAL"));
+        }
+        if i32::from(this.read_scalar(&family_field)?.to_u16()?) != family {
+            return Ok(Err("EAFNOSUPPORT"));
+        }
+        if u64::from(addrlen) < layout.size.bytes() {
+            return Ok(Err("EINVAL"));
+        }
+
+        // The port and address are in network byte order, so we read their bytes.
+        let port_field = this.project_field_named(&sockaddr, &format!("{prefix}_port"))?;
+        let port = this.read_bytes_ptr_strip_provenance(port_field.ptr(), Size::from_bytes(2))?;
+        let port = u16::from_be_bytes(port.try_into().unwrap());
+        let ip_field = this.project_field_named(&sockaddr, &format!("{prefix}_addr"))?;
+        let ip = this.read_bytes_ptr_strip_provenance(ip_field.ptr(), ip_field.layout.size)?;
+        let ip: IpAddr = if is_ipv6 {
+            <[u8; 16]>::try_from(ip).unwrap().into()
+        } else {
+            <[u8; 4]>::try_from(ip).unwrap().into()
+        };
+        Ok(Ok(SocketAddr::new(ip, port)))
+    }
+
+    /// Reads the address that a socket connects or sends to. The unspecified address means the
+    /// local host, and there is no host besides that.
+    fn read_destination_addr(
+        &mut self,
+        addr: Pointer<Option<Provenance>>,
+        addrlen: u32,
+        is_ipv6: bool,
+    ) -> InterpResult<'tcx, Result<SocketAddr, &'static str>> {
+        let this = self.eval_context_mut();
+
+        let mut addr = match this.read_socket_addr(addr, addrlen, is_ipv6)? {
+            Ok(addr) => addr,
+            Err(errno) => return Ok(Err(errno)),
+        };
+        if addr.ip().is_unspecified() {
+            addr.set_ip(loopback_ip(is_ipv6));
+        }
+        if !addr.ip().is_loopback() {
+            return Ok(Err("ENETUNREACH"));
+        }
+        Ok(Ok(addr))
+    }
+
+    /// Stores `sockaddr` as a `sockaddr_in` or `sockaddr_in6` at `addr`. Like the kernel, this
+    /// truncates the address to the `socklen_t` at `addrlen`, and then updates that to the full
+    /// size of the address. Nothing is stored if `addr` is null.
+    fn write_socket_addr(
+        &mut self,
+        sockaddr: SocketAddr,
+        addr: Pointer<Option<Provenance>>,
+        addrlen: Pointer<Option<Provenance>>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        if this.ptr_is_null(addr)? {
+            return Ok(());
+        }
+        let addrlen = this.ptr_to_mplace(addrlen, this.libc_ty_layout("socklen_t"));
+        let capacity = this.read_scalar(&addrlen)?.to_u32()?;
+
+        let (layout, family, prefix, ip) = match sockaddr.ip() {
+            IpAddr::V4(ip) =>
+                (this.libc_ty_layout("sockaddr_in"), "AF_INET", "sin", ip.octets().to_vec()),
+            IpAddr::V6(ip) =>
+                (this.libc_ty_layout("sockaddr_in6"), "AF_INET6", "sin6", ip.octets().to_vec()),
+        };
+        // The address is assembled in a scratch allocation, so that it can be truncated.
+        let scratch = this.allocate(layout, MiriMemoryKind::Machine.into())?;
+        let size = layout.size;
+        this.write_bytes_ptr(scratch.ptr(), std::iter::repeat(0).take(size.bytes_usize()))?;
+        let family_field = this.project_field_named(&scratch, &format!("{prefix}_family"))?;
+        this.write_int(this.eval_libc_i32(family), &family_field)?;
+        let port_field = this.project_field_named(&scratch, &format!("{prefix}_port"))?;
+        this.write_bytes_ptr(port_field.ptr(), sockaddr.port().to_be_bytes())?;
+        let ip_field = this.project_field_named(&scratch, &format!("{prefix}_addr"))?;
+        this.write_bytes_ptr(ip_field.ptr(), ip)?;
+
+        let copied = size.min(Size::from_bytes(capacity));
+        this.mem_copy(scratch.ptr(), addr, copied, /*nonoverlapping*/ true)?;
+        this.deallocate_ptr(scratch.ptr(), None, MiriMemoryKind::Machine.into())?;
+        this.write_int(size.bytes(), &addrlen)?;
+        Ok(())
+    }
+}
+
+/// Retries a blocked socket call on the thread that made it, once `socket_notify` was called.
+struct SocketRetryCallback {
+    blocked: BlockedSocketOp,
+}
+
+impl VisitProvenance for SocketRetryCallback {
+    fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
+        let SocketRetryCallback { blocked } = self;
+        blocked.visit_provenance(visit);
+    }
+}
+
+impl<'mir, 'tcx: 'mir> MachineCallback<'mir, 'tcx> for SocketRetryCallback {
+    fn call(&self, this: &mut MiriInterpCx<'mir, 'tcx>) -> InterpResult<'tcx> {
+        let BlockedSocketOp { thread, op, dest } = self.blocked;
+        this.unblock_thread(thread);
+        match this.socket_try_op(op)? {
+            Some(result) => {
+                this.socket_acquire(thread);
+                let dest = this.ptr_to_mplace(dest, op.result_layout(this));
+                this.write_int(result, &dest)?;
+            }
+            None => {
+                this.machine.network.blocked.push(self.blocked);
+                this.block_thread(thread);
+            }
+        }
+        Ok(())
+    }
+}
@@ -10,6 +10,7 @@
 mod macos;
 
 pub use fs::{DirHandler, FileHandler};
+pub use linux::net::Network;
 
 // Make up some constants.
 const UID: u32 = 1000;
@@ -1,9 +1,12 @@
 use colored::*;
 use regex::bytes::Regex;
+use std::collections::HashMap;
 use std::ffi::OsString;
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
+use std::process::Output;
 use std::{env, process::Command};
+use ui_test::color_eyre::eyre::bail;
 use ui_test::{color_eyre::Result, Config, Match, Mode, OutputConflictHandling};
 use ui_test::{status_emitter, CommandBuilder, Format, RustfixMode};
 
@@ -221,6 +224,194 @@ fn ui(mode: Mode, path: &str, target: &str, with_dependencies: Dependencies) ->
     run_tests(mode, path, target, with_dependencies)
 }
 
+/// Checks that replaying a recorded execution reproduces it exactly. Every program in
+/// `tests/replay` is recorded with one seed and replayed with another, so the replayed execution
+/// only matches the recorded one if all of its nondeterminism is taken from the trace. Replaying a
+/// trace that is missing, malformed or does not match the program has to fail cleanly.
+///
+/// The programs whose name starts with `explore` are recorded with `-Zmiri-explore`, and have to
+/// fail; replaying their trace has to run into the same failure.
+fn replay(path: &str, target: &str) -> Result<()> {
+    let msg = format!("## Running replay tests in {path} against miri for {target}");
+    eprintln!("{}", msg.green().bold());
+
+    let config = test_config(target, path, Mode::Yolo { rustfix: RustfixMode::Disabled }, false);
+    let trace_dir = config.out_dir.join("replay");
+    std::fs::create_dir_all(&trace_dir)?;
+    // Let the programs read the host's clocks and random number generator, and preempt often, so
+    // that there is plenty to record.
+    let run = |file: &Path, flags: &[String]| -> Result<Output> {
+        let mut cmd = config.program.build(&config.out_dir);
+        cmd.arg(file).arg("-Zmiri-disable-isolation").arg("-Zmiri-preemption-rate=0.3").args(flags);
+        Ok(cmd.output()?)
+    };
+    let expect_error = |file: &Path, what: &str, output: &Output, error: &str| -> Result<()> {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() || !stderr.contains(error) {
+            bail!(
+                "{}: replaying {what} should fail with `{error}`, but it exited with {}:\n{stderr}",
+                file.display(),
+                output.status,
+            );
+        }
+        Ok(())
+    };
+
+    let mut files =
+        std::fs::read_dir(path)?.map(|entry| Ok(entry?.path())).collect::<Result<Vec<_>>>()?;
+    files.retain(|file| file.extension().is_some_and(|ext| ext == "rs"));
+    files.sort();
+    for file in &files {
+        eprintln!("   replay {}", file.display());
+        let trace = trace_dir.join(file.file_stem().unwrap()).with_extension("trace");
+        let explore = file.file_stem().unwrap().to_string_lossy().starts_with("explore");
+        let mut record_flags = vec![format!("-Zmiri-record={}", trace.display())];
+        record_flags.push(if explore { "-Zmiri-explore" } else { "-Zmiri-seed=1" }.into());
+        let recorded = run(file, &record_flags)?;
+        if recorded.status.success() == explore {
+            bail!(
+                "{}: recording exited with {}, but it should {}:\n{}",
+                file.display(),
+                recorded.status,
+                if explore { "find a failing execution" } else { "succeed" },
+                String::from_utf8_lossy(&recorded.stderr),
+            );
+        }
+        let replayed =
+            run(file, &[format!("-Zmiri-replay={}", trace.display()), "-Zmiri-seed=2".into()])?;
+        // The exploration runs the program over and over again, and reports which execution
+        // failed, so for explored programs we only compare the output of the failing execution
+        // and the error itself.
+        let same_output = if explore {
+            let last_line =
+                |bytes: &[u8]| bytes.split(|&b| b == b'\n').filter(|l| !l.is_empty()).last();
+            let first_line = |bytes: &[u8]| bytes.split(|&b| b == b'\n').next();
+            last_line(&replayed.stdout) == last_line(&recorded.stdout)
+                && first_line(&replayed.stderr) == first_line(&recorded.stderr)
+        } else {
+            replayed.stdout == recorded.stdout && replayed.stderr == recorded.stderr
+        };
+        if replayed.status != recorded.status || !same_output {
+            bail!(
+                "{}: the replayed execution differs from the recorded one\n\
+                recorded stdout:\n{}\nreplayed stdout:\n{}\nreplayed stderr:\n{}",
+                file.display(),
+                String::from_utf8_lossy(&recorded.stdout),
+                String::from_utf8_lossy(&replayed.stdout),
+                String::from_utf8_lossy(&replayed.stderr),
+            );
+        }
+
+        let missing = trace_dir.join("does-not-exist.trace");
+        let output = run(file, &[format!("-Zmiri-replay={}", missing.display())])?;
+        expect_error(file, "a missing trace", &output, "does not exist")?;
+
+        let malformed = trace_dir.join("malformed.trace");
+        std::fs::write(&malformed, "this is not a trace\n")?;
+        let output = run(file, &[format!("-Zmiri-replay={}", malformed.display())])?;
+        expect_error(file, "a malformed trace", &output, "failed to read the trace file")?;
+
+        // Every program allocates, which needs random bytes for the base address. Replacing
+        // them with a single byte makes the first request for randomness diverge.
+        let recorded_trace = std::fs::read_to_string(&trace)?;
+        let diverging = trace_dir.join("diverging.trace");
+        let events = recorded_trace
+            .lines()
+            .map(|line| if line.starts_with("random ") { "random 00" } else { line });
+        std::fs::write(&diverging, events.map(|line| format!("{line}\n")).collect::<String>())?;
+        let output = run(file, &[format!("-Zmiri-replay={}", diverging.display())])?;
+        expect_error(
+            file,
+            "a trace that does not match",
+            &output,
+            "diverged from the replayed trace",
+        )?;
+    }
+    Ok(())
+}
+
+/// Run the programs in `path` with `-Zmiri-coverage`, and check the reported counts against the
+/// `// count: N` and `// branch: [True: N, False: M]` annotations in the programs.
+fn coverage(path: &str, target: &str) -> Result<()> {
+    let msg = format!("## Running coverage tests in {path} against miri for {target}");
+    eprintln!("{}", msg.green().bold());
+
+    let config = test_config(target, path, Mode::Yolo { rustfix: RustfixMode::Disabled }, false);
+    let mut files =
+        std::fs::read_dir(path)?.map(|entry| Ok(entry?.path())).collect::<Result<Vec<_>>>()?;
+    files.retain(|file| file.extension().is_some_and(|ext| ext == "rs"));
+    files.sort();
+    for file in &files {
+        eprintln!("   coverage {}", file.display());
+        let report_dir = config.out_dir.join("coverage").join(file.file_stem().unwrap());
+        let _ = std::fs::remove_dir_all(&report_dir);
+        let mut cmd = config.program.build(&config.out_dir);
+        cmd.arg(file).arg(format!("-Zmiri-coverage={}", report_dir.display()));
+        let output = cmd.output()?;
+        if !output.status.success() {
+            bail!(
+                "{}: exited with {}:\n{}",
+                file.display(),
+                output.status,
+                String::from_utf8_lossy(&output.stderr),
+            );
+        }
+        let reports = std::fs::read_dir(&report_dir)?
+            .map(|entry| Ok(entry?.path()))
+            .collect::<Result<Vec<_>>>()?;
+        let [report] = &reports[..] else {
+            bail!("{}: expected one report, found {reports:?}", file.display());
+        };
+        let report = std::fs::read_to_string(report)?;
+
+        // Collect the count and branches of every line of this file in the report.
+        let header = format!("{}:", file.file_name().unwrap().to_string_lossy());
+        let mut counts = HashMap::new();
+        let mut branches: HashMap<usize, Vec<&str>> = HashMap::new();
+        let mut last_line = 0;
+        for line in report.lines().skip_while(|line| !line.ends_with(&header)).skip(1) {
+            if line.is_empty() {
+                break;
+            }
+            if let Some(branch) = line.strip_prefix("  |  Branch (") {
+                let (_, taken) = branch.split_once(": ").unwrap();
+                branches.entry(last_line).or_default().push(taken);
+            } else if let Some((number, rest)) = line.split_once('|') {
+                let Ok(number) = number.trim().parse::<usize>() else { continue };
+                let (count, _) = rest.split_once('|').unwrap();
+                counts.insert(number, count.trim());
+                last_line = number;
+            }
+        }
+
+        let source = std::fs::read_to_string(file)?;
+        for (index, line) in source.lines().enumerate() {
+            let number = index + 1;
+            let Some((_, annotation)) = line.split_once("// count: ") else { continue };
+            let (count, branch) = match annotation.split_once(", branch: ") {
+                Some((count, branch)) => (count, Some(branch)),
+                None => (annotation, None),
+            };
+            if counts.get(&number) != Some(&count) {
+                bail!(
+                    "{}:{number}: expected a count of {count}, but the report says {:?}:\n{report}",
+                    file.display(),
+                    counts.get(&number),
+                );
+            }
+            let reported = branches.get(&number);
+            if branch.is_some_and(|branch| !reported.is_some_and(|taken| taken.contains(&branch))) {
+                bail!(
+                    "{}:{number}: expected a branch {}, but the report has {reported:?}:\n{report}",
+                    file.display(),
+                    branch.unwrap(),
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
 fn get_target() -> String {
     env::var("MIRI_TEST_TARGET").ok().unwrap_or_else(get_host)
 }
@@ -263,6 +454,8 @@ fn main() -> Result<()> {
             WithoutDependencies,
         )?;
     }
+    replay("tests/replay", &target)?;
+    coverage("tests/coverage", &target)?;
 
     Ok(())
 }
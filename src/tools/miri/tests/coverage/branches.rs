@@ -0,0 +1,26 @@
+// Lines annotated with `count:` must be reported with that execution count, and lines annotated
+// with `branch:` must have a branch with those counts.
+
+fn classify(n: u32) -> &'static str {
+    if n % 2 == 0 { // count: 3, branch: [True: 2, False: 1]
+        "even" // count: 2
+    } else {
+        "odd" // count: 1
+    }
+}
+
+#[allow(dead_code)]
+fn never_called() {
+    println!("never called"); // count: 0
+}
+
+fn main() {
+    let mut large = 0; // count: 1
+    for n in [1, 2, 4] {
+        if n > 10 { // count: 3, branch: [True: 0, False: 3]
+            large += 1; // count: 0
+        }
+        println!("{n} is {}", classify(n)); // count: 3
+    }
+    println!("{large} large numbers");
+}
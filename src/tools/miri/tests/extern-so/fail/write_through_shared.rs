@@ -0,0 +1,16 @@
+//@only-target-linux
+//@only-on-host
+
+// Writes made by native code are checked like any other write through the pointer argument.
+
+extern "C" {
+    fn double_ints(ptr: *mut i32, len: usize);
+}
+
+fn main() {
+    let ints = [1, 2];
+    let ptr = &ints as *const [i32; 2] as *mut i32;
+    unsafe {
+        double_ints(ptr, 2); //~ ERROR: /write access .* only grants SharedReadOnly permission/
+    }
+}
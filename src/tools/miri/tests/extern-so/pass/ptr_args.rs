@@ -0,0 +1,59 @@
+//@only-target-linux
+//@only-on-host
+
+use std::mem::MaybeUninit;
+
+extern "C" {
+    fn fill_bytes(ptr: *mut u8, len: usize, value: u8);
+    fn sum_ints(ptr: *const i32, len: usize) -> i32;
+    fn double_ints(ptr: *mut i32, len: usize);
+    fn swap_ints(a: *mut i32, b: *mut i32);
+}
+
+fn read_only() {
+    let ints = [1, 2, 3, 4];
+    assert_eq!(unsafe { sum_ints(ints.as_ptr(), ints.len()) }, 10);
+    // Immutable statics can be read as well.
+    static INTS: [i32; 3] = [10, 20, 30];
+    assert_eq!(unsafe { sum_ints(INTS.as_ptr(), INTS.len()) }, 60);
+}
+
+fn write_heap() {
+    let mut ints = vec![1, 2, 3, 4];
+    // Only the elements the native code gets to see are changed.
+    unsafe { double_ints(ints.as_mut_ptr().add(1), 2) };
+    assert_eq!(ints, [1, 4, 6, 4]);
+}
+
+fn write_uninit() {
+    let mut buf = MaybeUninit::<[u8; 8]>::uninit();
+    unsafe { fill_bytes(buf.as_mut_ptr().cast(), 8, 7) };
+    assert_eq!(unsafe { buf.assume_init() }, [7; 8]);
+}
+
+fn same_allocation() {
+    let mut pair = [1, 2];
+    let ptr = pair.as_mut_ptr();
+    unsafe { swap_ints(ptr, ptr.add(1)) };
+    assert_eq!(pair, [2, 1]);
+}
+
+fn references() {
+    let mut a = 1;
+    let mut b = 2;
+    unsafe { swap_ints(&mut a, &mut b) };
+    assert_eq!((a, b), (2, 1));
+}
+
+fn null() {
+    assert_eq!(unsafe { sum_ints(std::ptr::null(), 0) }, 0);
+}
+
+fn main() {
+    read_only();
+    write_heap();
+    write_uninit();
+    same_allocation();
+    references();
+    null();
+}
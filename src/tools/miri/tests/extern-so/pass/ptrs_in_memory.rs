@@ -0,0 +1,83 @@
+//@only-target-linux
+//@only-on-host
+
+// Native code can follow pointers stored in the memory it is passed, and keep them for later
+// calls.
+
+#[repr(C)]
+struct Stream {
+    next_in: *const u8,
+    avail_in: usize,
+}
+
+#[repr(C)]
+struct CopyStream {
+    next_in: *const u8,
+    avail_in: usize,
+    next_out: *mut u8,
+    avail_out: usize,
+    state: *mut u8,
+}
+
+extern "C" {
+    fn stream_avail(stream: *const Stream) -> usize;
+    fn remember_ptr(slot: *mut *mut i32, ptr: *mut i32);
+    fn copy_init(stream: *mut CopyStream) -> i32;
+    fn copy_step(stream: *mut CopyStream) -> usize;
+    fn copy_end(stream: *mut CopyStream);
+}
+
+fn ptr_in_memory() {
+    let input = [1u8, 2, 3];
+    let stream = Stream { next_in: input.as_ptr(), avail_in: input.len() };
+    assert_eq!(unsafe { stream_avail(&stream) }, 3);
+}
+
+fn ptr_stored_by_native_code() {
+    let mut x = 1;
+    let mut slot = std::ptr::null_mut::<i32>();
+    unsafe {
+        remember_ptr(&mut slot, &mut x);
+        // The stored pointer can be used from Rust.
+        *slot = 2;
+    }
+    assert_eq!(x, 2);
+}
+
+fn stream_across_calls() {
+    let input = [1u8, 2, 3, 4, 5, 6];
+    let mut output = [0u8; 4];
+    let mut stream = CopyStream {
+        next_in: input.as_ptr(),
+        avail_in: input.len(),
+        next_out: output.as_mut_ptr(),
+        avail_out: output.len(),
+        state: std::ptr::null_mut(),
+    };
+    unsafe {
+        assert_eq!(copy_init(&mut stream), 0);
+        // The output buffer is full after the first call.
+        assert_eq!(copy_step(&mut stream), 4);
+        assert_eq!(stream.avail_in, 2);
+        assert_eq!(stream.avail_out, 0);
+        assert_eq!(output, [1, 2, 3, 4]);
+
+        // The next call continues where the first one stopped, with the state native code
+        // allocated and the input pointer it advanced.
+        stream.next_out = output.as_mut_ptr();
+        stream.avail_out = output.len();
+        assert_eq!(copy_step(&mut stream), 6);
+        assert_eq!(stream.avail_in, 0);
+        assert_eq!(stream.avail_out, 2);
+        assert_eq!(output[..2], [5, 6]);
+
+        copy_end(&mut stream);
+        assert!(stream.state.is_null());
+    }
+}
+
+fn main() {
+    ptr_in_memory();
+    ptr_stored_by_native_code();
+    stream_across_calls();
+}
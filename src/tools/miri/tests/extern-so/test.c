@@ -1,4 +1,6 @@
+#include <stddef.h>
 #include <stdio.h>
+#include <stdlib.h>
 
 int add_one_int(int x) {
   return 2 + x;
@@ -25,3 +27,85 @@ short add_int16(short x) {
 long add_short_to_long(short x, long y) {
   return x + y;
 }
+
+// functions with pointer arguments
+
+void fill_bytes(unsigned char *ptr, size_t len, unsigned char value) {
+  for (size_t i = 0; i < len; i++) {
+    ptr[i] = value;
+  }
+}
+
+int sum_ints(const int *ptr, size_t len) {
+  int sum = 0;
+  for (size_t i = 0; i < len; i++) {
+    sum += ptr[i];
+  }
+  return sum;
+}
+
+void double_ints(int *ptr, size_t len) {
+  for (size_t i = 0; i < len; i++) {
+    ptr[i] *= 2;
+  }
+}
+
+void swap_ints(int *a, int *b) {
+  int tmp = *a;
+  *a = *b;
+  *b = tmp;
+}
+
+// functions whose pointer arguments point to pointers
+
+struct stream {
+  const unsigned char *next_in;
+  size_t avail_in;
+};
+
+size_t stream_avail(const struct stream *s) {
+  return s->avail_in;
+}
+
+void remember_ptr(int **slot, int *ptr) {
+  *slot = ptr;
+}
+
+// a stream in the style of zlib's `z_stream`, whose state lives in memory allocated by C and
+// which is kept across calls
+
+struct copy_state {
+  size_t total;
+};
+
+struct copy_stream {
+  const unsigned char *next_in;
+  size_t avail_in;
+  unsigned char *next_out;
+  size_t avail_out;
+  struct copy_state *state;
+};
+
+int copy_init(struct copy_stream *s) {
+  s->state = malloc(sizeof(struct copy_state));
+  if (s->state == NULL) {
+    return -1;
+  }
+  s->state->total = 0;
+  return 0;
+}
+
+size_t copy_step(struct copy_stream *s) {
+  while (s->avail_in > 0 && s->avail_out > 0) {
+    *s->next_out++ = *s->next_in++;
+    s->avail_in--;
+    s->avail_out--;
+    s->state->total++;
+  }
+  return s->state->total;
+}
+
+void copy_end(struct copy_stream *s) {
+  free(s->state);
+  s->state = NULL;
+}
@@ -0,0 +1,42 @@
+//@ignore-target-windows: No libc on Windows
+//@compile-flags: -Zmiri-explore=1
+//@normalize-stderr-test: "execution [0-9]+" -> "execution N"
+//@normalize-stderr-test: "choice [0-9]+" -> "choice N"
+
+// The two threads lock the same two mutexes in opposite order, so they deadlock if the first one
+// is preempted right after taking its first lock. Without preemption, the first thread takes and
+// releases both locks before the second one even starts, so only exploring finds the deadlock.
+
+use std::cell::UnsafeCell;
+use std::sync::Arc;
+use std::thread;
+
+struct Mutex(UnsafeCell<libc::pthread_mutex_t>);
+
+unsafe impl Send for Mutex {}
+unsafe impl Sync for Mutex {}
+
+fn new_lock() -> Arc<Mutex> {
+    Arc::new(Mutex(UnsafeCell::new(libc::PTHREAD_MUTEX_INITIALIZER)))
+}
+
+fn main() {
+    let a = new_lock();
+    let b = new_lock();
+    let (a2, b2) = (a.clone(), b.clone());
+
+    let t1 = thread::spawn(move || unsafe {
+        assert_eq!(libc::pthread_mutex_lock(a.0.get()), 0);
+        assert_eq!(libc::pthread_mutex_lock(b.0.get()), 0); //~ ERROR: deadlock
+        assert_eq!(libc::pthread_mutex_unlock(b.0.get()), 0);
+        assert_eq!(libc::pthread_mutex_unlock(a.0.get()), 0);
+    });
+    let t2 = thread::spawn(move || unsafe {
+        assert_eq!(libc::pthread_mutex_lock(b2.0.get()), 0);
+        assert_eq!(libc::pthread_mutex_lock(a2.0.get()), 0);
+        assert_eq!(libc::pthread_mutex_unlock(a2.0.get()), 0);
+        assert_eq!(libc::pthread_mutex_unlock(b2.0.get()), 0);
+    });
+    t1.join().unwrap();
+    t2.join().unwrap();
+}
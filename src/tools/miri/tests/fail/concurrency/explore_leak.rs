@@ -0,0 +1,23 @@
+//@compile-flags: -Zmiri-explore=1 -Zmiri-disable-leak-backtraces
+//@error-in-other-file: the evaluated program leaked memory
+//@normalize-stderr-test: "execution [0-9]+" -> "execution N"
+//@normalize-stderr-test: "choice [0-9]+" -> "choice N"
+
+// The main thread only leaks if the other thread sets the flag before the main thread reads it,
+// which needs a preemption. Every execution is checked for leaks, not just the last one, so
+// exploring finds the leak.
+
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use std::thread;
+
+static READY: AtomicBool = AtomicBool::new(false);
+static FLAG: AtomicBool = AtomicBool::new(false);
+
+fn main() {
+    let t = thread::spawn(|| FLAG.store(true, SeqCst));
+    READY.load(SeqCst);
+    if FLAG.load(SeqCst) {
+        std::mem::forget(Box::new(42));
+    }
+    t.join().unwrap();
+}
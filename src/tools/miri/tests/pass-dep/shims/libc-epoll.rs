@@ -0,0 +1,414 @@
+//@only-target-linux
+
+use std::convert::TryInto;
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn main() {
+    test_epoll_eventfd();
+    test_epoll_socketpair();
+    test_epoll_pipe();
+    test_epoll_edge_triggered();
+    test_epoll_oneshot();
+    test_epoll_ctl_errors();
+    test_epoll_wait_timeout();
+    test_epoll_wait_blocking();
+    test_epoll_wait_blocking_eventfd_out();
+    test_epoll_wait_blocking_socketpair_out();
+    test_epoll_wait_synchronizes();
+}
+
+/// Calls `epoll_wait` and returns the ready events as `(events, data)` pairs, sorted by data
+/// since the order in which events are reported is unspecified.
+fn wait(epfd: i32, timeout: i32) -> Vec<(u32, u64)> {
+    let mut events = [libc::epoll_event { events: 0, u64: 0 }; 8];
+    let res = unsafe { libc::epoll_wait(epfd, events.as_mut_ptr(), 8, timeout) };
+    assert!(res >= 0);
+    let mut ready: Vec<_> =
+        events[..res.try_into().unwrap()].iter().map(|event| (event.events, event.u64)).collect();
+    ready.sort_by_key(|&(_, data)| data);
+    ready
+}
+
+fn add(epfd: i32, fd: i32, events: i32, data: u64) {
+    let mut event = libc::epoll_event { events: events as u32, u64: data };
+    let res = unsafe { libc::epoll_ctl(epfd, libc::EPOLL_CTL_ADD, fd, &mut event) };
+    assert_eq!(res, 0);
+}
+
+fn write_counter(fd: i32, val: u64) {
+    let bytes = val.to_ne_bytes();
+    let res = unsafe { libc::write(fd, bytes.as_ptr().cast(), 8) };
+    assert_eq!(res, 8);
+}
+
+fn test_epoll_eventfd() {
+    let epfd = unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) };
+    assert_ne!(epfd, -1);
+    let fd = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK | libc::EFD_CLOEXEC) };
+    assert_ne!(fd, -1);
+    add(epfd, fd, libc::EPOLLIN | libc::EPOLLOUT, 42);
+
+    // The counter is zero, so the eventfd is only writable.
+    assert_eq!(wait(epfd, 0), [(libc::EPOLLOUT as u32, 42)]);
+
+    // Reading from an empty counter would block.
+    let mut buf = [0u8; 8];
+    let res = unsafe { libc::read(fd, buf.as_mut_ptr().cast(), 8) };
+    assert_eq!(res, -1);
+    assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::EAGAIN));
+
+    write_counter(fd, 3);
+    write_counter(fd, 4);
+    assert_eq!(wait(epfd, 0), [((libc::EPOLLIN | libc::EPOLLOUT) as u32, 42)]);
+
+    // Reading returns the counter, and resets it.
+    let res = unsafe { libc::read(fd, buf.as_mut_ptr().cast(), 8) };
+    assert_eq!(res, 8);
+    assert_eq!(u64::from_ne_bytes(buf), 7);
+    assert_eq!(wait(epfd, 0), [(libc::EPOLLOUT as u32, 42)]);
+
+    unsafe {
+        assert_eq!(libc::close(fd), 0);
+        assert_eq!(libc::close(epfd), 0);
+    }
+}
+
+fn test_epoll_socketpair() {
+    let epfd = unsafe { libc::epoll_create1(0) };
+    assert_ne!(epfd, -1);
+    let mut fds = [-1, -1];
+    let res = unsafe {
+        libc::socketpair(
+            libc::AF_UNIX,
+            libc::SOCK_STREAM | libc::SOCK_NONBLOCK,
+            0,
+            fds.as_mut_ptr(),
+        )
+    };
+    assert_eq!(res, 0);
+    add(epfd, fds[0], libc::EPOLLIN | libc::EPOLLRDHUP, 0);
+    add(epfd, fds[1], libc::EPOLLIN | libc::EPOLLOUT, 1);
+
+    // Only the second socket asked to be told about writability.
+    assert_eq!(wait(epfd, 0), [(libc::EPOLLOUT as u32, 1)]);
+
+    let data = *b"abcde";
+    let res = unsafe { libc::write(fds[1], data.as_ptr().cast(), 5) };
+    assert_eq!(res, 5);
+    assert_eq!(wait(epfd, 0), [(libc::EPOLLIN as u32, 0), (libc::EPOLLOUT as u32, 1)]);
+
+    // Reads return at most what is in the buffer.
+    let mut buf = [0u8; 8];
+    let res = unsafe { libc::read(fds[0], buf.as_mut_ptr().cast(), 3) };
+    assert_eq!(res, 3);
+    assert_eq!(&buf[..3], b"abc");
+    let res = unsafe { libc::read(fds[0], buf.as_mut_ptr().cast(), 8) };
+    assert_eq!(res, 2);
+    assert_eq!(&buf[..2], b"de");
+    assert_eq!(wait(epfd, 0), [(libc::EPOLLOUT as u32, 1)]);
+
+    // Closing one end hangs up the other one, and removes the closed one from the interest list.
+    unsafe {
+        assert_eq!(libc::close(fds[1]), 0);
+    }
+    assert_eq!(wait(epfd, 0), [((libc::EPOLLIN | libc::EPOLLRDHUP | libc::EPOLLHUP) as u32, 0)]);
+    let res = unsafe { libc::read(fds[0], buf.as_mut_ptr().cast(), 8) };
+    assert_eq!(res, 0);
+    let res = unsafe { libc::write(fds[0], data.as_ptr().cast(), 5) };
+    assert_eq!(res, -1);
+    assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::EPIPE));
+
+    unsafe {
+        assert_eq!(libc::close(fds[0]), 0);
+        assert_eq!(libc::close(epfd), 0);
+    }
+}
+
+fn test_epoll_pipe() {
+    let epfd = unsafe { libc::epoll_create1(0) };
+    assert_ne!(epfd, -1);
+    let mut fds = [-1, -1];
+    let res = unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_NONBLOCK) };
+    assert_eq!(res, 0);
+    add(epfd, fds[0], libc::EPOLLIN, 0);
+    add(epfd, fds[1], libc::EPOLLOUT, 1);
+
+    assert_eq!(wait(epfd, 0), [(libc::EPOLLOUT as u32, 1)]);
+
+    let data = *b"abc";
+    let res = unsafe { libc::write(fds[1], data.as_ptr().cast(), 3) };
+    assert_eq!(res, 3);
+    assert_eq!(wait(epfd, 0), [(libc::EPOLLIN as u32, 0), (libc::EPOLLOUT as u32, 1)]);
+
+    // A read does not overwrite the part of the buffer that it did not fill.
+    let mut buf = [0xffu8; 8];
+    let res = unsafe { libc::read(fds[0], buf.as_mut_ptr().cast(), 8) };
+    assert_eq!(res, 3);
+    assert_eq!(buf, *b"abc\xff\xff\xff\xff\xff");
+
+    let res = unsafe { libc::read(fds[0], buf.as_mut_ptr().cast(), 8) };
+    assert_eq!(res, -1);
+    assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::EAGAIN));
+
+    // Once the write end is closed, the read end hits the end of file.
+    unsafe {
+        assert_eq!(libc::close(fds[1]), 0);
+    }
+    assert_eq!(wait(epfd, 0), [(libc::EPOLLHUP as u32, 0)]);
+    let res = unsafe { libc::read(fds[0], buf.as_mut_ptr().cast(), 8) };
+    assert_eq!(res, 0);
+    unsafe {
+        assert_eq!(libc::close(fds[0]), 0);
+    }
+
+    // Once the read end is closed, the write end reports an error.
+    let res = unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_NONBLOCK) };
+    assert_eq!(res, 0);
+    add(epfd, fds[1], libc::EPOLLOUT, 1);
+    unsafe {
+        assert_eq!(libc::close(fds[0]), 0);
+    }
+    assert_eq!(wait(epfd, 0), [((libc::EPOLLOUT | libc::EPOLLERR) as u32, 1)]);
+    let res = unsafe { libc::write(fds[1], data.as_ptr().cast(), 3) };
+    assert_eq!(res, -1);
+    assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::EPIPE));
+
+    unsafe {
+        assert_eq!(libc::close(fds[1]), 0);
+        assert_eq!(libc::close(epfd), 0);
+    }
+}
+
+fn test_epoll_edge_triggered() {
+    let epfd = unsafe { libc::epoll_create1(0) };
+    assert_ne!(epfd, -1);
+    let fd = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK) };
+    assert_ne!(fd, -1);
+    add(epfd, fd, libc::EPOLLIN | libc::EPOLLOUT | libc::EPOLLET, 7);
+
+    // The readiness is reported once after registering, and then only after a change.
+    assert_eq!(wait(epfd, 0), [(libc::EPOLLOUT as u32, 7)]);
+    assert_eq!(wait(epfd, 0), []);
+
+    write_counter(fd, 1);
+    assert_eq!(wait(epfd, 0), [((libc::EPOLLIN | libc::EPOLLOUT) as u32, 7)]);
+    assert_eq!(wait(epfd, 0), []);
+
+    // Even though the counter was already readable, another write is another edge.
+    write_counter(fd, 1);
+    assert_eq!(wait(epfd, 0), [((libc::EPOLLIN | libc::EPOLLOUT) as u32, 7)]);
+
+    unsafe {
+        assert_eq!(libc::close(fd), 0);
+        assert_eq!(libc::close(epfd), 0);
+    }
+}
+
+fn test_epoll_oneshot() {
+    let epfd = unsafe { libc::epoll_create1(0) };
+    assert_ne!(epfd, -1);
+    let fd = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK) };
+    assert_ne!(fd, -1);
+    add(epfd, fd, libc::EPOLLOUT | libc::EPOLLONESHOT, 3);
+
+    assert_eq!(wait(epfd, 0), [(libc::EPOLLOUT as u32, 3)]);
+    assert_eq!(wait(epfd, 0), []);
+
+    // Modifying the interest re-arms it.
+    let mut event = libc::epoll_event { events: libc::EPOLLOUT as u32, u64: 4 };
+    let res = unsafe { libc::epoll_ctl(epfd, libc::EPOLL_CTL_MOD, fd, &mut event) };
+    assert_eq!(res, 0);
+    assert_eq!(wait(epfd, 0), [(libc::EPOLLOUT as u32, 4)]);
+    assert_eq!(wait(epfd, 0), [(libc::EPOLLOUT as u32, 4)]);
+
+    unsafe {
+        assert_eq!(libc::close(fd), 0);
+        assert_eq!(libc::close(epfd), 0);
+    }
+}
+
+fn test_epoll_ctl_errors() {
+    let epfd = unsafe { libc::epoll_create1(0) };
+    assert_ne!(epfd, -1);
+    let fd = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK) };
+    assert_ne!(fd, -1);
+    let mut event = libc::epoll_event { events: libc::EPOLLIN as u32, u64: 0 };
+
+    let res = unsafe { libc::epoll_ctl(epfd, libc::EPOLL_CTL_MOD, fd, &mut event) };
+    assert_eq!(res, -1);
+    assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::ENOENT));
+
+    add(epfd, fd, libc::EPOLLIN, 0);
+    let res = unsafe { libc::epoll_ctl(epfd, libc::EPOLL_CTL_ADD, fd, &mut event) };
+    assert_eq!(res, -1);
+    assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::EEXIST));
+
+    let res = unsafe { libc::epoll_ctl(epfd, libc::EPOLL_CTL_DEL, fd, std::ptr::null_mut()) };
+    assert_eq!(res, 0);
+    let res = unsafe { libc::epoll_ctl(epfd, libc::EPOLL_CTL_DEL, fd, std::ptr::null_mut()) };
+    assert_eq!(res, -1);
+    assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::ENOENT));
+
+    let res = unsafe { libc::epoll_ctl(epfd, libc::EPOLL_CTL_ADD, epfd, &mut event) };
+    assert_eq!(res, -1);
+    assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::EINVAL));
+
+    let mut events = [libc::epoll_event { events: 0, u64: 0 }; 1];
+    let res = unsafe { libc::epoll_wait(epfd, events.as_mut_ptr(), 0, 0) };
+    assert_eq!(res, -1);
+    assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::EINVAL));
+
+    unsafe {
+        assert_eq!(libc::close(fd), 0);
+        assert_eq!(libc::close(epfd), 0);
+    }
+}
+
+fn test_epoll_wait_timeout() {
+    let epfd = unsafe { libc::epoll_create1(0) };
+    assert_ne!(epfd, -1);
+    let fd = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK) };
+    assert_ne!(fd, -1);
+    add(epfd, fd, libc::EPOLLIN, 0);
+
+    let start = Instant::now();
+    assert_eq!(wait(epfd, 200), []);
+    // When running natively, the timeout might be rounded to the system clock granularity.
+    assert!(start.elapsed() >= Duration::from_millis(199));
+
+    unsafe {
+        assert_eq!(libc::close(fd), 0);
+        assert_eq!(libc::close(epfd), 0);
+    }
+}
+
+fn test_epoll_wait_blocking() {
+    let epfd = unsafe { libc::epoll_create1(0) };
+    assert_ne!(epfd, -1);
+    let fd = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK) };
+    assert_ne!(fd, -1);
+    add(epfd, fd, libc::EPOLLIN, 5);
+
+    // The writer sleeps first, so that the main thread is blocked in `epoll_wait` when it writes.
+    let writer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        write_counter(fd, 1);
+    });
+    assert_eq!(wait(epfd, -1), [(libc::EPOLLIN as u32, 5)]);
+    writer.join().unwrap();
+
+    unsafe {
+        assert_eq!(libc::close(fd), 0);
+        assert_eq!(libc::close(epfd), 0);
+    }
+}
+
+fn test_epoll_wait_blocking_eventfd_out() {
+    let epfd = unsafe { libc::epoll_create1(0) };
+    assert_ne!(epfd, -1);
+    let fd = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK) };
+    assert_ne!(fd, -1);
+    add(epfd, fd, libc::EPOLLOUT, 6);
+
+    // At its maximum value, the counter cannot be added to, so the eventfd is not writable.
+    write_counter(fd, u64::MAX - 1);
+    assert_eq!(wait(epfd, 0), []);
+
+    // Reading resets the counter, which has to wake up the main thread.
+    let reader = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        let mut buf = [0u8; 8];
+        let res = unsafe { libc::read(fd, buf.as_mut_ptr().cast(), 8) };
+        assert_eq!(res, 8);
+        assert_eq!(u64::from_ne_bytes(buf), u64::MAX - 1);
+    });
+    assert_eq!(wait(epfd, -1), [(libc::EPOLLOUT as u32, 6)]);
+    reader.join().unwrap();
+
+    unsafe {
+        assert_eq!(libc::close(fd), 0);
+        assert_eq!(libc::close(epfd), 0);
+    }
+}
+
+fn test_epoll_wait_blocking_socketpair_out() {
+    let epfd = unsafe { libc::epoll_create1(0) };
+    assert_ne!(epfd, -1);
+    let mut fds = [-1, -1];
+    let res = unsafe {
+        libc::socketpair(
+            libc::AF_UNIX,
+            libc::SOCK_STREAM | libc::SOCK_NONBLOCK,
+            0,
+            fds.as_mut_ptr(),
+        )
+    };
+    assert_eq!(res, 0);
+    add(epfd, fds[1], libc::EPOLLOUT, 7);
+
+    // Fill the buffer of the peer until writing would block.
+    let data = [0u8; 4096];
+    let mut written = 0;
+    loop {
+        let res = unsafe { libc::write(fds[1], data.as_ptr().cast(), data.len()) };
+        if res == -1 {
+            assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::EAGAIN));
+            break;
+        }
+        written += usize::try_from(res).unwrap();
+    }
+    assert!(written > 0);
+    assert_eq!(wait(epfd, 0), []);
+
+    // Reading from the other end makes room, which has to wake up the main thread.
+    let fd = fds[0];
+    let reader = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        let mut buf = [0u8; 4096];
+        let res = unsafe { libc::read(fd, buf.as_mut_ptr().cast(), buf.len()) };
+        assert_eq!(res, 4096);
+    });
+    assert_eq!(wait(epfd, -1), [(libc::EPOLLOUT as u32, 7)]);
+    reader.join().unwrap();
+
+    unsafe {
+        assert_eq!(libc::close(fds[0]), 0);
+        assert_eq!(libc::close(fds[1]), 0);
+        assert_eq!(libc::close(epfd), 0);
+    }
+}
+
+/// Whatever happened before a file descriptor became ready happens-before `epoll_wait` reports
+/// it, whether `epoll_wait` had to block or not.
+fn test_epoll_wait_synchronizes() {
+    static mut DATA: u64 = 0;
+
+    for writer_sleeps in [true, false] {
+        let epfd = unsafe { libc::epoll_create1(0) };
+        assert_ne!(epfd, -1);
+        let fd = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK) };
+        assert_ne!(fd, -1);
+        add(epfd, fd, libc::EPOLLIN, 8);
+
+        let writer = thread::spawn(move || {
+            if writer_sleeps {
+                thread::sleep(Duration::from_millis(100));
+            }
+            unsafe { DATA += 1 };
+            write_counter(fd, 1);
+        });
+        if !writer_sleeps {
+            thread::sleep(Duration::from_millis(100));
+        }
+        assert_eq!(wait(epfd, -1), [(libc::EPOLLIN as u32, 8)]);
+        // This is only synchronized through the eventfd: the writer has not been joined yet.
+        assert_eq!(unsafe { DATA }, if writer_sleeps { 1 } else { 2 });
+        writer.join().unwrap();
+
+        unsafe {
+            assert_eq!(libc::close(fd), 0);
+            assert_eq!(libc::close(epfd), 0);
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//@compile-flags: -Zmiri-permissive-provenance -Zmiri-backtrace=full
+//@only-target-x86_64-unknown-linux: support for tokio only on linux and x86
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+
+#[tokio::main]
+async fn main() {
+    tcp().await;
+    udp().await;
+}
+
+async fn tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        stream.read_to_end(&mut request).await.unwrap();
+        stream.write_all(&request.repeat(2)).await.unwrap();
+    });
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(b"echo").await.unwrap();
+    stream.shutdown().await.unwrap();
+    let mut reply = Vec::new();
+    stream.read_to_end(&mut reply).await.unwrap();
+    assert_eq!(reply, b"echoecho");
+
+    server.await.unwrap();
+}
+
+async fn udp() {
+    let a = UdpSocket::bind("[::1]:0").await.unwrap();
+    let b = UdpSocket::bind("[::1]:0").await.unwrap();
+    let b_addr = b.local_addr().unwrap();
+
+    let receiver = tokio::spawn(async move {
+        let mut buf = [0; 8];
+        let (len, from) = b.recv_from(&mut buf).await.unwrap();
+        b.send_to(&buf[..len], from).await.unwrap();
+    });
+
+    a.send_to(b"hello", b_addr).await.unwrap();
+    let mut buf = [0; 8];
+    assert_eq!(a.recv(&mut buf).await.unwrap(), 5);
+    assert_eq!(&buf[..5], b"hello");
+
+    receiver.await.unwrap();
+}
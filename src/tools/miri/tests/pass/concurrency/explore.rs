@@ -0,0 +1,81 @@
+//@compile-flags: -Zmiri-explore=1
+// Check that correctly synchronized code passes in every execution that `-Zmiri-explore` covers.
+
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+
+struct SpinLock {
+    locked: AtomicBool,
+    data: UnsafeCell<u32>,
+}
+
+unsafe impl Sync for SpinLock {}
+
+impl SpinLock {
+    fn with<R>(&self, f: impl FnOnce(&mut u32) -> R) -> R {
+        while self.locked.swap(true, Ordering::Acquire) {
+            thread::yield_now();
+        }
+        let result = f(unsafe { &mut *self.data.get() });
+        self.locked.store(false, Ordering::Release);
+        result
+    }
+}
+
+fn spin_lock() {
+    static LOCK: SpinLock = SpinLock { locked: AtomicBool::new(false), data: UnsafeCell::new(0) };
+
+    let threads: Vec<_> = (0..2).map(|_| thread::spawn(|| LOCK.with(|data| *data += 1))).collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(LOCK.with(|data| *data), 2);
+}
+
+fn message_passing() {
+    static READY: AtomicBool = AtomicBool::new(false);
+    static mut MESSAGE: u32 = 0;
+
+    let sender = thread::spawn(|| {
+        unsafe { MESSAGE = 42 };
+        READY.store(true, Ordering::Release);
+    });
+    let receiver = thread::spawn(|| {
+        if READY.load(Ordering::Acquire) {
+            assert_eq!(unsafe { MESSAGE }, 42);
+        }
+    });
+    sender.join().unwrap();
+    receiver.join().unwrap();
+}
+
+fn compare_exchange_weak_counter() {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let threads: Vec<_> = (0..2)
+        .map(|_| {
+            thread::spawn(|| {
+                let mut current = COUNTER.load(Ordering::Relaxed);
+                while let Err(actual) = COUNTER.compare_exchange_weak(
+                    current,
+                    current + 1,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    current = actual;
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(COUNTER.load(Ordering::Relaxed), 2);
+}
+
+fn main() {
+    spin_lock();
+    message_passing();
+    compare_exchange_weak_counter();
+}
@@ -0,0 +1,188 @@
+//@only-target-linux
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::thread;
+
+fn main() {
+    test_tcp(Ipv4Addr::LOCALHOST.into());
+    test_tcp(Ipv6Addr::LOCALHOST.into());
+    test_tcp_errors();
+    test_tcp_nonblocking();
+    test_tcp_blocking_send();
+    test_tcp_synchronizes();
+    test_tcp_unspecified();
+    test_udp();
+    test_udp_connected();
+}
+
+fn test_tcp(ip: std::net::IpAddr) {
+    let listener = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+    let server_addr = listener.local_addr().unwrap();
+    assert_eq!(server_addr.ip(), ip);
+    assert_ne!(server_addr.port(), 0);
+
+    let client = thread::spawn(move || {
+        let mut stream = TcpStream::connect(server_addr).unwrap();
+        assert_eq!(stream.peer_addr().unwrap(), server_addr);
+        stream.write_all(b"ping").unwrap();
+        stream.shutdown(Shutdown::Write).unwrap();
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply).unwrap();
+        assert_eq!(reply, "pong");
+        stream.local_addr().unwrap()
+    });
+
+    let (mut stream, client_addr) = listener.accept().unwrap();
+    assert_eq!(stream.local_addr().unwrap(), server_addr);
+    assert_eq!(stream.peer_addr().unwrap(), client_addr);
+    let mut request = Vec::new();
+    stream.read_to_end(&mut request).unwrap();
+    assert_eq!(request, b"ping");
+    stream.write_all(b"pong").unwrap();
+    drop(stream);
+
+    assert_eq!(client.join().unwrap(), client_addr);
+}
+
+fn test_tcp_errors() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    // The address is taken, until the listener is closed.
+    assert_eq!(TcpListener::bind(addr).unwrap_err().kind(), ErrorKind::AddrInUse);
+    drop(listener);
+    assert_eq!(TcpStream::connect(addr).unwrap_err().kind(), ErrorKind::ConnectionRefused);
+    let listener = TcpListener::bind(addr).unwrap();
+    drop(listener);
+
+    // There is no network besides the loopback interface.
+    let err = TcpListener::bind("192.0.2.1:0").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AddrNotAvailable);
+
+    // Reading from a stream whose peer hung up reaches the end of file.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (server, _) = listener.accept().unwrap();
+    drop(server);
+    let mut buf = [0; 4];
+    assert_eq!(client.read(&mut buf).unwrap(), 0);
+}
+
+fn test_tcp_nonblocking() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    assert_eq!(listener.accept().unwrap_err().kind(), ErrorKind::WouldBlock);
+
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+    server.set_nonblocking(true).unwrap();
+    let mut buf = [0; 8];
+    assert_eq!(server.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+
+    client.write_all(b"data").unwrap();
+    assert_eq!(server.peek(&mut buf).unwrap(), 4);
+    assert_eq!(server.read(&mut buf).unwrap(), 4);
+    assert_eq!(&buf[..4], b"data");
+    assert_eq!(server.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+}
+
+fn test_tcp_blocking_send() {
+    // More than fits into the buffer of the receiver, so the sender has to wait for it to read.
+    let data = vec![7u8; 300_000];
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+
+    let sender = thread::spawn(move || {
+        client.write_all(&data).unwrap();
+    });
+    let mut received = Vec::new();
+    server.read_to_end(&mut received).unwrap();
+    assert_eq!(received.len(), 300_000);
+    assert!(received.iter().all(|&b| b == 7));
+    sender.join().unwrap();
+}
+
+fn test_tcp_synchronizes() {
+    static mut DATA: u32 = 0;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+
+    let sender = thread::spawn(move || {
+        unsafe { DATA = 42 };
+        client.write_all(b"ready").unwrap();
+    });
+    let mut buf = [0; 5];
+    server.read_exact(&mut buf).unwrap();
+    // Receiving the data synchronizes with sending it, so this is not a data race.
+    assert_eq!(unsafe { DATA }, 42);
+    sender.join().unwrap();
+}
+
+fn test_tcp_unspecified() {
+    // Sockets bound to the unspecified address accept connections to the loopback address.
+    let listener = TcpListener::bind("0.0.0.0:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let client = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    let (server, client_addr) = listener.accept().unwrap();
+    assert_eq!(client.local_addr().unwrap(), client_addr);
+    assert_eq!(server.local_addr().unwrap(), SocketAddr::from(([127, 0, 0, 1], port)));
+}
+
+fn test_udp() {
+    let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let a_addr = a.local_addr().unwrap();
+    let b_addr = b.local_addr().unwrap();
+
+    // Datagrams keep their boundaries.
+    a.send_to(b"first", b_addr).unwrap();
+    a.send_to(b"second", b_addr).unwrap();
+    let mut buf = [0; 16];
+    assert_eq!(b.peek_from(&mut buf).unwrap(), (5, a_addr));
+    assert_eq!(b.recv_from(&mut buf).unwrap(), (5, a_addr));
+    assert_eq!(&buf[..5], b"first");
+    // Whatever does not fit into the buffer is discarded.
+    assert_eq!(b.recv_from(&mut buf[..3]).unwrap(), (3, a_addr));
+    assert_eq!(&buf[..3], b"sec");
+
+    b.set_nonblocking(true).unwrap();
+    assert_eq!(b.recv_from(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+    b.set_nonblocking(false).unwrap();
+
+    // Block until another thread sends something.
+    let sender = thread::spawn(move || {
+        a.send_to(b"later", b_addr).unwrap();
+    });
+    assert_eq!(b.recv_from(&mut buf).unwrap(), (5, a_addr));
+    assert_eq!(&buf[..5], b"later");
+    sender.join().unwrap();
+
+    // Sending to an address that nobody is bound to succeeds, the datagram is just lost.
+    let c = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let c_addr = c.local_addr().unwrap();
+    drop(c);
+    b.send_to(b"lost", c_addr).unwrap();
+}
+
+fn test_udp_connected() {
+    let a = UdpSocket::bind("[::1]:0").unwrap();
+    let b = UdpSocket::bind("[::1]:0").unwrap();
+    let c = UdpSocket::bind("[::1]:0").unwrap();
+    a.connect(b.local_addr().unwrap()).unwrap();
+    assert_eq!(a.peer_addr().unwrap(), b.local_addr().unwrap());
+
+    // Connected sockets only receive from their peer.
+    c.send_to(b"ignored", a.local_addr().unwrap()).unwrap();
+    b.send_to(b"hello", a.local_addr().unwrap()).unwrap();
+    let mut buf = [0; 16];
+    assert_eq!(a.recv(&mut buf).unwrap(), 5);
+    assert_eq!(&buf[..5], b"hello");
+
+    a.send(b"reply").unwrap();
+    assert_eq!(b.recv_from(&mut buf).unwrap(), (5, a.local_addr().unwrap()));
+    assert_eq!(&buf[..5], b"reply");
+}
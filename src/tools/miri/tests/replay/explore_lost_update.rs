@@ -0,0 +1,25 @@
+//! Two threads increment a counter with a separate load and store, so an increment gets lost if
+//! one of them is preempted in between. Exploring finds that execution, and replaying its trace
+//! has to run into the same failure.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn main() {
+    let threads: Vec<_> = (0..2)
+        .map(|_| {
+            thread::spawn(|| {
+                let value = COUNTER.load(Ordering::SeqCst);
+                COUNTER.store(value + 1, Ordering::SeqCst);
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    let count = COUNTER.load(Ordering::SeqCst);
+    println!("{count}");
+    assert_eq!(count, 2);
+}
@@ -0,0 +1,13 @@
+//! Randomness and the host's clocks are different in every run, and so is the iteration order of
+//! a `HashMap`.
+
+use std::collections::HashMap;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+fn main() {
+    let start = Instant::now();
+    let map: HashMap<u32, u32> = (0..8).map(|i| (i, i * i)).collect();
+    println!("{:?}", map.keys().collect::<Vec<_>>());
+    println!("{}", SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos());
+    println!("{}", start.elapsed().as_nanos());
+}
@@ -0,0 +1,27 @@
+//! The order in which the threads run depends on when Miri preempts them, and the addresses of
+//! their allocations are random.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+fn main() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let handles: Vec<_> = (0..3)
+        .map(|id| {
+            let log = Arc::clone(&log);
+            thread::spawn(move || {
+                for step in 0..4 {
+                    let value = Box::new(step);
+                    log.lock().unwrap().push((id, step, &*value as *const i32 as usize % 4096));
+                    thread::yield_now();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    for (id, step, addr) in log.lock().unwrap().iter() {
+        println!("thread {id} step {step} at {addr:#x}");
+    }
+}
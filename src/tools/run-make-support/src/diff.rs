@@ -0,0 +1,150 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{out_dir, read_to_string, test_dir};
+
+/// Compares the output of a test with the expected output, like `$(DIFF)` and
+/// `$(RUSTC_TEST_OP)` in Makefiles.
+pub fn diff() -> Diff {
+    Diff::new()
+}
+
+/// A comparison of an actual output with the expected one. Before comparing, paths to
+/// [`out_dir`] and to the test's directory in the actual output are replaced with `$TMPDIR` and
+/// `$DIR`, and line endings are normalized. With `--bless`, an expected output that was read from
+/// a file is updated instead of compared.
+#[derive(Debug, Default)]
+pub struct Diff {
+    expected: Option<String>,
+    expected_name: Option<String>,
+    expected_file: Option<PathBuf>,
+    actual: Option<String>,
+    actual_name: Option<String>,
+    normalizers: Vec<(String, String)>,
+}
+
+impl Diff {
+    pub fn new() -> Self {
+        Diff::default()
+    }
+
+    /// Read the expected output from `path`. A missing file counts as empty output, so that
+    /// blessing can create it.
+    pub fn expected_file(&mut self, path: impl AsRef<Path>) -> &mut Self {
+        let path = path.as_ref();
+        let contents = if path.exists() { read_to_string(path) } else { String::new() };
+        self.expected = Some(contents);
+        self.expected_name = Some(path.display().to_string());
+        self.expected_file = Some(path.to_path_buf());
+        self
+    }
+
+    pub fn expected_text(&mut self, name: &str, text: impl AsRef<str>) -> &mut Self {
+        self.expected = Some(text.as_ref().to_string());
+        self.expected_name = Some(name.to_string());
+        self
+    }
+
+    pub fn actual_file(&mut self, path: impl AsRef<Path>) -> &mut Self {
+        let path = path.as_ref();
+        self.actual = Some(read_to_string(path));
+        self.actual_name = Some(path.display().to_string());
+        self
+    }
+
+    pub fn actual_text(&mut self, name: &str, text: impl AsRef<str>) -> &mut Self {
+        self.actual = Some(text.as_ref().to_string());
+        self.actual_name = Some(name.to_string());
+        self
+    }
+
+    /// Replace every occurrence of `from` in the actual output with `to`, before the paths are
+    /// normalized.
+    pub fn normalize(&mut self, from: &str, to: &str) -> &mut Self {
+        self.normalizers.push((from.to_string(), to.to_string()));
+        self
+    }
+
+    /// Compares the outputs, and panics with a line diff if they differ.
+    #[track_caller]
+    pub fn run(&mut self) {
+        let expected = self.expected.as_deref().expect("expected output not set");
+        let expected_name = self.expected_name.as_deref().unwrap();
+        let actual = self.actual.as_deref().expect("actual output not set");
+        let actual_name = self.actual_name.as_deref().unwrap();
+
+        let actual = self.normalized(actual);
+        let expected = expected.replace("\r\n", "\n");
+        if actual == expected {
+            return;
+        }
+
+        if let Some(expected_file) = &self.expected_file {
+            if std::env::var_os("RUSTC_BLESS_TEST").is_some() {
+                if let Err(e) = fs::write(expected_file, &actual) {
+                    panic!("failed to bless `{}`: {e}", expected_file.display());
+                }
+                return;
+            }
+        }
+
+        panic!(
+            "`{actual_name}` differs from `{expected_name}`:\n--- {expected_name}\n+++ {actual_name}\n{}",
+            line_diff(&expected, &actual)
+        );
+    }
+
+    fn normalized(&self, actual: &str) -> String {
+        let mut actual = actual.replace("\r\n", "\n");
+        for (from, to) in &self.normalizers {
+            actual = actual.replace(from, to);
+        }
+        for (path, placeholder) in [(out_dir(), "$TMPDIR"), (test_dir(), "$DIR")] {
+            let path = path.display().to_string();
+            actual = actual.replace(&path, placeholder);
+            // Paths in JSON output or debug formatting have their backslashes escaped.
+            if path.contains('\\') {
+                actual = actual.replace(&path.replace('\\', "\\\\"), placeholder);
+            }
+        }
+        actual
+    }
+}
+
+/// A line-by-line diff of `expected` and `actual`, based on their longest common subsequence.
+fn line_diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    // `common[i][j]` is the length of the longest common subsequence of `expected[i..]` and
+    // `actual[j..]`.
+    let mut common = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = if expected[i] == actual[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            writeln!(out, " {}", expected[i]).unwrap();
+            i += 1;
+            j += 1;
+        } else if j == actual.len() || (i < expected.len() && common[i + 1][j] >= common[i][j + 1])
+        {
+            writeln!(out, "-{}", expected[i]).unwrap();
+            i += 1;
+        } else {
+            writeln!(out, "+{}", actual[j]).unwrap();
+            j += 1;
+        }
+    }
+    out
+}
@@ -0,0 +1,201 @@
+//! Support library for the `rmake.rs` recipes of `run-make` tests.
+//!
+//! compiletest compiles each `rmake.rs` against this library and runs it from the test's source
+//! directory, with the same environment that Makefile-based tests get (`RUSTC`, `RUSTDOC`,
+//! `TMPDIR`, `TARGET`, ...). The helpers here mirror what `tests/run-make/tools.mk` provides to
+//! Makefiles. They panic when something goes wrong, showing the command line and its output, so
+//! a recipe can be written as a plain sequence of steps.
+
+mod diff;
+mod rustc;
+mod rustdoc;
+
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+pub use diff::{diff, Diff};
+pub use rustc::{aux_build, bare_rustc, rustc, Rustc};
+pub use rustdoc::{bare_rustdoc, rustdoc, Rustdoc};
+
+/// Returns the value of the environment variable `name`, which compiletest is expected to set.
+#[track_caller]
+pub fn env_var(name: &str) -> String {
+    match env::var(name) {
+        Ok(value) => value,
+        Err(e) => panic!("failed to read environment variable `{name}`: {e}"),
+    }
+}
+
+/// The directory that tests write their artifacts to, `$TMPDIR` in Makefiles. It is created
+/// empty for every run of the test.
+pub fn out_dir() -> PathBuf {
+    env_var("TMPDIR").into()
+}
+
+/// The source directory of the test, which the recipe is run from.
+pub fn test_dir() -> PathBuf {
+    env::current_dir().unwrap()
+}
+
+/// The root of the Rust source tree, `$S` in Makefiles.
+pub fn source_root() -> PathBuf {
+    env_var("S").into()
+}
+
+/// The target triple that the tests compile for.
+pub fn target() -> String {
+    env_var("TARGET")
+}
+
+/// Whether the target is a Windows target.
+pub fn is_windows() -> bool {
+    target().contains("windows")
+}
+
+/// Whether the target uses the MSVC toolchain.
+pub fn is_msvc() -> bool {
+    target().contains("msvc")
+}
+
+/// Whether the target is an Apple target.
+pub fn is_darwin() -> bool {
+    target().contains("darwin")
+}
+
+/// The file name of an executable called `name`, e.g. `foo.exe` on Windows.
+pub fn bin_name(name: &str) -> String {
+    if is_windows() { format!("{name}.exe") } else { name.to_string() }
+}
+
+/// The file name of the rlib of the crate `name`.
+pub fn rust_lib_name(name: &str) -> String {
+    format!("lib{name}.rlib")
+}
+
+/// The file name of a static library called `name`, e.g. `libfoo.a` or `foo.lib`.
+pub fn static_lib_name(name: &str) -> String {
+    if is_msvc() { format!("{name}.lib") } else { format!("lib{name}.a") }
+}
+
+/// The file name of a dynamic library called `name`, e.g. `libfoo.so` or `foo.dll`.
+pub fn dynamic_lib_name(name: &str) -> String {
+    if is_windows() {
+        format!("{name}.dll")
+    } else if is_darwin() {
+        format!("lib{name}.dylib")
+    } else {
+        format!("lib{name}.so")
+    }
+}
+
+/// Reads the file at `path`, panicking with its path if that fails.
+#[track_caller]
+pub fn read_file(path: impl AsRef<Path>) -> Vec<u8> {
+    let path = path.as_ref();
+    match fs::read(path) {
+        Ok(contents) => contents,
+        Err(e) => panic!("failed to read `{}`: {e}", path.display()),
+    }
+}
+
+/// Reads the file at `path` as UTF-8, panicking with its path if that fails.
+#[track_caller]
+pub fn read_to_string(path: impl AsRef<Path>) -> String {
+    let path = path.as_ref();
+    match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => panic!("failed to read `{}`: {e}", path.display()),
+    }
+}
+
+/// The files directly inside `dir` whose extension is `ext`, sorted by path.
+#[track_caller]
+pub fn files_with_extension(dir: impl AsRef<Path>, ext: &str) -> Vec<PathBuf> {
+    let dir = dir.as_ref();
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => panic!("failed to read directory `{}`: {e}", dir.display()),
+    };
+    let mut files: Vec<PathBuf> = entries
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|e| e == ext))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Whether `haystack` contains `needle`, e.g. whether an object file mentions a symbol.
+pub fn contains_bytes(haystack: &[u8], needle: &[u8]) -> bool {
+    needle.is_empty() || haystack.windows(needle.len()).any(|window| window == needle)
+}
+
+/// The standard output of a finished command, lossily converted to UTF-8.
+pub fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// The standard error of a finished command, lossily converted to UTF-8.
+pub fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+/// Runs the executable `name` that was built into [`out_dir`], and checks that it succeeds.
+#[track_caller]
+pub fn run(name: &str) -> Output {
+    run_command(&mut run_cmd(name), true)
+}
+
+/// Runs the executable `name` that was built into [`out_dir`], and checks that it fails.
+#[track_caller]
+pub fn run_fail(name: &str) -> Output {
+    run_command(&mut run_cmd(name), false)
+}
+
+/// Like `$(TARGET_RPATH_ENV)` in Makefiles, programs built by the tests can find the target's
+/// standard library and the dynamic libraries in [`out_dir`].
+fn run_cmd(name: &str) -> Command {
+    let mut cmd = Command::new(out_dir().join(bin_name(name)));
+    add_dylib_path(&mut cmd, &[out_dir(), env_var("TARGET_RPATH_DIR").into()]);
+    cmd
+}
+
+/// Prepends `dirs` to the dynamic library search path of `cmd`.
+fn add_dylib_path(cmd: &mut Command, dirs: &[PathBuf]) {
+    let var = env_var("LD_LIB_PATH_ENVVAR");
+    let existing = env::var_os(&var).unwrap_or_default();
+    let paths = dirs.iter().cloned().chain(env::split_paths(&existing));
+    let path: OsString = env::join_paths(paths).unwrap();
+    cmd.env(var, path);
+}
+
+/// Runs `cmd`, and checks that it succeeds if `expect_success` is true, or that it fails
+/// otherwise.
+#[track_caller]
+fn run_command(cmd: &mut Command, expect_success: bool) -> Output {
+    let output = match cmd.output() {
+        Ok(output) => output,
+        Err(e) => panic!("failed to run `{cmd:?}`: {e}"),
+    };
+    if output.status.success() != expect_success {
+        handle_failed_output(cmd, &output);
+    }
+    output
+}
+
+/// Panics with the command line and the output of a command that did not exit as expected.
+#[track_caller]
+pub fn handle_failed_output(cmd: &Command, output: &Output) -> ! {
+    let expected = if output.status.success() { "fail" } else { "succeed" };
+    panic!(
+        "command was expected to {expected}: {cmd:?}\n\
+         status: {}\n\
+         --- stdout ---\n{}\n\
+         --- stderr ---\n{}\n",
+        output.status,
+        stdout(output),
+        stderr(output),
+    );
+}
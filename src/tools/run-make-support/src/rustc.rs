@@ -0,0 +1,164 @@
+use std::ffi::OsStr;
+use std::path::Path;
+use std::process::{Command, Output};
+
+use crate::{add_dylib_path, env_var, out_dir, run_command};
+
+/// A `rustc` invocation that writes its output to [`out_dir`] and can use the libraries built
+/// there, like `$(RUSTC)` in Makefiles.
+pub fn rustc() -> Rustc {
+    Rustc::new()
+}
+
+/// A `rustc` invocation for an auxiliary library, i.e. [`rustc`] with `--crate-type=lib`.
+pub fn aux_build() -> Rustc {
+    let mut rustc = Rustc::new();
+    rustc.crate_type("lib");
+    rustc
+}
+
+/// A `rustc` invocation without any default arguments, like `$(BARE_RUSTC)` in Makefiles.
+pub fn bare_rustc() -> Rustc {
+    Rustc::bare()
+}
+
+#[derive(Debug)]
+pub struct Rustc {
+    cmd: Command,
+}
+
+impl Rustc {
+    fn new() -> Self {
+        let mut rustc = Rustc::bare();
+        rustc.arg("--out-dir").arg(out_dir()).arg("-L").arg(out_dir());
+        rustc.arg("-Ainternal_features");
+        // Set by compiletest when the target needs it, e.g. on musl hosts.
+        if let Ok(flags) = std::env::var("RUSTFLAGS") {
+            rustc.args(flags.split_whitespace());
+        }
+        if let Ok(linker) = std::env::var("RUSTC_LINKER") {
+            rustc.arg(format!("-Clinker={linker}"));
+        }
+        rustc
+    }
+
+    fn bare() -> Self {
+        let mut cmd = Command::new(env_var("RUSTC"));
+        add_dylib_path(&mut cmd, &[out_dir(), env_var("HOST_RPATH_DIR").into()]);
+        Rustc { cmd }
+    }
+
+    /// The source file to compile.
+    pub fn input(&mut self, path: impl AsRef<Path>) -> &mut Self {
+        self.cmd.arg(path.as_ref());
+        self
+    }
+
+    /// The file to write the output to, instead of a file named after the crate in [`out_dir`].
+    pub fn output(&mut self, path: impl AsRef<Path>) -> &mut Self {
+        self.cmd.arg("-o").arg(path.as_ref());
+        self
+    }
+
+    pub fn crate_name(&mut self, name: &str) -> &mut Self {
+        self.cmd.arg(format!("--crate-name={name}"));
+        self
+    }
+
+    pub fn crate_type(&mut self, crate_type: &str) -> &mut Self {
+        self.cmd.arg(format!("--crate-type={crate_type}"));
+        self
+    }
+
+    pub fn edition(&mut self, edition: &str) -> &mut Self {
+        self.cmd.arg(format!("--edition={edition}"));
+        self
+    }
+
+    pub fn target(&mut self, target: &str) -> &mut Self {
+        self.cmd.arg(format!("--target={target}"));
+        self
+    }
+
+    /// Kinds of output to produce, e.g. `"obj,metadata"`.
+    pub fn emit(&mut self, kinds: &str) -> &mut Self {
+        self.cmd.arg(format!("--emit={kinds}"));
+        self
+    }
+
+    /// Compiler information to print, e.g. `"crate-name"`.
+    pub fn print(&mut self, info: &str) -> &mut Self {
+        self.cmd.arg(format!("--print={info}"));
+        self
+    }
+
+    /// Optimize, i.e. pass `-O`.
+    pub fn opt(&mut self) -> &mut Self {
+        self.cmd.arg("-O");
+        self
+    }
+
+    pub fn codegen_opt(&mut self, opt: &str) -> &mut Self {
+        self.cmd.arg(format!("-C{opt}"));
+        self
+    }
+
+    pub fn unstable_opt(&mut self, opt: &str) -> &mut Self {
+        self.cmd.arg(format!("-Z{opt}"));
+        self
+    }
+
+    pub fn cfg(&mut self, cfg: &str) -> &mut Self {
+        self.cmd.arg("--cfg").arg(cfg);
+        self
+    }
+
+    /// Make the crate at `path` available as the extern crate `name`.
+    pub fn extern_(&mut self, name: &str, path: impl AsRef<Path>) -> &mut Self {
+        let mut arg = std::ffi::OsString::from(format!("{name}="));
+        arg.push(path.as_ref());
+        self.cmd.arg("--extern").arg(arg);
+        self
+    }
+
+    /// Add a directory to the library search path.
+    pub fn library_search_path(&mut self, path: impl AsRef<Path>) -> &mut Self {
+        self.cmd.arg("-L").arg(path.as_ref());
+        self
+    }
+
+    pub fn arg(&mut self, arg: impl AsRef<OsStr>) -> &mut Self {
+        self.cmd.arg(arg);
+        self
+    }
+
+    pub fn args(&mut self, args: impl IntoIterator<Item = impl AsRef<OsStr>>) -> &mut Self {
+        self.cmd.args(args);
+        self
+    }
+
+    pub fn env(&mut self, key: impl AsRef<OsStr>, value: impl AsRef<OsStr>) -> &mut Self {
+        self.cmd.env(key, value);
+        self
+    }
+
+    /// Runs the compiler without checking whether it succeeded.
+    pub fn command_output(&mut self) -> Output {
+        match self.cmd.output() {
+            Ok(output) => output,
+            Err(e) => panic!("failed to run `{:?}`: {e}", self.cmd),
+        }
+    }
+
+    /// Runs the compiler, and checks that it succeeds.
+    #[track_caller]
+    pub fn run(&mut self) -> Output {
+        run_command(&mut self.cmd, true)
+    }
+
+    /// Runs the compiler, and checks that it fails.
+    #[track_caller]
+    pub fn run_fail(&mut self) -> Output {
+        run_command(&mut self.cmd, false)
+    }
+}
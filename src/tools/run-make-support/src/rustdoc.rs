@@ -0,0 +1,115 @@
+use std::ffi::OsStr;
+use std::path::Path;
+use std::process::{Command, Output};
+
+use crate::{add_dylib_path, env_var, out_dir, run_command};
+
+/// A `rustdoc` invocation that can use the target's standard library, like `$(RUSTDOC)` in
+/// Makefiles.
+pub fn rustdoc() -> Rustdoc {
+    Rustdoc::new()
+}
+
+/// A `rustdoc` invocation without any default arguments, like `$(BARE_RUSTDOC)` in Makefiles.
+pub fn bare_rustdoc() -> Rustdoc {
+    Rustdoc::bare()
+}
+
+#[derive(Debug)]
+pub struct Rustdoc {
+    cmd: Command,
+}
+
+impl Rustdoc {
+    fn new() -> Self {
+        let mut rustdoc = Rustdoc::bare();
+        rustdoc.arg("-L").arg(env_var("TARGET_RPATH_DIR"));
+        if let Ok(linker) = std::env::var("RUSTC_LINKER") {
+            rustdoc.arg(format!("-Clinker={linker}"));
+        }
+        rustdoc
+    }
+
+    fn bare() -> Self {
+        let mut cmd = Command::new(env_var("RUSTDOC"));
+        add_dylib_path(&mut cmd, &[out_dir(), env_var("HOST_RPATH_DIR").into()]);
+        Rustdoc { cmd }
+    }
+
+    /// The source file to document.
+    pub fn input(&mut self, path: impl AsRef<Path>) -> &mut Self {
+        self.cmd.arg(path.as_ref());
+        self
+    }
+
+    /// The directory to write the documentation to.
+    pub fn out_dir(&mut self, path: impl AsRef<Path>) -> &mut Self {
+        self.cmd.arg("--out-dir").arg(path.as_ref());
+        self
+    }
+
+    pub fn crate_name(&mut self, name: &str) -> &mut Self {
+        self.cmd.arg(format!("--crate-name={name}"));
+        self
+    }
+
+    pub fn edition(&mut self, edition: &str) -> &mut Self {
+        self.cmd.arg(format!("--edition={edition}"));
+        self
+    }
+
+    /// Run the doctests instead of generating documentation.
+    pub fn test(&mut self) -> &mut Self {
+        self.cmd.arg("--test");
+        self
+    }
+
+    /// Make the crate at `path` available as the extern crate `name`.
+    pub fn extern_(&mut self, name: &str, path: impl AsRef<Path>) -> &mut Self {
+        let mut arg = std::ffi::OsString::from(format!("{name}="));
+        arg.push(path.as_ref());
+        self.cmd.arg("--extern").arg(arg);
+        self
+    }
+
+    /// Add a directory to the library search path.
+    pub fn library_search_path(&mut self, path: impl AsRef<Path>) -> &mut Self {
+        self.cmd.arg("-L").arg(path.as_ref());
+        self
+    }
+
+    pub fn arg(&mut self, arg: impl AsRef<OsStr>) -> &mut Self {
+        self.cmd.arg(arg);
+        self
+    }
+
+    pub fn args(&mut self, args: impl IntoIterator<Item = impl AsRef<OsStr>>) -> &mut Self {
+        self.cmd.args(args);
+        self
+    }
+
+    pub fn env(&mut self, key: impl AsRef<OsStr>, value: impl AsRef<OsStr>) -> &mut Self {
+        self.cmd.env(key, value);
+        self
+    }
+
+    /// Runs rustdoc without checking whether it succeeded.
+    pub fn command_output(&mut self) -> Output {
+        match self.cmd.output() {
+            Ok(output) => output,
+            Err(e) => panic!("failed to run `{:?}`: {e}", self.cmd),
+        }
+    }
+
+    /// Runs rustdoc, and checks that it succeeds.
+    #[track_caller]
+    pub fn run(&mut self) -> Output {
+        run_command(&mut self.cmd, true)
+    }
+
+    /// Runs rustdoc, and checks that it fails.
+    #[track_caller]
+    pub fn run_fail(&mut self) -> Output {
+        run_command(&mut self.cmd, false)
+    }
+}
@@ -103,18 +103,22 @@ pub fn check(path: &Path, bad: &mut bool) {
         if let Some(ext) = file_path.extension().and_then(OsStr::to_str) {
             // files that are neither an expected extension or an exception should not exist
             // they're probably typos or not meant to exist
+            let is_diagnostics_json =
+                file_path.to_str().is_some_and(|path| path.ends_with(".diagnostics.json"));
             if !(EXPECTED_TEST_FILE_EXTENSIONS.contains(&ext)
+                || is_diagnostics_json
                 || EXTENSION_EXCEPTION_PATHS.iter().any(|path| file_path.ends_with(path)))
             {
                 tidy_error!(bad, "file {} has unexpected extension {}", file_path.display(), ext);
             }
-            if ext == "stderr" || ext == "stdout" || ext == "fixed" {
+            if ext == "stderr" || ext == "stdout" || ext == "fixed" || is_diagnostics_json {
                 // Test output filenames have one of the formats:
                 // ```
                 // $testname.stderr
                 // $testname.$mode.stderr
                 // $testname.$revision.stderr
                 // $testname.$revision.$mode.stderr
+                // $testname.diagnostics.json
                 // ```
                 //
                 // For now, just make sure that there is a corresponding
@@ -0,0 +1,59 @@
+// edition:2021
+
+// Seed program for the fuzzer: closures, pattern matching, loops and async code.
+
+pub enum Expr {
+    Num(i64),
+    Neg(Box<Expr>),
+    Bin(Box<Expr>, char, Box<Expr>),
+}
+
+pub fn eval(expr: &Expr) -> Result<i64, String> {
+    Ok(match expr {
+        Expr::Num(n) => *n,
+        Expr::Neg(inner) => -eval(inner)?,
+        Expr::Bin(lhs, op, rhs) => {
+            let (lhs, rhs) = (eval(lhs)?, eval(rhs)?);
+            match op {
+                '+' => lhs.checked_add(rhs).ok_or("overflow")?,
+                '/' if rhs == 0 => return Err(format!("division of {lhs} by zero")),
+                '/' => lhs / rhs,
+                other => return Err(format!("unknown operator {other:?}")),
+            }
+        }
+    })
+}
+
+pub fn search(haystack: &[u8], needle: u8) -> Option<usize> {
+    let mut index = 0;
+    'outer: loop {
+        while let Some(&byte) = haystack.get(index) {
+            if byte == needle {
+                break 'outer Some(index);
+            }
+            index += 1;
+        }
+        break None;
+    }
+}
+
+pub fn counter() -> impl FnMut() -> u32 {
+    let mut count = 0;
+    move || {
+        count += 1;
+        count
+    }
+}
+
+pub async fn add_later(a: u32, b: impl std::future::Future<Output = u32>) -> u32 {
+    a + b.await
+}
+
+pub fn main() {
+    let expr = Expr::Bin(Box::new(Expr::Num(1)), '+', Box::new(Expr::Neg(Box::new(Expr::Num(3)))));
+    assert_eq!(eval(&expr), Ok(-2));
+    assert_eq!(search(b"hello", b'l'), Some(2));
+    let mut next = counter();
+    assert_eq!((next(), next()), (1, 2));
+    let _future = add_later(1, async { 2 });
+}
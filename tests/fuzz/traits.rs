@@ -0,0 +1,52 @@
+// Seed program for the fuzzer: traits, generics and associated items.
+
+use std::fmt::Debug;
+use std::ops::Add;
+
+pub trait Polygon {
+    const SIDES: usize;
+}
+
+pub trait Shape: Debug {
+    type Unit: Copy + Add<Output = Self::Unit>;
+
+    fn area(&self) -> Self::Unit;
+
+    fn double_area(&self) -> Self::Unit {
+        self.area() + self.area()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Square<T>(pub T);
+
+impl<T> Polygon for Square<T> {
+    const SIDES: usize = 4;
+}
+
+impl<T: Copy + Debug + std::ops::Mul<Output = T> + Add<Output = T>> Shape for Square<T> {
+    type Unit = T;
+
+    fn area(&self) -> T {
+        self.0 * self.0
+    }
+}
+
+pub fn total<'a, S>(shapes: impl IntoIterator<Item = &'a S>) -> Option<S::Unit>
+where
+    S: Shape + 'a,
+{
+    shapes.into_iter().map(Shape::area).reduce(|a, b| a + b)
+}
+
+pub fn dynamic(shapes: &[Box<dyn Shape<Unit = u32>>]) -> u32 {
+    shapes.iter().map(|shape| shape.double_area()).sum()
+}
+
+pub fn main() {
+    let squares = [Square(1u32), Square(2), Square(3)];
+    assert_eq!(total(&squares), Some(14));
+    let boxed: Vec<Box<dyn Shape<Unit = u32>>> = vec![Box::new(Square(2u32))];
+    assert_eq!(dynamic(&boxed), 8);
+    assert_eq!(<Square<u8> as Polygon>::SIDES, 4);
+}
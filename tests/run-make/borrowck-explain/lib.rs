@@ -0,0 +1,9 @@
+pub fn longest<'a, 'b>(x: &'a str, y: &'b str) -> &'a str {
+    if x.len() > y.len() { x } else { y }
+}
+
+pub fn push_while_borrowed(v: &mut Vec<u32>) {
+    let first = &v[0];
+    v.push(1);
+    let _copy = *first;
+}
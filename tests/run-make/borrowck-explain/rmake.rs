@@ -0,0 +1,40 @@
+// Checks that `-Zborrowck-explain` writes out the constraint path and the liveness behind each
+// borrowck error, both as annotated source and as a graphviz graph.
+
+use run_make_support::{files_with_extension, out_dir, read_to_string, rustc};
+
+fn main() {
+    let dump_dir = out_dir().join("explain");
+    rustc()
+        .input("lib.rs")
+        .crate_type("lib")
+        .unstable_opt("borrowck-explain")
+        .unstable_opt(&format!("dump-mir-dir={}", dump_dir.display()))
+        .run_fail();
+
+    let texts = files_with_extension(&dump_dir, "txt");
+    let graphs = files_with_extension(&dump_dir, "dot");
+    assert_eq!(texts.len(), graphs.len(), "every explanation should come with a graph");
+    let explanation = |function: &str| {
+        let path = texts
+            .iter()
+            .find(|path| path.file_name().unwrap().to_str().unwrap().contains(function))
+            .unwrap_or_else(|| panic!("no explanation written for `{function}`: {texts:?}"));
+        read_to_string(path)
+    };
+
+    let longest = explanation(".longest.");
+    assert!(longest.contains("must outlive"), "{longest}");
+    assert!(longest.contains("is a universal region, live at all points"), "{longest}");
+    assert!(longest.contains("    if x.len() > y.len() { x } else { y }"), "{longest}");
+
+    let borrowed = explanation(".push_while_borrowed.");
+    assert!(borrowed.contains("the borrow `&"), "{borrowed}");
+    assert!(borrowed.contains("the error is here"), "{borrowed}");
+    assert!(borrowed.contains("    let _copy = *first;"), "{borrowed}");
+
+    for graph in graphs {
+        let graph = read_to_string(graph);
+        assert!(graph.starts_with("digraph ConstraintPath {"), "{graph}");
+    }
+}
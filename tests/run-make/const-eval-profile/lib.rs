@@ -0,0 +1,16 @@
+pub const fn fib(n: u64) -> u64 {
+    if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+}
+
+const fn squares() -> [u64; 16] {
+    let mut table = [0; 16];
+    let mut i = 0;
+    while i < table.len() {
+        table[i] = (i * i) as u64;
+        i += 1;
+    }
+    table
+}
+
+pub const FIB: u64 = fib(10);
+pub static SQUARES: [u64; 16] = squares();
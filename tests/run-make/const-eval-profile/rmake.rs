@@ -0,0 +1,48 @@
+// Checks that `-Zconst-eval-profile` writes a report for each constant and static, with the
+// steps, calls and allocations of every function evaluated for it.
+
+use run_make_support::{files_with_extension, out_dir, read_to_string, rustc};
+
+fn main() {
+    let dump_dir = out_dir().join("profile");
+    rustc()
+        .input("lib.rs")
+        .crate_type("lib")
+        .unstable_opt("const-eval-profile")
+        .unstable_opt(&format!("dump-mir-dir={}", dump_dir.display()))
+        .run();
+
+    let reports = files_with_extension(&dump_dir, "txt");
+    let report = |item: &str| {
+        let path = reports
+            .iter()
+            .find(|path| path.file_name().unwrap().to_str().unwrap().contains(item))
+            .unwrap_or_else(|| panic!("no profile written for `{item}`: {reports:?}"));
+        read_to_string(path)
+    };
+    // The columns of the row of `function`: steps, calls, allocations, bytes and self time.
+    let row = |report: &str, function: &str| -> Vec<String> {
+        let line = report
+            .lines()
+            .find(|line| line.ends_with(&format!("  {function}")))
+            .unwrap_or_else(|| panic!("`{function}` missing from profile:\n{report}"));
+        line.split_whitespace().take(5).map(String::from).collect()
+    };
+
+    let fib = report(".FIB.");
+    assert!(fib.starts_with("// const-eval profile of `FIB`"), "{fib}");
+    // `fib(10)` calls `fib` 177 times in total.
+    let fib_row = row(&fib, "fib");
+    assert_eq!(fib_row[1], "177", "{fib}");
+    assert_ne!(fib_row[0], "0", "{fib}");
+    // The constant itself is evaluated once.
+    assert_eq!(row(&fib, "FIB")[1], "1", "{fib}");
+
+    let squares = report(".SQUARES.");
+    let squares_row = row(&squares, "squares");
+    assert_eq!(squares_row[1], "1", "{squares}");
+    // The loop runs a terminator at least once per iteration.
+    assert!(squares_row[0].parse::<u64>().unwrap() >= 16, "{squares}");
+    // The table lives in memory.
+    assert!(squares_row[3].parse::<u64>().unwrap() >= 128, "{squares}");
+}
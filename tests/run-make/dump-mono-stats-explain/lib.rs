@@ -0,0 +1,31 @@
+#![crate_type = "lib"]
+
+fn leaf<T: Default>() -> T {
+    T::default()
+}
+
+fn middle<T: Default>() -> T {
+    leaf::<T>()
+}
+
+pub fn entry() -> (u8, u16, u32) {
+    (middle(), middle(), middle())
+}
+
+async fn tick() {}
+
+async fn hold_buffer() -> u8 {
+    let buffer = [0u8; 4096];
+    tick().await;
+    buffer[0]
+}
+
+pub fn spawn() -> impl std::future::Future<Output = u8> {
+    hold_buffer()
+}
+
+pub fn capture() -> u8 {
+    let table = [1u8; 1024];
+    let lookup = move |i: usize| table[i];
+    lookup(0)
+}
@@ -0,0 +1,34 @@
+// Checks that `-Zdump-mono-stats` explains why generic items were instantiated, and lists the
+// largest types with the captured and saved state of closures and futures.
+
+use run_make_support::{out_dir, read_to_string, rustc};
+
+fn main() {
+    rustc().input("lib.rs").unstable_opt(&format!("dump-mono-stats={}", out_dir().display())).run();
+
+    let mono_items = read_to_string(out_dir().join("lib.mono_items.md"));
+    // Every instantiation of `leaf` is explained by the chain leading to it.
+    for ty in ["u8", "u16", "u32"] {
+        let line = format!("- `fn leaf::<{ty}>` (estimated cost ");
+        let line = mono_items
+            .lines()
+            .find(|l| l.starts_with(&line))
+            .unwrap_or_else(|| panic!("no instantiation of `leaf::<{ty}>`:\n{mono_items}"));
+        assert!(line.ends_with(&format!("used by `fn middle::<{ty}>` <- `fn entry`")), "{line}");
+    }
+    assert!(mono_items.contains("- `fn entry` (estimated cost "), "{mono_items}");
+
+    let type_sizes = read_to_string(out_dir().join("lib.type_sizes.md"));
+    // The future of `hold_buffer` keeps the buffer across the `.await`.
+    let future = type_sizes
+        .split("\n## ")
+        .find(|section| section.starts_with("`{async fn body@"))
+        .unwrap_or_else(|| panic!("no future in the type sizes:\n{type_sizes}"));
+    assert!(future.contains("| `buffer` | saved local | `[u8; 4096]` | 4096 |"), "{future}");
+    // The closure in `capture` captures the table by value.
+    let closure = type_sizes
+        .split("\n## ")
+        .find(|section| section.starts_with("`{closure@"))
+        .unwrap_or_else(|| panic!("no closure in the type sizes:\n{type_sizes}"));
+    assert!(closure.contains("| `table` | upvar | `[u8; 1024]` | 1024 |"), "{closure}");
+}
@@ -0,0 +1,9 @@
+trait Foo {}
+
+impl Foo for u32 {}
+
+fn needs_foo<T: Foo>(_: T) {}
+
+pub fn main() {
+    needs_foo(1i64);
+}
@@ -0,0 +1,32 @@
+// Checks that `-Zdump-solver-proof-trees` writes the proof tree of a failing goal as JSON, and as
+// an HTML viewer with the same JSON embedded into it.
+
+use run_make_support::{files_with_extension, out_dir, read_to_string, rustc};
+
+fn main() {
+    let dump_dir = out_dir().join("proof-trees");
+    rustc()
+        .input("lib.rs")
+        .crate_type("lib")
+        .unstable_opt("next-solver=dump-tree-on-error")
+        .unstable_opt(&format!("dump-solver-proof-trees={}", dump_dir.display()))
+        .run_fail();
+
+    let trees: Vec<_> = files_with_extension(&dump_dir, "json")
+        .into_iter()
+        .map(read_to_string)
+        .filter(|tree| tree.starts_with(r#"{"goal":"i64: Foo""#))
+        .collect();
+    assert_eq!(trees.len(), 1, "no proof tree written for `i64: Foo`: {trees:?}");
+    let tree = &trees[0];
+    assert!(tree.contains(r#""kind":"root""#), "{tree}");
+    assert!(tree.contains(r#""result":"no solution""#), "{tree}");
+    // The only impl doesn't apply, so its candidate is part of the tree.
+    assert!(tree.contains("candidate: "), "{tree}");
+
+    let viewers = files_with_extension(&dump_dir, "html");
+    assert!(
+        viewers.iter().any(|viewer| read_to_string(viewer).contains(tree.as_str())),
+        "no viewer embeds the proof tree: {viewers:?}",
+    );
+}
@@ -0,0 +1,11 @@
+// Checks that rustc refuses to overwrite its input file, or a directory, with the executable.
+
+use run_make_support::{diff, rustc, stderr};
+
+fn main() {
+    let file = rustc().input("main.rs").output("main.rs").run_fail();
+    diff().expected_file("file.stderr").actual_text("stderr", stderr(&file)).run();
+
+    let folder = rustc().input("main.rs").output(".").run_fail();
+    diff().expected_file("folder.stderr").actual_text("stderr", stderr(&folder)).run();
+}
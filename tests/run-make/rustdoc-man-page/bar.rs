@@ -0,0 +1,10 @@
+//! Says goodbye.
+#![crate_type = "bin"]
+
+/// The arguments of the program.
+struct Args {
+    /// Whether to wave.
+    wave: bool,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+//! Greets people.
+//!
+//! Prints a greeting for every name it is given.
+//!
+//! # Options
+//!
+//! `--loud` prints the greetings in capital letters.
+
+/// The arguments of the program.
+struct Args {
+    /// The name of the person to greet.
+    name: String,
+    undocumented: bool,
+}
+
+fn main() {}
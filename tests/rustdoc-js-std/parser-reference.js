@@ -0,0 +1,285 @@
+const PARSED = [
+    {
+        query: '&str',
+        elems: [
+            {
+                name: "reference",
+                fullPath: ["reference"],
+                pathWithoutLast: [],
+                pathLast: "reference",
+                generics: [
+                    {
+                        name: "str",
+                        fullPath: ["str"],
+                        pathWithoutLast: [],
+                        pathLast: "str",
+                        generics: [],
+                        typeFilter: -1,
+                    },
+                ],
+                typeFilter: 1,
+            },
+        ],
+        foundElems: 1,
+        original: '&str',
+        returned: [],
+        userQuery: '&str',
+        error: null,
+    },
+    {
+        query: '&mut u8 -> &[u8]',
+        elems: [
+            {
+                name: "reference",
+                fullPath: ["reference"],
+                pathWithoutLast: [],
+                pathLast: "reference",
+                generics: [
+                    {
+                        name: "mut",
+                        fullPath: ["mut"],
+                        pathWithoutLast: [],
+                        pathLast: "mut",
+                        generics: [],
+                        typeFilter: 0,
+                    },
+                    {
+                        name: "u8",
+                        fullPath: ["u8"],
+                        pathWithoutLast: [],
+                        pathLast: "u8",
+                        generics: [],
+                        typeFilter: -1,
+                    },
+                ],
+                typeFilter: 1,
+            },
+        ],
+        foundElems: 2,
+        original: '&mut u8 -> &[u8]',
+        returned: [
+            {
+                name: "reference",
+                fullPath: ["reference"],
+                pathWithoutLast: [],
+                pathLast: "reference",
+                generics: [
+                    {
+                        name: "[]",
+                        fullPath: ["[]"],
+                        pathWithoutLast: [],
+                        pathLast: "[]",
+                        generics: [
+                            {
+                                name: "u8",
+                                fullPath: ["u8"],
+                                pathWithoutLast: [],
+                                pathLast: "u8",
+                                generics: [],
+                                typeFilter: -1,
+                            },
+                        ],
+                        typeFilter: 1,
+                    },
+                ],
+                typeFilter: 1,
+            },
+        ],
+        userQuery: '&mut u8 -> &[u8]',
+        error: null,
+    },
+    {
+        query: '& mut Vec<&str>',
+        elems: [
+            {
+                name: "reference",
+                fullPath: ["reference"],
+                pathWithoutLast: [],
+                pathLast: "reference",
+                generics: [
+                    {
+                        name: "mut",
+                        fullPath: ["mut"],
+                        pathWithoutLast: [],
+                        pathLast: "mut",
+                        generics: [],
+                        typeFilter: 0,
+                    },
+                    {
+                        name: "vec",
+                        fullPath: ["vec"],
+                        pathWithoutLast: [],
+                        pathLast: "vec",
+                        generics: [
+                            {
+                                name: "reference",
+                                fullPath: ["reference"],
+                                pathWithoutLast: [],
+                                pathLast: "reference",
+                                generics: [
+                                    {
+                                        name: "str",
+                                        fullPath: ["str"],
+                                        pathWithoutLast: [],
+                                        pathLast: "str",
+                                        generics: [],
+                                        typeFilter: -1,
+                                    },
+                                ],
+                                typeFilter: 1,
+                            },
+                        ],
+                        typeFilter: -1,
+                    },
+                ],
+                typeFilter: 1,
+            },
+        ],
+        foundElems: 1,
+        original: '& mut Vec<&str>',
+        returned: [],
+        userQuery: '& mut vec<&str>',
+        error: null,
+    },
+    {
+        query: '&mutex',
+        elems: [
+            {
+                name: "reference",
+                fullPath: ["reference"],
+                pathWithoutLast: [],
+                pathLast: "reference",
+                generics: [
+                    {
+                        name: "mutex",
+                        fullPath: ["mutex"],
+                        pathWithoutLast: [],
+                        pathLast: "mutex",
+                        generics: [],
+                        typeFilter: -1,
+                    },
+                ],
+                typeFilter: 1,
+            },
+        ],
+        foundElems: 1,
+        original: '&mutex',
+        returned: [],
+        userQuery: '&mutex',
+        error: null,
+    },
+    {
+        query: '&',
+        elems: [
+            {
+                name: "reference",
+                fullPath: ["reference"],
+                pathWithoutLast: [],
+                pathLast: "reference",
+                generics: [],
+                typeFilter: 1,
+            },
+        ],
+        foundElems: 1,
+        original: '&',
+        returned: [],
+        userQuery: '&',
+        error: null,
+    },
+    {
+        query: 'Iterator<Item=&str>',
+        elems: [
+            {
+                name: "iterator",
+                fullPath: ["iterator"],
+                pathWithoutLast: [],
+                pathLast: "iterator",
+                generics: [],
+                bindings: [
+                    [
+                        'item',
+                        [
+                            {
+                                name: "reference",
+                                fullPath: ["reference"],
+                                pathWithoutLast: [],
+                                pathLast: "reference",
+                                generics: [
+                                    {
+                                        name: "str",
+                                        fullPath: ["str"],
+                                        pathWithoutLast: [],
+                                        pathLast: "str",
+                                        generics: [],
+                                        typeFilter: -1,
+                                    },
+                                ],
+                                typeFilter: 1,
+                            },
+                        ]
+                    ],
+                ],
+                typeFilter: -1,
+            },
+        ],
+        foundElems: 1,
+        original: 'Iterator<Item=&str>',
+        returned: [],
+        userQuery: 'iterator<item=&str>',
+        error: null,
+    },
+    {
+        query: '&Item=u8',
+        elems: [],
+        foundElems: 0,
+        original: '&Item=u8',
+        returned: [],
+        userQuery: '&item=u8',
+        error: 'Type parameter `=` cannot be within reference `&`',
+    },
+    {
+        query: 'macro:&str',
+        elems: [],
+        foundElems: 0,
+        original: 'macro:&str',
+        returned: [],
+        userQuery: 'macro:&str',
+        error: 'Invalid search type: primitive `&` and `macro` both specified',
+    },
+    {
+        query: '&&u8',
+        elems: [
+            {
+                name: "reference",
+                fullPath: ["reference"],
+                pathWithoutLast: [],
+                pathLast: "reference",
+                generics: [
+                    {
+                        name: "reference",
+                        fullPath: ["reference"],
+                        pathWithoutLast: [],
+                        pathLast: "reference",
+                        generics: [
+                            {
+                                name: "u8",
+                                fullPath: ["u8"],
+                                pathWithoutLast: [],
+                                pathLast: "u8",
+                                generics: [],
+                                typeFilter: -1,
+                            },
+                        ],
+                        typeFilter: 1,
+                    },
+                ],
+                typeFilter: 1,
+            },
+        ],
+        foundElems: 1,
+        original: '&&u8',
+        returned: [],
+        userQuery: '&&u8',
+        error: null,
+    },
+];
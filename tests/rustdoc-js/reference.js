@@ -0,0 +1,96 @@
+// exact-check
+
+const EXPECTED = [
+    {
+        'query': '&str -> PathBuf',
+        'others': [
+            { 'path': 'reference', 'name': 'from_str' },
+            { 'path': 'reference', 'name': 'from_option' },
+        ],
+    },
+    {
+        'query': '&mut str -> PathBuf',
+        'others': [
+            { 'path': 'reference', 'name': 'from_mut_str' },
+        ],
+    },
+    {
+        'query': 'str -> PathBuf',
+        'others': [
+            { 'path': 'reference', 'name': 'from_str' },
+            { 'path': 'reference', 'name': 'from_mut_str' },
+            { 'path': 'reference', 'name': 'from_option' },
+        ],
+    },
+    {
+        'query': 'String -> PathBuf',
+        'others': [
+            { 'path': 'reference', 'name': 'from_string' },
+        ],
+    },
+    {
+        'query': '&PathBuf -> &Path',
+        'others': [
+            { 'path': 'reference', 'name': 'as_path' },
+        ],
+    },
+    {
+        'query': '&mut PathBuf -> &mut Path',
+        'others': [
+            { 'path': 'reference', 'name': 'as_mut_path' },
+        ],
+    },
+    {
+        'query': '&mut PathBuf -> &Path',
+        'others': [],
+    },
+    {
+        'query': '&PathBuf -> &mut Path',
+        'others': [],
+    },
+    {
+        'query': '& -> &',
+        'others': [
+            { 'path': 'reference', 'name': 'as_path' },
+            { 'path': 'reference', 'name': 'lines' },
+        ],
+    },
+    {
+        'query': '&mut -> &mut',
+        'others': [
+            { 'path': 'reference', 'name': 'as_mut_path' },
+        ],
+    },
+    {
+        'query': '&mut Read -> usize',
+        'others': [
+            { 'path': 'reference', 'name': 'read' },
+        ],
+    },
+    {
+        'query': 'Read -> usize',
+        'others': [
+            { 'path': 'reference', 'name': 'read_impl' },
+            { 'path': 'reference', 'name': 'read' },
+        ],
+    },
+    {
+        'query': '&str -> Iterator<Item=&str>',
+        'others': [
+            { 'path': 'reference', 'name': 'lines' },
+        ],
+    },
+    {
+        'query': '&str -> Iterator<Item=char>',
+        'others': [
+            { 'path': 'reference', 'name': 'chars' },
+        ],
+    },
+    {
+        'query': '&str -> Iterator',
+        'others': [
+            { 'path': 'reference', 'name': 'lines' },
+            { 'path': 'reference', 'name': 'chars' },
+        ],
+    },
+];
@@ -0,0 +1,44 @@
+pub struct PathBuf;
+pub struct Path;
+
+pub trait Read {}
+
+pub fn from_str(_: &str) -> PathBuf {
+    loop {}
+}
+
+pub fn from_mut_str(_: &mut str) -> PathBuf {
+    loop {}
+}
+
+pub fn from_string(_: String) -> PathBuf {
+    loop {}
+}
+
+pub fn from_option(_: Option<&str>) -> PathBuf {
+    loop {}
+}
+
+pub fn as_path(_: &PathBuf) -> &Path {
+    loop {}
+}
+
+pub fn as_mut_path(_: &mut PathBuf) -> &mut Path {
+    loop {}
+}
+
+pub fn read<R: Read>(_: &mut R) -> usize {
+    loop {}
+}
+
+pub fn read_impl(_: impl Read) -> usize {
+    loop {}
+}
+
+pub fn lines(_: &str) -> impl Iterator<Item = &str> {
+    std::iter::empty()
+}
+
+pub fn chars(_: &str) -> impl Iterator<Item = char> {
+    std::iter::empty()
+}
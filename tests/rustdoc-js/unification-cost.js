@@ -0,0 +1,39 @@
+// exact-check
+
+// Results which need their types to be unboxed fewer times to match the query come first.
+const EXPECTED = [
+    {
+        'query': 'u8 -> u16',
+        'others': [
+            { 'path': 'unification_cost', 'name': 'exact' },
+            { 'path': 'unification_cost', 'name': 'shallow' },
+            { 'path': 'unification_cost', 'name': 'deep' },
+            { 'path': 'unification_cost', 'name': 'bounded' },
+        ],
+    },
+    {
+        'query': 'Wrap<u8> -> u16',
+        'others': [
+            { 'path': 'unification_cost', 'name': 'shallow' },
+            { 'path': 'unification_cost', 'name': 'deep' },
+        ],
+    },
+    {
+        'query': 'Into<u8> -> u16',
+        'others': [
+            { 'path': 'unification_cost', 'name': 'bounded' },
+        ],
+    },
+    {
+        'query': 'u8',
+        'in_args': [
+            { 'path': 'unification_cost', 'name': 'exact' },
+            { 'path': 'unification_cost', 'name': 'shallow' },
+            { 'path': 'unification_cost', 'name': 'deep' },
+            { 'path': 'unification_cost', 'name': 'bounded' },
+        ],
+        'returned': [
+            { 'path': 'unification_cost', 'name': 'unrelated' },
+        ],
+    },
+];
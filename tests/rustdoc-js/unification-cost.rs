@@ -0,0 +1,21 @@
+pub struct Wrap<T>(T);
+
+pub fn exact(_: u8) -> u16 {
+    loop {}
+}
+
+pub fn shallow(_: Wrap<u8>) -> u16 {
+    loop {}
+}
+
+pub fn deep(_: Wrap<Wrap<u8>>) -> u16 {
+    loop {}
+}
+
+pub fn bounded<T: Into<u8>>(_: T) -> u16 {
+    loop {}
+}
+
+pub fn unrelated(_: Wrap<u16>) -> u8 {
+    loop {}
+}
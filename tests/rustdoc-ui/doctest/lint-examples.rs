@@ -0,0 +1,27 @@
+// check-pass
+// compile-flags:--test -Z unstable-options --lint-examples
+// normalize-stderr-test: "tests/rustdoc-ui/doctest" -> "$$DIR"
+
+/// ```
+/// let x = 1;
+/// ```
+pub fn unused_variable() {}
+
+/// Hidden lines are linted too.
+///
+/// ```
+/// # let mut y = 1;
+/// println!("{y}");
+/// ```
+pub fn hidden_line() {}
+
+/// Examples which are not compiled are not linted.
+///
+/// ```compile_fail
+/// let z: u8 = "";
+/// ```
+///
+/// ```ignore
+/// let z = 1;
+/// ```
+pub fn not_compiled() {}
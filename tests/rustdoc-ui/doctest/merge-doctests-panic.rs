@@ -0,0 +1,17 @@
+// Checks that a failing doctest compiled into a single binary with `--merge-doctests` reports the
+// output of its test harness, and that its panic location points into the documentation.
+
+// ignore-windows
+// compile-flags:-Z unstable-options --test --merge-doctests --test-args=--test-threads=1
+// rustc-env:RUST_BACKTRACE=0
+// normalize-stdout-test: "tests/rustdoc-ui/doctest" -> "$$DIR"
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+// normalize-stdout-test "_doctest_main_\w+" -> "_doctest_main_$$ID"
+// failure-status: 101
+
+/// ```
+/// println!("stdout 1");
+/// eprintln!("stderr 1");
+/// panic!("oh no");
+/// ```
+pub struct SomeStruct;
@@ -0,0 +1,38 @@
+// Checks that doctests compiled into a single binary with `--merge-doctests` behave like doctests
+// compiled on their own, and that doctests which can't be merged still work.
+
+// check-pass
+// compile-flags:-Z unstable-options --test --merge-doctests --test-args=--test-threads=1
+// normalize-stdout-test: "tests/rustdoc-ui/doctest" -> "$$DIR"
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+
+/// ```
+/// let a = true;
+/// assert!(a);
+/// ```
+/// ```should_panic
+/// panic!()
+/// ```
+/// ```no_run
+/// loop {
+///     println!("Hello, world");
+/// }
+/// ```
+/// ```
+/// let x: u8 = "1".parse()?;
+/// Ok::<(), std::num::ParseIntError>(())
+/// ```
+/// ```
+/// fn main() {
+///     assert_eq!(2 + 2, 4);
+/// }
+/// ```
+/// ```
+/// #![allow(dead_code)]
+/// fn unused() {}
+/// ```
+/// ```compile_fail
+/// let x = 5;
+/// x += 2; // shouldn't compile!
+/// ```
+pub fn f() {}
@@ -0,0 +1,33 @@
+// compile-flags: -Z print-coroutine-layout --crate-type lib
+// edition:2021
+// build-pass
+// ignore-pass
+
+#![allow(dropping_copy_types)]
+
+async fn wait() {}
+
+pub async fn test(arg: [u8; 8192]) {
+    wait().await;
+    drop(arg);
+}
+
+pub async fn overlap() {
+    {
+        let a = [0u8; 1024];
+        wait().await;
+        drop(a);
+    }
+    {
+        let b = [0u8; 2048];
+        wait().await;
+        drop(b);
+    }
+}
+
+pub async fn shared() {
+    let c = [0u8; 512];
+    wait().await;
+    wait().await;
+    drop(c);
+}
@@ -0,0 +1,7 @@
+// Checks the structured diagnostics of an error with span labels and a suggestion.
+
+// check-diagnostics-json
+
+fn main() {
+    let _x: u32 = 1u64; //~ ERROR mismatched types
+}
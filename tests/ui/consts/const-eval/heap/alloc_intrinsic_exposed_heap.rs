@@ -0,0 +1,65 @@
+#![feature(core_intrinsics)]
+#![feature(const_heap)]
+#![feature(const_mut_refs)]
+
+use std::cell::Cell;
+use std::intrinsics;
+
+struct Owner<T> {
+    ptr: *mut T,
+}
+
+const fn owner_u8() -> Owner<u8> {
+    unsafe {
+        let ptr = intrinsics::const_allocate(1, 1);
+        *ptr = 42;
+        Owner { ptr }
+    }
+}
+
+const fn owner_cell() -> Owner<Cell<u8>> {
+    unsafe {
+        let ptr = intrinsics::const_allocate(1, 1) as *mut Cell<u8>;
+        *ptr = Cell::new(42);
+        Owner { ptr }
+    }
+}
+
+// A type that writes to its heap memory through a shared reference, without any `UnsafeCell`.
+struct Buf {
+    ptr: *mut u8,
+}
+
+impl Buf {
+    const fn new() -> Buf {
+        unsafe {
+            let ptr = intrinsics::const_allocate(1, 1);
+            *ptr = 42;
+            Buf { ptr }
+        }
+    }
+
+    fn set(&self, value: u8) {
+        unsafe { *self.ptr = value };
+    }
+}
+
+// Owned by value, so every use could write to or deallocate the heap allocation.
+const BY_VALUE: Owner<u8> = owner_u8();
+//~^ ERROR mutable pointer in final value of constant
+
+// Only shared, but the heap allocation can still be written to through the `Cell`.
+const INTERIOR_MUT: &Owner<Cell<u8>> = &owner_cell();
+//~^ ERROR mutable pointer in final value of constant
+
+// Only shared, but the heap allocation was not made global.
+const SHARED: &Owner<u8> = &owner_u8();
+//~^ ERROR mutable pointer in final value of constant
+
+// Only shared, but `Buf::set` writes to the heap allocation.
+const BUF: &Buf = &Buf::new();
+//~^ ERROR mutable pointer in final value of constant
+
+fn main() {
+    BUF.set(0);
+}
@@ -0,0 +1,122 @@
+// run-pass
+// Heap allocations can be part of the final value of a constant once they were made global with
+// `const_make_global`, even if the references to them are raw, mutable pointers like in `Vec`.
+// That lets lookup tables be built with growable collections at compile time.
+#![feature(core_intrinsics)]
+#![feature(const_heap)]
+#![feature(const_mut_refs)]
+
+use std::intrinsics;
+use std::mem::{align_of, size_of};
+
+struct ConstVec<T> {
+    ptr: *mut T,
+    len: usize,
+    cap: usize,
+}
+
+unsafe impl<T: Sync> Sync for ConstVec<T> {}
+
+impl<T: Copy> ConstVec<T> {
+    const fn new() -> Self {
+        ConstVec { ptr: std::ptr::null_mut(), len: 0, cap: 0 }
+    }
+
+    const fn push(&mut self, value: T) {
+        if self.len == self.cap {
+            let cap = if self.cap == 0 { 4 } else { self.cap * 2 };
+            unsafe {
+                let ptr = intrinsics::const_allocate(cap * size_of::<T>(), align_of::<T>());
+                let ptr = ptr as *mut T;
+                let mut i = 0;
+                while i < self.len {
+                    *ptr.add(i) = *self.ptr.add(i);
+                    i += 1;
+                }
+                if self.cap > 0 {
+                    let old = self.ptr as *mut u8;
+                    intrinsics::const_deallocate(old, self.cap * size_of::<T>(), align_of::<T>());
+                }
+                self.ptr = ptr;
+            }
+            self.cap = cap;
+        }
+        unsafe { *self.ptr.add(self.len) = value };
+        self.len += 1;
+    }
+
+    const fn into_global(mut self) -> Self {
+        self.ptr = unsafe { intrinsics::const_make_global(self.ptr as *mut u8) } as *mut T;
+        self
+    }
+
+    const fn as_slice(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+struct ConstString {
+    bytes: ConstVec<u8>,
+}
+
+impl ConstString {
+    const fn new() -> Self {
+        ConstString { bytes: ConstVec::new() }
+    }
+
+    const fn push_str(&mut self, s: &str) {
+        let s = s.as_bytes();
+        let mut i = 0;
+        while i < s.len() {
+            self.bytes.push(s[i]);
+            i += 1;
+        }
+    }
+
+    const fn into_global(self) -> Self {
+        ConstString { bytes: self.bytes.into_global() }
+    }
+
+    const fn as_str(&self) -> &str {
+        unsafe { std::str::from_utf8_unchecked(self.bytes.as_slice()) }
+    }
+}
+
+const fn squares(n: u32) -> ConstVec<u32> {
+    let mut v = ConstVec::new();
+    let mut i = 0;
+    while i < n {
+        v.push(i * i);
+        i += 1;
+    }
+    v
+}
+
+const fn join(words: &[&str], sep: &str) -> ConstString {
+    let mut s = ConstString::new();
+    let mut i = 0;
+    while i < words.len() {
+        if i > 0 {
+            s.push_str(sep);
+        }
+        s.push_str(words[i]);
+        i += 1;
+    }
+    s
+}
+
+const SQUARES: &ConstVec<u32> = &squares(10).into_global();
+static SMALL_SQUARES: &ConstVec<u32> = &squares(3).into_global();
+// The heap allocation is immutable, so it can also be owned by value.
+const SQUARES_BY_VALUE: ConstVec<u32> = squares(5).into_global();
+const GREETING: &ConstString = &join(&["hello", "const", "heap"], ", ").into_global();
+// Reusing the heap allocations of another constant.
+const GREETING_STR: &str = GREETING.as_str();
+
+fn main() {
+    assert_eq!(SQUARES.as_slice(), [0, 1, 4, 9, 16, 25, 36, 49, 64, 81]);
+    assert_eq!(SMALL_SQUARES.as_slice(), [0, 1, 4]);
+    assert_eq!(SQUARES_BY_VALUE.as_slice(), [0, 1, 4, 9, 16]);
+    assert_eq!(GREETING.as_str(), "hello, const, heap");
+    assert_eq!(GREETING_STR, "hello, const, heap");
+}
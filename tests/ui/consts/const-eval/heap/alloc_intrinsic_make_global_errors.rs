@@ -0,0 +1,40 @@
+#![feature(core_intrinsics)]
+#![feature(const_heap)]
+#![feature(const_mut_refs)]
+
+use std::intrinsics;
+
+const WRITE: () = unsafe {
+    let ptr = intrinsics::const_allocate(4, 4);
+    intrinsics::const_make_global(ptr);
+    *ptr = 0;
+    //~^ error: evaluation of constant value failed
+};
+
+const DEALLOCATE: () = unsafe {
+    let ptr = intrinsics::const_allocate(4, 4);
+    intrinsics::const_make_global(ptr);
+    intrinsics::const_deallocate(ptr, 4, 4);
+    //~^ error: evaluation of constant value failed
+};
+
+const NOT_HEAP: () = unsafe {
+    let mut x = 0u8;
+    intrinsics::const_make_global(&mut x);
+    //~^ error: evaluation of constant value failed
+};
+
+const WITH_OFFSET: () = unsafe {
+    let ptr = intrinsics::const_allocate(4, 4);
+    intrinsics::const_make_global(ptr.add(1));
+    //~^ error: evaluation of constant value failed
+};
+
+const TWICE: () = unsafe {
+    let ptr = intrinsics::const_allocate(4, 4);
+    intrinsics::const_make_global(ptr);
+    intrinsics::const_make_global(ptr);
+    //~^ error: evaluation of constant value failed
+};
+
+fn main() {}
@@ -0,0 +1,12 @@
+#![feature(box_patterns)]
+
+use std::rc::Rc;
+
+fn main() {
+    let shape: Rc<Option<u8>> = Rc::new(None);
+    match shape {
+        box Some(_) => {}
+        //~^ ERROR mismatched types
+        _ => {}
+    }
+}
@@ -0,0 +1,50 @@
+// build-fail
+// edition:2021
+// compile-flags: -Zfuture-size-limit=1024 --crate-type lib
+
+#![deny(large_futures)]
+#![allow(dropping_copy_types)]
+
+use std::future::Future;
+
+async fn wait() {}
+
+pub async fn small() {
+    let buffer = [0u8; 512];
+    wait().await;
+    drop(buffer);
+}
+
+pub async fn big() { //~ ERROR this future is 2050 bytes
+    let buffer = [0u8; 2048];
+    wait().await;
+    drop(buffer);
+}
+
+#[allow(large_futures)]
+pub async fn big_but_allowed() {
+    let buffer = [0u8; 2048];
+    wait().await;
+    drop(buffer);
+}
+
+pub fn big_block() -> impl Future<Output = ()> {
+    async { //~ ERROR this future is 2050 bytes
+        let buffer = [0u8; 2048];
+        wait().await;
+        drop(buffer);
+    }
+}
+
+// Reported once, with the size of the largest instantiation.
+pub async fn generic<const N: usize>() { //~ ERROR this future is 4098 bytes
+    let buffer = [0u8; N];
+    wait().await;
+    drop(buffer);
+}
+
+pub fn instantiate_generic() {
+    let _ = generic::<2048>();
+    let _ = generic::<4096>();
+    let _ = generic::<512>();
+}
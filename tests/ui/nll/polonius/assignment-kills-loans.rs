@@ -5,7 +5,9 @@
 // for code accepted by NLL. They are all variations from example code in the NLL RFC.
 
 // check-pass
-// compile-flags: -Z polonius
+// revisions: nll legacy next
+// [legacy] compile-flags: -Z polonius
+// [next] compile-flags: -Z polonius=next
 
 struct List<T> {
     value: T,
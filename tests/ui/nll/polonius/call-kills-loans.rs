@@ -5,7 +5,9 @@
 // missing `killed` facts.
 
 // check-pass
-// compile-flags: -Z polonius
+// revisions: nll legacy next
+// [legacy] compile-flags: -Z polonius
+// [next] compile-flags: -Z polonius=next
 
 struct Thing;
 
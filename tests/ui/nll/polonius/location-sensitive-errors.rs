@@ -0,0 +1,47 @@
+// Checks that the location-sensitive loan liveness of `-Zpolonius=next` still emits the errors of
+// NLLs outside of the problem case #3: loans used later, loans escaping the function at some point
+// of a loop, references to locals escaping the function, and loans flowing backward in the CFG
+// through an invariant region.
+
+// revisions: nll polonius
+// [polonius] compile-flags: -Zpolonius=next
+
+fn use_later() {
+    let mut data = ('a', 'b', 'c');
+    let c = &mut data.0;
+    capitalize(c);
+    data.0 = 'e';
+    //~^ ERROR cannot assign to `data.0` because it is borrowed
+    data.0 = 'f';
+    capitalize(c);
+}
+
+fn escaping_in_loop<'a>(f: fn(&'a mut u32), arg: &'a mut u32) {
+    loop {
+        f(arg);
+        //~^ ERROR cannot borrow `*arg` as mutable more than once at a time
+    }
+}
+
+fn escaping_local() -> &'static u32 {
+    let x = 0;
+    &x
+    //~^ ERROR cannot return reference to local variable `x`
+}
+
+fn flowing_backward() {
+    let mut a = 1;
+    let b = 2;
+    let cell = std::cell::Cell::new(&b);
+    // `reader` gets the same region as `cell` here, but `cell` is not used after `a` is borrowed
+    // into it: the loan only reaches `reader` through the earlier point.
+    let reader = &cell;
+    cell.set(&a);
+    a = 3;
+    //~^ ERROR cannot assign to `a` because it is borrowed
+    println!("{}", reader.get());
+}
+
+fn capitalize(_: &mut char) {}
+
+fn main() {}
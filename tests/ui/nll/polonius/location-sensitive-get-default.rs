@@ -0,0 +1,47 @@
+// Checks that the location-sensitive loan liveness of `-Zpolonius=next` accepts the conditional
+// return of a borrow, the NLL problem case #3, while still rejecting the conflicts on the path
+// where the borrow is returned. The same cases with NLLs are in `tests/ui/nll/get_default.rs`.
+
+// revisions: nll polonius
+// [polonius] compile-flags: -Zpolonius=next
+
+struct Map {
+}
+
+impl Map {
+    fn get(&self) -> Option<&String> { None }
+    fn set(&mut self, v: String) { }
+}
+
+fn ok(map: &mut Map) -> &String {
+    loop {
+        match map.get() {
+            Some(v) => {
+                return v;
+            }
+            None => {
+                map.set(String::new());
+                //[nll]~^ ERROR borrowed as immutable
+            }
+        }
+    }
+}
+
+fn err(map: &mut Map) -> &String {
+    loop {
+        match map.get() {
+            Some(v) => {
+                map.set(String::new());
+                //[nll]~^ ERROR borrowed as immutable
+                //[polonius]~^^ ERROR borrowed as immutable
+                return v;
+            }
+            None => {
+                map.set(String::new());
+                //[nll]~^ ERROR borrowed as immutable
+            }
+        }
+    }
+}
+
+fn main() {}
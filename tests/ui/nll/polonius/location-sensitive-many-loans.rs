@@ -0,0 +1,38 @@
+// A stress test for the location-sensitive loan liveness of `-Zpolonius=next`: many loans whose
+// regions are all live until the end of a function with many points. Overall, the traversals of
+// the loans are then quadratic in the size of the function, like the computation of their NLL
+// scopes, and both modes should compile this in similar time.
+
+// check-pass
+// revisions: nll polonius
+// [polonius] compile-flags: -Zpolonius=next
+
+macro_rules! many_loans {
+    ($data:ident, $($r:ident)*) => {
+        $(
+            let $r = &$data;
+            if $r.len() > 1 {
+                $data.len();
+            }
+        )*
+        0 $(+ $r.len())*
+    };
+}
+
+fn many_loans(data: Vec<u32>) -> usize {
+    many_loans!(
+        data,
+        r0 r1 r2 r3 r4 r5 r6 r7 r8 r9 r10 r11 r12 r13 r14 r15
+        r16 r17 r18 r19 r20 r21 r22 r23 r24 r25 r26 r27 r28 r29 r30 r31
+        r32 r33 r34 r35 r36 r37 r38 r39 r40 r41 r42 r43 r44 r45 r46 r47
+        r48 r49 r50 r51 r52 r53 r54 r55 r56 r57 r58 r59 r60 r61 r62 r63
+        r64 r65 r66 r67 r68 r69 r70 r71 r72 r73 r74 r75 r76 r77 r78 r79
+        r80 r81 r82 r83 r84 r85 r86 r87 r88 r89 r90 r91 r92 r93 r94 r95
+        r96 r97 r98 r99 r100 r101 r102 r103 r104 r105 r106 r107 r108 r109 r110 r111
+        r112 r113 r114 r115 r116 r117 r118 r119 r120 r121 r122 r123 r124 r125 r126 r127
+    )
+}
+
+fn main() {
+    many_loans(vec![1, 2, 3]);
+}
@@ -0,0 +1,49 @@
+// Checks that the location-sensitive loan liveness of `-Zpolonius=next` accepts variants of the NLL
+// problem case #3, which NLLs reject: a borrow escaping the function on one path, while the
+// borrowed place is mutated on the other paths.
+
+// check-pass
+// compile-flags: -Zpolonius=next
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+fn get_default<'r, K: Hash + Eq + Copy, V: Default>(
+    map: &'r mut HashMap<K, V>,
+    key: K,
+) -> &'r mut V {
+    match map.get_mut(&key) {
+        Some(value) => value,
+        None => {
+            map.insert(key, V::default());
+            map.get_mut(&key).unwrap()
+        }
+    }
+}
+
+fn get_or_insert(map: &mut HashMap<u32, String>) -> &String {
+    if let Some(value) = map.get(&22) {
+        return value;
+    }
+    map.insert(22, String::from("hi"));
+    &map[&22]
+}
+
+fn first_or_push(v: &mut Vec<u32>) -> &mut u32 {
+    loop {
+        if let Some(first) = v.first_mut() {
+            return first;
+        }
+        v.push(0);
+    }
+}
+
+fn store_or_clear<'a>(v: &'a mut Vec<u32>, out: &mut Option<&'a u32>) {
+    if let Some(first) = v.first() {
+        *out = Some(first);
+        return;
+    }
+    v.clear();
+}
+
+fn main() {}
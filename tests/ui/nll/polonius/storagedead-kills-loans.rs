@@ -4,7 +4,9 @@
 // Polonius because of these missing `killed` facts.
 
 // check-pass
-// compile-flags: -Z polonius
+// revisions: nll legacy next
+// [legacy] compile-flags: -Z polonius
+// [next] compile-flags: -Z polonius=next
 
 use std::{io, mem};
 use std::io::Read;
@@ -0,0 +1,33 @@
+// run-pass
+// Deref patterns match through smart pointers and bind the fields of the pointee.
+#![feature(box_patterns)]
+#![feature(deref_patterns)]
+#![allow(incomplete_features)]
+
+use std::rc::Rc;
+
+enum Shape {
+    Circle(u32),
+    Square(u32),
+}
+
+fn area(shape: Rc<Shape>) -> u32 {
+    match shape {
+        box Shape::Circle(r) => 3 * r * r,
+        box Shape::Square(s) => s * s,
+    }
+}
+
+fn first_two(v: Vec<u32>) -> Option<(u32, u32)> {
+    match v {
+        box [a, b, ..] => Some((a, b)),
+        box _ => None,
+    }
+}
+
+fn main() {
+    assert_eq!(area(Rc::new(Shape::Circle(2))), 12);
+    assert_eq!(area(Rc::new(Shape::Square(3))), 9);
+    assert_eq!(first_two(vec![1, 2, 3]), Some((1, 2)));
+    assert_eq!(first_two(vec![1]), None);
+}
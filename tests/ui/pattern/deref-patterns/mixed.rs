@@ -0,0 +1,43 @@
+// Deref patterns can be mixed with the constructors of the pointer type, which are still matched.
+// Exhaustiveness is checked through the deref patterns.
+#![feature(box_patterns)]
+#![feature(deref_patterns)]
+#![feature(deref_pure_trait)]
+#![allow(incomplete_features)]
+
+use std::ops::{Deref, DerefPure};
+
+enum Ptr {
+    Boxed(Box<bool>),
+    Empty,
+}
+
+impl Deref for Ptr {
+    type Target = bool;
+    fn deref(&self) -> &bool {
+        match self {
+            Ptr::Boxed(b) => b,
+            Ptr::Empty => &false,
+        }
+    }
+}
+
+unsafe impl DerefPure for Ptr {}
+
+fn exhaustive(ptr: Ptr) -> u32 {
+    match ptr {
+        Ptr::Empty => 0,
+        box true => 1,
+        box false => 2,
+    }
+}
+
+fn non_exhaustive(ptr: Ptr) -> u32 {
+    match ptr {
+        //~^ ERROR non-exhaustive patterns: `box false` not covered
+        Ptr::Empty => 0,
+        box true => 1,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+// Deref patterns are checked for exhaustiveness through the pointee type.
+#![feature(box_patterns)]
+#![feature(deref_patterns)]
+#![allow(incomplete_features)]
+
+use std::rc::Rc;
+
+enum Shape {
+    Circle,
+    Square,
+    Triangle,
+}
+
+fn exhaustive(shape: Rc<Shape>) {
+    match shape {
+        box Shape::Circle => {}
+        box Shape::Square | box Shape::Triangle => {}
+    }
+}
+
+fn non_exhaustive(shape: Rc<Shape>) {
+    match shape {
+        //~^ ERROR non-exhaustive patterns: `box Shape::Triangle` not covered
+        box Shape::Circle => {}
+        box Shape::Square => {}
+    }
+}
+
+fn main() {}
@@ -0,0 +1,28 @@
+// run-pass
+// Deref patterns match string literals through the `str` pointee of `String` and `Box<str>`.
+#![feature(box_patterns)]
+#![feature(deref_patterns)]
+#![allow(incomplete_features)]
+
+fn string(s: String) -> u32 {
+    match s {
+        box "hello" => 1,
+        box "bye" => 2,
+        box _ => 0,
+    }
+}
+
+fn boxed_str(s: Box<str>) -> u32 {
+    match s {
+        box "hello" => 1,
+        box _ => 0,
+    }
+}
+
+fn main() {
+    assert_eq!(string(String::from("hello")), 1);
+    assert_eq!(string(String::from("bye")), 2);
+    assert_eq!(string(String::from("hi")), 0);
+    assert_eq!(boxed_str("hello".into()), 1);
+    assert_eq!(boxed_str("bye".into()), 0);
+}
@@ -0,0 +1,30 @@
+// Checks that matches on `usize` and `isize` are exhaustive without a wildcard when the ranges
+// reaching the bounds of the type are half-open, since the maximum is platform-dependent.
+
+// check-pass
+#![feature(exclusive_range_pattern)]
+
+fn usize_to_str(n: usize) -> &'static str {
+    match n {
+        0 => "zero",
+        1.. => "positive",
+    }
+}
+
+fn isize_to_str(n: isize) -> &'static str {
+    match n {
+        ..0 => "negative",
+        0.. => "non-negative",
+    }
+}
+
+fn main() {
+    match 0usize {
+        0.. => {}
+    }
+    match 0isize {
+        ..0 | 0.. => {}
+    }
+    assert_eq!(usize_to_str(0), "zero");
+    assert_eq!(isize_to_str(-1), "negative");
+}
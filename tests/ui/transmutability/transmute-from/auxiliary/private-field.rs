@@ -0,0 +1,4 @@
+#[repr(C)]
+pub struct Private {
+    x: u8,
+}
@@ -0,0 +1,49 @@
+// run-pass
+//! Parsing packets from bytes without `unsafe` code, with `#[derive(TransmuteFrom)]`.
+
+#![feature(transmutability)]
+
+use std::mem::{self, Assume, TransmuteFrom};
+
+#[derive(TransmuteFrom)]
+#[repr(C)]
+struct Header {
+    version: u8,
+    kind: u8,
+    len: [u8; 2],
+}
+
+#[derive(TransmuteFrom)]
+#[repr(C)]
+struct Packet {
+    header: Header,
+    checksum: [u8; 4],
+}
+
+#[repr(C, align(4))]
+struct Aligned([u8; 8]);
+
+fn main() {
+    let bytes = [1, 2, 0, 8, 0xde, 0xad, 0xbe, 0xef];
+    let packet: &Packet = mem::transmute_ref(&bytes).unwrap();
+    assert_eq!(packet.header.version, 1);
+    assert_eq!(packet.header.kind, 2);
+    assert_eq!(u16::from_be_bytes(packet.header.len), 8);
+    assert_eq!(packet.checksum, [0xde, 0xad, 0xbe, 0xef]);
+
+    let header: Header = mem::transmute_from([3u8, 4, 0, 0]);
+    assert_eq!((header.version, header.kind), (3, 4));
+
+    let mut buf = [0u8; 4];
+    let header: &mut Header = mem::transmute_mut(&mut buf).unwrap();
+    header.kind = 5;
+    assert_eq!(buf, [0, 5, 0, 0]);
+
+    let aligned = Aligned([0; 8]);
+    assert!(mem::transmute_ref::<4, u32>(aligned.0[0..4].try_into().unwrap()).is_some());
+    assert!(mem::transmute_ref::<4, u32>(aligned.0[1..5].try_into().unwrap()).is_none());
+
+    // Validity can be assumed, at the cost of `unsafe`.
+    let b: bool = unsafe { <bool as TransmuteFrom<u8, { Assume::VALIDITY }>>::transmute(1) };
+    assert!(b);
+}
@@ -0,0 +1,7 @@
+#![feature(transmutability)]
+
+use std::mem;
+
+fn main() {
+    let _: bool = mem::transmute_from(2u8); //~ ERROR cannot be safely transmuted
+}
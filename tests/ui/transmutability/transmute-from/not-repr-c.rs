@@ -0,0 +1,16 @@
+//! The layout of a type without `#[repr(C)]` is unspecified, so deriving `TransmuteFrom` does not
+//! make it transmutable.
+
+#![feature(transmutability)]
+
+use std::mem::{self, TransmuteFrom};
+
+#[derive(TransmuteFrom)]
+struct Rust {
+    a: u8,
+    b: u8,
+}
+
+fn main() {
+    let _: Rust = mem::transmute_from([0u8; 2]); //~ ERROR cannot be safely transmuted
+}
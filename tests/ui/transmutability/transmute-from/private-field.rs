@@ -0,0 +1,20 @@
+// aux-build:private-field.rs
+//! Deriving `TransmuteFrom` only grants access to the fields the deriving type can see: wrapping a
+//! foreign type does not make its private fields constructible.
+
+#![feature(transmutability)]
+
+extern crate private_field;
+
+use private_field::Private;
+use std::mem::{self, TransmuteFrom};
+
+#[derive(TransmuteFrom)]
+#[repr(C)]
+struct Local {
+    private: Private,
+}
+
+fn main() {
+    let _: Local = mem::transmute_from([0u8; 1]); //~ ERROR cannot be safely transmuted
+}
@@ -0,0 +1,25 @@
+//! `transmute_mut` needs the bytes and `Dst` to be transmutable in both directions.
+
+#![feature(transmutability)]
+
+use std::mem::{self, TransmuteFrom};
+
+// Writing a `Padded` through the reference would leave its padding byte uninitialized.
+#[derive(TransmuteFrom)]
+#[repr(C)]
+struct Padded {
+    a: u8,
+    b: u16,
+}
+
+// The bytes may hold values that are not a valid `bool`.
+#[derive(TransmuteFrom)]
+#[repr(C)]
+struct Flag {
+    set: bool,
+}
+
+fn main() {
+    let _ = mem::transmute_mut::<4, Padded>(&mut [0; 4]); //~ ERROR cannot be safely transmuted
+    let _ = mem::transmute_mut::<1, Flag>(&mut [2]); //~ ERROR cannot be safely transmuted
+}
@@ -0,0 +1,17 @@
+//! Types with interior mutability cannot be derived into: `transmute_ref` would otherwise allow
+//! mutating the bytes behind a shared reference.
+
+#![feature(transmutability)]
+
+use std::cell::UnsafeCell;
+use std::mem::{self, TransmuteFrom};
+
+#[derive(TransmuteFrom)]
+#[repr(C)]
+struct Cell {
+    value: UnsafeCell<u8>,
+}
+
+fn main() {
+    let _: Option<&Cell> = mem::transmute_ref(&[0u8; 1]); //~ ERROR cannot be safely transmuted
+}